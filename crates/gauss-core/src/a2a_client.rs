@@ -53,9 +53,12 @@ impl A2aClient {
         if let Some(token) = &self.auth_token {
             req = req.bearer_auth(token);
         }
-        let resp = req.send().await.map_err(|e| {
-            GaussError::provider("a2a", format!("Discovery request failed: {e}"))
-        })?;
+        let resp = crate::telemetry::inject_trace_context(req)
+            .send()
+            .await
+            .map_err(|e| {
+                GaussError::provider("a2a", format!("Discovery request failed: {e}"))
+            })?;
         if !resp.status().is_success() {
             return Err(GaussError::provider(
                 "a2a",
@@ -115,9 +118,12 @@ impl A2aClient {
             req = req.bearer_auth(token);
         }
 
-        let resp = req.send().await.map_err(|e| {
-            GaussError::provider("a2a", format!("Stream request failed: {e}"))
-        })?;
+        let resp = crate::telemetry::inject_trace_context(req)
+            .send()
+            .await
+            .map_err(|e| {
+                GaussError::provider("a2a", format!("Stream request failed: {e}"))
+            })?;
         if !resp.status().is_success() {
             return Err(GaussError::provider(
                 "a2a",
@@ -268,9 +274,12 @@ impl A2aClient {
             req = req.bearer_auth(token);
         }
 
-        let resp = req.send().await.map_err(|e| {
-            GaussError::provider("a2a", format!("HTTP request failed: {e}"))
-        })?;
+        let resp = crate::telemetry::inject_trace_context(req)
+            .send()
+            .await
+            .map_err(|e| {
+                GaussError::provider("a2a", format!("HTTP request failed: {e}"))
+            })?;
 
         if !resp.status().is_success() {
             return Err(GaussError::provider(
@@ -3,10 +3,13 @@ use std::pin::Pin;
 use std::sync::Arc;
 use tracing::{debug, info, warn};
 
+use crate::cost::{CostEstimate, RunCost};
 use crate::error::{self, GaussError};
 use crate::message::{Message, Usage};
+use crate::plugin::{GaussEvent, PluginRegistry};
 use crate::provider::{FinishReason, GenerateOptions, Provider, ReasoningEffort};
 use crate::streaming::StreamEvent;
+use crate::telemetry::{SpanBuilder, SpanRecord, SpanType, TelemetryCollector, TraceContext};
 use crate::tool::{Tool, ToolChoice};
 
 /// Conditions that stop the agent loop.
@@ -20,6 +23,27 @@ pub enum StopCondition {
     TextGenerated,
     /// Custom condition via callback name (evaluated externally).
     Custom(String),
+    /// Stop before the next provider call once the estimated cost reaches this many USD.
+    MaxCostUsd(f64),
+    /// Stop before the next provider call once input + output tokens reach this budget.
+    MaxTotalTokens(u64),
+    /// Stop before the next provider call once reasoning tokens reach this budget.
+    MaxReasoningTokens(u64),
+}
+
+/// Why an agent run ended.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StopReason {
+    /// The model answered without requesting more tool calls.
+    #[default]
+    Completed,
+    /// A [`StopCondition`] such as `TextGenerated` or `HasToolCall` matched.
+    StopCondition,
+    /// A cost or token budget was reached before the next provider call.
+    BudgetExhausted,
+    /// The agent used all of its `max_steps`.
+    MaxSteps,
 }
 
 /// Callback types for agent events.
@@ -53,6 +77,14 @@ pub struct AgentOutput {
     pub citations: Vec<crate::message::Citation>,
     /// Aggregated grounding metadata from Google Search grounding.
     pub grounding_metadata: Vec<crate::message::GroundingMetadata>,
+    /// Estimated cost of the run, per step and per model.
+    pub cost: RunCost,
+    /// Unique id of this run.
+    pub run_id: String,
+    /// Trace and span id of the run span, when telemetry is enabled.
+    pub trace: Option<TraceContext>,
+    /// Why the run ended.
+    pub stop_reason: StopReason,
 }
 
 /// Result from a single agent step.
@@ -62,6 +94,8 @@ pub struct StepResult {
     pub message: Message,
     pub finish_reason: FinishReason,
     pub usage: Usage,
+    /// Estimated cost of this step's provider call.
+    pub cost: CostEstimate,
     pub tool_calls: Vec<ToolCallInfo>,
     pub tool_results: Vec<ToolResultInfo>,
 }
@@ -92,6 +126,9 @@ pub struct Agent {
     stop_conditions: Vec<StopCondition>,
     on_step_finish: Option<OnStepFinishFn>,
     on_tool_call: Option<OnToolCallFn>,
+    telemetry: Option<crate::Shared<TelemetryCollector>>,
+    plugins: Option<crate::Shared<PluginRegistry>>,
+    chain_responses: bool,
 }
 
 impl Clone for Agent {
@@ -106,6 +143,9 @@ impl Clone for Agent {
             stop_conditions: self.stop_conditions.clone(),
             on_step_finish: self.on_step_finish.clone(),
             on_tool_call: self.on_tool_call.clone(),
+            telemetry: self.telemetry.clone(),
+            plugins: self.plugins.clone(),
+            chain_responses: self.chain_responses,
         }
     }
 }
@@ -122,6 +162,9 @@ impl Agent {
             stop_conditions: Vec::new(),
             on_step_finish: None,
             on_tool_call: None,
+            telemetry: None,
+            plugins: None,
+            chain_responses: false,
         }
     }
 
@@ -147,11 +190,26 @@ impl Agent {
                 StopCondition::Custom(_) => {
                     // Custom conditions are evaluated externally
                 }
+                StopCondition::MaxCostUsd(_)
+                | StopCondition::MaxTotalTokens(_)
+                | StopCondition::MaxReasoningTokens(_) => {
+                    // Budgets are checked before each provider call
+                }
             }
         }
         false
     }
 
+    /// Check if a budget stop condition has been reached by what the run spent so far.
+    fn budget_exhausted(&self, usage: &Usage, cost: &RunCost) -> bool {
+        self.stop_conditions.iter().any(|cond| match cond {
+            StopCondition::MaxCostUsd(max) => cost.total_cost_usd >= *max,
+            StopCondition::MaxTotalTokens(max) => usage.total_tokens() >= *max,
+            StopCondition::MaxReasoningTokens(max) => usage.reasoning_tokens.unwrap_or(0) >= *max,
+            _ => false,
+        })
+    }
+
     /// Validate output against schema if provided.
     fn validate_output(&self, text: &str) -> error::Result<Option<serde_json::Value>> {
         if let Some(ref schema) = self.options.output_schema {
@@ -181,8 +239,165 @@ impl Agent {
 
     /// Run the agent with the given messages.
     pub async fn run(&self, messages: Vec<Message>) -> error::Result<AgentOutput> {
+        let Some(ref telemetry) = self.telemetry else {
+            return self.run_steps(messages, None).await.0;
+        };
+        let (result, span) = self
+            .run_traced(messages, crate::telemetry::child_of_current())
+            .await;
+        telemetry.record_run(span);
+        result
+    }
+
+    /// Run with a span tree rooted at `context` and return the run span
+    /// instead of recording it (used by orchestrators to nest agent runs).
+    pub(crate) async fn run_traced(
+        &self,
+        messages: Vec<Message>,
+        context: TraceContext,
+    ) -> (error::Result<AgentOutput>, SpanRecord) {
+        let span = self.run_span(&context);
+        let (result, steps) = self.run_steps(messages, Some(&context)).await;
+        let span = steps.into_iter().fold(span, SpanBuilder::child);
+
+        match result {
+            Ok(output) => {
+                let span = finish_run_span(
+                    span,
+                    &output.run_id,
+                    output.steps,
+                    &output.usage,
+                    &output.cost,
+                );
+                (Ok(output), span)
+            }
+            Err(e) => {
+                let span = span.finish_with_error(e.to_string());
+                (Err(e), span)
+            }
+        }
+    }
+
+    /// The (unfinished) root span of a run.
+    fn run_span(&self, context: &TraceContext) -> SpanBuilder {
+        SpanBuilder::new(format!("invoke_agent {}", self.name), SpanType::AgentRun)
+            .context(context.clone())
+            .attribute("agent_name", self.name.clone())
+            .attribute("model", self.provider.model())
+            .attribute("provider", self.provider.name())
+    }
+
+    /// The (unfinished) span of one provider call.
+    fn model_span(&self, context: &TraceContext) -> SpanBuilder {
+        SpanBuilder::new(
+            format!("chat {}", self.provider.model()),
+            SpanType::ModelCall,
+        )
+        .context(context.clone())
+        .attribute("model", self.provider.model())
+        .attribute("provider", self.provider.name())
+    }
+
+    /// Run as part of an orchestrator: traced as a child of `parent` (the
+    /// run span is pushed to `spans`) when given, a plain `run()` otherwise.
+    pub(crate) async fn run_child(
+        &self,
+        messages: Vec<Message>,
+        parent: Option<&TraceContext>,
+        spans: &mut Vec<SpanRecord>,
+    ) -> error::Result<AgentOutput> {
+        match parent {
+            Some(parent) => {
+                let (result, span) = self.run_traced(messages, parent.child()).await;
+                spans.push(span);
+                result
+            }
+            None => self.run(messages).await,
+        }
+    }
+
+    /// Use `registry` unless the agent already has its own (orchestrators
+    /// pass theirs down to member agents).
+    pub(crate) fn inherit_plugins(&mut self, registry: &crate::Shared<PluginRegistry>) {
+        self.plugins.get_or_insert_with(|| registry.clone());
+    }
+
+    /// Publish a lifecycle event, if a plugin registry is attached. Async
+    /// handlers run in the background so slow plugins don't stall the loop.
+    async fn emit(&self, event: impl FnOnce() -> GaussEvent) {
+        if let Some(ref plugins) = self.plugins {
+            plugins.dispatch(&event()).await;
+        }
+    }
+
+    /// The agent loop. With a trace context, also returns one span per step
+    /// (with model and tool call children).
+    async fn run_steps(
+        &self,
+        messages: Vec<Message>,
+        trace: Option<&TraceContext>,
+    ) -> (error::Result<AgentOutput>, Vec<SpanRecord>) {
+        let run_id = uuid::Uuid::new_v4().to_string();
+        let started = std::time::Instant::now();
+        self.emit(|| GaussEvent::AgentStart {
+            agent_name: self.name.clone(),
+            session_id: run_id.clone(),
+        })
+        .await;
+
+        let mut step_spans = Vec::new();
+        let result = self
+            .run_loop(messages, run_id.clone(), trace, &mut step_spans)
+            .await;
+
+        self.emit(|| match &result {
+            Ok(output) => GaussEvent::AgentFinish {
+                agent_name: self.name.clone(),
+                session_id: run_id,
+                result_text: output.text.clone(),
+                steps: output.steps,
+                duration_ms: started.elapsed().as_millis() as u64,
+            },
+            Err(e) => GaussEvent::Error {
+                source: self.name.clone(),
+                message: e.to_string(),
+            },
+        })
+        .await;
+        (result, step_spans)
+    }
+
+    /// Run the `on_step_finish` callback and publish a step event.
+    async fn step_finished(&self, run_id: &str, step: &StepResult, started: std::time::Instant) {
+        if let Some(ref on_step_finish) = self.on_step_finish {
+            on_step_finish(step).await;
+        }
+        self.emit(|| GaussEvent::StepFinish {
+            agent_name: self.name.clone(),
+            session_id: run_id.to_string(),
+            step_index: step.step_index,
+            finish_reason: finish_reason_value(&step.finish_reason)
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            tool_calls: step.tool_calls.len(),
+            input_tokens: step.usage.input_tokens,
+            output_tokens: step.usage.output_tokens,
+            duration_ms: started.elapsed().as_millis() as u64,
+        })
+        .await;
+    }
+
+    async fn run_loop(
+        &self,
+        messages: Vec<Message>,
+        run_id: String,
+        trace: Option<&TraceContext>,
+        step_spans: &mut Vec<SpanRecord>,
+    ) -> error::Result<AgentOutput> {
         let mut all_messages = Vec::new();
         let mut total_usage = Usage::default();
+        let mut cost = RunCost::default();
         let mut step_results = Vec::new();
         let mut thinking_parts: Vec<String> = Vec::new();
         let mut all_citations: Vec<crate::message::Citation> = Vec::new();
@@ -193,13 +408,45 @@ impl Agent {
         }
         all_messages.extend(messages);
 
+        // Messages before `sent` are already stored server-side under
+        // `options.previous_response_id`.
+        let mut options = self.options.clone();
+        let mut sent = 0;
+
+        let mut stop_reason = StopReason::MaxSteps;
         for step in 0..self.max_steps {
-            info!(agent = %self.name, step, "Executing step");
+            if self.budget_exhausted(&total_usage, &cost) {
+                info!(agent = %self.name, step, cost_usd = cost.total_cost_usd, "Budget exhausted, stopping");
+                stop_reason = StopReason::BudgetExhausted;
+                break;
+            }
 
-            let result = self
-                .provider
-                .generate(&all_messages, &self.tools, &self.options)
-                .await?;
+            info!(agent = %self.name, step, "Executing step");
+            let step_started = std::time::Instant::now();
+
+            let step_ctx = trace.map(TraceContext::child);
+            let step_span = step_ctx.as_ref().map(|ctx| {
+                SpanBuilder::new(format!("step {step}"), SpanType::AgentStep)
+                    .context(ctx.clone())
+                    .attribute("step", step)
+            });
+            let mut step_children = Vec::new();
+
+            let model_ctx = step_ctx.as_ref().map(TraceContext::child);
+            let model_span = model_ctx.as_ref().map(|ctx| self.model_span(ctx));
+            let generated = crate::telemetry::in_trace_scope(
+                model_ctx,
+                self.provider
+                    .generate(&all_messages[sent..], &self.tools, &options),
+            )
+            .await;
+            let result = match generated {
+                Ok(result) => result,
+                Err(e) => {
+                    fail_step_span(step_span, model_span, &e, step_spans);
+                    return Err(e);
+                }
+            };
 
             if let Some(ref t) = result.thinking {
                 thinking_parts.push(t.clone());
@@ -211,10 +458,22 @@ impl Agent {
                 all_grounding.push(gm.clone());
             }
 
-            total_usage.input_tokens += result.usage.input_tokens;
-            total_usage.output_tokens += result.usage.output_tokens;
-            if let Some(rt) = result.usage.reasoning_tokens {
-                *total_usage.reasoning_tokens.get_or_insert(0) += rt;
+            accumulate_usage(&mut total_usage, &result.usage);
+            // A router reports the model that actually served the call.
+            let model = result
+                .routing
+                .as_ref()
+                .map_or(self.provider.model(), |r| r.model.as_str());
+            let step_cost = cost.record_call(model, &result.usage, &result.provider_metadata);
+
+            if let Some(model_span) = model_span {
+                step_children.push(finish_model_span(
+                    model_span,
+                    &result.usage,
+                    &result.finish_reason,
+                    &step_cost,
+                    result.message.text(),
+                ));
             }
 
             let tool_calls_in_step = result.message.tool_calls();
@@ -237,34 +496,64 @@ impl Agent {
             }
 
             all_messages.push(result.message.clone());
+            if let Some(id) = result.provider_metadata["response_id"].as_str()
+                && self.chain_responses
+            {
+                options.previous_response_id = Some(id.to_string());
+                sent = all_messages.len();
+            }
 
             if !has_tool_calls || result.finish_reason != FinishReason::ToolCalls {
+                finish_step_span(step_span, step_children, &result.finish_reason, step_spans);
                 let step_result = StepResult {
                     step_index: step,
                     message: result.message,
                     finish_reason: result.finish_reason,
                     usage: result.usage,
+                    cost: step_cost,
                     tool_calls: tool_call_infos,
                     tool_results: Vec::new(),
                 };
 
-                if let Some(ref on_step_finish) = self.on_step_finish {
-                    on_step_finish(&step_result).await;
-                }
+                self.step_finished(&run_id, &step_result, step_started)
+                    .await;
 
                 step_results.push(step_result);
+                stop_reason = StopReason::Completed;
                 break;
             }
 
             // Execute tool calls
             let mut tool_results_vec = Vec::new();
+            let mut artifact_parts = Vec::new();
             for (tc_id, tc_name, tc_args) in &tool_calls_in_step {
                 debug!(tool = tc_name, "Executing tool");
+                let tool_started = std::time::Instant::now();
+                self.emit(|| GaussEvent::ToolCallStart {
+                    tool_name: tc_name.to_string(),
+                    arguments: (*tc_args).clone(),
+                })
+                .await;
+
+                let tool_ctx = step_ctx.as_ref().map(TraceContext::child);
+                let tool_span = tool_ctx
+                    .as_ref()
+                    .map(|ctx| tool_span(ctx, tc_id, tc_name, tc_args));
 
                 let tool = self.tools.iter().find(|t| t.name == *tc_name);
+                let mut error_type = None;
                 match tool {
-                    Some(t) => match t.execute((*tc_args).clone()).await {
-                        Ok(result_val) => {
+                    Some(t) => match crate::telemetry::in_trace_scope(
+                        tool_ctx,
+                        t.execute((*tc_args).clone()),
+                    )
+                    .await
+                    {
+                        Ok(mut result_val) => {
+                            if t.returns_artifacts() {
+                                artifact_parts
+                                    .extend(crate::tool::split_artifacts(&mut result_val));
+                            }
                             tool_results_vec.push(ToolResultInfo {
                                 tool_call_id: tc_id.to_string(),
                                 tool_name: tc_name.to_string(),
@@ -275,6 +564,7 @@ impl Agent {
                         }
                         Err(e) => {
                             warn!(tool = tc_name, error = %e, "Tool execution failed");
+                            error_type = Some(e.kind());
                             let error_val = serde_json::Value::String(format!("Error: {e}"));
                             tool_results_vec.push(ToolResultInfo {
                                 tool_call_id: tc_id.to_string(),
@@ -287,6 +577,7 @@ impl Agent {
                     },
                     None => {
                         warn!(tool = tc_name, "Tool not found");
+                        error_type = Some("tool_not_found");
                         let error_val =
                             serde_json::Value::String(format!("Error: Tool '{tc_name}' not found"));
                         tool_results_vec.push(ToolResultInfo {
@@ -298,13 +589,31 @@ impl Agent {
                         all_messages.push(Message::tool_result(*tc_id, error_val));
                     }
                 }
+
+                if let Some(info) = tool_results_vec.last() {
+                    self.emit(|| GaussEvent::ToolCallFinish {
+                        tool_name: info.tool_name.clone(),
+                        result: info.result.clone(),
+                        duration_ms: tool_started.elapsed().as_millis() as u64,
+                        is_error: info.is_error,
+                    })
+                    .await;
+                }
+                if let (Some(span), Some(info)) = (tool_span, tool_results_vec.last()) {
+                    let error_type = info.is_error.then(|| error_type.unwrap_or("tool"));
+                    step_children.push(finish_tool_span(span, &info.result, error_type));
+                }
             }
+            all_messages.extend(crate::tool::artifacts_message(artifact_parts));
+
+            finish_step_span(step_span, step_children, &result.finish_reason, step_spans);
 
             let step_result = StepResult {
                 step_index: step,
                 message: result.message,
                 finish_reason: result.finish_reason,
                 usage: result.usage,
+                cost: step_cost,
                 tool_calls: tool_call_infos,
                 tool_results: tool_results_vec,
             };
@@ -312,13 +621,13 @@ impl Agent {
             // Check stop conditions
             let should_stop = self.should_stop(&step_result);
 
-            if let Some(ref on_step_finish) = self.on_step_finish {
-                on_step_finish(&step_result).await;
-            }
+            self.step_finished(&run_id, &step_result, step_started)
+                .await;
 
             step_results.push(step_result);
 
             if should_stop {
+                stop_reason = StopReason::StopCondition;
                 break;
             }
         }
@@ -352,6 +661,10 @@ impl Agent {
             },
             citations: all_citations,
             grounding_metadata: all_grounding,
+            cost,
+            run_id,
+            trace: trace.cloned(),
+            stop_reason,
         })
     }
 
@@ -369,20 +682,65 @@ impl Agent {
         }
         all_messages.extend(messages);
 
+        // Resolve the parent trace here: the stream is polled outside the caller's scope.
+        let trace = self
+            .telemetry
+            .as_ref()
+            .map(|_| crate::telemetry::child_of_current());
+
         let stream = async_stream::stream! {
-            for step in 0..self.max_steps {
+            let mut total_usage = Usage::default();
+            let mut cost = RunCost::default();
+            let run_id = uuid::Uuid::new_v4().to_string();
+            let started = std::time::Instant::now();
+            let run_span = trace.as_ref().map(|ctx| self.run_span(ctx));
+            let mut step_spans = Vec::new();
+            self.emit(|| GaussEvent::AgentStart {
+                agent_name: self.name.clone(),
+                session_id: run_id.clone(),
+            })
+            .await;
+
+            let mut final_text = String::new();
+            let mut steps = 0;
+            let mut stop_reason = StopReason::MaxSteps;
+            let mut failure = None;
+            // Messages before `sent` are already stored server-side under
+            // `options.previous_response_id`.
+            let mut options = self.options.clone();
+            let mut sent = 0;
+            'steps: for step in 0..self.max_steps {
+                if self.budget_exhausted(&total_usage, &cost) {
+                    info!(agent = %self.name, step, cost_usd = cost.total_cost_usd, "Budget exhausted, stopping");
+                    stop_reason = StopReason::BudgetExhausted;
+                    break;
+                }
+
                 yield Ok(AgentStreamEvent::StepStart { step });
+                let step_started = std::time::Instant::now();
 
-                let stream_result = self
-                    .provider
-                    .stream(&all_messages, &self.tools, &self.options)
-                    .await;
+                let step_ctx = trace.as_ref().map(TraceContext::child);
+                let step_span = step_ctx.as_ref().map(|ctx| {
+                    SpanBuilder::new(format!("step {step}"), SpanType::AgentStep)
+                        .context(ctx.clone())
+                        .attribute("step", step)
+                });
+                let mut step_children = Vec::new();
+                let model_ctx = step_ctx.as_ref().map(TraceContext::child);
+                let model_span = model_ctx.as_ref().map(|ctx| self.model_span(ctx));
+
+                let stream_result = crate::telemetry::in_trace_scope(
+                    model_ctx,
+                    self.provider.stream(&all_messages[sent..], &self.tools, &options),
+                )
+                .await;
 
                 let mut inner_stream = match stream_result {
                     Ok(s) => s,
                     Err(e) => {
-                        yield Err(e);
-                        return;
+                        fail_step_span(step_span, model_span, &e, &mut step_spans);
+                        failure = Some(e);
+                        break;
                     }
                 };
 
@@ -391,6 +749,11 @@ impl Agent {
                 let mut tool_call_buffers: Vec<(String, String, String)> = Vec::new(); // (id, name, args)
                 let mut step_finish_reason = FinishReason::Stop;
                 let mut step_usage = Usage::default();
+                let mut reasoning_buffer = String::new();
+                let mut reasoning_items = Vec::new();
+                let mut response_id = None;
+                let mut step_metadata = serde_json::Value::Null;
+                let mut routed_model = None;
 
                 while let Some(event) = inner_stream.next().await {
                     match event {
@@ -420,16 +783,48 @@ impl Agent {
                             step_usage = u;
                         }
                         Ok(StreamEvent::Done) => break,
-                        Ok(other) => {
-                            yield Ok(AgentStreamEvent::RawEvent { step, event: other });
+                        Ok(event) => {
+                            match &event {
+                                StreamEvent::ReasoningDelta(delta) => reasoning_buffer.push_str(delta),
+                                StreamEvent::ReasoningItem { id, encrypted_content } => {
+                                    reasoning_items.push(crate::message::Content::Reasoning {
+                                        text: std::mem::take(&mut reasoning_buffer),
+                                        id: id.clone(),
+                                        encrypted_content: encrypted_content.clone(),
+                                    });
+                                }
+                                StreamEvent::ProviderMetadata(meta) => {
+                                    response_id = meta["response_id"].as_str().map(String::from);
+                                    step_metadata = meta.clone();
+                                }
+                                StreamEvent::Routing(decision) => routed_model = Some(decision.model.clone()),
+                                _ => {}
+                            }
+                            yield Ok(AgentStreamEvent::RawEvent { step, event });
                         }
                         Err(e) => {
-                            yield Err(e);
-                            return;
+                            fail_step_span(step_span, model_span, &e, &mut step_spans);
+                            failure = Some(e);
+                            break 'steps;
                         }
                     }
                 }
 
+                accumulate_usage(&mut total_usage, &step_usage);
+                let model = routed_model.as_deref().unwrap_or(self.provider.model());
+                let step_cost = cost.record_call(model, &step_usage, &step_metadata);
+                if let Some(model_span) = model_span {
+                    step_children.push(finish_model_span(
+                        model_span,
+                        &step_usage,
+                        &step_finish_reason,
+                        &step_cost,
+                        (!text_buffer.is_empty()).then_some(text_buffer.as_str()),
+                    ));
+                }
+                steps = step + 1;
+                final_text = text_buffer.clone();
+
                 let has_tool_calls = !tool_call_buffers.is_empty()
                     && tool_call_buffers.iter().any(|(_, name, _)| !name.is_empty());
 
@@ -438,18 +833,29 @@ impl Agent {
                     finish_reason: step_finish_reason.clone(),
                     has_tool_calls,
                 });
+                self.emit(|| GaussEvent::StepFinish {
+                    agent_name: self.name.clone(),
+                    session_id: run_id.clone(),
+                    step_index: step,
+                    finish_reason: finish_reason_value(&step_finish_reason)
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                    tool_calls: tool_call_buffers.iter().filter(|(_, name, _)| !name.is_empty()).count(),
+                    input_tokens: step_usage.input_tokens,
+                    output_tokens: step_usage.output_tokens,
+                    duration_ms: step_started.elapsed().as_millis() as u64,
+                })
+                .await;
 
                 if !has_tool_calls || step_finish_reason != FinishReason::ToolCalls {
-                    yield Ok(AgentStreamEvent::Done {
-                        text: text_buffer,
-                        steps: step + 1,
-                        usage: step_usage,
-                    });
-                    return;
+                    finish_step_span(step_span, step_children, &step_finish_reason, &mut step_spans);
+                    stop_reason = StopReason::Completed;
+                    break;
                 }
 
                 // Build assistant message with tool calls and execute them
-                let mut assistant_content = Vec::new();
+                let mut assistant_content = reasoning_items;
                 if !text_buffer.is_empty() {
                     assistant_content.push(crate::message::Content::Text { text: text_buffer.clone() });
                 }
@@ -468,16 +874,44 @@ impl Agent {
                     content: assistant_content,
                     name: None,
                 });
+                if let Some(id) = response_id.filter(|_| self.chain_responses) {
+                    options.previous_response_id = Some(id);
+                    sent = all_messages.len();
+                }
 
                 // Execute tool calls
+                let mut artifact_parts = Vec::new();
                 for (tc_id, tc_name, tc_args_str) in &tool_call_buffers {
                     if tc_name.is_empty() { continue; }
                     let tc_args: serde_json::Value = serde_json::from_str(tc_args_str).unwrap_or(serde_json::json!({}));
+                    let tool_started = std::time::Instant::now();
+                    self.emit(|| GaussEvent::ToolCallStart {
+                        tool_name: tc_name.clone(),
+                        arguments: tc_args.clone(),
+                    })
+                    .await;
+                    let tool_finished = |result: &serde_json::Value, is_error: bool| GaussEvent::ToolCallFinish {
+                        tool_name: tc_name.clone(),
+                        result: result.clone(),
+                        duration_ms: tool_started.elapsed().as_millis() as u64,
+                        is_error,
+                    };
+                    let tool_ctx = step_ctx.as_ref().map(TraceContext::child);
+                    let tool_span = tool_ctx
+                        .as_ref()
+                        .map(|ctx| tool_span(ctx, tc_id, tc_name, &tc_args));
 
                     let tool = self.tools.iter().find(|t| t.name == *tc_name);
                     match tool {
-                        Some(t) => match t.execute(tc_args).await {
-                            Ok(result_val) => {
+                        Some(t) => match crate::telemetry::in_trace_scope(tool_ctx, t.execute(tc_args)).await {
+                            Ok(mut result_val) => {
+                                if t.returns_artifacts() {
+                                    artifact_parts.extend(crate::tool::split_artifacts(&mut result_val));
+                                }
+                                if let Some(span) = tool_span {
+                                    step_children.push(finish_tool_span(span, &result_val, None));
+                                }
+                                self.emit(|| tool_finished(&result_val, false)).await;
                                 yield Ok(AgentStreamEvent::ToolResult {
                                     step,
                                     tool_name: tc_name.clone(),
@@ -488,6 +922,10 @@ impl Agent {
                             }
                             Err(e) => {
                                 let error_val = serde_json::Value::String(format!("Error: {e}"));
+                                if let Some(span) = tool_span {
+                                    step_children.push(finish_tool_span(span, &error_val, Some(e.kind())));
+                                }
+                                self.emit(|| tool_finished(&error_val, true)).await;
                                 yield Ok(AgentStreamEvent::ToolResult {
                                     step,
                                     tool_name: tc_name.clone(),
@@ -499,6 +937,10 @@ impl Agent {
                         },
                         None => {
                             let error_val = serde_json::Value::String(format!("Error: Tool '{tc_name}' not found"));
+                            if let Some(span) = tool_span {
+                                step_children.push(finish_tool_span(span, &error_val, Some("tool_not_found")));
+                            }
+                            self.emit(|| tool_finished(&error_val, true)).await;
                             yield Ok(AgentStreamEvent::ToolResult {
                                 step,
                                 tool_name: tc_name.clone(),
@@ -509,7 +951,42 @@ impl Agent {
                         }
                     }
                 }
+                all_messages.extend(crate::tool::artifacts_message(artifact_parts));
+                finish_step_span(step_span, step_children, &step_finish_reason, &mut step_spans);
+            }
+
+            let run_span = run_span.map(|span| step_spans.into_iter().fold(span, SpanBuilder::child));
+            if let Some(e) = failure {
+                self.emit(|| GaussEvent::Error {
+                    source: self.name.clone(),
+                    message: e.to_string(),
+                })
+                .await;
+                if let (Some(telemetry), Some(span)) = (&self.telemetry, run_span) {
+                    telemetry.record_run(span.finish_with_error(e.to_string()));
+                }
+                yield Err(e);
+                return;
+            }
+
+            self.emit(|| GaussEvent::AgentFinish {
+                agent_name: self.name.clone(),
+                session_id: run_id.clone(),
+                result_text: final_text.clone(),
+                steps,
+                duration_ms: started.elapsed().as_millis() as u64,
+            })
+            .await;
+            if let (Some(telemetry), Some(span)) = (&self.telemetry, run_span) {
+                telemetry.record_run(finish_run_span(span, &run_id, steps, &total_usage, &cost));
             }
+            yield Ok(AgentStreamEvent::Done {
+                text: final_text,
+                steps,
+                usage: total_usage,
+                cost,
+                stop_reason,
+            });
         };
 
         Ok(Box::pin(stream))
@@ -531,11 +1008,141 @@ impl Agent {
                 finish_reason: crate::provider::FinishReason::Stop,
                 has_tool_calls: false,
             }),
+            Ok(AgentStreamEvent::Done {
+                text: output.text,
+                steps: output.steps,
+                usage: output.usage,
+                cost: output.cost,
+                stop_reason: output.stop_reason,
+            }),
         ];
         Ok(Box::pin(futures::stream::iter(events)))
     }
 }
 
+fn finish_reason_value(reason: &FinishReason) -> serde_json::Value {
+    serde_json::to_value(reason).unwrap_or(serde_json::Value::Null)
+}
+
+/// Close a successful run span with the run's totals.
+fn finish_run_span(
+    span: SpanBuilder,
+    run_id: &str,
+    steps: usize,
+    usage: &Usage,
+    cost: &RunCost,
+) -> SpanRecord {
+    span.attribute("gauss.run_id", run_id)
+        .attribute("steps", steps)
+        .attribute("input_tokens", usage.input_tokens)
+        .attribute("output_tokens", usage.output_tokens)
+        .attribute("cost_usd", cost.total_cost_usd)
+        .finish()
+}
+
+/// Close a provider call span with the model that served it, its usage,
+/// finish reason and cost.
+fn finish_model_span(
+    span: SpanBuilder,
+    usage: &Usage,
+    finish_reason: &FinishReason,
+    cost: &CostEstimate,
+    response: Option<&str>,
+) -> SpanRecord {
+    let mut span = span
+        .attribute("response_model", cost.model.clone())
+        .attribute("input_tokens", usage.input_tokens)
+        .attribute("output_tokens", usage.output_tokens)
+        .attribute("finish_reason", finish_reason_value(finish_reason))
+        .attribute("cost_usd", cost.total_cost_usd);
+    if let Some(rt) = usage.reasoning_tokens {
+        span = span.attribute("reasoning_tokens", rt);
+    }
+    if let Some(ct) = usage.cache_read_tokens {
+        span = span.attribute("cache_read_tokens", ct);
+    }
+    if let Some(text) = response {
+        span = span.attribute("response", text);
+    }
+    span.finish()
+}
+
+/// The (unfinished) span of one tool execution.
+fn tool_span(
+    context: &TraceContext,
+    id: &str,
+    name: &str,
+    args: &serde_json::Value,
+) -> SpanBuilder {
+    SpanBuilder::new(format!("execute_tool {name}"), SpanType::ToolCall)
+        .context(context.clone())
+        .attribute("tool_name", name)
+        .attribute("tool_call_id", id)
+        .attribute("args", args.clone())
+}
+
+/// Close a tool span with its result; `error_type` marks a failed call.
+fn finish_tool_span(
+    span: SpanBuilder,
+    result: &serde_json::Value,
+    error_type: Option<&str>,
+) -> SpanRecord {
+    let span = span.attribute("result", result.clone());
+    match error_type {
+        Some(error_type) => span
+            .attribute("error.type", error_type)
+            .finish_with_error(result.as_str().unwrap_or("tool error")),
+        None => span.finish(),
+    }
+}
+
+/// Close a step span (if tracing) whose provider call failed.
+fn fail_step_span(
+    step_span: Option<SpanBuilder>,
+    model_span: Option<SpanBuilder>,
+    error: &GaussError,
+    out: &mut Vec<SpanRecord>,
+) {
+    if let (Some(step_span), Some(model_span)) = (step_span, model_span) {
+        let message = error.to_string();
+        let model_span = model_span
+            .attribute("error.type", error.kind())
+            .finish_with_error(message.clone());
+        out.push(step_span.child(model_span).finish_with_error(message));
+    }
+}
+
+/// Close a step span (if tracing) with its model and tool call children.
+fn finish_step_span(
+    span: Option<SpanBuilder>,
+    children: Vec<SpanRecord>,
+    finish_reason: &FinishReason,
+    out: &mut Vec<SpanRecord>,
+) {
+    if let Some(span) = span {
+        let span = children
+            .into_iter()
+            .fold(span, SpanBuilder::child)
+            .attribute("finish_reason", finish_reason_value(finish_reason));
+        out.push(span.finish());
+    }
+}
+
+/// Add one provider call's token counts into a running total.
+fn accumulate_usage(total: &mut Usage, usage: &Usage) {
+    total.input_tokens += usage.input_tokens;
+    total.output_tokens += usage.output_tokens;
+    if let Some(rt) = usage.reasoning_tokens {
+        *total.reasoning_tokens.get_or_insert(0) += rt;
+    }
+    if let Some(ct) = usage.cache_read_tokens {
+        *total.cache_read_tokens.get_or_insert(0) += ct;
+    }
+    if let Some(ct) = usage.cache_creation_tokens {
+        *total.cache_creation_tokens.get_or_insert(0) += ct;
+    }
+}
+
 /// Events emitted during agent streaming.
 #[derive(Debug, Clone)]
 pub enum AgentStreamEvent {
@@ -565,10 +1172,13 @@ pub enum AgentStreamEvent {
         step: usize,
         event: StreamEvent,
     },
+    /// The run ended; `usage` and `cost` cover every step.
     Done {
         text: String,
         steps: usize,
         usage: Usage,
+        cost: RunCost,
+        stop_reason: StopReason,
     },
 }
 
@@ -583,6 +1193,9 @@ pub struct AgentBuilder {
     stop_conditions: Vec<StopCondition>,
     on_step_finish: Option<OnStepFinishFn>,
     on_tool_call: Option<OnToolCallFn>,
+    telemetry: Option<crate::Shared<TelemetryCollector>>,
+    plugins: Option<crate::Shared<PluginRegistry>>,
+    chain_responses: bool,
 }
 
 impl AgentBuilder {
@@ -621,6 +1234,20 @@ impl AgentBuilder {
         self
     }
 
+    /// Record a span tree (run → step → model/tool call) for every `run()`
+    /// into `collector`, and update its metrics.
+    pub fn telemetry(mut self, collector: crate::Shared<TelemetryCollector>) -> Self {
+        self.telemetry = Some(collector);
+        self
+    }
+
+    /// Publish lifecycle events (run, step and tool call start/finish) to
+    /// the plugin registry's event bus.
+    pub fn plugins(mut self, registry: crate::Shared<PluginRegistry>) -> Self {
+        self.plugins = Some(registry);
+        self
+    }
+
     pub fn temperature(mut self, temp: f64) -> Self {
         self.options.temperature = Some(temp);
         self
@@ -705,6 +1332,24 @@ impl AgentBuilder {
         self
     }
 
+    /// Continue from a stored OpenAI Responses response. Later steps resend
+    /// the whole conversation unless [`chain_responses`](Self::chain_responses)
+    /// is on.
+    pub fn previous_response_id(mut self, id: impl Into<String>) -> Self {
+        self.options.previous_response_id = Some(id.into());
+        self
+    }
+
+    /// Chain each step from the previous step's stored OpenAI Responses
+    /// response, sending only the new messages (default off). Only enable it
+    /// when every step is served by a Responses provider: behind a router,
+    /// fallback, hedge or ensemble, another provider would get just the tail
+    /// of the conversation.
+    pub fn chain_responses(mut self, enabled: bool) -> Self {
+        self.chain_responses = enabled;
+        self
+    }
+
     /// Enable code execution with the given config — adds runtime tools to the agent.
     #[cfg(not(target_arch = "wasm32"))]
     pub fn code_execution(mut self, config: crate::code_execution::CodeExecutionConfig) -> Self {
@@ -735,6 +1380,9 @@ impl AgentBuilder {
             stop_conditions: self.stop_conditions,
             on_step_finish: self.on_step_finish,
             on_tool_call: self.on_tool_call,
+            telemetry: self.telemetry,
+            plugins: self.plugins,
+            chain_responses: self.chain_responses,
         }
     }
 }
@@ -0,0 +1,447 @@
+//! Model catalog — context windows, output limits, pricing, capabilities
+//! and tokenizer family per model.
+//!
+//! The catalog ships with a built-in data set (`models.json`) and can be
+//! overridden or extended at runtime from JSON (or TOML with the
+//! `config-toml` feature). Cost estimation, context tracking and provider
+//! capability checks all read from the process-wide [`global`] catalog.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::{OnceLock, RwLock, RwLockReadGuard};
+
+use crate::cost::{self, CostEstimate, DEFAULT_PRICING, Pricing};
+use crate::error::{self, GaussError};
+use crate::message::Usage;
+use crate::provider::ProviderCapabilities;
+
+const BUILTIN_MODELS: &str = include_str!("models.json");
+
+/// Context window assumed for models missing from the catalog.
+pub const DEFAULT_CONTEXT_WINDOW: usize = 128_000;
+
+/// Tokenizer family used to count tokens for a model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenizerFamily {
+    /// OpenAI `cl100k_base` (GPT-4, GPT-3.5).
+    Cl100k,
+    /// OpenAI `o200k_base` (GPT-4o, GPT-4.1, GPT-5, o-series).
+    O200k,
+    Claude,
+    Gemini,
+    Llama,
+    /// No known encoding; counts fall back to the generic estimator.
+    #[default]
+    Approximate,
+}
+
+/// Model-level capability flags. Provider-level features (streaming,
+/// grounding, image generation, …) stay with the provider.
+///
+/// Unset flags (`None`, or omitted in JSON) leave the provider's value alone.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModelCapabilities {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_use: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vision: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audio: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub structured_output: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extended_thinking: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reasoning_effort: Option<bool>,
+}
+
+impl ModelCapabilities {
+    /// Overlay these model flags onto a provider's capabilities. Flags set here
+    /// win over the provider's; unset flags keep the provider's value.
+    pub fn apply(&self, mut caps: ProviderCapabilities) -> ProviderCapabilities {
+        let overlay = |flag: Option<bool>, current: &mut bool| {
+            if let Some(value) = flag {
+                *current = value;
+            }
+        };
+        overlay(self.tool_use, &mut caps.tool_use);
+        overlay(self.vision, &mut caps.vision);
+        overlay(self.audio, &mut caps.audio);
+        overlay(self.structured_output, &mut caps.structured_output);
+        overlay(self.extended_thinking, &mut caps.extended_thinking);
+        overlay(self.reasoning_effort, &mut caps.reasoning_effort);
+        caps
+    }
+}
+
+/// Catalog entry for a model (or model family, matched by prefix).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelInfo {
+    /// Model id, also used as a prefix for dated/suffixed variants.
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    pub context_window: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_output_tokens: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pricing: Option<Pricing>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capabilities: Option<ModelCapabilities>,
+    #[serde(default)]
+    pub tokenizer: TokenizerFamily,
+}
+
+impl ModelInfo {
+    pub fn new(id: impl Into<String>, context_window: usize) -> Self {
+        Self {
+            id: id.into(),
+            aliases: Vec::new(),
+            provider: None,
+            context_window,
+            max_output_tokens: None,
+            pricing: None,
+            capabilities: None,
+            tokenizer: TokenizerFamily::default(),
+        }
+    }
+
+    fn names(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.id.as_str()).chain(self.aliases.iter().map(String::as_str))
+    }
+}
+
+/// A set of model entries with prefix lookup.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModelCatalog {
+    models: Vec<ModelInfo>,
+}
+
+impl ModelCatalog {
+    /// An empty catalog.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The built-in data set.
+    pub fn builtin() -> Self {
+        serde_json::from_str(BUILTIN_MODELS).expect("built-in model catalog is valid JSON")
+    }
+
+    pub fn models(&self) -> &[ModelInfo] {
+        &self.models
+    }
+
+    /// Add an entry, replacing any entry with the same id.
+    pub fn insert(&mut self, info: ModelInfo) {
+        match self.models.iter_mut().find(|m| m.id == info.id) {
+            Some(existing) => *existing = info,
+            None => self.models.push(info),
+        }
+    }
+
+    pub fn remove(&mut self, id: &str) -> Option<ModelInfo> {
+        let index = self.models.iter().position(|m| m.id == id)?;
+        Some(self.models.remove(index))
+    }
+
+    /// Merge entries from a JSON document shaped like `{"models": [...]}`.
+    ///
+    /// Entries whose id already exists are patched field by field (a `null`
+    /// removes an optional field); new ids are added.
+    pub fn merge_json(&mut self, json: &str) -> error::Result<()> {
+        let value: Value = serde_json::from_str(json).map_err(|e| GaussError::Config {
+            message: format!("Invalid JSON model catalog: {e}"),
+        })?;
+        self.merge_value(value)
+    }
+
+    /// Merge entries from a TOML document with a `[[models]]` array.
+    #[cfg(feature = "config-toml")]
+    pub fn merge_toml(&mut self, toml: &str) -> error::Result<()> {
+        let value: Value = toml::from_str(toml).map_err(|e| GaussError::Config {
+            message: format!("Invalid TOML model catalog: {e}"),
+        })?;
+        self.merge_value(value)
+    }
+
+    /// Detect format from file extension and merge.
+    pub fn merge_file(&mut self, path: &str) -> error::Result<()> {
+        let content = std::fs::read_to_string(path).map_err(|e| GaussError::Config {
+            message: format!("Failed to read model catalog '{path}': {e}"),
+        })?;
+
+        #[cfg(feature = "config-toml")]
+        if path.ends_with(".toml") {
+            return self.merge_toml(&content);
+        }
+        self.merge_json(&content)
+    }
+
+    fn merge_value(&mut self, value: Value) -> error::Result<()> {
+        let entries = match value {
+            Value::Object(mut map) => map.remove("models").unwrap_or(Value::Null),
+            other => other,
+        };
+        let Value::Array(entries) = entries else {
+            return Err(GaussError::Config {
+                message: "Model catalog must contain a `models` array".to_string(),
+            });
+        };
+
+        for patch in entries {
+            let id = patch
+                .get("id")
+                .and_then(Value::as_str)
+                .ok_or_else(|| GaussError::Config {
+                    message: "Model catalog entry is missing `id`".to_string(),
+                })?
+                .to_string();
+
+            let merged = match self.models.iter().find(|m| m.id == id) {
+                Some(existing) => {
+                    let mut base = serde_json::to_value(existing).unwrap_or(Value::Null);
+                    merge_patch(&mut base, patch);
+                    base
+                }
+                None => patch,
+            };
+            let info: ModelInfo =
+                serde_json::from_value(merged).map_err(|e| GaussError::Config {
+                    message: format!("Invalid model catalog entry '{id}': {e}"),
+                })?;
+            self.insert(info);
+        }
+        Ok(())
+    }
+
+    /// Find the entry for a model: an exact id/alias match first, then the
+    /// longest id/alias that prefixes the model name. Vendor prefixes such as
+    /// `openai/` or `accounts/.../models/` are ignored.
+    pub fn lookup(&self, model: &str) -> Option<&ModelInfo> {
+        let normalized = cost::normalize_model(model);
+        let last_segment = normalized.rsplit('/').next().unwrap_or(&normalized);
+        let candidates = [normalized.as_str(), last_segment];
+
+        for candidate in candidates {
+            if let Some(info) = self
+                .models
+                .iter()
+                .find(|m| m.names().any(|n| n.eq_ignore_ascii_case(candidate)))
+            {
+                return Some(info);
+            }
+        }
+
+        candidates.iter().find_map(|candidate| {
+            self.models
+                .iter()
+                .filter_map(|m| {
+                    m.names()
+                        .filter(|n| candidate.starts_with(&n.to_lowercase()))
+                        .map(str::len)
+                        .max()
+                        .map(|len| (len, m))
+                })
+                .max_by_key(|(len, _)| *len)
+                .map(|(_, m)| m)
+        })
+    }
+
+    pub fn context_window(&self, model: &str) -> usize {
+        self.lookup(model)
+            .map(|m| m.context_window)
+            .unwrap_or(DEFAULT_CONTEXT_WINDOW)
+    }
+
+    pub fn max_output_tokens(&self, model: &str) -> Option<usize> {
+        self.lookup(model).and_then(|m| m.max_output_tokens)
+    }
+
+    /// Pricing for a model, or conservative default pricing when unknown.
+    pub fn pricing(&self, model: &str) -> Pricing {
+        self.lookup(model)
+            .and_then(|m| m.pricing)
+            .unwrap_or(DEFAULT_PRICING)
+    }
+
+    pub fn tokenizer(&self, model: &str) -> TokenizerFamily {
+        self.lookup(model).map(|m| m.tokenizer).unwrap_or_default()
+    }
+
+    /// Overlay the model's catalog capabilities (if any) onto a provider's.
+    pub fn capabilities(&self, model: &str, base: ProviderCapabilities) -> ProviderCapabilities {
+        match self.lookup(model).and_then(|m| m.capabilities) {
+            Some(caps) => caps.apply(base),
+            None => base,
+        }
+    }
+
+    pub fn estimate_cost(&self, model: &str, usage: &Usage) -> CostEstimate {
+        cost::estimate_cost_with_pricing(model, &self.pricing(model), usage)
+    }
+}
+
+/// RFC 7386-style merge: objects merge recursively, `null` deletes, anything
+/// else replaces.
+fn merge_patch(target: &mut Value, patch: Value) {
+    match (target, patch) {
+        (Value::Object(target), Value::Object(patch)) => {
+            for (key, value) in patch {
+                if value.is_null() {
+                    target.remove(&key);
+                } else {
+                    merge_patch(target.entry(key).or_insert(Value::Null), value);
+                }
+            }
+        }
+        (target, patch) => *target = patch,
+    }
+}
+
+/// The process-wide catalog, initialised from the built-in data set.
+pub fn global() -> &'static RwLock<ModelCatalog> {
+    static GLOBAL: OnceLock<RwLock<ModelCatalog>> = OnceLock::new();
+    GLOBAL.get_or_init(|| RwLock::new(ModelCatalog::builtin()))
+}
+
+/// Read the global catalog, recovering from a poisoned lock.
+pub(crate) fn read() -> RwLockReadGuard<'static, ModelCatalog> {
+    global().read().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_catalog_parses() {
+        let catalog = ModelCatalog::builtin();
+        assert!(catalog.models().len() > 10);
+        assert!(catalog.models().iter().all(|m| m.context_window > 0));
+    }
+
+    #[test]
+    fn lookup_prefers_longest_prefix() {
+        let catalog = ModelCatalog::builtin();
+        assert_eq!(
+            catalog.lookup("gpt-4o-mini-2024-07-18").unwrap().id,
+            "gpt-4o-mini"
+        );
+        assert_eq!(catalog.lookup("gpt-4o-2024-08-06").unwrap().id, "gpt-4o");
+        assert_eq!(catalog.lookup("gpt-4-0613").unwrap().id, "gpt-4");
+        assert_eq!(catalog.lookup("openai/gpt-5.2").unwrap().id, "gpt-5");
+        assert_eq!(
+            catalog
+                .lookup("meta-llama/Llama-3.3-70B-Instruct")
+                .unwrap()
+                .id,
+            "llama-3.3"
+        );
+        assert!(catalog.lookup("totally-unknown").is_none());
+        assert_eq!(
+            catalog.context_window("totally-unknown"),
+            DEFAULT_CONTEXT_WINDOW
+        );
+    }
+
+    #[test]
+    fn merge_json_patches_and_extends() {
+        let mut catalog = ModelCatalog::builtin();
+        catalog
+            .merge_json(
+                r#"{"models": [
+                    {"id": "gpt-4o", "pricing": {"input_per_million": 2.5}},
+                    {"id": "acme-1", "aliases": ["acme"], "context_window": 32000,
+                     "pricing": {"input_per_million": 1.0, "output_per_million": 2.0,
+                                 "reasoning_per_million": 2.0}}
+                ]}"#,
+            )
+            .unwrap();
+
+        let gpt = catalog.lookup("gpt-4o").unwrap();
+        assert_eq!(gpt.pricing.unwrap().input_per_million, 2.5);
+        assert_eq!(gpt.pricing.unwrap().output_per_million, 15.0);
+        assert_eq!(gpt.context_window, 128_000);
+
+        assert_eq!(catalog.context_window("acme"), 32_000);
+        assert_eq!(catalog.pricing("acme-1-preview").output_per_million, 2.0);
+    }
+
+    #[test]
+    fn merge_json_rejects_bad_entries() {
+        let mut catalog = ModelCatalog::new();
+        assert!(
+            catalog
+                .merge_json(r#"{"models": [{"context_window": 1}]}"#)
+                .is_err()
+        );
+        assert!(catalog.merge_json(r#"{"models": [{"id": "x"}]}"#).is_err());
+        assert!(catalog.merge_json(r#"{"other": []}"#).is_err());
+    }
+
+    #[test]
+    fn long_context_pricing_applies_above_threshold() {
+        let catalog = ModelCatalog::builtin();
+        let short = Usage {
+            input_tokens: 100_000,
+            ..Usage::default()
+        };
+        let long = Usage {
+            input_tokens: 300_000,
+            ..Usage::default()
+        };
+        let short_cost = catalog.estimate_cost("claude-sonnet-4-5", &short);
+        let long_cost = catalog.estimate_cost("claude-sonnet-4-5", &long);
+        assert!((short_cost.input_cost_usd - 0.3).abs() < 1e-9);
+        assert!((long_cost.input_cost_usd - 1.8).abs() < 1e-9);
+    }
+
+    #[test]
+    fn capabilities_overlay_model_flags() {
+        let catalog = ModelCatalog::builtin();
+        let base = ProviderCapabilities {
+            streaming: true,
+            extended_thinking: true,
+            citations: true,
+            ..Default::default()
+        };
+        let caps = catalog.capabilities("claude-3-haiku-20240307", base.clone());
+        assert!(caps.streaming && caps.citations && caps.vision);
+        assert!(!caps.extended_thinking);
+        assert!(
+            catalog
+                .capabilities("claude-opus-4-1", base.clone())
+                .extended_thinking
+        );
+
+        // A partial entry only overrides the flags it sets.
+        let mut partial = ModelCatalog::new();
+        partial
+            .merge_json(r#"{"models": [{"id": "custom", "context_window": 1000, "capabilities": {"vision": true}}]}"#)
+            .unwrap();
+        let caps = partial.capabilities("custom", base);
+        assert!(caps.vision && caps.extended_thinking && caps.citations);
+    }
+
+    #[cfg(feature = "config-toml")]
+    #[test]
+    fn merge_toml_extends() {
+        let mut catalog = ModelCatalog::new();
+        catalog
+            .merge_toml(
+                r#"
+                [[models]]
+                id = "local-model"
+                context_window = 8192
+                tokenizer = "llama"
+                "#,
+            )
+            .unwrap();
+        assert_eq!(catalog.tokenizer("local-model-q4"), TokenizerFamily::Llama);
+    }
+}
@@ -4,6 +4,14 @@
 //! and Bash. Each runtime runs code in a subprocess with configurable timeout and
 //! captures stdout, stderr, and exit code.
 //!
+//! On Unix every subprocess is started in its own process group. The whole group is
+//! killed when the execution times out, when the calling future is dropped, and when
+//! the call returns, so background grandchildren cannot outlive the tool call.
+//! Captured output is capped per stream (see [`RuntimeConfig::max_stdout_bytes`]).
+//!
+//! With a [`WorkspaceConfig`] set, code runs in a scratch directory with staged input
+//! files, and generated files come back as [`ExecutionResult::artifacts`].
+//!
 //! # Quick Start
 //!
 //! ```no_run
@@ -26,8 +34,14 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 
+use crate::code_session::{DEFAULT_SESSION_ID, KernelSpec, SessionConfig, SessionManager};
+use crate::code_wasm::WasmRuntimeConfig;
+use crate::code_workspace::{Artifact, InputFile, Workspace, WorkspaceConfig};
 use crate::error::{GaussError, Result};
 
+/// Default cap on captured stdout/stderr bytes (1 MiB per stream).
+pub const DEFAULT_MAX_OUTPUT_BYTES: usize = 1024 * 1024;
+
 /// Result of executing code in a runtime.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExecutionResult {
@@ -36,14 +50,42 @@ pub struct ExecutionResult {
     pub exit_code: i32,
     pub timed_out: bool,
     pub runtime: String,
+    /// Whether stdout exceeded `max_stdout_bytes` and was cut.
+    #[serde(default)]
+    pub stdout_truncated: bool,
+    /// Whether stderr exceeded `max_stderr_bytes` and was cut.
+    #[serde(default)]
+    pub stderr_truncated: bool,
+    /// Files created or modified in the scratch workspace (if one was configured).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub artifacts: Vec<Artifact>,
 }
 
 impl ExecutionResult {
     pub fn success(&self) -> bool {
         self.exit_code == 0 && !self.timed_out
     }
+
+    /// Whether any captured stream was truncated.
+    pub fn truncated(&self) -> bool {
+        self.stdout_truncated || self.stderr_truncated
+    }
+}
+
+/// Incremental output emitted while code is running.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum ExecutionEvent {
+    /// A chunk of stdout.
+    Stdout(String),
+    /// A chunk of stderr.
+    Stderr(String),
 }
 
+/// Sender half used to stream [`ExecutionEvent`]s out of a running execution.
+#[cfg(not(target_arch = "wasm32"))]
+pub type ExecutionEventSender = tokio::sync::mpsc::Sender<ExecutionEvent>;
+
 /// Configuration for code execution.
 #[derive(Debug, Clone)]
 pub struct RuntimeConfig {
@@ -55,6 +97,13 @@ pub struct RuntimeConfig {
     pub env: Vec<(String, String)>,
     /// Sandbox configuration.
     pub sandbox: Option<SandboxConfig>,
+    /// Maximum stdout bytes kept in the result (0 = unlimited).
+    pub max_stdout_bytes: usize,
+    /// Maximum stderr bytes kept in the result (0 = unlimited).
+    pub max_stderr_bytes: usize,
+    /// Run each execution in a scratch workspace and collect generated files.
+    /// Takes precedence over `working_dir`.
+    pub workspace: Option<WorkspaceConfig>,
 }
 
 impl Default for RuntimeConfig {
@@ -64,6 +113,9 @@ impl Default for RuntimeConfig {
             working_dir: None,
             env: Vec::new(),
             sandbox: None,
+            max_stdout_bytes: DEFAULT_MAX_OUTPUT_BYTES,
+            max_stderr_bytes: DEFAULT_MAX_OUTPUT_BYTES,
+            workspace: None,
         }
     }
 }
@@ -78,8 +130,37 @@ pub trait CodeRuntime: Send + Sync {
     /// Execute code and return the result.
     async fn execute(&self, code: &str, config: &RuntimeConfig) -> Result<ExecutionResult>;
 
+    /// Execute code, sending stdout/stderr chunks to `events` as they are produced.
+    ///
+    /// The returned result still holds the (capped) captured output. The default
+    /// implementation runs [`execute`](Self::execute) and emits the output once it finishes.
+    async fn execute_streaming(
+        &self,
+        code: &str,
+        config: &RuntimeConfig,
+        events: ExecutionEventSender,
+    ) -> Result<ExecutionResult> {
+        let result = self.execute(code, config).await?;
+        if !result.stdout.is_empty() {
+            let _ = events
+                .send(ExecutionEvent::Stdout(result.stdout.clone()))
+                .await;
+        }
+        if !result.stderr.is_empty() {
+            let _ = events
+                .send(ExecutionEvent::Stderr(result.stderr.clone()))
+                .await;
+        }
+        Ok(result)
+    }
+
     /// Check if this runtime is available on the system.
     async fn is_available(&self) -> bool;
+
+    /// How to start a persistent kernel for this runtime, if sessions are supported.
+    fn kernel_spec(&self, _config: &SessionConfig) -> Option<KernelSpec> {
+        None
+    }
 }
 
 // ─── Python Runtime ────────────────────────────────────────────────
@@ -118,12 +199,32 @@ impl CodeRuntime for PythonRuntime {
     }
 
     async fn execute(&self, code: &str, config: &RuntimeConfig) -> Result<ExecutionResult> {
-        run_subprocess(&self.interpreter, &["-c", code], config, "python").await
+        run_subprocess(&self.interpreter, &["-c", code], config, "python", None).await
+    }
+
+    async fn execute_streaming(
+        &self,
+        code: &str,
+        config: &RuntimeConfig,
+        events: ExecutionEventSender,
+    ) -> Result<ExecutionResult> {
+        run_subprocess(
+            &self.interpreter,
+            &["-c", code],
+            config,
+            "python",
+            Some(events),
+        )
+        .await
     }
 
     async fn is_available(&self) -> bool {
         check_command(&self.interpreter, &["--version"]).await
     }
+
+    fn kernel_spec(&self, config: &SessionConfig) -> Option<KernelSpec> {
+        Some(KernelSpec::python(&self.interpreter, config))
+    }
 }
 
 // ─── JavaScript Runtime ────────────────────────────────────────────
@@ -161,12 +262,32 @@ impl CodeRuntime for JavaScriptRuntime {
     }
 
     async fn execute(&self, code: &str, config: &RuntimeConfig) -> Result<ExecutionResult> {
-        run_subprocess(&self.interpreter, &["-e", code], config, "javascript").await
+        run_subprocess(&self.interpreter, &["-e", code], config, "javascript", None).await
+    }
+
+    async fn execute_streaming(
+        &self,
+        code: &str,
+        config: &RuntimeConfig,
+        events: ExecutionEventSender,
+    ) -> Result<ExecutionResult> {
+        run_subprocess(
+            &self.interpreter,
+            &["-e", code],
+            config,
+            "javascript",
+            Some(events),
+        )
+        .await
     }
 
     async fn is_available(&self) -> bool {
         check_command(&self.interpreter, &["--version"]).await
     }
+
+    fn kernel_spec(&self, config: &SessionConfig) -> Option<KernelSpec> {
+        Some(KernelSpec::javascript(&self.interpreter, config))
+    }
 }
 
 // ─── Bash Runtime ──────────────────────────────────────────────────
@@ -204,12 +325,25 @@ impl CodeRuntime for BashRuntime {
     }
 
     async fn execute(&self, code: &str, config: &RuntimeConfig) -> Result<ExecutionResult> {
-        run_subprocess(&self.shell, &["-c", code], config, "bash").await
+        run_subprocess(&self.shell, &["-c", code], config, "bash", None).await
+    }
+
+    async fn execute_streaming(
+        &self,
+        code: &str,
+        config: &RuntimeConfig,
+        events: ExecutionEventSender,
+    ) -> Result<ExecutionResult> {
+        run_subprocess(&self.shell, &["-c", code], config, "bash", Some(events)).await
     }
 
     async fn is_available(&self) -> bool {
         check_command(&self.shell, &["--version"]).await
     }
+
+    fn kernel_spec(&self, config: &SessionConfig) -> Option<KernelSpec> {
+        Some(KernelSpec::bash(&self.shell, config))
+    }
 }
 
 // ─── Subprocess Execution ──────────────────────────────────────────
@@ -220,6 +354,7 @@ async fn run_subprocess(
     args: &[&str],
     config: &RuntimeConfig,
     runtime_name: &str,
+    events: Option<ExecutionEventSender>,
 ) -> Result<ExecutionResult> {
     use tokio::process::Command;
 
@@ -227,7 +362,11 @@ async fn run_subprocess(
     cmd.args(args)
         .stdout(std::process::Stdio::piped())
         .stderr(std::process::Stdio::piped())
-        .stdin(std::process::Stdio::null());
+        .stdin(std::process::Stdio::null())
+        .kill_on_drop(true);
+
+    #[cfg(unix)]
+    cmd.process_group(0);
 
     if let Some(ref dir) = config.working_dir {
         cmd.current_dir(dir);
@@ -241,32 +380,329 @@ async fn run_subprocess(
         sandbox.apply_to_command(&mut cmd);
     }
 
-    let child = cmd.spawn().map_err(|e| {
-        GaussError::tool(runtime_name, format!("Failed to spawn process: {e}"))
-    })?;
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| GaussError::tool(runtime_name, format!("Failed to spawn process: {e}")))?;
+    // Dropped on every exit path, including cancellation of this future.
+    let mut group = ProcessGroupGuard::new(child.id());
+
+    let (Some(stdout_pipe), Some(stderr_pipe)) = (child.stdout.take(), child.stderr.take()) else {
+        return Err(GaussError::tool(
+            runtime_name,
+            "Failed to capture process output",
+        ));
+    };
+
+    let mut stdout = CappedOutput::new(config.max_stdout_bytes);
+    let mut stderr = CappedOutput::new(config.max_stderr_bytes);
+
+    let run = async {
+        tokio::join!(
+            stdout.read_from(stdout_pipe, events.as_ref(), ExecutionEvent::Stdout),
+            stderr.read_from(stderr_pipe, events.as_ref(), ExecutionEvent::Stderr),
+            child.wait(),
+        )
+    };
 
-    match tokio::time::timeout(config.timeout, child.wait_with_output()).await {
-        Ok(Ok(output)) => Ok(ExecutionResult {
-            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
-            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
-            exit_code: output.status.code().unwrap_or(-1),
+    let outcome = tokio::time::timeout(config.timeout, run)
+        .await
+        .map(|(out, err, status)| {
+            if status.is_ok() {
+                group.leader_reaped();
+            }
+            out.and(err).and(status)
+        });
+    match outcome {
+        Ok(Ok(status)) => Ok(ExecutionResult {
+            stdout_truncated: stdout.truncated(),
+            stderr_truncated: stderr.truncated(),
+            stdout: stdout.into_string(),
+            stderr: stderr.into_string(),
+            exit_code: status.code().unwrap_or(-1),
             timed_out: false,
             runtime: runtime_name.to_string(),
+            artifacts: Vec::new(),
         }),
         Ok(Err(e)) => Err(GaussError::tool(
             runtime_name,
             format!("execution error: {e}"),
         )),
-        Err(_) => Ok(ExecutionResult {
-            stdout: String::new(),
-            stderr: format!("Execution timed out after {:?}", config.timeout),
-            exit_code: -1,
-            timed_out: true,
-            runtime: runtime_name.to_string(),
-        }),
+        Err(_) => {
+            group.kill();
+            let _ = child.start_kill();
+            let _ = child.wait().await;
+
+            let stderr_truncated = stderr.truncated();
+            let mut stderr = stderr.into_string();
+            if !stderr.is_empty() && !stderr.ends_with('\n') {
+                stderr.push('\n');
+            }
+            stderr.push_str(&format!("Execution timed out after {:?}", config.timeout));
+            Ok(ExecutionResult {
+                stdout_truncated: stdout.truncated(),
+                stdout: stdout.into_string(),
+                stderr,
+                stderr_truncated,
+                exit_code: -1,
+                timed_out: true,
+                runtime: runtime_name.to_string(),
+                artifacts: Vec::new(),
+            })
+        }
+    }
+}
+
+/// Captured output of one stream, keeping at most `limit` bytes (0 = unlimited).
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) struct CappedOutput {
+    buf: Vec<u8>,
+    limit: usize,
+    dropped: usize,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl CappedOutput {
+    pub(crate) fn new(limit: usize) -> Self {
+        Self {
+            buf: Vec::new(),
+            limit,
+            dropped: 0,
+        }
+    }
+
+    pub(crate) fn push(&mut self, chunk: &[u8]) {
+        let room = if self.limit == 0 {
+            chunk.len()
+        } else {
+            self.limit.saturating_sub(self.buf.len()).min(chunk.len())
+        };
+        self.buf.extend_from_slice(&chunk[..room]);
+        self.dropped += chunk.len() - room;
+    }
+
+    pub(crate) fn truncated(&self) -> bool {
+        self.dropped > 0
+    }
+
+    /// Drain `reader` to EOF. Bytes past the limit are discarded (not kept in memory)
+    /// so the child never blocks on a full pipe.
+    async fn read_from<R>(
+        &mut self,
+        mut reader: R,
+        events: Option<&ExecutionEventSender>,
+        wrap: fn(String) -> ExecutionEvent,
+    ) -> std::io::Result<()>
+    where
+        R: tokio::io::AsyncRead + Unpin,
+    {
+        use tokio::io::AsyncReadExt;
+
+        let mut chunk = [0u8; 8192];
+        // Bytes of a multibyte character split across reads, held back so
+        // streamed events don't decode each half as U+FFFD.
+        let mut pending = Vec::new();
+        loop {
+            let n = reader.read(&mut chunk).await?;
+            if n == 0 {
+                if let Some(tx) = events
+                    && !pending.is_empty()
+                {
+                    let _ = tx
+                        .send(wrap(String::from_utf8_lossy(&pending).into_owned()))
+                        .await;
+                }
+                return Ok(());
+            }
+            self.push(&chunk[..n]);
+            if let Some(tx) = events {
+                pending.extend_from_slice(&chunk[..n]);
+                let complete = utf8_complete_len(&pending);
+                if complete > 0 {
+                    let text = String::from_utf8_lossy(&pending[..complete]).into_owned();
+                    pending.drain(..complete);
+                    let _ = tx.send(wrap(text)).await;
+                }
+            }
+        }
+    }
+
+    pub(crate) fn into_string(self) -> String {
+        let mut out = String::from_utf8_lossy(&self.buf).into_owned();
+        if self.dropped > 0 {
+            if !out.ends_with('\n') {
+                out.push('\n');
+            }
+            out.push_str(&format!(
+                "[output truncated: {} bytes omitted]",
+                self.dropped
+            ));
+        }
+        out
+    }
+}
+
+/// Length of the prefix of `bytes` that doesn't end in the middle of a UTF-8
+/// sequence. Invalid bytes count as complete; they decode to U+FFFD.
+#[cfg(not(target_arch = "wasm32"))]
+fn utf8_complete_len(bytes: &[u8]) -> usize {
+    let len = bytes.len();
+    // A sequence is at most 4 bytes, so only the last 3 can start an
+    // unfinished one.
+    for start in (len.saturating_sub(3)..len).rev() {
+        let width = match bytes[start] {
+            b if b & 0b1100_0000 == 0b1000_0000 => continue,
+            b if b & 0b1110_0000 == 0b1100_0000 => 2,
+            b if b & 0b1111_0000 == 0b1110_0000 => 3,
+            b if b & 0b1111_1000 == 0b1111_0000 => 4,
+            _ => return len,
+        };
+        return if start + width > len { start } else { len };
+    }
+    len
+}
+
+/// Kills the subprocess' whole process group when dropped (Unix only).
+///
+/// Grandchildren spawned by the code (e.g. `sleep 100 &`) share the group, so they
+/// are terminated together with the interpreter instead of being left running.
+///
+/// The group id is only safe to signal while the group leader is unreaped or
+/// other members keep the group alive; once it is empty the id can be reused by
+/// an unrelated group. The guard therefore disarms after [`kill`](Self::kill) and
+/// after [`leader_reaped`](Self::leader_reaped).
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) struct ProcessGroupGuard {
+    #[cfg_attr(not(unix), allow(dead_code))]
+    pgid: Option<u32>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl ProcessGroupGuard {
+    pub(crate) fn new(pid: Option<u32>) -> Self {
+        Self { pgid: pid }
+    }
+
+    /// Kill the group and disarm. Call before the leader is reaped.
+    pub(crate) fn kill(&mut self) {
+        #[cfg(unix)]
+        if let Some(pgid) = self.pgid.take() {
+            // SAFETY: killpg has no memory-safety preconditions; a stale group id
+            // simply yields ESRCH, which is ignored.
+            unsafe {
+                libc::killpg(pgid as libc::pid_t, libc::SIGKILL);
+            }
+        }
+    }
+
+    /// The leader has been waited on: kill any descendants still holding the
+    /// group, then disarm so the id is never signalled after the group is gone.
+    pub(crate) fn leader_reaped(&mut self) {
+        #[cfg(unix)]
+        if let Some(pgid) = self.pgid {
+            // SAFETY: signal 0 only checks that the group still has members.
+            let alive = unsafe { libc::killpg(pgid as libc::pid_t, 0) } == 0;
+            if !alive {
+                self.pgid = None;
+            }
+        }
+        self.kill();
     }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+impl Drop for ProcessGroupGuard {
+    fn drop(&mut self) {
+        self.kill();
+    }
+}
+
+/// Execute code, running it in a scratch workspace when `config.workspace` is set.
+///
+/// `files` are staged into the workspace first; files the code creates or modifies
+/// are returned as [`ExecutionResult::artifacts`]. Without a workspace, `files` must
+/// be empty.
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn execute_with_files(
+    runtime: &dyn CodeRuntime,
+    code: &str,
+    config: &RuntimeConfig,
+    files: &[InputFile],
+) -> Result<ExecutionResult> {
+    match PreparedWorkspace::new(runtime.name(), config, files)? {
+        Some(ws) => {
+            let result = runtime.execute(code, &ws.config).await?;
+            ws.finish(result)
+        }
+        None => runtime.execute(code, config).await,
+    }
+}
+
+/// Streaming counterpart of [`execute_with_files`].
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn execute_streaming_with_files(
+    runtime: &dyn CodeRuntime,
+    code: &str,
+    config: &RuntimeConfig,
+    files: &[InputFile],
+    events: ExecutionEventSender,
+) -> Result<ExecutionResult> {
+    match PreparedWorkspace::new(runtime.name(), config, files)? {
+        Some(ws) => {
+            let result = runtime.execute_streaming(code, &ws.config, events).await?;
+            ws.finish(result)
+        }
+        None => runtime.execute_streaming(code, config, events).await,
+    }
+}
+
+/// A staged workspace plus the runtime config pointing into it.
+#[cfg(not(target_arch = "wasm32"))]
+struct PreparedWorkspace {
+    workspace: Workspace,
+    before: crate::code_workspace::WorkspaceSnapshot,
+    config: RuntimeConfig,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl PreparedWorkspace {
+    fn new(
+        runtime_name: &str,
+        config: &RuntimeConfig,
+        files: &[InputFile],
+    ) -> Result<Option<Self>> {
+        let Some(ref ws_config) = config.workspace else {
+            if !files.is_empty() {
+                return Err(GaussError::tool(
+                    runtime_name,
+                    "Input files require a workspace (RuntimeConfig::workspace)",
+                ));
+            }
+            return Ok(None);
+        };
+
+        let workspace = Workspace::create(ws_config)?;
+        workspace.stage(files)?;
+        let before = workspace.snapshot();
+        let mut config = config.clone();
+        config.working_dir = Some(workspace.path().to_string_lossy().into_owned());
+        Ok(Some(Self {
+            workspace,
+            before,
+            config,
+        }))
+    }
+
+    fn finish(self, mut result: ExecutionResult) -> Result<ExecutionResult> {
+        result.artifacts = self.workspace.collect(&self.before);
+        Ok(result)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn snapshot_files(staged: &std::sync::Mutex<Vec<InputFile>>) -> Vec<InputFile> {
+    staged.lock().map(|f| f.clone()).unwrap_or_default()
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 async fn check_command(program: &str, args: &[&str]) -> bool {
     tokio::process::Command::new(program)
@@ -289,6 +725,15 @@ async fn check_command(program: &str, args: &[&str]) -> bool {
 pub fn code_execution_tool(
     runtime: std::sync::Arc<dyn CodeRuntime>,
     config: RuntimeConfig,
+) -> crate::tool::Tool {
+    staged_code_execution_tool(runtime, config, Default::default())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn staged_code_execution_tool(
+    runtime: std::sync::Arc<dyn CodeRuntime>,
+    config: RuntimeConfig,
+    staged_files: Arc<std::sync::Mutex<Vec<InputFile>>>,
 ) -> crate::tool::Tool {
     let rt_name = runtime.name().to_string();
     let description = format!(
@@ -296,37 +741,34 @@ pub fn code_execution_tool(
         rt_name
     );
 
-    crate::tool::Tool::builder(
-        &format!("execute_{}", rt_name),
-        &description,
-    )
-    .parameters_json(serde_json::json!({
-        "type": "object",
-        "properties": {
-            "code": {
-                "type": "string",
-                "description": format!("{} code to execute", rt_name)
-            }
-        },
-        "required": ["code"]
-    }))
-    .execute(move |args| {
-        let runtime = runtime.clone();
-        let config = config.clone();
-        Box::pin(async move {
-            let code = args
-                .get("code")
-                .and_then(|v| v.as_str())
-                .ok_or_else(|| {
-                    GaussError::tool("code_execution", "Missing 'code' argument")
-                })?;
-            let result = runtime.execute(code, &config).await?;
-            serde_json::to_value(&result).map_err(|e| {
-                GaussError::tool("code_execution", format!("Serialize error: {e}"))
+    crate::tool::Tool::builder(&format!("execute_{}", rt_name), &description)
+        .parameters_json(serde_json::json!({
+            "type": "object",
+            "properties": {
+                "code": {
+                    "type": "string",
+                    "description": format!("{} code to execute", rt_name)
+                }
+            },
+            "required": ["code"]
+        }))
+        .with_artifacts()
+        .execute(move |args| {
+            let runtime = runtime.clone();
+            let config = config.clone();
+            let files = snapshot_files(&staged_files);
+            Box::pin(async move {
+                let code = args
+                    .get("code")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| GaussError::tool("code_execution", "Missing 'code' argument"))?;
+                let result = execute_with_files(runtime.as_ref(), code, &config, &files).await?;
+                serde_json::to_value(&result).map_err(|e| {
+                    GaussError::tool("code_execution", format!("Serialize error: {e}"))
+                })
             })
         })
-    })
-    .build()
+        .build()
 }
 
 // ─── Sandbox Configuration ─────────────────────────────────────────
@@ -423,6 +865,17 @@ pub struct CodeExecutionConfig {
     pub sandbox: SandboxConfig,
     /// Custom interpreter paths (e.g., "python" → "/usr/local/bin/python3.12").
     pub interpreters: HashMap<String, String>,
+    /// Maximum stdout bytes kept per execution (0 = unlimited).
+    pub max_stdout_bytes: usize,
+    /// Maximum stderr bytes kept per execution (0 = unlimited).
+    pub max_stderr_bytes: usize,
+    /// Persistent kernel sessions. When set, `tools()` and `unified_tool()` keep
+    /// interpreter state between calls instead of starting a fresh process each time.
+    pub session: Option<SessionConfig>,
+    /// Scratch workspace for input files and generated artifacts.
+    pub workspace: Option<WorkspaceConfig>,
+    /// Hermetic WASM runtimes (requires the `wasm-runtime` feature; ignored otherwise).
+    pub wasm: Vec<WasmRuntimeConfig>,
 }
 
 impl Default for CodeExecutionConfig {
@@ -436,6 +889,11 @@ impl Default for CodeExecutionConfig {
             env: Vec::new(),
             sandbox: SandboxConfig::default(),
             interpreters: HashMap::new(),
+            max_stdout_bytes: DEFAULT_MAX_OUTPUT_BYTES,
+            max_stderr_bytes: DEFAULT_MAX_OUTPUT_BYTES,
+            session: None,
+            workspace: None,
+            wasm: Vec::new(),
         }
     }
 }
@@ -468,6 +926,9 @@ impl CodeExecutionConfig {
             working_dir: self.working_dir.clone(),
             env: self.env.clone(),
             sandbox: Some(self.sandbox.clone()),
+            max_stdout_bytes: self.max_stdout_bytes,
+            max_stderr_bytes: self.max_stderr_bytes,
+            workspace: self.workspace.clone(),
         }
     }
 }
@@ -483,6 +944,11 @@ pub struct CodeExecutionConfigBuilder {
     env: Vec<(String, String)>,
     sandbox: Option<SandboxConfig>,
     interpreters: HashMap<String, String>,
+    max_stdout_bytes: Option<usize>,
+    max_stderr_bytes: Option<usize>,
+    session: Option<SessionConfig>,
+    workspace: Option<WorkspaceConfig>,
+    wasm: Vec<WasmRuntimeConfig>,
 }
 
 impl CodeExecutionConfigBuilder {
@@ -526,6 +992,41 @@ impl CodeExecutionConfigBuilder {
         self
     }
 
+    /// Cap both stdout and stderr at `bytes` (0 = unlimited).
+    pub fn max_output_bytes(mut self, bytes: usize) -> Self {
+        self.max_stdout_bytes = Some(bytes);
+        self.max_stderr_bytes = Some(bytes);
+        self
+    }
+
+    pub fn max_stdout_bytes(mut self, bytes: usize) -> Self {
+        self.max_stdout_bytes = Some(bytes);
+        self
+    }
+
+    pub fn max_stderr_bytes(mut self, bytes: usize) -> Self {
+        self.max_stderr_bytes = Some(bytes);
+        self
+    }
+
+    /// Keep a persistent interpreter per session instead of a fresh process per call.
+    pub fn session(mut self, config: SessionConfig) -> Self {
+        self.session = Some(config);
+        self
+    }
+
+    /// Run code in a scratch workspace and return generated files as artifacts.
+    pub fn workspace(mut self, config: WorkspaceConfig) -> Self {
+        self.workspace = Some(config);
+        self
+    }
+
+    /// Add a hermetic WASM runtime (requires the `wasm-runtime` feature).
+    pub fn wasm(mut self, config: WasmRuntimeConfig) -> Self {
+        self.wasm.push(config);
+        self
+    }
+
     pub fn build(self) -> CodeExecutionConfig {
         CodeExecutionConfig {
             python: self.python.unwrap_or(true),
@@ -536,6 +1037,11 @@ impl CodeExecutionConfigBuilder {
             env: self.env,
             sandbox: self.sandbox.unwrap_or_default(),
             interpreters: self.interpreters,
+            max_stdout_bytes: self.max_stdout_bytes.unwrap_or(DEFAULT_MAX_OUTPUT_BYTES),
+            max_stderr_bytes: self.max_stderr_bytes.unwrap_or(DEFAULT_MAX_OUTPUT_BYTES),
+            session: self.session,
+            workspace: self.workspace,
+            wasm: self.wasm,
         }
     }
 }
@@ -549,6 +1055,8 @@ impl CodeExecutionConfigBuilder {
 pub struct CodeExecutionOrchestrator {
     runtimes: Vec<(String, Arc<dyn CodeRuntime>)>,
     config: CodeExecutionConfig,
+    sessions: Arc<SessionManager>,
+    staged_files: Arc<std::sync::Mutex<Vec<InputFile>>>,
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -593,7 +1101,47 @@ impl CodeExecutionOrchestrator {
             ));
         }
 
-        Self { runtimes, config }
+        for wasm in &config.wasm {
+            #[cfg(feature = "wasm-runtime")]
+            match crate::code_wasm::WasmRuntime::new(wasm.clone()) {
+                Ok(rt) => runtimes.push((wasm.name.clone(), Arc::new(rt))),
+                Err(e) => tracing::warn!(runtime = %wasm.name, "skipping WASM runtime: {e}"),
+            }
+            #[cfg(not(feature = "wasm-runtime"))]
+            tracing::warn!(
+                runtime = %wasm.name,
+                "skipping WASM runtime: gauss-core built without the `wasm-runtime` feature"
+            );
+        }
+
+        let sessions = Arc::new(SessionManager::new(
+            config.session.clone().unwrap_or_default(),
+        ));
+        Self {
+            runtimes,
+            config,
+            sessions,
+            staged_files: Arc::new(std::sync::Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Stage an input file into the workspace of every subsequent execution,
+    /// including those made through this orchestrator's tools.
+    ///
+    /// Requires [`CodeExecutionConfig::workspace`]. In a persistent session a staged
+    /// file is written once and not overwritten by later cells.
+    pub fn stage_file(&self, file: InputFile) {
+        if let Ok(mut staged) = self.staged_files.lock() {
+            staged.retain(|f| f.path != file.path);
+            staged.push(file);
+        }
+    }
+
+    /// Remove all staged input files.
+    pub fn clear_staged_files(&self) {
+        if let Ok(mut staged) = self.staged_files.lock() {
+            staged.clear();
+        }
     }
 
     /// Add a custom runtime.
@@ -606,23 +1154,39 @@ impl CodeExecutionOrchestrator {
     }
 
     /// Produce Tool instances for all enabled runtimes.
+    ///
+    /// With [`CodeExecutionConfig::session`] set, the tools run in the
+    /// [`DEFAULT_SESSION_ID`] session (see [`session_tools`](Self::session_tools)).
     pub fn tools(&self) -> Vec<crate::tool::Tool> {
+        if self.config.session.is_some() {
+            return self.session_tools(DEFAULT_SESSION_ID);
+        }
         let runtime_config = self.config.to_runtime_config();
         self.runtimes
             .iter()
-            .map(|(_, rt)| code_execution_tool(rt.clone(), runtime_config.clone()))
+            .map(|(_, rt)| {
+                staged_code_execution_tool(
+                    rt.clone(),
+                    runtime_config.clone(),
+                    self.staged_files.clone(),
+                )
+            })
             .collect()
     }
 
     /// Produce a single "execute_code" meta-tool that dispatches to the correct runtime
     /// based on a `language` argument.
     pub fn unified_tool(&self) -> crate::tool::Tool {
+        if self.config.session.is_some() {
+            return self.unified_session_tool(DEFAULT_SESSION_ID);
+        }
         let runtimes: HashMap<String, Arc<dyn CodeRuntime>> = self
             .runtimes
             .iter()
             .cloned()
             .collect();
         let runtime_config = self.config.to_runtime_config();
+        let staged_files = self.staged_files.clone();
 
         let languages: Vec<String> = runtimes.keys().cloned().collect();
         let lang_list = languages.join(", ");
@@ -648,9 +1212,11 @@ impl CodeExecutionOrchestrator {
             },
             "required": ["language", "code"]
         }))
+        .with_artifacts()
         .execute(move |args| {
             let runtimes = runtimes.clone();
             let config = runtime_config.clone();
+            let files = snapshot_files(&staged_files);
             Box::pin(async move {
                 let language = args
                     .get("language")
@@ -673,7 +1239,7 @@ impl CodeExecutionOrchestrator {
                     )
                 })?;
 
-                let result = runtime.execute(code, &config).await?;
+                let result = execute_with_files(runtime.as_ref(), code, &config, &files).await?;
                 serde_json::to_value(&result).map_err(|e| {
                     GaussError::tool("execute_code", format!("Serialize error: {e}"))
                 })
@@ -682,6 +1248,207 @@ impl CodeExecutionOrchestrator {
         .build()
     }
 
+    /// Produce per-runtime tools bound to a persistent session.
+    ///
+    /// Each tool accepts `{ "code": "...", "reset": false }`; `reset` discards the
+    /// session state before running the code.
+    pub fn session_tools(&self, session_id: impl Into<String>) -> Vec<crate::tool::Tool> {
+        let session_id = session_id.into();
+        self.runtimes
+            .iter()
+            .filter(|(_, rt)| rt.kernel_spec(self.sessions.config()).is_some())
+            .map(|(name, rt)| {
+                let tool_name = format!("execute_{name}");
+                let description = format!(
+                    "Execute {name} code in a persistent session: variables, imports and loaded data \
+                     are kept between calls. Pass {{\"code\": \"<your code>\"}} and optionally \
+                     {{\"reset\": true}} to start from a clean state. Returns stdout, stderr, exit_code."
+                );
+                let sessions = self.sessions.clone();
+                let session_id = session_id.clone();
+                let name = name.clone();
+                let runtime = rt.clone();
+                let config = self.config.to_runtime_config();
+                let staged_files = self.staged_files.clone();
+                crate::tool::Tool::builder(tool_name, description)
+                    .parameters_json(serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "code": {
+                                "type": "string",
+                                "description": format!("{name} code to execute")
+                            },
+                            "reset": {
+                                "type": "boolean",
+                                "description": "Discard session state before running the code"
+                            }
+                        },
+                        "required": ["code"]
+                    }))
+                    .with_artifacts()
+                    .execute(move |args| {
+                        let sessions = sessions.clone();
+                        let session_id = session_id.clone();
+                        let name = name.clone();
+                        let runtime = runtime.clone();
+                        let config = config.clone();
+                        let files = snapshot_files(&staged_files);
+                        Box::pin(async move {
+                            let code = args
+                                .get("code")
+                                .and_then(|v| v.as_str())
+                                .ok_or_else(|| {
+                                    GaussError::tool("code_execution", "Missing 'code' argument")
+                                })?;
+                            let reset = args.get("reset").and_then(|v| v.as_bool()).unwrap_or(false);
+                            let result = run_in_session(
+                                &sessions,
+                                &session_id,
+                                &name,
+                                runtime.as_ref(),
+                                code,
+                                &config,
+                                &files,
+                                reset,
+                            )
+                            .await?;
+                            serde_json::to_value(&result).map_err(|e| {
+                                GaussError::tool("code_execution", format!("Serialize error: {e}"))
+                            })
+                        })
+                    })
+                    .build()
+            })
+            .collect()
+    }
+
+    /// Session-bound variant of [`unified_tool`](Self::unified_tool).
+    pub fn unified_session_tool(&self, session_id: impl Into<String>) -> crate::tool::Tool {
+        let session_id = session_id.into();
+        let runtimes: HashMap<String, Arc<dyn CodeRuntime>> = self
+            .runtimes
+            .iter()
+            .filter(|(_, rt)| rt.kernel_spec(self.sessions.config()).is_some())
+            .cloned()
+            .collect();
+        let sessions = self.sessions.clone();
+        let runtime_config = self.config.to_runtime_config();
+        let staged_files = self.staged_files.clone();
+
+        let languages: Vec<String> = runtimes.keys().cloned().collect();
+        let lang_list = languages.join(", ");
+
+        crate::tool::Tool::builder(
+            "execute_code",
+            format!(
+                "Execute code in one of: {lang_list}, in a persistent session where state is kept \
+                 between calls. Pass {{\"language\": \"...\", \"code\": \"...\"}} and optionally \
+                 {{\"reset\": true}} to start from a clean state."
+            ),
+        )
+        .parameters_json(serde_json::json!({
+            "type": "object",
+            "properties": {
+                "language": {
+                    "type": "string",
+                    "enum": languages,
+                    "description": "Programming language / runtime to use"
+                },
+                "code": {
+                    "type": "string",
+                    "description": "Source code to execute"
+                },
+                "reset": {
+                    "type": "boolean",
+                    "description": "Discard session state before running the code"
+                }
+            },
+            "required": ["language", "code"]
+        }))
+        .with_artifacts()
+        .execute(move |args| {
+            let runtimes = runtimes.clone();
+            let sessions = sessions.clone();
+            let session_id = session_id.clone();
+            let config = runtime_config.clone();
+            let files = snapshot_files(&staged_files);
+            Box::pin(async move {
+                let language = args
+                    .get("language")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| {
+                        GaussError::tool("execute_code", "Missing 'language' argument")
+                    })?;
+                let code = args
+                    .get("code")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| GaussError::tool("execute_code", "Missing 'code' argument"))?;
+                let reset = args.get("reset").and_then(|v| v.as_bool()).unwrap_or(false);
+
+                let runtime = runtimes.get(language).ok_or_else(|| {
+                    GaussError::tool(
+                        "execute_code",
+                        format!(
+                            "Unknown language: {language}. Available: {:?}",
+                            runtimes.keys().collect::<Vec<_>>()
+                        ),
+                    )
+                })?;
+
+                let result = run_in_session(
+                    &sessions,
+                    &session_id,
+                    language,
+                    runtime.as_ref(),
+                    code,
+                    &config,
+                    &files,
+                    reset,
+                )
+                .await?;
+                serde_json::to_value(&result)
+                    .map_err(|e| GaussError::tool("execute_code", format!("Serialize error: {e}")))
+            })
+        })
+        .build()
+    }
+
+    /// Execute code in a persistent session, starting its kernel on first use.
+    pub async fn execute_in_session(
+        &self,
+        session_id: &str,
+        language: &str,
+        code: &str,
+    ) -> Result<ExecutionResult> {
+        let runtime = self.runtime(language)?;
+        run_in_session(
+            &self.sessions,
+            session_id,
+            language,
+            runtime.as_ref(),
+            code,
+            &self.config.to_runtime_config(),
+            &snapshot_files(&self.staged_files),
+            false,
+        )
+        .await
+    }
+
+    /// Discard the state of a session — all runtimes, or only `language`.
+    pub async fn reset_session(&self, session_id: &str, language: Option<&str>) {
+        self.sessions.reset(session_id, language).await;
+    }
+
+    /// Shut down every kernel belonging to a session.
+    pub async fn close_session(&self, session_id: &str) {
+        self.sessions.close(session_id).await;
+    }
+
+    /// The session registry shared by this orchestrator's session tools.
+    pub fn sessions(&self) -> &Arc<SessionManager> {
+        &self.sessions
+    }
+
     /// Check which runtimes are actually available on this system.
     pub async fn available_runtimes(&self) -> Vec<String> {
         let mut available = Vec::new();
@@ -699,8 +1466,49 @@ impl CodeExecutionOrchestrator {
         language: &str,
         code: &str,
     ) -> Result<ExecutionResult> {
-        let rt = self
-            .runtimes
+        self.execute_with_files(language, code, &[]).await
+    }
+
+    /// Execute code with extra input files staged next to any staged via
+    /// [`stage_file`](Self::stage_file). Requires [`CodeExecutionConfig::workspace`].
+    pub async fn execute_with_files(
+        &self,
+        language: &str,
+        code: &str,
+        files: &[InputFile],
+    ) -> Result<ExecutionResult> {
+        let runtime = self.runtime(language)?;
+        let mut all_files = snapshot_files(&self.staged_files);
+        all_files.extend_from_slice(files);
+        execute_with_files(
+            runtime.as_ref(),
+            code,
+            &self.config.to_runtime_config(),
+            &all_files,
+        )
+        .await
+    }
+
+    /// Execute code in a specific runtime, streaming output chunks to `events`.
+    pub async fn execute_streaming(
+        &self,
+        language: &str,
+        code: &str,
+        events: ExecutionEventSender,
+    ) -> Result<ExecutionResult> {
+        let runtime = self.runtime(language)?;
+        execute_streaming_with_files(
+            runtime.as_ref(),
+            code,
+            &self.config.to_runtime_config(),
+            &snapshot_files(&self.staged_files),
+            events,
+        )
+        .await
+    }
+
+    fn runtime(&self, language: &str) -> Result<Arc<dyn CodeRuntime>> {
+        self.runtimes
             .iter()
             .find(|(name, _)| name == language)
             .map(|(_, rt)| rt.clone())
@@ -709,10 +1517,29 @@ impl CodeExecutionOrchestrator {
                     "orchestrator",
                     format!("No runtime for language: {language}"),
                 )
-            })?;
+            })
+    }
+}
 
-        rt.execute(code, &self.config.to_runtime_config()).await
+#[cfg(not(target_arch = "wasm32"))]
+#[allow(clippy::too_many_arguments)]
+async fn run_in_session(
+    sessions: &SessionManager,
+    session_id: &str,
+    name: &str,
+    runtime: &dyn CodeRuntime,
+    code: &str,
+    config: &RuntimeConfig,
+    files: &[InputFile],
+    reset: bool,
+) -> Result<ExecutionResult> {
+    let session = sessions
+        .session(session_id, name, runtime)
+        .ok_or_else(|| GaussError::tool(name, "Runtime does not support persistent sessions"))?;
+    if reset {
+        session.reset().await;
     }
+    session.execute_with_files(code, config, files).await
 }
 
 #[cfg(test)]
@@ -770,6 +1597,126 @@ mod tests {
         assert!(!result.success());
     }
 
+    #[tokio::test]
+    async fn test_timeout_kills_process_group() {
+        let rt = BashRuntime::new();
+        if !rt.is_available().await {
+            return;
+        }
+        let marker = std::env::temp_dir().join(format!("gauss-pg-{}", uuid::Uuid::new_v4()));
+        let config = RuntimeConfig {
+            timeout: Duration::from_millis(200),
+            ..Default::default()
+        };
+        let code = format!(
+            "(sleep 1; touch {}) & echo started; sleep 10",
+            marker.display()
+        );
+        let result = rt.execute(&code, &config).await.unwrap();
+        assert!(result.timed_out);
+        assert_eq!(result.stdout.trim(), "started");
+
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        assert!(!marker.exists(), "grandchild survived the timeout");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_group_guard_disarms_once_the_group_is_gone() {
+        let mut child = tokio::process::Command::new("true")
+            .process_group(0)
+            .spawn()
+            .unwrap();
+        let mut group = ProcessGroupGuard::new(child.id());
+        child.wait().await.unwrap();
+        group.leader_reaped();
+        assert!(group.pgid.is_none());
+    }
+
+    #[test]
+    fn test_utf8_complete_len() {
+        let euro = "€".as_bytes(); // 3 bytes
+        assert_eq!(utf8_complete_len(b"abc"), 3);
+        assert_eq!(utf8_complete_len(&[b'a', euro[0]]), 1);
+        assert_eq!(utf8_complete_len(&[b'a', euro[0], euro[1]]), 1);
+        assert_eq!(utf8_complete_len(&[b'a', euro[0], euro[1], euro[2]]), 4);
+        // Stray continuation and invalid bytes are passed through.
+        assert_eq!(utf8_complete_len(&[b'a', 0x80]), 2);
+        assert_eq!(utf8_complete_len(&[b'a', 0xff]), 2);
+    }
+
+    #[tokio::test]
+    async fn test_streamed_multibyte_split_across_reads() {
+        // 8192 isn't a multiple of 3, so the first read ends mid-character.
+        let text = "€".repeat(4000);
+        let (tx, mut rx) = tokio::sync::mpsc::channel(16);
+        let mut out = CappedOutput::new(0);
+        out.read_from(text.as_bytes(), Some(&tx), ExecutionEvent::Stdout)
+            .await
+            .unwrap();
+        drop(tx);
+
+        let mut streamed = String::new();
+        while let Some(ExecutionEvent::Stdout(chunk)) = rx.recv().await {
+            streamed.push_str(&chunk);
+        }
+        assert_eq!(streamed, text);
+        assert_eq!(out.into_string(), text);
+    }
+
+    #[tokio::test]
+    async fn test_output_cap() {
+        let rt = BashRuntime::new();
+        if !rt.is_available().await {
+            return;
+        }
+        let config = RuntimeConfig {
+            max_stdout_bytes: 10,
+            ..Default::default()
+        };
+        let result = rt
+            .execute("printf 'abcdefghijklmnopqrstuvwxyz'; echo err >&2", &config)
+            .await
+            .unwrap();
+        assert!(result.success());
+        assert!(result.stdout_truncated);
+        assert!(!result.stderr_truncated);
+        assert!(result.stdout.starts_with("abcdefghij\n"));
+        assert!(
+            result
+                .stdout
+                .contains("[output truncated: 16 bytes omitted]")
+        );
+        assert_eq!(result.stderr.trim(), "err");
+    }
+
+    #[tokio::test]
+    async fn test_execute_streaming() {
+        let rt = BashRuntime::new();
+        if !rt.is_available().await {
+            return;
+        }
+        let (tx, mut rx) = tokio::sync::mpsc::channel(16);
+        let config = RuntimeConfig::default();
+        let result = rt
+            .execute_streaming("echo one; sleep 0.1; echo two >&2", &config, tx)
+            .await
+            .unwrap();
+        assert!(result.success());
+
+        let mut events = Vec::new();
+        while let Some(event) = rx.recv().await {
+            events.push(event);
+        }
+        assert_eq!(
+            events,
+            vec![
+                ExecutionEvent::Stdout("one\n".to_string()),
+                ExecutionEvent::Stderr("two\n".to_string()),
+            ]
+        );
+    }
+
     #[tokio::test]
     async fn test_exit_code() {
         let rt = BashRuntime::new();
@@ -828,6 +1775,14 @@ mod tests {
         assert!(cfg.sandbox.no_network);
         assert!(cfg.sandbox.read_only_fs);
         assert_eq!(cfg.interpreters.get("python").unwrap(), "/usr/local/bin/python3.12");
+        assert_eq!(cfg.max_stdout_bytes, DEFAULT_MAX_OUTPUT_BYTES);
+
+        let capped = CodeExecutionConfig::builder()
+            .max_output_bytes(4096)
+            .build();
+        let rc = capped.to_runtime_config();
+        assert_eq!(rc.max_stdout_bytes, 4096);
+        assert_eq!(rc.max_stderr_bytes, 4096);
     }
 
     #[test]
@@ -891,6 +1846,100 @@ mod tests {
         assert!(err.is_err());
     }
 
+    #[tokio::test]
+    async fn test_orchestrator_session_tools() {
+        let config = CodeExecutionConfig::builder()
+            .javascript(false)
+            .bash(false)
+            .session(SessionConfig::default())
+            .build();
+        let orch = CodeExecutionOrchestrator::new(config);
+        if orch.available_runtimes().await.is_empty() {
+            return;
+        }
+        let tools = orch.tools();
+        assert_eq!(tools.len(), 1);
+        assert!(
+            tools[0]
+                .parameters
+                .properties
+                .as_ref()
+                .unwrap()
+                .contains_key("reset")
+        );
+
+        tools[0]
+            .execute(serde_json::json!({"code": "counter = 41"}))
+            .await
+            .unwrap();
+        let out = tools[0]
+            .execute(serde_json::json!({"code": "counter + 1"}))
+            .await
+            .unwrap();
+        assert_eq!(out["stdout"].as_str().unwrap().trim(), "42");
+
+        let reset = tools[0]
+            .execute(serde_json::json!({"code": "print('counter' in globals())", "reset": true}))
+            .await
+            .unwrap();
+        assert_eq!(reset["stdout"].as_str().unwrap().trim(), "False");
+
+        let other = orch
+            .execute_in_session("other", "python", "print('counter' in globals())")
+            .await
+            .unwrap();
+        assert_eq!(other.stdout.trim(), "False");
+        orch.close_session(DEFAULT_SESSION_ID).await;
+        assert_eq!(orch.sessions().session_ids(), vec!["other"]);
+    }
+
+    #[tokio::test]
+    async fn test_orchestrator_workspace_artifacts() {
+        let config = CodeExecutionConfig::builder()
+            .javascript(false)
+            .python(false)
+            .workspace(WorkspaceConfig::default())
+            .build();
+        let orch = CodeExecutionOrchestrator::new(config);
+        if orch.available_runtimes().await.is_empty() {
+            return;
+        }
+        orch.stage_file(InputFile::new("in.csv", "a,b\n1,2\n"));
+
+        let result = orch
+            .execute_with_files(
+                "bash",
+                "wc -l < in.csv > count.txt; printf '\\x89PNG\\r\\n\\x1a\\n' > chart.png",
+                &[InputFile::new("extra/notes.md", "# hi")],
+            )
+            .await
+            .unwrap();
+        assert!(result.success(), "{result:?}");
+        let summary: Vec<(&str, &str)> = result
+            .artifacts
+            .iter()
+            .map(|a| (a.path.as_str(), a.media_type.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![("chart.png", "image/png"), ("count.txt", "text/plain")]
+        );
+
+        let tool = &orch.tools()[0];
+        let out = tool
+            .execute(serde_json::json!({"code": "cat in.csv > copy.csv"}))
+            .await
+            .unwrap();
+        assert_eq!(out["artifacts"][0]["path"], "copy.csv");
+        assert_eq!(out["artifacts"][0]["media_type"], "text/csv");
+
+        let no_ws = CodeExecutionOrchestrator::new(CodeExecutionConfig::all());
+        let err = no_ws
+            .execute_with_files("bash", "true", &[InputFile::new("a.txt", "x")])
+            .await;
+        assert!(err.is_err());
+    }
+
     #[tokio::test]
     async fn test_config_python_only() {
         let config = CodeExecutionConfig::python_only();
@@ -0,0 +1,1086 @@
+//! Stateful code execution — persistent REPL kernels.
+//!
+//! A [`CodeSession`] keeps one long-lived interpreter ("kernel") alive between
+//! executions so variables, imports and loaded data survive across tool calls.
+//! Kernels are started lazily on the first cell, shut down after
+//! [`SessionConfig::idle_timeout`] of inactivity, and restarted from scratch on
+//! [`CodeSession::reset`] or when a cell exceeds its time limit.
+//!
+//! With `RuntimeConfig::workspace` set, each kernel runs in its own scratch workspace
+//! that lives as long as the kernel; files changed by a cell are returned as that
+//! cell's artifacts.
+//!
+//! # Wire protocol
+//!
+//! The host talks to a small driver script running inside the interpreter over
+//! stdin/stdout, one frame per line:
+//!
+//! ```text
+//! host   → kernel:  exec <max_stdout> <max_stderr> <base64 code>
+//! kernel → host:    <sentinel> <exit_code> <stdout_dropped> <stderr_dropped> <base64 stdout> <base64 stderr>
+//! ```
+//!
+//! The sentinel is a random token handed to the kernel via `GAUSS_KERNEL_SENTINEL`,
+//! so output the code writes straight to the kernel's stdout is never mistaken for a
+//! response frame; such lines are attached to the current cell's stdout instead.
+//!
+//! ```no_run
+//! use gauss_core::code_execution::*;
+//! use gauss_core::code_session::SessionConfig;
+//!
+//! # async fn example() -> gauss_core::error::Result<()> {
+//! let orch = CodeExecutionOrchestrator::new(
+//!     CodeExecutionConfig::builder().session(SessionConfig::default()).build(),
+//! );
+//! orch.execute_in_session("analysis", "python", "x = 40").await?;
+//! let result = orch.execute_in_session("analysis", "python", "x + 2").await?;
+//! assert_eq!(result.stdout.trim(), "42");
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+
+use crate::code_execution::{CodeRuntime, ExecutionResult, ProcessGroupGuard, RuntimeConfig};
+use crate::code_workspace::{InputFile, Workspace};
+use crate::error::{GaussError, Result};
+
+/// Session id used by orchestrator tools that are not bound to an explicit session.
+pub const DEFAULT_SESSION_ID: &str = "default";
+
+/// Kernel stderr kept for diagnostics when a kernel dies unexpectedly.
+const KERNEL_STDERR_LIMIT: usize = 64 * 1024;
+
+// ─── Configuration ─────────────────────────────────────────────────
+
+/// Configuration for persistent kernel sessions.
+#[derive(Debug, Clone)]
+pub struct SessionConfig {
+    /// Kernels idle for longer than this are shut down and their state discarded.
+    pub idle_timeout: Duration,
+    /// Wall-clock limit per cell. `None` uses `RuntimeConfig::timeout`.
+    /// A cell that exceeds it kills the kernel, so the session state is lost.
+    pub cell_timeout: Option<Duration>,
+    /// Memory limit for the kernel process in bytes (0 = unlimited).
+    pub max_memory_bytes: u64,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            idle_timeout: Duration::from_secs(600),
+            cell_timeout: None,
+            max_memory_bytes: 0,
+        }
+    }
+}
+
+/// How to launch a kernel: an interpreter running one of the bundled drivers.
+#[derive(Debug, Clone)]
+pub struct KernelSpec {
+    pub program: String,
+    pub args: Vec<String>,
+    pub env: Vec<(String, String)>,
+}
+
+impl KernelSpec {
+    /// Python kernel: cells run in a shared `__main__` namespace and the value of a
+    /// trailing expression is printed, like the interactive interpreter.
+    pub fn python(interpreter: &str, config: &SessionConfig) -> Self {
+        Self {
+            program: interpreter.to_string(),
+            args: vec!["-u".into(), "-c".into(), PYTHON_DRIVER.into()],
+            env: memory_env(config),
+        }
+    }
+
+    /// Node.js kernel: cells run in a shared `vm` context; promises are awaited.
+    pub fn javascript(interpreter: &str, config: &SessionConfig) -> Self {
+        let mut args = Vec::new();
+        if config.max_memory_bytes > 0 {
+            let mb = (config.max_memory_bytes / (1024 * 1024)).max(1);
+            args.push(format!("--max-old-space-size={mb}"));
+        }
+        args.push("-e".into());
+        args.push(JAVASCRIPT_DRIVER.into());
+        Self {
+            program: interpreter.to_string(),
+            args,
+            env: Vec::new(),
+        }
+    }
+
+    /// Bash kernel: cells are `eval`ed in one shell, so variables and `cd` persist.
+    pub fn bash(shell: &str, config: &SessionConfig) -> Self {
+        Self {
+            program: shell.to_string(),
+            args: vec!["-c".into(), BASH_DRIVER.into()],
+            env: memory_env(config),
+        }
+    }
+}
+
+fn memory_env(config: &SessionConfig) -> Vec<(String, String)> {
+    if config.max_memory_bytes > 0 {
+        vec![(
+            "GAUSS_KERNEL_MEM_LIMIT".to_string(),
+            config.max_memory_bytes.to_string(),
+        )]
+    } else {
+        Vec::new()
+    }
+}
+
+// ─── Session ───────────────────────────────────────────────────────
+
+/// A persistent interpreter session for one runtime.
+pub struct CodeSession {
+    runtime: String,
+    spec: KernelSpec,
+    config: SessionConfig,
+    kernel: tokio::sync::Mutex<Option<Kernel>>,
+    last_used: Mutex<Instant>,
+}
+
+impl CodeSession {
+    pub fn new(runtime: impl Into<String>, spec: KernelSpec, config: SessionConfig) -> Arc<Self> {
+        Arc::new(Self {
+            runtime: runtime.into(),
+            spec,
+            config,
+            kernel: tokio::sync::Mutex::new(None),
+            last_used: Mutex::new(Instant::now()),
+        })
+    }
+
+    /// Runtime name (e.g., "python").
+    pub fn runtime(&self) -> &str {
+        &self.runtime
+    }
+
+    /// Time since the last cell finished (or the session was created).
+    pub fn idle_for(&self) -> Duration {
+        self.last_used
+            .lock()
+            .map(|t| t.elapsed())
+            .unwrap_or_default()
+    }
+
+    /// Whether a kernel process is currently alive.
+    pub async fn is_running(&self) -> bool {
+        self.kernel.lock().await.is_some()
+    }
+
+    /// Discard all session state. The next cell starts a fresh kernel.
+    pub async fn reset(&self) {
+        self.kernel.lock().await.take();
+    }
+
+    /// Run one cell in the kernel, starting it if needed.
+    ///
+    /// `config` supplies the working directory, environment and sandbox used when the
+    /// kernel is started, plus the per-cell output caps and (absent a
+    /// `cell_timeout`) the time limit.
+    pub async fn execute(
+        self: &Arc<Self>,
+        code: &str,
+        config: &RuntimeConfig,
+    ) -> Result<ExecutionResult> {
+        self.execute_with_files(code, config, &[]).await
+    }
+
+    /// Run one cell after staging `files` into the kernel's workspace.
+    ///
+    /// Files already present in the workspace are left untouched, so a cell's edits
+    /// to a staged file survive later cells.
+    pub async fn execute_with_files(
+        self: &Arc<Self>,
+        code: &str,
+        config: &RuntimeConfig,
+        files: &[InputFile],
+    ) -> Result<ExecutionResult> {
+        let mut slot = self.kernel.lock().await;
+        self.touch();
+
+        if slot.is_none() {
+            *slot = Some(Kernel::spawn(&self.runtime, &self.spec, config)?);
+            self.watch_idle();
+        }
+        let kernel = slot.as_mut().expect("kernel started above");
+
+        let before = match kernel.workspace {
+            Some(ref ws) => {
+                let fresh: Vec<InputFile> = files
+                    .iter()
+                    .filter(|f| !ws.path().join(&f.path).exists())
+                    .cloned()
+                    .collect();
+                ws.stage(&fresh)?;
+                Some(ws.snapshot())
+            }
+            None if !files.is_empty() => {
+                return Err(GaussError::tool(
+                    &self.runtime,
+                    "Input files require a workspace (RuntimeConfig::workspace)",
+                ));
+            }
+            None => None,
+        };
+
+        let timeout = self.config.cell_timeout.unwrap_or(config.timeout);
+        let outcome = tokio::time::timeout(timeout, kernel.run_cell(code, config)).await;
+        let result = match outcome {
+            Ok(Ok(cell)) => {
+                let mut result = cell.into_result(&self.runtime);
+                if let (Some(ws), Some(before)) = (&kernel.workspace, &before) {
+                    result.artifacts = ws.collect(before);
+                }
+                Ok(result)
+            }
+            Ok(Err(CellError::Exited(status))) => {
+                // The code terminated the interpreter (e.g. `exit 3`); report it like a
+                // one-shot run and start over on the next cell.
+                let stderr = kernel.stderr_snapshot();
+                slot.take();
+                Ok(ExecutionResult {
+                    stdout: String::new(),
+                    stderr: format!("{stderr}[kernel exited; session state was reset]"),
+                    exit_code: status,
+                    timed_out: false,
+                    runtime: self.runtime.clone(),
+                    stdout_truncated: false,
+                    stderr_truncated: false,
+                    artifacts: Vec::new(),
+                })
+            }
+            Ok(Err(CellError::Io(e))) => {
+                slot.take();
+                Err(GaussError::tool(
+                    &self.runtime,
+                    format!("kernel I/O error: {e}"),
+                ))
+            }
+            Err(_) => {
+                slot.take();
+                Ok(ExecutionResult {
+                    stdout: String::new(),
+                    stderr: format!(
+                        "Execution timed out after {timeout:?}; kernel restarted and session state was reset"
+                    ),
+                    exit_code: -1,
+                    timed_out: true,
+                    runtime: self.runtime.clone(),
+                    stdout_truncated: false,
+                    stderr_truncated: false,
+                    artifacts: Vec::new(),
+                })
+            }
+        };
+
+        self.touch();
+        result
+    }
+
+    fn touch(&self) {
+        if let Ok(mut t) = self.last_used.lock() {
+            *t = Instant::now();
+        }
+    }
+
+    /// Shut the kernel down once it has been idle for `idle_timeout`.
+    fn watch_idle(self: &Arc<Self>) {
+        let weak: Weak<Self> = Arc::downgrade(self);
+        let idle_timeout = self.config.idle_timeout;
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(idle_timeout / 4 + Duration::from_millis(10)).await;
+                let Some(session) = weak.upgrade() else {
+                    return;
+                };
+                // Skip while a cell holds the lock; it refreshes `last_used` when done.
+                let Ok(mut slot) = session.kernel.try_lock() else {
+                    continue;
+                };
+                if slot.is_none() {
+                    return;
+                }
+                if session.idle_for() >= idle_timeout {
+                    tracing::debug!(runtime = %session.runtime, "Shutting down idle kernel");
+                    slot.take();
+                    return;
+                }
+            }
+        });
+    }
+}
+
+// ─── Kernel Process ────────────────────────────────────────────────
+
+struct Kernel {
+    // Fields drop in declaration order: the process group is killed before the
+    // child handle can be reaped, and the workspace directory is removed last.
+    group: ProcessGroupGuard,
+    child: tokio::process::Child,
+    stdin: tokio::process::ChildStdin,
+    stdout: BufReader<tokio::process::ChildStdout>,
+    stderr: Arc<Mutex<Vec<u8>>>,
+    sentinel: String,
+    workspace: Option<Workspace>,
+}
+
+enum CellError {
+    Exited(i32),
+    Io(std::io::Error),
+}
+
+impl From<std::io::Error> for CellError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+struct CellOutput {
+    exit_code: i32,
+    stdout: String,
+    stderr: String,
+    stdout_dropped: usize,
+    stderr_dropped: usize,
+}
+
+impl CellOutput {
+    fn into_result(self, runtime: &str) -> ExecutionResult {
+        ExecutionResult {
+            stdout: with_marker(self.stdout, self.stdout_dropped),
+            stderr: with_marker(self.stderr, self.stderr_dropped),
+            exit_code: self.exit_code,
+            timed_out: false,
+            runtime: runtime.to_string(),
+            stdout_truncated: self.stdout_dropped > 0,
+            stderr_truncated: self.stderr_dropped > 0,
+            artifacts: Vec::new(),
+        }
+    }
+}
+
+fn with_marker(mut text: String, dropped: usize) -> String {
+    if dropped > 0 {
+        if !text.ends_with('\n') {
+            text.push('\n');
+        }
+        text.push_str(&format!("[output truncated: {dropped} bytes omitted]"));
+    }
+    text
+}
+
+impl Kernel {
+    fn spawn(runtime: &str, spec: &KernelSpec, config: &RuntimeConfig) -> Result<Self> {
+        use tokio::process::Command;
+
+        let sentinel = format!("__GAUSS_{}__", uuid::Uuid::new_v4().simple());
+        let workspace = config
+            .workspace
+            .as_ref()
+            .map(Workspace::create)
+            .transpose()?;
+
+        let mut cmd = Command::new(&spec.program);
+        cmd.args(&spec.args)
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .kill_on_drop(true);
+
+        #[cfg(unix)]
+        cmd.process_group(0);
+
+        if let Some(ref ws) = workspace {
+            cmd.current_dir(ws.path());
+        } else if let Some(ref dir) = config.working_dir {
+            cmd.current_dir(dir);
+        }
+        for (key, val) in config.env.iter().chain(&spec.env) {
+            cmd.env(key, val);
+        }
+        if let Some(ref sandbox) = config.sandbox {
+            sandbox.apply_to_command(&mut cmd);
+        }
+        cmd.env("GAUSS_KERNEL_SENTINEL", &sentinel);
+
+        let mut child = cmd
+            .spawn()
+            .map_err(|e| GaussError::tool(runtime, format!("Failed to start kernel: {e}")))?;
+        let group = ProcessGroupGuard::new(child.id());
+
+        let (Some(stdin), Some(stdout), Some(mut stderr_pipe)) =
+            (child.stdin.take(), child.stdout.take(), child.stderr.take())
+        else {
+            return Err(GaussError::tool(runtime, "Failed to attach kernel pipes"));
+        };
+
+        let stderr = Arc::new(Mutex::new(Vec::new()));
+        let sink = stderr.clone();
+        tokio::spawn(async move {
+            let mut chunk = [0u8; 4096];
+            while let Ok(n) = stderr_pipe.read(&mut chunk).await {
+                if n == 0 {
+                    break;
+                }
+                if let Ok(mut buf) = sink.lock() {
+                    let room = KERNEL_STDERR_LIMIT.saturating_sub(buf.len()).min(n);
+                    buf.extend_from_slice(&chunk[..room]);
+                }
+            }
+        });
+
+        Ok(Self {
+            child,
+            stdin,
+            stdout: BufReader::new(stdout),
+            stderr,
+            sentinel,
+            group,
+            workspace,
+        })
+    }
+
+    async fn run_cell(
+        &mut self,
+        code: &str,
+        config: &RuntimeConfig,
+    ) -> std::result::Result<CellOutput, CellError> {
+        let frame = format!(
+            "exec {} {} {}\n",
+            config.max_stdout_bytes,
+            config.max_stderr_bytes,
+            BASE64.encode(code)
+        );
+        if self.stdin.write_all(frame.as_bytes()).await.is_err()
+            || self.stdin.flush().await.is_err()
+        {
+            return Err(CellError::Exited(self.exit_status().await?));
+        }
+
+        // Lines that are not response frames were written directly to the kernel's
+        // stdout (e.g. by a child process); keep them as part of the cell's output.
+        let mut stray = String::new();
+        loop {
+            let Some(line) = self.next_line().await? else {
+                return Err(CellError::Exited(self.exit_status().await?));
+            };
+            let Some(at) = line.find(self.sentinel.as_str()) else {
+                stray.push_str(&line);
+                stray.push('\n');
+                continue;
+            };
+            // Output written without a trailing newline shares the line with the frame.
+            stray.push_str(&line[..at]);
+            let frame = &line[at + self.sentinel.len()..];
+            let mut cell = parse_response(frame).ok_or_else(|| {
+                CellError::Io(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "malformed kernel response",
+                ))
+            })?;
+            if !stray.is_empty() {
+                stray.push_str(&cell.stdout);
+                cell.stdout = stray;
+            }
+            return Ok(cell);
+        }
+    }
+
+    /// Next line of kernel stdout. User code may print arbitrary bytes, so the
+    /// line is decoded lossily rather than failing the session on bad UTF-8.
+    async fn next_line(&mut self) -> std::io::Result<Option<String>> {
+        let mut buf = Vec::new();
+        if self.stdout.read_until(b'\n', &mut buf).await? == 0 {
+            return Ok(None);
+        }
+        if buf.last() == Some(&b'\n') {
+            buf.pop();
+            if buf.last() == Some(&b'\r') {
+                buf.pop();
+            }
+        }
+        Ok(Some(String::from_utf8_lossy(&buf).into_owned()))
+    }
+
+    async fn exit_status(&mut self) -> std::io::Result<i32> {
+        let status = self.child.wait().await?;
+        self.group.leader_reaped();
+        Ok(status.code().unwrap_or(-1))
+    }
+
+    fn stderr_snapshot(&self) -> String {
+        let text = self
+            .stderr
+            .lock()
+            .map(|b| String::from_utf8_lossy(&b).into_owned())
+            .unwrap_or_default();
+        if text.is_empty() || text.ends_with('\n') {
+            text
+        } else {
+            format!("{text}\n")
+        }
+    }
+}
+
+fn parse_response(frame: &str) -> Option<CellOutput> {
+    let mut fields = frame.trim_start().split(' ');
+    let exit_code = fields.next()?.parse().ok()?;
+    let stdout_dropped = fields.next()?.parse().ok()?;
+    let stderr_dropped = fields.next()?.parse().ok()?;
+    let stdout = decode(fields.next()?)?;
+    let stderr = decode(fields.next().unwrap_or(""))?;
+    Some(CellOutput {
+        exit_code,
+        stdout,
+        stderr,
+        stdout_dropped,
+        stderr_dropped,
+    })
+}
+
+fn decode(field: &str) -> Option<String> {
+    let bytes = BASE64.decode(field.trim()).ok()?;
+    Some(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+// ─── Session Manager ───────────────────────────────────────────────
+
+/// Registry of sessions keyed by `(session_id, runtime)`.
+pub struct SessionManager {
+    config: SessionConfig,
+    sessions: Mutex<HashMap<(String, String), Arc<CodeSession>>>,
+}
+
+impl SessionManager {
+    pub fn new(config: SessionConfig) -> Self {
+        Self {
+            config,
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn config(&self) -> &SessionConfig {
+        &self.config
+    }
+
+    /// Get the session for `session_id` on `runtime`, creating it if needed.
+    /// Returns `None` if the runtime has no kernel support.
+    ///
+    /// Sessions idle for longer than `idle_timeout` are evicted first.
+    pub fn session(
+        &self,
+        session_id: &str,
+        runtime_name: &str,
+        runtime: &dyn CodeRuntime,
+    ) -> Option<Arc<CodeSession>> {
+        let mut sessions = self.sessions.lock().ok()?;
+        Self::evict_idle_locked(&mut sessions, self.config.idle_timeout);
+        let key = (session_id.to_string(), runtime_name.to_string());
+        if let Some(existing) = sessions.get(&key) {
+            return Some(existing.clone());
+        }
+        let spec = runtime.kernel_spec(&self.config)?;
+        let session = CodeSession::new(runtime_name, spec, self.config.clone());
+        sessions.insert(key, session.clone());
+        Some(session)
+    }
+
+    /// Reset every runtime of a session, or only `runtime` when given.
+    pub async fn reset(&self, session_id: &str, runtime: Option<&str>) {
+        for session in self.matching(session_id, runtime) {
+            session.reset().await;
+        }
+    }
+
+    /// Shut down and forget all kernels of a session.
+    pub async fn close(&self, session_id: &str) {
+        let removed: Vec<Arc<CodeSession>> = match self.sessions.lock() {
+            Ok(mut sessions) => {
+                let keys: Vec<_> = sessions
+                    .keys()
+                    .filter(|(id, _)| id == session_id)
+                    .cloned()
+                    .collect();
+                keys.iter().filter_map(|k| sessions.remove(k)).collect()
+            }
+            Err(_) => Vec::new(),
+        };
+        for session in removed {
+            session.reset().await;
+        }
+    }
+
+    /// Forget sessions idle for longer than `idle_timeout`, shutting down their
+    /// kernels. Sessions running a cell are kept. Returns how many were evicted.
+    pub fn evict_idle(&self) -> usize {
+        match self.sessions.lock() {
+            Ok(mut sessions) => Self::evict_idle_locked(&mut sessions, self.config.idle_timeout),
+            Err(_) => 0,
+        }
+    }
+
+    fn evict_idle_locked(
+        sessions: &mut HashMap<(String, String), Arc<CodeSession>>,
+        idle_timeout: Duration,
+    ) -> usize {
+        let before = sessions.len();
+        // Dropping the last handle drops the kernel, which kills its process group.
+        sessions.retain(|_, session| {
+            session.kernel.try_lock().is_err() || session.idle_for() < idle_timeout
+        });
+        before - sessions.len()
+    }
+
+    /// Ids of all known sessions.
+    pub fn session_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self
+            .sessions
+            .lock()
+            .map(|s| s.keys().map(|(id, _)| id.clone()).collect())
+            .unwrap_or_default();
+        ids.sort();
+        ids.dedup();
+        ids
+    }
+
+    fn matching(&self, session_id: &str, runtime: Option<&str>) -> Vec<Arc<CodeSession>> {
+        self.sessions
+            .lock()
+            .map(|sessions| {
+                sessions
+                    .iter()
+                    .filter(|((id, rt), _)| id == session_id && runtime.is_none_or(|r| r == rt))
+                    .map(|(_, s)| s.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+// ─── Drivers ───────────────────────────────────────────────────────
+
+const PYTHON_DRIVER: &str = r#"
+import ast, base64, os, sys, tempfile, traceback
+
+_S = os.environ.pop("GAUSS_KERNEL_SENTINEL")
+_mem = int(os.environ.pop("GAUSS_KERNEL_MEM_LIMIT", "0") or 0)
+if _mem > 0:
+    try:
+        import resource
+        resource.setrlimit(resource.RLIMIT_AS, (_mem, _mem))
+    except Exception:
+        pass
+
+_proto = os.fdopen(os.dup(1), "w")
+_stdin = os.fdopen(os.dup(0), "r")
+_null = os.open(os.devnull, os.O_RDONLY)
+os.dup2(_null, 0)
+_ns = {"__name__": "__main__", "__builtins__": __builtins__}
+
+def _run(code):
+    tree = ast.parse(code, "<cell>", "exec")
+    last = None
+    if tree.body and isinstance(tree.body[-1], ast.Expr):
+        last = ast.Expression(tree.body.pop().value)
+    exec(compile(tree, "<cell>", "exec"), _ns)
+    if last is not None:
+        value = eval(compile(last, "<cell>", "eval"), _ns)
+        if value is not None:
+            print(repr(value))
+
+def _read(f, limit):
+    f.seek(0)
+    size = os.fstat(f.fileno()).st_size
+    data = f.read(limit) if limit > 0 else f.read()
+    return base64.b64encode(data).decode(), size - len(data)
+
+for line in _stdin:
+    parts = line.split(" ")
+    if parts[0] != "exec" or len(parts) != 4:
+        continue
+    max_out, max_err = int(parts[1]), int(parts[2])
+    code = base64.b64decode(parts[3]).decode("utf-8", "replace")
+    out, err = tempfile.TemporaryFile(), tempfile.TemporaryFile()
+    saved = os.dup(1), os.dup(2)
+    sys.stdout.flush(); sys.stderr.flush()
+    os.dup2(out.fileno(), 1); os.dup2(err.fileno(), 2)
+    status = 0
+    try:
+        _run(code)
+    except SystemExit as e:
+        status = e.code if isinstance(e.code, int) else (0 if e.code is None else 1)
+    except BaseException:
+        traceback.print_exc()
+        status = 1
+    finally:
+        sys.stdout.flush(); sys.stderr.flush()
+        os.dup2(saved[0], 1); os.dup2(saved[1], 2)
+        os.close(saved[0]); os.close(saved[1])
+    o, od = _read(out, max_out)
+    e, ed = _read(err, max_err)
+    out.close(); err.close()
+    _proto.write("%s %d %d %d %s %s\n" % (_S, status, od, ed, o, e))
+    _proto.flush()
+"#;
+
+const JAVASCRIPT_DRIVER: &str = r#"
+const vm = require('vm');
+const util = require('util');
+const readline = require('readline');
+const S = process.env.GAUSS_KERNEL_SENTINEL;
+delete process.env.GAUSS_KERNEL_SENTINEL;
+
+let out = [], err = [];
+const fmt = (args) => util.formatWithOptions({ colors: false }, ...args) + '\n';
+const console_ = {
+  log: (...a) => out.push(fmt(a)), info: (...a) => out.push(fmt(a)), debug: (...a) => out.push(fmt(a)),
+  error: (...a) => err.push(fmt(a)), warn: (...a) => err.push(fmt(a)),
+  dir: (o) => out.push(util.inspect(o) + '\n'), table: (o) => out.push(util.inspect(o) + '\n'),
+};
+const ctx = vm.createContext({
+  console: console_, require, process, Buffer, URL, URLSearchParams, TextEncoder, TextDecoder,
+  setTimeout, clearTimeout, setInterval, clearInterval, setImmediate, queueMicrotask,
+});
+
+const cap = (chunks, limit) => {
+  const buf = Buffer.from(chunks.join(''), 'utf8');
+  const kept = limit > 0 ? buf.subarray(0, limit) : buf;
+  return [kept.toString('base64'), buf.length - kept.length];
+};
+
+async function run(line) {
+  const parts = line.split(' ');
+  if (parts[0] !== 'exec' || parts.length !== 4) return;
+  const code = Buffer.from(parts[3], 'base64').toString('utf8');
+  out = []; err = [];
+  let status = 0;
+  try {
+    let value = vm.runInContext(code, ctx, { filename: 'cell.js' });
+    if (value && typeof value.then === 'function') value = await value;
+    if (value !== undefined) out.push(util.inspect(value) + '\n');
+  } catch (e) {
+    err.push((e && e.stack ? e.stack : String(e)) + '\n');
+    status = 1;
+  }
+  const [o, od] = cap(out, Number(parts[1]));
+  const [e, ed] = cap(err, Number(parts[2]));
+  process.stdout.write(`${S} ${status} ${od} ${ed} ${o} ${e}\n`);
+}
+
+let queue = Promise.resolve();
+const rl = readline.createInterface({ input: process.stdin });
+rl.on('line', (line) => { queue = queue.then(() => run(line)); });
+"#;
+
+const BASH_DRIVER: &str = r#"
+__gauss_s="$GAUSS_KERNEL_SENTINEL"
+unset GAUSS_KERNEL_SENTINEL
+if [ -n "$GAUSS_KERNEL_MEM_LIMIT" ]; then
+  ulimit -v $(( GAUSS_KERNEL_MEM_LIMIT / 1024 )) 2>/dev/null
+fi
+unset GAUSS_KERNEL_MEM_LIMIT
+exec 3<&0 4>&1
+__gauss_cap() {
+  local f=$1 limit=$2 size
+  size=$(wc -c < "$f")
+  if [ "$limit" -gt 0 ] && [ "$size" -gt "$limit" ]; then
+    __gauss_b64=$(head -c "$limit" "$f" | base64 | tr -d '\n')
+    __gauss_dropped=$(( size - limit ))
+  else
+    __gauss_b64=$(base64 < "$f" | tr -d '\n')
+    __gauss_dropped=0
+  fi
+}
+while IFS=' ' read -r __gauss_op __gauss_mo __gauss_me __gauss_code <&3; do
+  [ "$__gauss_op" = "exec" ] || continue
+  __gauss_out=$(mktemp) __gauss_err=$(mktemp)
+  eval "$(printf '%s' "$__gauss_code" | base64 -d)" >"$__gauss_out" 2>"$__gauss_err" </dev/null
+  __gauss_rc=$?
+  __gauss_cap "$__gauss_out" "$__gauss_mo"; __gauss_o=$__gauss_b64 __gauss_od=$__gauss_dropped
+  __gauss_cap "$__gauss_err" "$__gauss_me"; __gauss_e=$__gauss_b64 __gauss_ed=$__gauss_dropped
+  rm -f "$__gauss_out" "$__gauss_err"
+  printf '%s %d %d %d %s %s\n' "$__gauss_s" "$__gauss_rc" "$__gauss_od" "$__gauss_ed" "$__gauss_o" "$__gauss_e" >&4
+done
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::code_execution::{BashRuntime, JavaScriptRuntime, PythonRuntime};
+
+    async fn session_for(
+        runtime: &dyn CodeRuntime,
+        config: SessionConfig,
+    ) -> Option<Arc<CodeSession>> {
+        if !runtime.is_available().await {
+            return None;
+        }
+        let spec = runtime.kernel_spec(&config)?;
+        Some(CodeSession::new(runtime.name(), spec, config))
+    }
+
+    #[test]
+    fn test_parse_response() {
+        let cell = parse_response(" 0 0 3 aGkK ").unwrap();
+        assert_eq!(cell.exit_code, 0);
+        assert_eq!(cell.stdout, "hi\n");
+        assert_eq!(cell.stderr, "");
+        assert_eq!(cell.stderr_dropped, 3);
+        assert!(parse_response(" x 0 0 ").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_python_session_keeps_state() {
+        let Some(session) = session_for(&PythonRuntime::new(), SessionConfig::default()).await
+        else {
+            return;
+        };
+        let config = RuntimeConfig::default();
+        let first = session
+            .execute("import math\nx = 40", &config)
+            .await
+            .unwrap();
+        assert!(first.success(), "{first:?}");
+        let second = session
+            .execute("print('side')\nx + math.floor(2.5)", &config)
+            .await
+            .unwrap();
+        assert_eq!(second.stdout, "side\n42\n");
+
+        let failed = session.execute("undefined_name", &config).await.unwrap();
+        assert_eq!(failed.exit_code, 1);
+        assert!(failed.stderr.contains("NameError"));
+
+        session.reset().await;
+        let after_reset = session.execute("x", &config).await.unwrap();
+        assert!(after_reset.stderr.contains("NameError"));
+    }
+
+    #[tokio::test]
+    async fn test_javascript_session_keeps_state() {
+        let Some(session) = session_for(&JavaScriptRuntime::new(), SessionConfig::default()).await
+        else {
+            return;
+        };
+        let config = RuntimeConfig::default();
+        session
+            .execute("globalThis.total = 1; var items = [1, 2]", &config)
+            .await
+            .unwrap();
+        let result = session
+            .execute("console.log('sum'); total + items.length", &config)
+            .await
+            .unwrap();
+        assert!(result.success(), "{result:?}");
+        assert_eq!(result.stdout, "sum\n3\n");
+
+        let awaited = session
+            .execute("Promise.resolve(7)", &config)
+            .await
+            .unwrap();
+        assert_eq!(awaited.stdout.trim(), "7");
+    }
+
+    #[tokio::test]
+    async fn test_bash_session_keeps_state() {
+        let Some(session) = session_for(&BashRuntime::new(), SessionConfig::default()).await else {
+            return;
+        };
+        let config = RuntimeConfig::default();
+        session
+            .execute("GREETING=hello; cd /tmp", &config)
+            .await
+            .unwrap();
+        let result = session
+            .execute(
+                "echo \"$GREETING from $PWD\"; echo oops >&2; false",
+                &config,
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.stdout.trim(), "hello from /tmp");
+        assert_eq!(result.stderr.trim(), "oops");
+        assert_eq!(result.exit_code, 1);
+
+        let exited = session.execute("exit 3", &config).await.unwrap();
+        assert_eq!(exited.exit_code, 3);
+        assert!(!session.is_running().await);
+        let fresh = session
+            .execute("echo \"[$GREETING]\"", &config)
+            .await
+            .unwrap();
+        assert_eq!(fresh.stdout.trim(), "[]");
+    }
+
+    #[tokio::test]
+    async fn test_cell_timeout_restarts_kernel() {
+        let config = SessionConfig {
+            cell_timeout: Some(Duration::from_millis(300)),
+            ..Default::default()
+        };
+        let Some(session) = session_for(&PythonRuntime::new(), config).await else {
+            return;
+        };
+        let rc = RuntimeConfig::default();
+        session.execute("x = 1", &rc).await.unwrap();
+        let result = session
+            .execute("import time; time.sleep(5)", &rc)
+            .await
+            .unwrap();
+        assert!(result.timed_out);
+        assert!(!session.is_running().await);
+        let after = session.execute("print('x' in dir())", &rc).await.unwrap();
+        assert_eq!(after.stdout.trim(), "False");
+    }
+
+    #[tokio::test]
+    async fn test_output_cap_in_session() {
+        let Some(session) = session_for(&PythonRuntime::new(), SessionConfig::default()).await
+        else {
+            return;
+        };
+        let config = RuntimeConfig {
+            max_stdout_bytes: 5,
+            ..Default::default()
+        };
+        let result = session
+            .execute("print('abcdefghij')", &config)
+            .await
+            .unwrap();
+        assert!(result.stdout_truncated);
+        assert!(
+            result
+                .stdout
+                .starts_with("abcde\n[output truncated: 6 bytes omitted]")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_session_workspace_artifacts() {
+        let Some(session) = session_for(&PythonRuntime::new(), SessionConfig::default()).await
+        else {
+            return;
+        };
+        let config = RuntimeConfig {
+            workspace: Some(crate::code_workspace::WorkspaceConfig::default()),
+            ..Default::default()
+        };
+        let input = [InputFile::new("in.txt", "3")];
+        let first = session
+            .execute_with_files(
+                "n = int(open('in.txt').read())\nopen('out.txt', 'w').write(str(n * 2))",
+                &config,
+                &input,
+            )
+            .await
+            .unwrap();
+        assert!(first.success(), "{first:?}");
+        let paths: Vec<&str> = first.artifacts.iter().map(|a| a.path.as_str()).collect();
+        assert_eq!(paths, vec!["out.txt"]);
+
+        // Unchanged files are not reported again; the staged input is not re-staged.
+        let second = session
+            .execute_with_files("open('in.txt', 'w').write('9')", &config, &input)
+            .await
+            .unwrap();
+        let paths: Vec<&str> = second.artifacts.iter().map(|a| a.path.as_str()).collect();
+        assert_eq!(paths, vec!["in.txt"]);
+        let third = session
+            .execute("print(open('in.txt').read())", &config)
+            .await
+            .unwrap();
+        assert_eq!(third.stdout.trim(), "9");
+    }
+
+    #[tokio::test]
+    async fn test_idle_timeout_shuts_down_kernel() {
+        let config = SessionConfig {
+            idle_timeout: Duration::from_millis(200),
+            ..Default::default()
+        };
+        let Some(session) = session_for(&BashRuntime::new(), config).await else {
+            return;
+        };
+        session
+            .execute("true", &RuntimeConfig::default())
+            .await
+            .unwrap();
+        assert!(session.is_running().await);
+        tokio::time::sleep(Duration::from_millis(600)).await;
+        assert!(!session.is_running().await);
+    }
+
+    #[tokio::test]
+    async fn test_session_manager() {
+        let runtime = BashRuntime::new();
+        if !runtime.is_available().await {
+            return;
+        }
+        let manager = SessionManager::new(SessionConfig::default());
+        let a = manager.session("a", "bash", &runtime).unwrap();
+        let b = manager.session("b", "bash", &runtime).unwrap();
+        assert!(Arc::ptr_eq(
+            &a,
+            &manager.session("a", "bash", &runtime).unwrap()
+        ));
+
+        let config = RuntimeConfig::default();
+        a.execute("V=1", &config).await.unwrap();
+        let in_b = b.execute("echo \"[$V]\"", &config).await.unwrap();
+        assert_eq!(in_b.stdout.trim(), "[]");
+
+        assert_eq!(manager.session_ids(), vec!["a", "b"]);
+        manager.close("a").await;
+        assert_eq!(manager.session_ids(), vec!["b"]);
+    }
+
+    #[tokio::test]
+    async fn test_session_manager_evicts_idle_sessions() {
+        let runtime = BashRuntime::new();
+        if !runtime.is_available().await {
+            return;
+        }
+        let manager = SessionManager::new(SessionConfig {
+            idle_timeout: Duration::from_millis(200),
+            ..Default::default()
+        });
+        let stale = manager.session("stale", "bash", &runtime).unwrap();
+        stale
+            .execute("true", &RuntimeConfig::default())
+            .await
+            .unwrap();
+        drop(stale);
+        assert_eq!(manager.evict_idle(), 0);
+
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        manager.session("fresh", "bash", &runtime).unwrap();
+        assert_eq!(manager.session_ids(), vec!["fresh"]);
+    }
+
+    #[tokio::test]
+    async fn test_non_utf8_output_keeps_session() {
+        let Some(session) = session_for(&BashRuntime::new(), SessionConfig::default()).await else {
+            return;
+        };
+        let config = RuntimeConfig::default();
+        let garbled = session
+            // fd 4 is the kernel's own stdout, bypassing the per-cell capture.
+            .execute("printf 'a\\377b\\n' >&4; X=5", &config)
+            .await
+            .unwrap();
+        assert!(garbled.success(), "{}", garbled.stderr);
+        assert_eq!(garbled.stdout.trim(), "a\u{fffd}b");
+
+        let after = session.execute("echo $X", &config).await.unwrap();
+        assert_eq!(after.stdout.trim(), "5");
+    }
+}
@@ -0,0 +1,580 @@
+//! Hermetic WebAssembly code runtime.
+//!
+//! [`WasmRuntime`] (feature `wasm-runtime`) runs a WASI preview 1 module — usually an
+//! interpreter such as CPython or QuickJS built for `wasm32-wasi` — inside wasmtime.
+//! It needs no interpreter on the host and gives the guest:
+//!
+//! - no network access and none of the host's environment variables,
+//! - one writable scratch directory (the workspace, or a fresh temp dir) plus the
+//!   read-only directories listed in [`WasmRuntimeConfig::preopens`],
+//! - a CPU budget in wasmtime fuel and a wall-clock deadline via epoch interruption,
+//! - a cap on linear memory.
+//!
+//! [`WasmRuntimeConfig`] is plain data and is always available, so it can be listed in
+//! [`CodeExecutionConfig::wasm`](crate::code_execution::CodeExecutionConfig::wasm) in any
+//! build; the orchestrator skips it when the `wasm-runtime` feature is off.
+//!
+//! ```no_run
+//! use gauss_core::code_execution::CodeExecutionConfig;
+//! use gauss_core::code_wasm::WasmRuntimeConfig;
+//!
+//! let config = CodeExecutionConfig::builder()
+//!     .python(false)
+//!     .javascript(false)
+//!     .bash(false)
+//!     .wasm(
+//!         WasmRuntimeConfig::python("/opt/wasm/python.wasm")
+//!             .preopen("/opt/wasm/lib", "/usr/local/lib")
+//!             .fuel(5_000_000_000),
+//!     )
+//!     .build();
+//! ```
+
+use std::path::PathBuf;
+
+/// Default cap on guest linear memory (256 MiB).
+pub const DEFAULT_WASM_MAX_MEMORY_BYTES: usize = 256 * 1024 * 1024;
+
+/// Placeholder in [`WasmRuntimeConfig::args`] that is replaced with the code to run.
+pub const CODE_PLACEHOLDER: &str = "{code}";
+
+/// Describes a WASI module to run as a code runtime.
+#[derive(Debug, Clone)]
+pub struct WasmRuntimeConfig {
+    /// Runtime name used for tool names and `language` dispatch (e.g. "python-wasm").
+    pub name: String,
+    /// Path to the `.wasm` (or `.wat`) module.
+    pub module: PathBuf,
+    /// Guest argv. [`CODE_PLACEHOLDER`] is replaced with the code; without it the
+    /// code is piped to the guest's stdin.
+    pub args: Vec<String>,
+    /// Read-only host directories mapped into the guest, as `(host, guest)` paths.
+    pub preopens: Vec<(PathBuf, String)>,
+    /// Guest path of the writable scratch directory.
+    pub scratch_dir: String,
+    /// Instruction budget in wasmtime fuel units (0 = unlimited).
+    pub fuel: u64,
+    /// Maximum guest linear memory in bytes (0 = unlimited).
+    pub max_memory_bytes: usize,
+}
+
+impl WasmRuntimeConfig {
+    /// A module that reads the code from stdin.
+    pub fn new(name: impl Into<String>, module: impl Into<PathBuf>) -> Self {
+        let name = name.into();
+        Self {
+            args: vec![name.clone()],
+            name,
+            module: module.into(),
+            preopens: Vec::new(),
+            scratch_dir: "/".to_string(),
+            fuel: 0,
+            max_memory_bytes: DEFAULT_WASM_MAX_MEMORY_BYTES,
+        }
+    }
+
+    /// CPython built for WASI, run as `python -c <code>`.
+    ///
+    /// The standard library must be preopened where the build expects it, e.g.
+    /// `.preopen("<host lib dir>", "/usr/local/lib")`.
+    pub fn python(module: impl Into<PathBuf>) -> Self {
+        Self::new("python-wasm", module).args(["python", "-c", CODE_PLACEHOLDER])
+    }
+
+    /// QuickJS built for WASI, run as `qjs -e <code>`.
+    pub fn javascript(module: impl Into<PathBuf>) -> Self {
+        Self::new("javascript-wasm", module).args(["qjs", "-e", CODE_PLACEHOLDER])
+    }
+
+    pub fn args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.args = args.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Map a host directory into the guest, read-only.
+    pub fn preopen(mut self, host: impl Into<PathBuf>, guest: impl Into<String>) -> Self {
+        self.preopens.push((host.into(), guest.into()));
+        self
+    }
+
+    pub fn scratch_dir(mut self, guest: impl Into<String>) -> Self {
+        self.scratch_dir = guest.into();
+        self
+    }
+
+    pub fn fuel(mut self, fuel: u64) -> Self {
+        self.fuel = fuel;
+        self
+    }
+
+    pub fn max_memory_bytes(mut self, bytes: usize) -> Self {
+        self.max_memory_bytes = bytes;
+        self
+    }
+
+    /// Guest argv for `code`, and whether the code goes to stdin instead.
+    #[cfg(feature = "wasm-runtime")]
+    fn argv(&self, code: &str) -> (Vec<String>, bool) {
+        let inline = self.args.iter().any(|a| a.contains(CODE_PLACEHOLDER));
+        let argv = self
+            .args
+            .iter()
+            .map(|a| a.replace(CODE_PLACEHOLDER, code))
+            .collect();
+        (argv, !inline)
+    }
+}
+
+#[cfg(feature = "wasm-runtime")]
+pub use runtime::WasmRuntime;
+
+#[cfg(feature = "wasm-runtime")]
+mod runtime {
+    use std::path::PathBuf;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use async_trait::async_trait;
+    use bytes::Bytes;
+    use wasmtime::{Config, Engine, Linker, Module, Store, StoreLimits, StoreLimitsBuilder, Trap};
+    use wasmtime_wasi::pipe::MemoryInputPipe;
+    use wasmtime_wasi::preview1::{self, WasiP1Ctx};
+    use wasmtime_wasi::{
+        DirPerms, FilePerms, I32Exit, OutputStream, Pollable, StdoutStream, StreamResult,
+        WasiCtxBuilder,
+    };
+
+    use super::WasmRuntimeConfig;
+    use crate::code_execution::{CappedOutput, CodeRuntime, ExecutionResult, RuntimeConfig};
+    use crate::code_workspace::{Workspace, WorkspaceConfig};
+    use crate::error::{GaussError, Result};
+
+    /// Granularity of the wall-clock deadline.
+    const EPOCH_TICK: Duration = Duration::from_millis(10);
+
+    /// Runs a WASI module in wasmtime with fuel, epoch and memory limits.
+    ///
+    /// The module is compiled once in [`WasmRuntime::new`]; every execution gets a fresh
+    /// store and instance, so no guest state carries over between calls.
+    pub struct WasmRuntime {
+        config: Arc<WasmRuntimeConfig>,
+        engine: Engine,
+        module: Module,
+        ticker_stop: Arc<AtomicBool>,
+    }
+
+    impl WasmRuntime {
+        /// Compile the module described by `config`.
+        pub fn new(config: WasmRuntimeConfig) -> Result<Self> {
+            let mut engine_config = Config::new();
+            engine_config.consume_fuel(true).epoch_interruption(true);
+            let engine = Engine::new(&engine_config)
+                .map_err(|e| GaussError::tool(&config.name, format!("wasm engine: {e}")))?;
+            let module = Module::from_file(&engine, &config.module).map_err(|e| {
+                GaussError::tool(
+                    &config.name,
+                    format!("Failed to load {}: {e:#}", config.module.display()),
+                )
+            })?;
+
+            // One ticker per engine; each store sets its deadline relative to it.
+            let ticker_stop = Arc::new(AtomicBool::new(false));
+            let (ticker_engine, stop) = (engine.clone(), ticker_stop.clone());
+            std::thread::Builder::new()
+                .name("gauss-wasm-epoch".to_string())
+                .spawn(move || {
+                    while !stop.load(Ordering::Relaxed) {
+                        std::thread::sleep(EPOCH_TICK);
+                        ticker_engine.increment_epoch();
+                    }
+                })
+                .map_err(|e| GaussError::tool(&config.name, format!("epoch ticker: {e}")))?;
+
+            Ok(Self {
+                config: Arc::new(config),
+                engine,
+                module,
+                ticker_stop,
+            })
+        }
+
+        pub fn config(&self) -> &WasmRuntimeConfig {
+            &self.config
+        }
+    }
+
+    impl Drop for WasmRuntime {
+        fn drop(&mut self) {
+            self.ticker_stop.store(true, Ordering::Relaxed);
+        }
+    }
+
+    #[async_trait]
+    impl CodeRuntime for WasmRuntime {
+        fn name(&self) -> &str {
+            &self.config.name
+        }
+
+        async fn execute(&self, code: &str, config: &RuntimeConfig) -> Result<ExecutionResult> {
+            let invocation = Invocation {
+                wasm: self.config.clone(),
+                engine: self.engine.clone(),
+                module: self.module.clone(),
+                code: code.to_string(),
+                config: config.clone(),
+            };
+            tokio::task::spawn_blocking(move || invocation.run())
+                .await
+                .map_err(|e| {
+                    GaussError::tool(&self.config.name, format!("wasm task failed: {e}"))
+                })?
+        }
+
+        async fn is_available(&self) -> bool {
+            true
+        }
+    }
+
+    struct GuestState {
+        wasi: WasiP1Ctx,
+        limits: StoreLimits,
+    }
+
+    /// Everything one blocking execution needs, owned so it can move to a worker thread.
+    struct Invocation {
+        wasm: Arc<WasmRuntimeConfig>,
+        engine: Engine,
+        module: Module,
+        code: String,
+        config: RuntimeConfig,
+    }
+
+    impl Invocation {
+        fn run(self) -> Result<ExecutionResult> {
+            let name = self.wasm.name.as_str();
+            let err =
+                |what: &str, e: wasmtime::Error| GaussError::tool(name, format!("{what}: {e:#}"));
+
+            // Without a workspace the guest still gets a private, throwaway directory.
+            let scratch_ws;
+            let scratch = match &self.config.working_dir {
+                Some(dir) => PathBuf::from(dir),
+                None => {
+                    scratch_ws = Workspace::create(&WorkspaceConfig::default())?;
+                    scratch_ws.path().to_path_buf()
+                }
+            };
+
+            let stdout = CappedPipe::new(self.config.max_stdout_bytes);
+            let stderr = CappedPipe::new(self.config.max_stderr_bytes);
+            let (argv, code_on_stdin) = self.wasm.argv(&self.code);
+
+            let mut builder = WasiCtxBuilder::new();
+            builder
+                .args(&argv)
+                .stdout(stdout.clone())
+                .stderr(stderr.clone())
+                .allow_ip_name_lookup(false);
+            if code_on_stdin {
+                builder.stdin(MemoryInputPipe::new(self.code.clone()));
+            }
+            for (key, val) in &self.config.env {
+                builder.env(key, val);
+            }
+            builder
+                .preopened_dir(
+                    &scratch,
+                    &self.wasm.scratch_dir,
+                    DirPerms::all(),
+                    FilePerms::all(),
+                )
+                .map_err(|e| err("scratch dir", e))?;
+            for (host, guest) in &self.wasm.preopens {
+                builder
+                    .preopened_dir(host, guest, DirPerms::READ, FilePerms::READ)
+                    .map_err(|e| err(&format!("preopen {}", host.display()), e))?;
+            }
+
+            let mut limits = StoreLimitsBuilder::new();
+            if self.wasm.max_memory_bytes > 0 {
+                limits = limits.memory_size(self.wasm.max_memory_bytes);
+            }
+            let mut store = Store::new(
+                &self.engine,
+                GuestState {
+                    wasi: builder.build_p1(),
+                    limits: limits.build(),
+                },
+            );
+            store.limiter(|state| &mut state.limits);
+            let fuel = if self.wasm.fuel == 0 {
+                u64::MAX
+            } else {
+                self.wasm.fuel
+            };
+            store.set_fuel(fuel).map_err(|e| err("fuel", e))?;
+            let ticks = self.config.timeout.as_millis() / EPOCH_TICK.as_millis() + 1;
+            store.set_epoch_deadline(u64::try_from(ticks).unwrap_or(u64::MAX));
+
+            let mut linker: Linker<GuestState> = Linker::new(&self.engine);
+            preview1::add_to_linker_sync(&mut linker, |state| &mut state.wasi)
+                .map_err(|e| err("wasi linker", e))?;
+
+            let outcome = linker
+                .instantiate(&mut store, &self.module)
+                .and_then(|instance| instance.get_typed_func::<(), ()>(&mut store, "_start"))
+                .and_then(|start| start.call(&mut store, ()));
+            drop(store);
+
+            let (exit_code, timed_out, note) = match outcome {
+                Ok(()) => (0, false, None),
+                Err(e) => match (e.downcast_ref::<I32Exit>(), e.downcast_ref::<Trap>()) {
+                    (Some(exit), _) => (exit.0, false, None),
+                    (None, Some(Trap::Interrupt)) => (
+                        -1,
+                        true,
+                        Some(format!(
+                            "Execution timed out after {:?}",
+                            self.config.timeout
+                        )),
+                    ),
+                    (None, Some(Trap::OutOfFuel)) => (
+                        -1,
+                        false,
+                        Some(format!(
+                            "Execution ran out of fuel ({} units)",
+                            self.wasm.fuel
+                        )),
+                    ),
+                    _ => (-1, false, Some(format!("wasm error: {e:#}"))),
+                },
+            };
+
+            let stdout = stdout.take();
+            let stderr = stderr.take();
+            let stderr_truncated = stderr.truncated();
+            let mut stderr = stderr.into_string();
+            if let Some(note) = note {
+                if !stderr.is_empty() && !stderr.ends_with('\n') {
+                    stderr.push('\n');
+                }
+                stderr.push_str(&note);
+            }
+            Ok(ExecutionResult {
+                stdout_truncated: stdout.truncated(),
+                stdout: stdout.into_string(),
+                stderr,
+                stderr_truncated,
+                exit_code,
+                timed_out,
+                runtime: name.to_string(),
+                artifacts: Vec::new(),
+            })
+        }
+    }
+
+    /// Guest stdout/stderr sink that keeps a capped copy and discards the rest,
+    /// so a chatty guest never traps on a full pipe.
+    #[derive(Clone)]
+    struct CappedPipe(Arc<Mutex<CappedOutput>>);
+
+    impl CappedPipe {
+        fn new(limit: usize) -> Self {
+            Self(Arc::new(Mutex::new(CappedOutput::new(limit))))
+        }
+
+        fn take(&self) -> CappedOutput {
+            let mut guard = self.0.lock().unwrap_or_else(|e| e.into_inner());
+            std::mem::replace(&mut *guard, CappedOutput::new(0))
+        }
+    }
+
+    #[async_trait]
+    impl OutputStream for CappedPipe {
+        fn write(&mut self, bytes: Bytes) -> StreamResult<()> {
+            if let Ok(mut out) = self.0.lock() {
+                out.push(&bytes);
+            }
+            Ok(())
+        }
+
+        fn flush(&mut self) -> StreamResult<()> {
+            Ok(())
+        }
+
+        fn check_write(&mut self) -> StreamResult<usize> {
+            Ok(64 * 1024)
+        }
+    }
+
+    #[async_trait]
+    impl Pollable for CappedPipe {
+        async fn ready(&mut self) {}
+    }
+
+    impl StdoutStream for CappedPipe {
+        fn stream(&self) -> Box<dyn OutputStream> {
+            Box::new(self.clone())
+        }
+
+        fn isatty(&self) -> bool {
+            false
+        }
+    }
+}
+
+#[cfg(all(test, feature = "wasm-runtime"))]
+mod tests {
+    use super::*;
+    use crate::code_execution::{CodeRuntime, RuntimeConfig, execute_with_files};
+    use crate::code_workspace::{Workspace, WorkspaceConfig};
+    use std::time::Duration;
+
+    const ECHO_STDIN: &str = r#"(module
+      (import "wasi_snapshot_preview1" "fd_read" (func $read (param i32 i32 i32 i32) (result i32)))
+      (import "wasi_snapshot_preview1" "fd_write" (func $write (param i32 i32 i32 i32) (result i32)))
+      (memory (export "memory") 1)
+      (func (export "_start")
+        (i32.store (i32.const 0) (i32.const 64))
+        (i32.store (i32.const 4) (i32.const 1024))
+        (drop (call $read (i32.const 0) (i32.const 0) (i32.const 1) (i32.const 8)))
+        (i32.store (i32.const 4) (i32.load (i32.const 8)))
+        (drop (call $write (i32.const 1) (i32.const 0) (i32.const 1) (i32.const 8)))))"#;
+
+    const SPIN: &str = r#"(module
+      (memory (export "memory") 1)
+      (func (export "_start") (loop $l (br $l))))"#;
+
+    const GROW: &str = r#"(module
+      (import "wasi_snapshot_preview1" "proc_exit" (func $exit (param i32)))
+      (memory (export "memory") 1)
+      (func (export "_start")
+        (if (i32.eq (memory.grow (i32.const 100)) (i32.const -1))
+          (then (call $exit (i32.const 3))))))"#;
+
+    /// Creates `path` in the first preopen (the scratch dir) and writes "artifact" to it.
+    fn write_file_module(path: &str) -> String {
+        format!(
+            r#"(module
+      (import "wasi_snapshot_preview1" "path_open"
+        (func $open (param i32 i32 i32 i32 i32 i64 i64 i32 i32) (result i32)))
+      (import "wasi_snapshot_preview1" "fd_write" (func $write (param i32 i32 i32 i32) (result i32)))
+      (import "wasi_snapshot_preview1" "proc_exit" (func $exit (param i32)))
+      (memory (export "memory") 1)
+      (data (i32.const 100) "{path}")
+      (data (i32.const 200) "artifact")
+      (func (export "_start") (local $err i32)
+        (local.set $err (call $open (i32.const 3) (i32.const 0) (i32.const 100) (i32.const {len})
+          (i32.const 1) (i64.const 64) (i64.const 0) (i32.const 0) (i32.const 16)))
+        (if (local.get $err) (then (call $exit (local.get $err))))
+        (i32.store (i32.const 0) (i32.const 200))
+        (i32.store (i32.const 4) (i32.const 8))
+        (drop (call $write (i32.load (i32.const 16)) (i32.const 0) (i32.const 1) (i32.const 8)))))"#,
+            len = path.len()
+        )
+    }
+
+    fn runtime(
+        wat: &str,
+        tweak: impl FnOnce(WasmRuntimeConfig) -> WasmRuntimeConfig,
+    ) -> (WasmRuntime, Workspace) {
+        let dir = Workspace::create(&WorkspaceConfig::default()).unwrap();
+        let path = dir.path().join("module.wat");
+        std::fs::write(&path, wat).unwrap();
+        let rt = WasmRuntime::new(tweak(WasmRuntimeConfig::new("test-wasm", path))).unwrap();
+        (rt, dir)
+    }
+
+    #[test]
+    fn test_argv_placeholder() {
+        let cfg = WasmRuntimeConfig::python("python.wasm");
+        let (argv, stdin) = cfg.argv("print(1)");
+        assert_eq!(argv, vec!["python", "-c", "print(1)"]);
+        assert!(!stdin);
+
+        let (argv, stdin) = WasmRuntimeConfig::new("m", "m.wasm").argv("x");
+        assert_eq!(argv, vec!["m"]);
+        assert!(stdin);
+    }
+
+    #[tokio::test]
+    async fn test_wasm_stdin_and_output_cap() {
+        let (rt, _dir) = runtime(ECHO_STDIN, |c| c);
+        let result = rt
+            .execute("hello wasm", &RuntimeConfig::default())
+            .await
+            .unwrap();
+        assert!(result.success(), "{result:?}");
+        assert_eq!(result.stdout, "hello wasm");
+
+        let config = RuntimeConfig {
+            max_stdout_bytes: 5,
+            ..Default::default()
+        };
+        let result = rt.execute("hello wasm", &config).await.unwrap();
+        assert!(result.stdout_truncated);
+        assert!(
+            result
+                .stdout
+                .starts_with("hello\n[output truncated: 5 bytes omitted]")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_wasm_fuel_and_timeout() {
+        let (rt, _dir) = runtime(SPIN, |c| c.fuel(1_000_000));
+        let result = rt.execute("", &RuntimeConfig::default()).await.unwrap();
+        assert!(!result.timed_out);
+        assert_eq!(result.exit_code, -1);
+        assert!(
+            result.stderr.contains("ran out of fuel"),
+            "{}",
+            result.stderr
+        );
+
+        let (rt, _dir) = runtime(SPIN, |c| c);
+        let config = RuntimeConfig {
+            timeout: Duration::from_millis(200),
+            ..Default::default()
+        };
+        let start = std::time::Instant::now();
+        let result = rt.execute("", &config).await.unwrap();
+        assert!(result.timed_out);
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_wasm_memory_cap() {
+        let (rt, _dir) = runtime(GROW, |c| c.max_memory_bytes(1024 * 1024));
+        let result = rt.execute("", &RuntimeConfig::default()).await.unwrap();
+        assert_eq!(result.exit_code, 3);
+
+        let (rt, _dir) = runtime(GROW, |c| c.max_memory_bytes(0));
+        let result = rt.execute("", &RuntimeConfig::default()).await.unwrap();
+        assert!(result.success(), "{result:?}");
+    }
+
+    #[tokio::test]
+    async fn test_wasm_scratch_dir_only() {
+        let config = RuntimeConfig {
+            workspace: Some(WorkspaceConfig::default()),
+            ..Default::default()
+        };
+
+        let (rt, _dir) = runtime(&write_file_module("out.txt"), |c| c);
+        let result = execute_with_files(&rt, "", &config, &[]).await.unwrap();
+        assert!(result.success(), "{result:?}");
+        assert_eq!(result.artifacts.len(), 1);
+        assert_eq!(result.artifacts[0].path, "out.txt");
+
+        let (rt, _dir) = runtime(&write_file_module("../escape.txt"), |c| c);
+        let result = execute_with_files(&rt, "", &config, &[]).await.unwrap();
+        assert_ne!(result.exit_code, 0);
+        assert!(result.artifacts.is_empty());
+    }
+}
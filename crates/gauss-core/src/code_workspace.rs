@@ -0,0 +1,426 @@
+//! Scratch workspaces and file artifacts for code execution.
+//!
+//! When a [`WorkspaceConfig`] is set, each execution runs inside a fresh scratch
+//! directory. Input files (e.g. from `Content::File` / `Content::Image`) are staged
+//! into it first; afterwards every file that was created or modified is returned as
+//! an [`Artifact`] on `ExecutionResult::artifacts`, with a detected MIME type and the
+//! bytes base64-encoded (subject to size limits).
+//!
+//! Artifacts are reported in tool results under an `"artifacts"` array; the agent
+//! lifts their bytes out of the code-execution tool results and sends images and
+//! text files to the model in a follow-up user message (see
+//! [`crate::tool::split_artifacts`]).
+
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::time::SystemTime;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{GaussError, Result};
+use crate::message::Content;
+
+/// Limits and location for per-execution scratch workspaces.
+#[derive(Debug, Clone)]
+pub struct WorkspaceConfig {
+    /// Parent directory for workspaces (default: the system temp dir).
+    pub root: Option<PathBuf>,
+    /// Files larger than this are listed without data.
+    pub max_artifact_bytes: u64,
+    /// Total artifact bytes returned per execution; later files are listed without data.
+    pub max_total_artifact_bytes: u64,
+    /// Maximum number of artifacts reported per execution.
+    pub max_artifacts: usize,
+    /// Keep the directory after execution (useful for debugging).
+    pub keep: bool,
+}
+
+impl Default for WorkspaceConfig {
+    fn default() -> Self {
+        Self {
+            root: None,
+            max_artifact_bytes: 10 * 1024 * 1024,
+            max_total_artifact_bytes: 50 * 1024 * 1024,
+            max_artifacts: 20,
+            keep: false,
+        }
+    }
+}
+
+/// A file staged into the workspace before code runs.
+#[derive(Debug, Clone)]
+pub struct InputFile {
+    /// Relative path inside the workspace (e.g. `data/sales.csv`).
+    pub path: String,
+    pub data: Vec<u8>,
+}
+
+impl InputFile {
+    pub fn new(path: impl Into<String>, data: impl Into<Vec<u8>>) -> Self {
+        Self {
+            path: path.into(),
+            data: data.into(),
+        }
+    }
+
+    /// Build an input file from inline `Content::File` or `Content::Image` data.
+    ///
+    /// Returns `None` for other content, URL-only content, or invalid base64.
+    pub fn from_content(path: impl Into<String>, content: &Content) -> Option<Self> {
+        let encoded = match content {
+            Content::File { base64, .. } | Content::Image { base64, .. } => base64.as_deref()?,
+            Content::GeneratedImage { data, .. } => data.as_str(),
+            _ => return None,
+        };
+        let data = BASE64.decode(encoded.trim()).ok()?;
+        Some(Self::new(path, data))
+    }
+}
+
+/// A file created or modified by executed code.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Artifact {
+    /// Path relative to the workspace root, using `/` separators.
+    pub path: String,
+    pub media_type: String,
+    pub size: u64,
+    /// Base64-encoded contents; `None` when the file exceeded a size limit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<String>,
+}
+
+impl Artifact {
+    /// Convert into a message content part (`GeneratedImage` for images, else `File`).
+    pub fn to_content(&self) -> Option<Content> {
+        let data = self.data.clone()?;
+        Some(if self.media_type.starts_with("image/") {
+            Content::GeneratedImage {
+                mime_type: self.media_type.clone(),
+                data,
+            }
+        } else {
+            Content::File {
+                url: None,
+                base64: Some(data),
+                media_type: Some(self.media_type.clone()),
+            }
+        })
+    }
+}
+
+/// Files present in a workspace at one point in time: path → (size, mtime).
+pub type WorkspaceSnapshot = HashMap<PathBuf, (u64, Option<SystemTime>)>;
+
+/// A scratch directory that is removed on drop (unless `keep` is set).
+#[derive(Debug)]
+pub struct Workspace {
+    dir: PathBuf,
+    config: WorkspaceConfig,
+}
+
+impl Workspace {
+    /// Create a fresh, empty workspace directory.
+    pub fn create(config: &WorkspaceConfig) -> Result<Self> {
+        let root = config.root.clone().unwrap_or_else(std::env::temp_dir);
+        let dir = root.join(format!("gauss-ws-{}", uuid::Uuid::new_v4().simple()));
+        std::fs::create_dir_all(&dir).map_err(|e| {
+            GaussError::tool("code_execution", format!("Failed to create workspace: {e}"))
+        })?;
+        Ok(Self {
+            dir,
+            config: config.clone(),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Write input files into the workspace. Paths must be relative and stay inside it.
+    pub fn stage(&self, files: &[InputFile]) -> Result<()> {
+        for file in files {
+            let relative = Path::new(&file.path);
+            let safe = !file.path.is_empty()
+                && relative
+                    .components()
+                    .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+            if !safe {
+                return Err(GaussError::tool(
+                    "code_execution",
+                    format!("Invalid input file path: {}", file.path),
+                ));
+            }
+            let target = self.dir.join(relative);
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| {
+                    GaussError::tool(
+                        "code_execution",
+                        format!("Failed to stage {}: {e}", file.path),
+                    )
+                })?;
+            }
+            std::fs::write(&target, &file.data).map_err(|e| {
+                GaussError::tool(
+                    "code_execution",
+                    format!("Failed to stage {}: {e}", file.path),
+                )
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Record the current files so later changes can be detected.
+    pub fn snapshot(&self) -> WorkspaceSnapshot {
+        let mut files = HashMap::new();
+        walk(&self.dir, &mut |path, meta| {
+            files.insert(path.to_path_buf(), (meta.len(), meta.modified().ok()));
+        });
+        files
+    }
+
+    /// Collect files that are new or changed relative to `before`.
+    pub fn collect(&self, before: &WorkspaceSnapshot) -> Vec<Artifact> {
+        let mut changed = Vec::new();
+        walk(&self.dir, &mut |path, meta| {
+            let current = (meta.len(), meta.modified().ok());
+            if before.get(path) != Some(&current) {
+                changed.push((path.to_path_buf(), meta.len()));
+            }
+        });
+        changed.sort();
+
+        let mut budget = self.config.max_total_artifact_bytes;
+        changed
+            .into_iter()
+            .take(self.config.max_artifacts)
+            .map(|(path, size)| {
+                let relative = path
+                    .strip_prefix(&self.dir)
+                    .unwrap_or(&path)
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                let bytes = if size <= self.config.max_artifact_bytes && size <= budget {
+                    std::fs::read(&path).ok()
+                } else {
+                    None
+                };
+                let media_type = detect_media_type(&path, bytes.as_deref());
+                let data = bytes.map(|b| {
+                    budget = budget.saturating_sub(b.len() as u64);
+                    BASE64.encode(b)
+                });
+                Artifact {
+                    path: relative,
+                    media_type,
+                    size,
+                    data,
+                }
+            })
+            .collect()
+    }
+}
+
+impl Drop for Workspace {
+    fn drop(&mut self) {
+        if !self.config.keep {
+            let _ = std::fs::remove_dir_all(&self.dir);
+        }
+    }
+}
+
+/// Visit regular files under `dir` recursively. Symlinks are not followed.
+fn walk(dir: &Path, visit: &mut dyn FnMut(&Path, &std::fs::Metadata)) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let Ok(meta) = std::fs::symlink_metadata(entry.path()) else {
+            continue;
+        };
+        if meta.is_dir() {
+            walk(&entry.path(), visit);
+        } else if meta.is_file() {
+            visit(&entry.path(), &meta);
+        }
+    }
+}
+
+/// Detect a MIME type from magic bytes, falling back to the file extension.
+pub fn detect_media_type(path: &Path, bytes: Option<&[u8]>) -> String {
+    const MAGIC: &[(&[u8], &str)] = &[
+        (b"\x89PNG\r\n\x1a\n", "image/png"),
+        (b"\xff\xd8\xff", "image/jpeg"),
+        (b"GIF87a", "image/gif"),
+        (b"GIF89a", "image/gif"),
+        (b"%PDF-", "application/pdf"),
+        (b"PK\x03\x04", "application/zip"),
+    ];
+    if let Some(bytes) = bytes {
+        if bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+            return "image/webp".to_string();
+        }
+        if let Some((_, mime)) = MAGIC.iter().find(|(magic, _)| bytes.starts_with(magic)) {
+            // Office documents are zip containers; prefer the extension when it is specific.
+            if *mime != "application/zip" {
+                return mime.to_string();
+            }
+        }
+    }
+
+    let ext = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "pdf" => "application/pdf",
+        "csv" => "text/csv",
+        "tsv" => "text/tab-separated-values",
+        "json" => "application/json",
+        "jsonl" | "ndjson" => "application/x-ndjson",
+        "txt" | "log" => "text/plain",
+        "md" => "text/markdown",
+        "html" | "htm" => "text/html",
+        "xml" => "application/xml",
+        "yaml" | "yml" => "application/yaml",
+        "parquet" => "application/vnd.apache.parquet",
+        "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        "zip" => "application/zip",
+        _ if bytes.is_some_and(|b| std::str::from_utf8(b).is_ok()) => "text/plain",
+        _ => "application/octet-stream",
+    }
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_media_type() {
+        let png = b"\x89PNG\r\n\x1a\n....";
+        assert_eq!(
+            detect_media_type(Path::new("plot.bin"), Some(png)),
+            "image/png"
+        );
+        assert_eq!(
+            detect_media_type(Path::new("out.csv"), Some(b"a,b\n1,2\n")),
+            "text/csv"
+        );
+        assert_eq!(
+            detect_media_type(Path::new("report.xlsx"), Some(b"PK\x03\x04rest")),
+            "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+        );
+        assert_eq!(
+            detect_media_type(Path::new("notes"), Some(b"hello")),
+            "text/plain"
+        );
+        assert_eq!(
+            detect_media_type(Path::new("blob"), Some(&[0xff, 0xfe, 0x00])),
+            "application/octet-stream"
+        );
+    }
+
+    #[test]
+    fn test_stage_and_collect() {
+        let ws = Workspace::create(&WorkspaceConfig::default()).unwrap();
+        ws.stage(&[
+            InputFile::new("data/in.csv", "a,b\n"),
+            InputFile::new("keep.txt", "same"),
+        ])
+        .unwrap();
+        let before = ws.snapshot();
+
+        std::fs::write(ws.path().join("data/in.csv"), "a,b\n1,2\n").unwrap();
+        std::fs::write(ws.path().join("out.json"), "{}").unwrap();
+        let artifacts = ws.collect(&before);
+
+        let paths: Vec<&str> = artifacts.iter().map(|a| a.path.as_str()).collect();
+        assert_eq!(paths, vec!["data/in.csv", "out.json"]);
+        assert_eq!(artifacts[1].media_type, "application/json");
+        assert_eq!(artifacts[1].data.as_deref(), Some("e30="));
+
+        let dir = ws.path().to_path_buf();
+        drop(ws);
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn test_stage_rejects_escaping_paths() {
+        let ws = Workspace::create(&WorkspaceConfig::default()).unwrap();
+        assert!(ws.stage(&[InputFile::new("../evil", "x")]).is_err());
+        assert!(ws.stage(&[InputFile::new("/etc/evil", "x")]).is_err());
+    }
+
+    #[test]
+    fn test_size_limits() {
+        let config = WorkspaceConfig {
+            max_artifact_bytes: 4,
+            max_artifacts: 2,
+            ..Default::default()
+        };
+        let ws = Workspace::create(&config).unwrap();
+        let before = ws.snapshot();
+        std::fs::write(ws.path().join("a.txt"), "tiny").unwrap();
+        std::fs::write(ws.path().join("b.txt"), "too large").unwrap();
+        std::fs::write(ws.path().join("c.txt"), "over count").unwrap();
+
+        let artifacts = ws.collect(&before);
+        assert_eq!(artifacts.len(), 2);
+        assert!(artifacts[0].data.is_some());
+        assert_eq!(artifacts[1].data, None);
+        assert_eq!(artifacts[1].size, 9);
+    }
+
+    #[test]
+    fn test_input_file_from_content() {
+        let image = Content::Image {
+            url: None,
+            base64: Some("aGVsbG8=".into()),
+            media_type: Some("image/png".into()),
+        };
+        assert_eq!(
+            InputFile::from_content("img.png", &image).unwrap().data,
+            b"hello"
+        );
+        let url_only = Content::File {
+            url: Some("https://example.com/x.csv".into()),
+            base64: None,
+            media_type: None,
+        };
+        assert!(InputFile::from_content("x.csv", &url_only).is_none());
+    }
+
+    #[test]
+    fn test_artifact_to_content() {
+        let image = Artifact {
+            path: "plot.png".into(),
+            media_type: "image/png".into(),
+            size: 3,
+            data: Some("AAAA".into()),
+        };
+        assert!(matches!(
+            image.to_content(),
+            Some(Content::GeneratedImage { .. })
+        ));
+        let csv = Artifact {
+            media_type: "text/csv".into(),
+            ..image.clone()
+        };
+        assert!(matches!(csv.to_content(), Some(Content::File { .. })));
+        let omitted = Artifact {
+            data: None,
+            ..image
+        };
+        assert!(omitted.to_content().is_none());
+    }
+}
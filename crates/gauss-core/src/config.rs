@@ -109,6 +109,9 @@ pub enum StopConditionDef {
     HasToolCall(String),
     TextGenerated,
     Custom(String),
+    MaxCostUsd(f64),
+    MaxTotalTokens(u64),
+    MaxReasoningTokens(u64),
 }
 
 impl From<StopConditionDef> for crate::agent::StopCondition {
@@ -118,6 +121,13 @@ impl From<StopConditionDef> for crate::agent::StopCondition {
             StopConditionDef::HasToolCall(name) => crate::agent::StopCondition::HasToolCall(name),
             StopConditionDef::TextGenerated => crate::agent::StopCondition::TextGenerated,
             StopConditionDef::Custom(name) => crate::agent::StopCondition::Custom(name),
+            StopConditionDef::MaxCostUsd(max) => crate::agent::StopCondition::MaxCostUsd(max),
+            StopConditionDef::MaxTotalTokens(max) => {
+                crate::agent::StopCondition::MaxTotalTokens(max)
+            }
+            StopConditionDef::MaxReasoningTokens(max) => {
+                crate::agent::StopCondition::MaxReasoningTokens(max)
+            }
         }
     }
 }
@@ -11,20 +11,9 @@ use serde::{Deserialize, Serialize};
 // Token Counter
 // ---------------------------------------------------------------------------
 
-/// Model context window sizes (approximate).
+/// Model context window size, from the global model catalog.
 pub fn context_window_size(model: &str) -> usize {
-    match model {
-        m if m.contains("gpt-4o") => 128_000,
-        m if m.contains("gpt-4-turbo") => 128_000,
-        m if m.contains("gpt-4") => 8_192,
-        m if m.contains("gpt-3.5") => 16_385,
-        m if m.contains("claude-3") => 200_000,
-        m if m.contains("claude-4") => 200_000,
-        m if m.contains("gemini") => 1_000_000,
-        m if m.contains("deepseek") => 64_000,
-        m if m.contains("llama") => 128_000,
-        _ => 128_000, // conservative default
-    }
+    crate::catalog::read().context_window(model)
 }
 
 /// Approximate token counter (4 chars ≈ 1 token).
@@ -55,9 +44,20 @@ pub fn count_tokens(text: &str) -> usize {
 /// Supports cl100k_base (GPT-4/3.5), o200k_base (GPT-4o), etc.
 #[cfg(all(feature = "native", not(target_arch = "wasm32")))]
 pub fn count_tokens_for_model(text: &str, model: &str) -> usize {
-    tiktoken_rs::get_bpe_from_model(model)
-        .map(|enc| enc.encode_ordinary(text).len())
-        .unwrap_or_else(|_| count_tokens(text))
+    use crate::catalog::TokenizerFamily;
+    use std::sync::OnceLock;
+    static O200K: OnceLock<Option<tiktoken_rs::CoreBPE>> = OnceLock::new();
+
+    match crate::catalog::read().tokenizer(model) {
+        TokenizerFamily::Cl100k => count_tokens(text),
+        TokenizerFamily::O200k => match O200K.get_or_init(|| tiktoken_rs::o200k_base().ok()) {
+            Some(enc) => enc.encode_ordinary(text).len(),
+            None => count_tokens(text),
+        },
+        _ => tiktoken_rs::get_bpe_from_model(model)
+            .map(|enc| enc.encode_ordinary(text).len())
+            .unwrap_or_else(|_| count_tokens(text)),
+    }
 }
 
 #[cfg(any(not(feature = "native"), target_arch = "wasm32"))]
@@ -80,7 +80,7 @@ pub fn count_message_tokens(msg: &Message) -> usize {
             crate::message::Content::ToolResult { content, .. } => {
                 count_tokens_approx(&content.to_string())
             }
-            crate::message::Content::Reasoning { text } => count_tokens_approx(text),
+            crate::message::Content::Reasoning { text, .. } => count_tokens_approx(text),
             crate::message::Content::File { .. } => 50,
             crate::message::Content::Document { data, .. } => {
                 data.as_ref().map_or(100, |d| count_tokens_approx(d))
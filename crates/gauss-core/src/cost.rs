@@ -1,19 +1,35 @@
 //! Cost estimation primitives for enterprise usage tracking.
 
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
 use crate::message::Usage;
 
-#[derive(Debug, Clone, Copy)]
+/// Per-million-token prices for a model, in USD.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Pricing {
     pub input_per_million: f64,
     pub output_per_million: f64,
     pub reasoning_per_million: f64,
+    #[serde(default)]
     pub cache_read_per_million: f64,
+    #[serde(default)]
     pub cache_write_per_million: f64,
+    /// Higher rates charged once a request's input exceeds a threshold.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub long_context: Option<LongContextPricing>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Long-context pricing tier (e.g. prompts above 200k tokens).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct LongContextPricing {
+    pub above_input_tokens: u64,
+    pub input_per_million: f64,
+    pub output_per_million: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CostEstimate {
     pub model: String,
     pub normalized_model: String,
@@ -31,158 +47,117 @@ pub struct CostEstimate {
     pub total_cost_usd: f64,
 }
 
-const DEFAULT_PRICING: Pricing = Pricing {
+impl CostEstimate {
+    /// Add another estimate's token counts and costs into this one.
+    pub fn accumulate(&mut self, other: &CostEstimate) {
+        if self.currency.is_empty() {
+            self.currency = other.currency.clone();
+        }
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.reasoning_tokens += other.reasoning_tokens;
+        self.cache_read_tokens += other.cache_read_tokens;
+        self.cache_creation_tokens += other.cache_creation_tokens;
+        self.input_cost_usd += other.input_cost_usd;
+        self.output_cost_usd += other.output_cost_usd;
+        self.reasoning_cost_usd += other.reasoning_cost_usd;
+        self.cache_read_cost_usd += other.cache_read_cost_usd;
+        self.cache_creation_cost_usd += other.cache_creation_cost_usd;
+        self.total_cost_usd += other.total_cost_usd;
+    }
+}
+
+/// Cost of a whole run (agent, team or graph), broken down per step and per model.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RunCost {
+    pub total_cost_usd: f64,
+    /// One estimate per provider call, in call order.
+    pub steps: Vec<CostEstimate>,
+    /// Estimates summed per model name.
+    pub by_model: BTreeMap<String, CostEstimate>,
+}
+
+impl RunCost {
+    /// Record the estimate of a single provider call.
+    pub fn record(&mut self, estimate: CostEstimate) {
+        self.total_cost_usd += estimate.total_cost_usd;
+        self.by_model
+            .entry(estimate.model.clone())
+            .or_insert_with(|| CostEstimate {
+                model: estimate.model.clone(),
+                normalized_model: estimate.normalized_model.clone(),
+                ..CostEstimate::default()
+            })
+            .accumulate(&estimate);
+        self.steps.push(estimate);
+    }
+
+    /// Record one provider call served by `model` and return its estimate.
+    ///
+    /// When `provider_metadata` carries a `usage_by_model` breakdown (see
+    /// [`ModelUsage`]), each model's tokens are priced and recorded
+    /// separately, and the returned estimate is their sum under `model`.
+    pub fn record_call(
+        &mut self,
+        model: &str,
+        usage: &Usage,
+        provider_metadata: &serde_json::Value,
+    ) -> CostEstimate {
+        let breakdown = provider_metadata
+            .get(ModelUsage::METADATA_KEY)
+            .and_then(|v| serde_json::from_value::<Vec<ModelUsage>>(v.clone()).ok())
+            .filter(|b| !b.is_empty());
+        let Some(breakdown) = breakdown else {
+            let estimate = estimate_cost(model, usage);
+            self.record(estimate.clone());
+            return estimate;
+        };
+        let mut total = CostEstimate {
+            model: model.to_string(),
+            normalized_model: normalize_model(model),
+            ..CostEstimate::default()
+        };
+        for part in breakdown {
+            let estimate = estimate_cost(&part.model, &part.usage);
+            total.accumulate(&estimate);
+            self.record(estimate);
+        }
+        total
+    }
+
+    /// Fold another run's cost into this one (e.g. a team member's run).
+    pub fn merge(&mut self, other: &RunCost) {
+        for estimate in &other.steps {
+            self.record(estimate.clone());
+        }
+    }
+}
+
+/// Tokens one model spent within a single provider call.
+///
+/// Providers that fan a call out to several models (e.g. an ensemble) list
+/// these under `provider_metadata.usage_by_model` so each model is priced at
+/// its own rate.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelUsage {
+    pub model: String,
+    pub usage: Usage,
+}
+
+impl ModelUsage {
+    pub const METADATA_KEY: &'static str = "usage_by_model";
+}
+
+pub(crate) const DEFAULT_PRICING: Pricing = Pricing {
     input_per_million: 1.0,
     output_per_million: 3.0,
     reasoning_per_million: 3.0,
     cache_read_per_million: 0.2,
     cache_write_per_million: 1.0,
+    long_context: None,
 };
 
-const PRICING_TABLE: &[(&str, Pricing)] = &[
-    (
-        "gpt-5",
-        Pricing {
-            input_per_million: 1.25,
-            output_per_million: 10.0,
-            reasoning_per_million: 10.0,
-            cache_read_per_million: 0.125,
-            cache_write_per_million: 1.25,
-        },
-    ),
-    (
-        "gpt-4o",
-        Pricing {
-            input_per_million: 5.0,
-            output_per_million: 15.0,
-            reasoning_per_million: 15.0,
-            cache_read_per_million: 2.5,
-            cache_write_per_million: 5.0,
-        },
-    ),
-    (
-        "gpt-4.1",
-        Pricing {
-            input_per_million: 2.0,
-            output_per_million: 8.0,
-            reasoning_per_million: 8.0,
-            cache_read_per_million: 1.0,
-            cache_write_per_million: 2.0,
-        },
-    ),
-    (
-        "o4",
-        Pricing {
-            input_per_million: 1.1,
-            output_per_million: 4.4,
-            reasoning_per_million: 4.4,
-            cache_read_per_million: 0.55,
-            cache_write_per_million: 1.1,
-        },
-    ),
-    (
-        "o3",
-        Pricing {
-            input_per_million: 1.0,
-            output_per_million: 4.0,
-            reasoning_per_million: 4.0,
-            cache_read_per_million: 0.5,
-            cache_write_per_million: 1.0,
-        },
-    ),
-    (
-        "claude-opus-4",
-        Pricing {
-            input_per_million: 15.0,
-            output_per_million: 75.0,
-            reasoning_per_million: 75.0,
-            cache_read_per_million: 1.5,
-            cache_write_per_million: 15.0,
-        },
-    ),
-    (
-        "claude-sonnet-4",
-        Pricing {
-            input_per_million: 3.0,
-            output_per_million: 15.0,
-            reasoning_per_million: 15.0,
-            cache_read_per_million: 0.3,
-            cache_write_per_million: 3.0,
-        },
-    ),
-    (
-        "claude-haiku-4",
-        Pricing {
-            input_per_million: 0.8,
-            output_per_million: 4.0,
-            reasoning_per_million: 4.0,
-            cache_read_per_million: 0.08,
-            cache_write_per_million: 0.8,
-        },
-    ),
-    (
-        "gemini-2.5-pro",
-        Pricing {
-            input_per_million: 1.25,
-            output_per_million: 5.0,
-            reasoning_per_million: 5.0,
-            cache_read_per_million: 0.125,
-            cache_write_per_million: 1.25,
-        },
-    ),
-    (
-        "gemini-2.5-flash",
-        Pricing {
-            input_per_million: 0.3,
-            output_per_million: 2.5,
-            reasoning_per_million: 2.5,
-            cache_read_per_million: 0.03,
-            cache_write_per_million: 0.3,
-        },
-    ),
-    (
-        "deepseek-reasoner",
-        Pricing {
-            input_per_million: 0.55,
-            output_per_million: 2.2,
-            reasoning_per_million: 2.2,
-            cache_read_per_million: 0.055,
-            cache_write_per_million: 0.55,
-        },
-    ),
-    (
-        "deepseek-chat",
-        Pricing {
-            input_per_million: 0.27,
-            output_per_million: 1.1,
-            reasoning_per_million: 1.1,
-            cache_read_per_million: 0.027,
-            cache_write_per_million: 0.27,
-        },
-    ),
-    (
-        "llama-3.3",
-        Pricing {
-            input_per_million: 0.59,
-            output_per_million: 0.79,
-            reasoning_per_million: 0.79,
-            cache_read_per_million: 0.059,
-            cache_write_per_million: 0.59,
-        },
-    ),
-    (
-        "mixtral",
-        Pricing {
-            input_per_million: 0.6,
-            output_per_million: 0.6,
-            reasoning_per_million: 0.6,
-            cache_read_per_million: 0.06,
-            cache_write_per_million: 0.6,
-        },
-    ),
-];
-
-fn normalize_model(model: &str) -> String {
+pub(crate) fn normalize_model(model: &str) -> String {
     let lower = model.trim().to_lowercase();
     if let Some((prefix, tail)) = lower.split_once('/')
         && matches!(
@@ -203,30 +178,38 @@ fn normalize_model(model: &str) -> String {
     lower
 }
 
-fn resolve_pricing(model: &str) -> Pricing {
-    let normalized = normalize_model(model);
-    for (prefix, pricing) in PRICING_TABLE {
-        if normalized.starts_with(prefix) {
-            return *pricing;
-        }
-    }
-    DEFAULT_PRICING
-}
-
 fn calc_usd(tokens: u64, per_million: f64) -> f64 {
     (tokens as f64 / 1_000_000.0) * per_million
 }
 
+/// Estimate the cost of a call using pricing from the global model catalog.
 pub fn estimate_cost(model: &str, usage: &Usage) -> CostEstimate {
-    let pricing = resolve_pricing(model);
+    crate::catalog::read().estimate_cost(model, usage)
+}
+
+/// Estimate the cost of a call with explicit pricing.
+pub fn estimate_cost_with_pricing(model: &str, pricing: &Pricing, usage: &Usage) -> CostEstimate {
     let normalized_model = normalize_model(model);
     let reasoning_tokens = usage.reasoning_tokens.unwrap_or(0);
     let cache_read_tokens = usage.cache_read_tokens.unwrap_or(0);
     let cache_creation_tokens = usage.cache_creation_tokens.unwrap_or(0);
 
-    let input_cost_usd = calc_usd(usage.input_tokens, pricing.input_per_million);
-    let output_cost_usd = calc_usd(usage.output_tokens, pricing.output_per_million);
-    let reasoning_cost_usd = calc_usd(reasoning_tokens, pricing.reasoning_per_million);
+    let (input_rate, output_rate, reasoning_rate) = match pricing.long_context {
+        Some(tier) if usage.input_tokens > tier.above_input_tokens => (
+            tier.input_per_million,
+            tier.output_per_million,
+            tier.output_per_million,
+        ),
+        _ => (
+            pricing.input_per_million,
+            pricing.output_per_million,
+            pricing.reasoning_per_million,
+        ),
+    };
+
+    let input_cost_usd = calc_usd(usage.input_tokens, input_rate);
+    let output_cost_usd = calc_usd(usage.output_tokens, output_rate);
+    let reasoning_cost_usd = calc_usd(reasoning_tokens, reasoning_rate);
     let cache_read_cost_usd = calc_usd(cache_read_tokens, pricing.cache_read_per_million);
     let cache_creation_cost_usd = calc_usd(cache_creation_tokens, pricing.cache_write_per_million);
     let total_cost_usd = input_cost_usd
@@ -281,4 +264,30 @@ mod tests {
         let estimate = estimate_cost("openai/gpt-5.2", &usage);
         assert_eq!(estimate.normalized_model, "gpt-5.2");
     }
+
+    #[test]
+    fn run_cost_breaks_down_per_step_and_model() {
+        let usage = Usage {
+            input_tokens: 100_000,
+            output_tokens: 0,
+            ..Usage::default()
+        };
+        let mut run = RunCost::default();
+        run.record(estimate_cost("gpt-4o", &usage));
+        run.record(estimate_cost("gpt-4o", &usage));
+        run.record(estimate_cost("claude-sonnet-4", &usage));
+
+        assert_eq!(run.steps.len(), 3);
+        assert_eq!(run.by_model.len(), 2);
+        let gpt = &run.by_model["gpt-4o"];
+        assert_eq!(gpt.input_tokens, 200_000);
+        assert!((gpt.total_cost_usd - 1.0).abs() < 1e-9);
+        assert!((run.total_cost_usd - 1.3).abs() < 1e-9);
+
+        let mut merged = RunCost::default();
+        merged.merge(&run);
+        merged.merge(&run);
+        assert_eq!(merged.steps.len(), 6);
+        assert!((merged.total_cost_usd - 2.6).abs() < 1e-9);
+    }
 }
@@ -40,9 +40,10 @@ pub enum GaussError {
     #[error("Schema validation error: {message}")]
     SchemaValidation { message: String },
 
-    #[error("Rate limited by provider '{provider}', retry after {retry_after_ms:?}ms")]
+    #[error("Rate limited by provider '{provider}', retry after {retry_after_ms:?}ms: {message}")]
     RateLimited {
         provider: String,
+        message: String,
         retry_after_ms: Option<u64>,
     },
 
@@ -99,9 +100,10 @@ impl GaussError {
         }
     }
 
-    pub fn rate_limited(provider: impl Into<String>, _message: impl Into<String>) -> Self {
+    pub fn rate_limited(provider: impl Into<String>, message: impl Into<String>) -> Self {
         Self::RateLimited {
             provider: provider.into(),
+            message: message.into(),
             retry_after_ms: None,
         }
     }
@@ -117,6 +119,34 @@ impl GaussError {
             message: message.into(),
         }
     }
+
+    pub fn plugin(name: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::PluginError {
+            plugin: name.into(),
+            message: message.into(),
+        }
+    }
+
+    /// Stable snake_case name of the variant, for metric labels and span attributes.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Provider { .. } => "provider",
+            Self::Agent { .. } => "agent",
+            Self::Tool { .. } => "tool",
+            Self::Stream { .. } => "stream",
+            Self::Config { .. } => "config",
+            Self::SchemaValidation { .. } => "schema_validation",
+            Self::RateLimited { .. } => "rate_limited",
+            Self::Authentication { .. } => "authentication",
+            Self::Aborted => "aborted",
+            Self::Timeout { .. } => "timeout",
+            Self::NoContentGenerated => "no_content_generated",
+            Self::Guardrail { .. } => "guardrail",
+            Self::CircuitBreakerOpen { .. } => "circuit_breaker_open",
+            Self::PluginError { .. } => "plugin",
+            Self::Internal { .. } => "internal",
+        }
+    }
 }
 
 pub type Result<T> = std::result::Result<T, GaussError>;
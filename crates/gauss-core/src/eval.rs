@@ -2,10 +2,24 @@
 //!
 //! Provides traits for scoring, built-in scorers, dataset loading,
 //! and a batch evaluation runner.
+//!
+//! Besides the string-based scorers there are model-graded ones: [`JudgeScorer`]
+//! (rubric grading), [`PairwiseScorer`], [`FaithfulnessScorer`] (RAG grounding) and
+//! the embedding-based [`SemanticSimilarityScorer`]. Their reasoning is recorded in
+//! [`EvalResult::metadata`].
+//!
+//! Agent runs can also be judged on the tools they called: give a case an
+//! [`ExpectedTrajectory`], evaluate with [`EvalRunner::run_agent`], and add a
+//! [`TrajectoryScorer`]. Scorers see the whole [`AgentOutput`] via [`Scorer::score_agent`].
 
+use crate::agent::AgentOutput;
 use crate::error;
+use crate::message::Message;
+use crate::provider::{GenerateOptions, Provider};
+use crate::rag::{Embedding, cosine_similarity};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
 use std::collections::HashMap;
 
 // ---------------------------------------------------------------------------
@@ -23,6 +37,9 @@ pub struct EvalCase {
     pub context: Option<String>,
     #[serde(default)]
     pub metadata: HashMap<String, serde_json::Value>,
+    /// Tool calls the agent is expected to make (see [`TrajectoryScorer`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_trajectory: Option<ExpectedTrajectory>,
 }
 
 /// The result of evaluating a single case.
@@ -35,6 +52,71 @@ pub struct EvalResult {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
     pub duration_ms: u64,
+    /// Per-scorer details (e.g. judge reasoning), keyed by scorer name.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub metadata: HashMap<String, Value>,
+    /// Spread across repeated trials (set when the runner uses `trials > 1`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trial_stats: Option<TrialStats>,
+}
+
+/// Per-case statistics over repeated trials. `EvalResult::scores` holds the means.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TrialStats {
+    pub trials: usize,
+    /// Trials whose `eval_fn` call failed (not scored).
+    pub errors: usize,
+    pub scores: HashMap<String, ScoreStats>,
+}
+
+/// Summary statistics of one scorer's scores.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct ScoreStats {
+    pub mean: f64,
+    /// Sample standard deviation (0 for a single value).
+    pub std_dev: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl ScoreStats {
+    pub fn from_values(values: &[f64]) -> Self {
+        if values.is_empty() {
+            return Self::default();
+        }
+        let n = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / n;
+        let variance = if values.len() > 1 {
+            values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0)
+        } else {
+            0.0
+        };
+        Self {
+            mean,
+            std_dev: variance.sqrt(),
+            min: values.iter().copied().fold(f64::INFINITY, f64::min),
+            max: values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        }
+    }
+}
+
+impl TrialStats {
+    fn from_trials(trials: &[EvalResult], errors: usize) -> Self {
+        let mut values: HashMap<String, Vec<f64>> = HashMap::new();
+        for trial in trials {
+            for (name, score) in &trial.scores {
+                values.entry(name.clone()).or_default().push(*score);
+            }
+        }
+        Self {
+            trials: trials.len() + errors,
+            errors,
+            scores: values
+                .into_iter()
+                .map(|(name, v)| (name, ScoreStats::from_values(&v)))
+                .collect(),
+        }
+    }
 }
 
 /// Aggregate results for a full evaluation run.
@@ -59,6 +141,35 @@ impl EvalReport {
     }
 }
 
+/// A score plus the scorer's explanation.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScoreDetail {
+    pub score: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// Scorer-specific details (per-criterion scores, extracted claims, ...).
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub metadata: Value,
+}
+
+impl ScoreDetail {
+    pub fn new(score: f64) -> Self {
+        Self {
+            score,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_reason(mut self, reason: impl Into<String>) -> Self {
+        self.reason = Some(reason.into());
+        self
+    }
+
+    fn has_details(&self) -> bool {
+        self.reason.is_some() || !self.metadata.is_null()
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Scorer Trait
 // ---------------------------------------------------------------------------
@@ -77,6 +188,36 @@ pub trait Scorer: Send + Sync {
         expected: Option<&str>,
         context: Option<&str>,
     ) -> error::Result<f64>;
+
+    /// Score a response and explain the score. The runner records the explanation in
+    /// [`EvalResult::metadata`]. Defaults to [`score`](Self::score) with no explanation.
+    async fn score_detailed(
+        &self,
+        input: &str,
+        output: &str,
+        expected: Option<&str>,
+        context: Option<&str>,
+    ) -> error::Result<ScoreDetail> {
+        Ok(ScoreDetail::new(
+            self.score(input, output, expected, context).await?,
+        ))
+    }
+
+    /// Score a full agent run (final text plus every step's tool calls).
+    /// Defaults to [`score_detailed`](Self::score_detailed) on the final text.
+    async fn score_agent(
+        &self,
+        case: &EvalCase,
+        output: &AgentOutput,
+    ) -> error::Result<ScoreDetail> {
+        self.score_detailed(
+            &case.input,
+            &output.text,
+            case.expected_output.as_deref(),
+            case.context.as_deref(),
+        )
+        .await
+    }
 }
 
 #[cfg(target_arch = "wasm32")]
@@ -91,6 +232,32 @@ pub trait Scorer {
         expected: Option<&str>,
         context: Option<&str>,
     ) -> error::Result<f64>;
+
+    async fn score_detailed(
+        &self,
+        input: &str,
+        output: &str,
+        expected: Option<&str>,
+        context: Option<&str>,
+    ) -> error::Result<ScoreDetail> {
+        Ok(ScoreDetail::new(
+            self.score(input, output, expected, context).await?,
+        ))
+    }
+
+    async fn score_agent(
+        &self,
+        case: &EvalCase,
+        output: &AgentOutput,
+    ) -> error::Result<ScoreDetail> {
+        self.score_detailed(
+            &case.input,
+            &output.text,
+            case.expected_output.as_deref(),
+            case.context.as_deref(),
+        )
+        .await
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -187,161 +354,1773 @@ impl Scorer for LengthRatioScorer {
 }
 
 // ---------------------------------------------------------------------------
-// Dataset Loading
+// Model-Graded Scorers
 // ---------------------------------------------------------------------------
 
-/// Load eval cases from a JSONL string.
-pub fn load_dataset_jsonl(jsonl: &str) -> error::Result<Vec<EvalCase>> {
-    jsonl
-        .lines()
-        .filter(|line| !line.trim().is_empty())
-        .enumerate()
-        .map(|(i, line)| {
-            serde_json::from_str(line).map_err(|e| {
-                error::GaussError::internal(format!("Failed to parse line {}: {}", i + 1, e))
-            })
-        })
-        .collect()
+/// One grading criterion of a [`Rubric`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RubricCriterion {
+    pub name: String,
+    pub description: String,
+    #[serde(default = "default_weight")]
+    pub weight: f64,
 }
 
-/// Load eval cases from a JSON array string.
-pub fn load_dataset_json(json: &str) -> error::Result<Vec<EvalCase>> {
-    serde_json::from_str(json)
-        .map_err(|e| error::GaussError::internal(format!("Failed to parse dataset: {}", e)))
+fn default_weight() -> f64 {
+    1.0
 }
 
-// ---------------------------------------------------------------------------
-// Eval Runner
-// ---------------------------------------------------------------------------
-
-/// Batch evaluation runner.
-pub struct EvalRunner {
-    scorers: Vec<crate::Shared<dyn Scorer>>,
-    /// Minimum average score to pass (default 0.5).
-    pub pass_threshold: f64,
+/// What a [`JudgeScorer`] grades against. The overall score is the weighted mean
+/// of the per-criterion scores.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rubric {
+    pub criteria: Vec<RubricCriterion>,
+    /// Extra instructions for the judge (domain, strictness, ...).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<String>,
 }
 
-impl Default for EvalRunner {
+impl Default for Rubric {
     fn default() -> Self {
-        Self::new()
+        Self {
+            criteria: vec![RubricCriterion {
+                name: "correctness".into(),
+                description: "The response correctly and completely answers the input, \
+                              agreeing with the reference answer when one is given."
+                    .into(),
+                weight: 1.0,
+            }],
+            instructions: None,
+        }
     }
 }
 
-impl EvalRunner {
+impl Rubric {
+    /// An empty rubric; add criteria with [`criterion`](Self::criterion).
     pub fn new() -> Self {
         Self {
-            scorers: Vec::new(),
-            pass_threshold: 0.5,
+            criteria: Vec::new(),
+            instructions: None,
         }
     }
 
-    pub fn with_threshold(mut self, threshold: f64) -> Self {
-        self.pass_threshold = threshold;
+    pub fn criterion(
+        mut self,
+        name: impl Into<String>,
+        description: impl Into<String>,
+        weight: f64,
+    ) -> Self {
+        self.criteria.push(RubricCriterion {
+            name: name.into(),
+            description: description.into(),
+            weight,
+        });
         self
     }
 
-    pub fn add_scorer(&mut self, scorer: crate::Shared<dyn Scorer>) {
-        self.scorers.push(scorer);
+    pub fn instructions(mut self, instructions: impl Into<String>) -> Self {
+        self.instructions = Some(instructions.into());
+        self
     }
 
-    /// Evaluate a single case given the actual output.
-    pub async fn evaluate_case(
-        &self,
-        case: &EvalCase,
-        actual_output: &str,
-    ) -> error::Result<EvalResult> {
-        let start = std::time::Instant::now();
-        let mut scores = HashMap::new();
+    /// Weighted mean of the judge's per-criterion scores; missing criteria count as 0.
+    fn aggregate(&self, graded: &[Value]) -> f64 {
+        let total_weight: f64 = self.criteria.iter().map(|c| c.weight).sum();
+        if total_weight <= 0.0 {
+            return 0.0;
+        }
+        let weighted: f64 = self
+            .criteria
+            .iter()
+            .map(|c| {
+                let score = graded
+                    .iter()
+                    .find(|g| g["name"].as_str() == Some(c.name.as_str()))
+                    .and_then(|g| g["score"].as_f64())
+                    .unwrap_or(0.0);
+                c.weight * score.clamp(0.0, 1.0)
+            })
+            .sum();
+        weighted / total_weight
+    }
+}
 
-        for scorer in &self.scorers {
-            let score = scorer
-                .score(
-                    &case.input,
-                    actual_output,
-                    case.expected_output.as_deref(),
-                    case.context.as_deref(),
-                )
-                .await?;
-            scores.insert(scorer.name().to_string(), score);
+/// Sends one grading request and parses the JSON verdict.
+async fn ask_judge(
+    provider: &dyn Provider,
+    options: &GenerateOptions,
+    system: &str,
+    prompt: String,
+    schema: Value,
+) -> error::Result<Value> {
+    let mut options = options.clone();
+    options.output_schema = Some(schema);
+    let messages = [Message::system(system), Message::user(prompt)];
+    let result = provider.generate(&messages, &[], &options).await?;
+    let text = result.text().unwrap_or_default();
+    parse_judge_json(text)
+        .ok_or_else(|| error::GaussError::internal(format!("Judge returned invalid JSON: {text}")))
+}
+
+/// Parse a JSON object from judge output, tolerating code fences and surrounding prose.
+pub(crate) fn parse_judge_json(text: &str) -> Option<Value> {
+    let trimmed = text.trim();
+    if let Ok(v @ Value::Object(_)) = serde_json::from_str(trimmed) {
+        return Some(v);
+    }
+    let start = trimmed.find('{')?;
+    let end = trimmed.rfind('}')?;
+    serde_json::from_str(trimmed.get(start..=end)?).ok()
+}
+
+fn judge_options() -> GenerateOptions {
+    GenerateOptions {
+        temperature: Some(0.0),
+        ..Default::default()
+    }
+}
+
+/// LLM-as-judge scorer — grades a response against a [`Rubric`] with any [`Provider`].
+///
+/// The judge sees the input, the response and, when present, the reference answer and
+/// context. It replies with a per-criterion score (0.0–1.0) and reason, requested via
+/// [`GenerateOptions::output_schema`].
+pub struct JudgeScorer {
+    provider: crate::Shared<dyn Provider>,
+    name: String,
+    rubric: Rubric,
+    options: GenerateOptions,
+}
+
+impl JudgeScorer {
+    pub fn new(provider: crate::Shared<dyn Provider>) -> Self {
+        Self {
+            provider,
+            name: "judge".into(),
+            rubric: Rubric::default(),
+            options: judge_options(),
         }
+    }
 
-        let avg = if scores.is_empty() {
-            0.0
-        } else {
-            scores.values().sum::<f64>() / scores.len() as f64
-        };
+    /// Scorer name used as the key in `EvalResult::scores` (default `"judge"`).
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
 
-        Ok(EvalResult {
-            case_id: case.id.clone(),
-            scores,
-            passed: avg >= self.pass_threshold,
-            actual_output: actual_output.to_string(),
-            error: None,
-            duration_ms: start.elapsed().as_millis() as u64,
-        })
+    pub fn with_rubric(mut self, rubric: Rubric) -> Self {
+        self.rubric = rubric;
+        self
     }
 
-    /// Run evaluation on a dataset with a provided evaluation function.
-    pub async fn run<F, Fut>(&self, dataset: &[EvalCase], eval_fn: F) -> error::Result<EvalReport>
-    where
-        F: Fn(&EvalCase) -> Fut,
-        Fut: std::future::Future<Output = error::Result<String>>,
-    {
-        let run_start = std::time::Instant::now();
-        let mut results = Vec::new();
-        let mut passed = 0;
-        let mut failed = 0;
-        let mut errored = 0;
+    /// Generation options for the judge call (default: temperature 0).
+    pub fn with_options(mut self, options: GenerateOptions) -> Self {
+        self.options = options;
+        self
+    }
 
-        for case in dataset {
-            match eval_fn(case).await {
-                Ok(output) => {
-                    let result = self.evaluate_case(case, &output).await?;
-                    if result.passed {
-                        passed += 1;
-                    } else {
-                        failed += 1;
+    /// JSON schema the judge must answer with.
+    pub fn output_schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "criteria": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "name": { "type": "string" },
+                            "score": { "type": "number", "minimum": 0, "maximum": 1 },
+                            "reason": { "type": "string" }
+                        },
+                        "required": ["name", "score", "reason"]
                     }
-                    results.push(result);
-                }
-                Err(e) => {
-                    errored += 1;
-                    results.push(EvalResult {
-                        case_id: case.id.clone(),
-                        scores: HashMap::new(),
-                        passed: false,
-                        actual_output: String::new(),
-                        error: Some(e.to_string()),
-                        duration_ms: 0,
-                    });
-                }
-            }
+                },
+                "reasoning": { "type": "string" }
+            },
+            "required": ["criteria", "reasoning"]
+        })
+    }
+
+    fn prompt(
+        &self,
+        input: &str,
+        output: &str,
+        expected: Option<&str>,
+        context: Option<&str>,
+    ) -> String {
+        let mut prompt = format!("INPUT:\n{input}\n\nRESPONSE:\n{output}\n");
+        if let Some(expected) = expected {
+            prompt.push_str(&format!("\nREFERENCE ANSWER:\n{expected}\n"));
         }
+        if let Some(context) = context {
+            prompt.push_str(&format!("\nCONTEXT:\n{context}\n"));
+        }
+        prompt.push_str("\nCRITERIA:\n");
+        for c in &self.rubric.criteria {
+            prompt.push_str(&format!("- {}: {}\n", c.name, c.description));
+        }
+        prompt
+    }
+}
 
-        // Compute average scores
-        let mut avg_scores: HashMap<String, f64> = HashMap::new();
-        let valid_results: Vec<&EvalResult> =
-            results.iter().filter(|r| r.error.is_none()).collect();
-        if !valid_results.is_empty() {
-            for r in &valid_results {
-                for (name, score) in &r.scores {
-                    *avg_scores.entry(name.clone()).or_insert(0.0) += score;
-                }
-            }
-            let count = valid_results.len() as f64;
-            for v in avg_scores.values_mut() {
-                *v /= count;
-            }
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Scorer for JudgeScorer {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn score(
+        &self,
+        input: &str,
+        output: &str,
+        expected: Option<&str>,
+        context: Option<&str>,
+    ) -> error::Result<f64> {
+        Ok(self
+            .score_detailed(input, output, expected, context)
+            .await?
+            .score)
+    }
+
+    async fn score_detailed(
+        &self,
+        input: &str,
+        output: &str,
+        expected: Option<&str>,
+        context: Option<&str>,
+    ) -> error::Result<ScoreDetail> {
+        let mut system = String::from(
+            "You are an impartial evaluator. Grade the RESPONSE to the INPUT on each \
+             criterion with a score from 0.0 (fails) to 1.0 (fully meets) and a short reason. \
+             Reply with JSON only: {\"criteria\": [{\"name\", \"score\", \"reason\"}], \
+             \"reasoning\": \"overall justification\"}.",
+        );
+        if let Some(ref instructions) = self.rubric.instructions {
+            system.push_str("\n\n");
+            system.push_str(instructions);
         }
+        let verdict = ask_judge(
+            self.provider.as_ref(),
+            &self.options,
+            &system,
+            self.prompt(input, output, expected, context),
+            Self::output_schema(),
+        )
+        .await?;
 
-        Ok(EvalReport {
-            total: dataset.len(),
-            passed,
-            failed,
-            errored,
-            avg_scores,
-            results,
-            total_duration_ms: run_start.elapsed().as_millis() as u64,
+        let criteria = verdict["criteria"].as_array().cloned().unwrap_or_default();
+        Ok(ScoreDetail {
+            score: self.rubric.aggregate(&criteria),
+            reason: verdict["reasoning"].as_str().map(String::from),
+            metadata: json!({ "criteria": criteria }),
+        })
+    }
+}
+
+/// Winner of a pairwise comparison.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PairwiseWinner {
+    A,
+    B,
+    Tie,
+}
+
+/// A judge's verdict on two responses to the same input.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PairwiseVerdict {
+    pub winner: PairwiseWinner,
+    pub reason: String,
+}
+
+/// Pairwise scorer — asks a judge whether the response beats the reference answer.
+///
+/// As a [`Scorer`], `output` is the candidate and `expected` the baseline: a win scores
+/// 1.0, a tie 0.5 and a loss 0.0. By default each pair is judged in both orders and
+/// averaged to cancel position bias.
+pub struct PairwiseScorer {
+    provider: crate::Shared<dyn Provider>,
+    criteria: String,
+    swap: bool,
+    options: GenerateOptions,
+}
+
+impl PairwiseScorer {
+    pub fn new(provider: crate::Shared<dyn Provider>) -> Self {
+        Self {
+            provider,
+            criteria: "Which response answers the input more accurately and helpfully?".into(),
+            swap: true,
+            options: judge_options(),
+        }
+    }
+
+    /// What the judge should compare on.
+    pub fn with_criteria(mut self, criteria: impl Into<String>) -> Self {
+        self.criteria = criteria.into();
+        self
+    }
+
+    /// Judge both orders (default `true`).
+    pub fn with_swap(mut self, swap: bool) -> Self {
+        self.swap = swap;
+        self
+    }
+
+    pub fn with_options(mut self, options: GenerateOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Compare two responses to `input`.
+    pub async fn compare(&self, input: &str, a: &str, b: &str) -> error::Result<PairwiseVerdict> {
+        let system = format!(
+            "You are an impartial evaluator comparing two responses. {} \
+             Reply with JSON only: {{\"winner\": \"a\" | \"b\" | \"tie\", \"reason\": \"...\"}}.",
+            self.criteria
+        );
+        let verdict = ask_judge(
+            self.provider.as_ref(),
+            &self.options,
+            &system,
+            format!("INPUT:\n{input}\n\nRESPONSE A:\n{a}\n\nRESPONSE B:\n{b}\n"),
+            json!({
+                "type": "object",
+                "properties": {
+                    "winner": { "type": "string", "enum": ["a", "b", "tie"] },
+                    "reason": { "type": "string" }
+                },
+                "required": ["winner", "reason"]
+            }),
+        )
+        .await?;
+        let winner = match verdict["winner"].as_str().map(str::to_lowercase).as_deref() {
+            Some("a") => PairwiseWinner::A,
+            Some("b") => PairwiseWinner::B,
+            _ => PairwiseWinner::Tie,
+        };
+        Ok(PairwiseVerdict {
+            winner,
+            reason: verdict["reason"].as_str().unwrap_or_default().to_string(),
         })
     }
 }
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Scorer for PairwiseScorer {
+    fn name(&self) -> &str {
+        "pairwise"
+    }
+
+    async fn score(
+        &self,
+        input: &str,
+        output: &str,
+        expected: Option<&str>,
+        context: Option<&str>,
+    ) -> error::Result<f64> {
+        Ok(self
+            .score_detailed(input, output, expected, context)
+            .await?
+            .score)
+    }
+
+    async fn score_detailed(
+        &self,
+        input: &str,
+        output: &str,
+        expected: Option<&str>,
+        _context: Option<&str>,
+    ) -> error::Result<ScoreDetail> {
+        let Some(baseline) = expected else {
+            return Ok(ScoreDetail::new(0.0).with_reason("no baseline to compare against"));
+        };
+
+        // (verdict, whether the candidate was shown as A)
+        let mut verdicts = vec![(self.compare(input, output, baseline).await?, true)];
+        if self.swap {
+            verdicts.push((self.compare(input, baseline, output).await?, false));
+        }
+
+        let points: f64 = verdicts
+            .iter()
+            .map(|(v, candidate_is_a)| match (v.winner, candidate_is_a) {
+                (PairwiseWinner::Tie, _) => 0.5,
+                (PairwiseWinner::A, true) | (PairwiseWinner::B, false) => 1.0,
+                _ => 0.0,
+            })
+            .sum();
+        let reason = verdicts
+            .iter()
+            .map(|(v, _)| v.reason.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+        Ok(ScoreDetail {
+            score: points / verdicts.len() as f64,
+            reason: Some(reason),
+            metadata: json!({
+                "verdicts": verdicts
+                    .iter()
+                    .map(|(v, candidate_is_a)| json!({
+                        "candidate_position": if *candidate_is_a { "a" } else { "b" },
+                        "winner": v.winner,
+                        "reason": v.reason,
+                    }))
+                    .collect::<Vec<_>>()
+            }),
+        })
+    }
+}
+
+/// Faithfulness scorer — the fraction of the response's claims supported by
+/// `EvalCase::context`, as judged by a [`Provider`]. Useful for RAG answers.
+///
+/// A response with no factual claims scores 1.0; a case without context scores 0.0.
+pub struct FaithfulnessScorer {
+    provider: crate::Shared<dyn Provider>,
+    options: GenerateOptions,
+}
+
+impl FaithfulnessScorer {
+    pub fn new(provider: crate::Shared<dyn Provider>) -> Self {
+        Self {
+            provider,
+            options: judge_options(),
+        }
+    }
+
+    pub fn with_options(mut self, options: GenerateOptions) -> Self {
+        self.options = options;
+        self
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Scorer for FaithfulnessScorer {
+    fn name(&self) -> &str {
+        "faithfulness"
+    }
+
+    async fn score(
+        &self,
+        input: &str,
+        output: &str,
+        expected: Option<&str>,
+        context: Option<&str>,
+    ) -> error::Result<f64> {
+        Ok(self
+            .score_detailed(input, output, expected, context)
+            .await?
+            .score)
+    }
+
+    async fn score_detailed(
+        &self,
+        input: &str,
+        output: &str,
+        _expected: Option<&str>,
+        context: Option<&str>,
+    ) -> error::Result<ScoreDetail> {
+        let Some(context) = context else {
+            return Ok(ScoreDetail::new(0.0).with_reason("no context provided"));
+        };
+        let verdict = ask_judge(
+            self.provider.as_ref(),
+            &self.options,
+            "You check answers for faithfulness to a source. List every factual claim in the \
+             RESPONSE and mark whether the CONTEXT supports it. Claims not stated in or \
+             directly implied by the CONTEXT are unsupported. Reply with JSON only: \
+             {\"claims\": [{\"claim\", \"supported\"}], \"reasoning\": \"...\"}.",
+            format!("QUESTION:\n{input}\n\nCONTEXT:\n{context}\n\nRESPONSE:\n{output}\n"),
+            json!({
+                "type": "object",
+                "properties": {
+                    "claims": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "claim": { "type": "string" },
+                                "supported": { "type": "boolean" }
+                            },
+                            "required": ["claim", "supported"]
+                        }
+                    },
+                    "reasoning": { "type": "string" }
+                },
+                "required": ["claims", "reasoning"]
+            }),
+        )
+        .await?;
+
+        let claims = verdict["claims"].as_array().cloned().unwrap_or_default();
+        let supported = claims
+            .iter()
+            .filter(|c| c["supported"].as_bool() == Some(true))
+            .count();
+        let score = if claims.is_empty() {
+            1.0
+        } else {
+            supported as f64 / claims.len() as f64
+        };
+        Ok(ScoreDetail {
+            score,
+            reason: verdict["reasoning"].as_str().map(String::from),
+            metadata: json!({ "claims": claims }),
+        })
+    }
+}
+
+/// Semantic similarity scorer — cosine similarity between the embeddings of the
+/// response and the expected output (negative similarity scores 0.0).
+pub struct SemanticSimilarityScorer {
+    embedder: crate::Shared<dyn Embedding>,
+}
+
+impl SemanticSimilarityScorer {
+    pub fn new(embedder: crate::Shared<dyn Embedding>) -> Self {
+        Self { embedder }
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Scorer for SemanticSimilarityScorer {
+    fn name(&self) -> &str {
+        "semantic_similarity"
+    }
+
+    async fn score(
+        &self,
+        _input: &str,
+        output: &str,
+        expected: Option<&str>,
+        _context: Option<&str>,
+    ) -> error::Result<f64> {
+        let Some(expected) = expected else {
+            return Ok(0.0);
+        };
+        let embeddings = self.embedder.embed_batch(&[output, expected]).await?;
+        match embeddings.as_slice() {
+            [a, b] => Ok(f64::from(cosine_similarity(a, b)).max(0.0)),
+            _ => Err(error::GaussError::internal(
+                "Embedding returned the wrong number of vectors",
+            )),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Trajectory Evaluation
+// ---------------------------------------------------------------------------
+
+/// How a [`TrajectoryScorer`] compares actual tool calls with the expected ones.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrajectoryMatch {
+    /// Same calls in the same order, nothing extra. Scores 1.0 or 0.0.
+    Exact,
+    /// Same calls in any order, nothing extra. Scores 1.0 or 0.0.
+    Unordered,
+    /// Expected calls appear in order; other calls may be interleaved.
+    /// Scores the fraction of the expected sequence matched.
+    #[default]
+    InOrder,
+    /// Expected calls appear in any order; extra calls are allowed.
+    /// Scores the fraction of expected calls matched.
+    Subset,
+}
+
+/// Matches one tool-call argument.
+///
+/// In JSON, `{"contains": "..."}`, `{"regex": "..."}`, `{"one_of": [...]}` and
+/// `{"any": true}` are matchers; any other value must be equal to the argument.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ArgMatcher {
+    /// String argument containing the substring.
+    Contains { contains: String },
+    /// String argument matching the regular expression.
+    Regex { regex: String },
+    /// Argument equal to one of the values.
+    OneOf { one_of: Vec<Value> },
+    /// Argument present with any value.
+    Any { any: bool },
+    /// Argument equal to the value.
+    Equals(Value),
+}
+
+impl ArgMatcher {
+    pub fn equals(value: impl Into<Value>) -> Self {
+        Self::Equals(value.into())
+    }
+
+    pub fn contains(substring: impl Into<String>) -> Self {
+        Self::Contains {
+            contains: substring.into(),
+        }
+    }
+
+    pub fn regex(pattern: impl Into<String>) -> Self {
+        Self::Regex {
+            regex: pattern.into(),
+        }
+    }
+
+    pub fn one_of(values: impl IntoIterator<Item = Value>) -> Self {
+        Self::OneOf {
+            one_of: values.into_iter().collect(),
+        }
+    }
+
+    pub fn any() -> Self {
+        Self::Any { any: true }
+    }
+
+    pub fn matches(&self, actual: Option<&Value>) -> bool {
+        self.matches_in(actual, &RegexCache::new())
+    }
+
+    /// Like [`matches`](Self::matches), reusing patterns already compiled in `regexes`.
+    fn matches_in(&self, actual: Option<&Value>, regexes: &RegexCache<'_>) -> bool {
+        let Some(actual) = actual else {
+            return false;
+        };
+        match self {
+            Self::Contains { contains } => actual.as_str().is_some_and(|s| s.contains(contains)),
+            Self::Regex { regex } => {
+                let Some(s) = actual.as_str() else {
+                    return false;
+                };
+                match regexes.get(regex.as_str()) {
+                    Some(compiled) => compiled.as_ref().is_some_and(|re| re.is_match(s)),
+                    None => regex::Regex::new(regex).is_ok_and(|re| re.is_match(s)),
+                }
+            }
+            Self::OneOf { one_of } => one_of.contains(actual),
+            Self::Any { .. } => true,
+            Self::Equals(expected) => expected == actual,
+        }
+    }
+}
+
+/// Compiled [`ArgMatcher::Regex`] patterns keyed by source; `None` marks an invalid pattern.
+type RegexCache<'a> = HashMap<&'a str, Option<regex::Regex>>;
+
+/// One tool call an agent is expected to make.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExpectedToolCall {
+    pub name: String,
+    /// Argument matchers keyed by argument name, or by JSON pointer (`/filter/year`)
+    /// for nested arguments. Arguments not listed are not checked.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub arguments: HashMap<String, ArgMatcher>,
+}
+
+impl ExpectedToolCall {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            arguments: HashMap::new(),
+        }
+    }
+
+    pub fn arg(mut self, key: impl Into<String>, matcher: ArgMatcher) -> Self {
+        self.arguments.insert(key.into(), matcher);
+        self
+    }
+
+    pub fn matches(&self, name: &str, arguments: &Value) -> bool {
+        self.matches_in(name, arguments, &RegexCache::new())
+    }
+
+    fn matches_in(&self, name: &str, arguments: &Value, regexes: &RegexCache<'_>) -> bool {
+        self.name == name
+            && self.arguments.iter().all(|(key, matcher)| {
+                let actual = if key.starts_with('/') {
+                    arguments.pointer(key)
+                } else {
+                    arguments.get(key)
+                };
+                matcher.matches_in(actual, regexes)
+            })
+    }
+
+    fn describe(&self) -> String {
+        let mut keys: Vec<&str> = self.arguments.keys().map(String::as_str).collect();
+        keys.sort_unstable();
+        format!("{}({})", self.name, keys.join(", "))
+    }
+}
+
+/// The tool calls an [`EvalCase`] expects, and how strictly to match them.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExpectedTrajectory {
+    pub calls: Vec<ExpectedToolCall>,
+    #[serde(default)]
+    pub mode: TrajectoryMatch,
+    /// Fail the case (and score the trajectory 0.0) if the agent needed more steps than this.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_steps: Option<usize>,
+}
+
+impl ExpectedTrajectory {
+    pub fn new(mode: TrajectoryMatch) -> Self {
+        Self {
+            mode,
+            ..Default::default()
+        }
+    }
+
+    pub fn call(mut self, call: ExpectedToolCall) -> Self {
+        self.calls.push(call);
+        self
+    }
+
+    pub fn max_steps(mut self, steps: usize) -> Self {
+        self.max_steps = Some(steps);
+        self
+    }
+
+    /// Score actual `(name, arguments)` calls against this trajectory, ignoring `max_steps`.
+    /// Returns the score and, per expected call, whether it was matched.
+    pub fn score_calls(&self, actual: &[(&str, &Value)]) -> (f64, Vec<bool>) {
+        let expected = &self.calls;
+        let regexes = self.compile_regexes();
+        let matched = match self.mode {
+            TrajectoryMatch::Exact => {
+                let prefix = expected
+                    .iter()
+                    .zip(actual)
+                    .take_while(|(e, (name, args))| e.matches_in(name, args, &regexes))
+                    .count();
+                (0..expected.len()).map(|i| i < prefix).collect()
+            }
+            TrajectoryMatch::InOrder => {
+                // Greedy earliest match is optimal for subsequence matching.
+                let mut remaining = actual.iter();
+                expected
+                    .iter()
+                    .map(|e| remaining.any(|(name, args)| e.matches_in(name, args, &regexes)))
+                    .collect()
+            }
+            TrajectoryMatch::Unordered | TrajectoryMatch::Subset => {
+                max_matching(expected, actual, &regexes)
+            }
+        };
+        let count = matched.iter().filter(|m| **m).count();
+        let score = match self.mode {
+            TrajectoryMatch::Exact | TrajectoryMatch::Unordered => {
+                if count == expected.len() && actual.len() == expected.len() {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            TrajectoryMatch::InOrder | TrajectoryMatch::Subset if expected.is_empty() => 1.0,
+            TrajectoryMatch::InOrder | TrajectoryMatch::Subset => {
+                count as f64 / expected.len() as f64
+            }
+        };
+        (score, matched)
+    }
+
+    /// Compile every regex argument pattern once for a whole scoring pass.
+    fn compile_regexes(&self) -> RegexCache<'_> {
+        self.calls
+            .iter()
+            .flat_map(|call| call.arguments.values())
+            .filter_map(|matcher| match matcher {
+                ArgMatcher::Regex { regex } => {
+                    Some((regex.as_str(), regex::Regex::new(regex).ok()))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Whether the run took more steps than `max_steps` allows.
+    pub fn exceeds_max_steps(&self, steps: usize) -> bool {
+        self.max_steps.is_some_and(|max| steps > max)
+    }
+}
+
+/// Maximum one-to-one matching between expected and actual calls (augmenting paths;
+/// trajectories are short). Returns, per expected call, whether it was matched.
+fn max_matching(
+    expected: &[ExpectedToolCall],
+    actual: &[(&str, &Value)],
+    regexes: &RegexCache<'_>,
+) -> Vec<bool> {
+    // Compatibility of each expected call with each actual call, computed once.
+    let compatible: Vec<Vec<bool>> = expected
+        .iter()
+        .map(|e| {
+            actual
+                .iter()
+                .map(|(name, args)| e.matches_in(name, args, regexes))
+                .collect()
+        })
+        .collect();
+
+    fn augment(
+        e: usize,
+        compatible: &[Vec<bool>],
+        owner: &mut [Option<usize>],
+        seen: &mut [bool],
+    ) -> bool {
+        for a in 0..owner.len() {
+            if seen[a] || !compatible[e][a] {
+                continue;
+            }
+            seen[a] = true;
+            if owner[a].is_none_or(|other| augment(other, compatible, owner, seen)) {
+                owner[a] = Some(e);
+                return true;
+            }
+        }
+        false
+    }
+
+    let mut owner = vec![None; actual.len()];
+    for e in 0..expected.len() {
+        augment(e, &compatible, &mut owner, &mut vec![false; actual.len()]);
+    }
+    let mut matched = vec![false; expected.len()];
+    for e in owner.into_iter().flatten() {
+        matched[e] = true;
+    }
+    matched
+}
+
+/// Scores an agent run's tool calls against [`EvalCase::expected_trajectory`].
+///
+/// Needs the full run: use [`EvalRunner::run_agent`] or
+/// [`EvalRunner::evaluate_agent_output`]. Text-only evaluation scores 0.0.
+pub struct TrajectoryScorer;
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Scorer for TrajectoryScorer {
+    fn name(&self) -> &str {
+        "trajectory"
+    }
+
+    async fn score(
+        &self,
+        _input: &str,
+        _output: &str,
+        _expected: Option<&str>,
+        _context: Option<&str>,
+    ) -> error::Result<f64> {
+        Ok(0.0)
+    }
+
+    async fn score_detailed(
+        &self,
+        _input: &str,
+        _output: &str,
+        _expected: Option<&str>,
+        _context: Option<&str>,
+    ) -> error::Result<ScoreDetail> {
+        Ok(ScoreDetail::new(0.0).with_reason("no agent run to inspect"))
+    }
+
+    async fn score_agent(
+        &self,
+        case: &EvalCase,
+        output: &AgentOutput,
+    ) -> error::Result<ScoreDetail> {
+        let Some(ref expected) = case.expected_trajectory else {
+            return Ok(ScoreDetail::new(0.0).with_reason("no expected trajectory"));
+        };
+        let actual: Vec<(&str, &Value)> = output
+            .step_results
+            .iter()
+            .flat_map(|step| &step.tool_calls)
+            .map(|call| (call.name.as_str(), &call.arguments))
+            .collect();
+        let (mut score, matched) = expected.score_calls(&actual);
+        let matched_count = matched.iter().filter(|m| **m).count();
+
+        let mut reasons = Vec::new();
+        if matched_count < expected.calls.len() {
+            let unmatched: Vec<String> = expected
+                .calls
+                .iter()
+                .zip(&matched)
+                .filter(|(_, m)| !**m)
+                .map(|(call, _)| call.describe())
+                .collect();
+            reasons.push(format!(
+                "{matched_count}/{} expected calls matched; unmatched: {}",
+                expected.calls.len(),
+                unmatched.join(", ")
+            ));
+        }
+        if matches!(
+            expected.mode,
+            TrajectoryMatch::Exact | TrajectoryMatch::Unordered
+        ) && actual.len() != expected.calls.len()
+        {
+            reasons.push(format!(
+                "expected {} calls, got {}",
+                expected.calls.len(),
+                actual.len()
+            ));
+        }
+        if let Some(max) = expected.max_steps
+            && expected.exceeds_max_steps(output.steps)
+        {
+            score = 0.0;
+            reasons.push(format!("took {} steps (max {max})", output.steps));
+        }
+
+        Ok(ScoreDetail {
+            score,
+            reason: (!reasons.is_empty()).then(|| reasons.join("; ")),
+            metadata: json!({
+                "mode": expected.mode,
+                "matched": matched_count,
+                "steps": output.steps,
+                "actual_calls": actual
+                    .iter()
+                    .map(|(name, args)| json!({ "name": name, "arguments": args }))
+                    .collect::<Vec<_>>(),
+            }),
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Dataset Loading
+// ---------------------------------------------------------------------------
+
+/// Load eval cases from a JSONL string.
+pub fn load_dataset_jsonl(jsonl: &str) -> error::Result<Vec<EvalCase>> {
+    jsonl
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .enumerate()
+        .map(|(i, line)| {
+            serde_json::from_str(line).map_err(|e| {
+                error::GaussError::internal(format!("Failed to parse line {}: {}", i + 1, e))
+            })
+        })
+        .collect()
+}
+
+/// Load eval cases from a JSON array string.
+pub fn load_dataset_json(json: &str) -> error::Result<Vec<EvalCase>> {
+    serde_json::from_str(json)
+        .map_err(|e| error::GaussError::internal(format!("Failed to parse dataset: {}", e)))
+}
+
+// ---------------------------------------------------------------------------
+// Eval Runner
+// ---------------------------------------------------------------------------
+
+/// Batch evaluation runner.
+///
+/// Cases run `concurrency` at a time (default 1), optionally rate limited and
+/// repeated `trials` times. With a checkpoint file every finished case is appended
+/// as a JSON line, and a rerun with the same file skips the cases already in it
+/// (cases that errored are run again unless `retry_errors` is turned off).
+///
+/// ```no_run
+/// # async fn demo(dataset: Vec<gauss_core::eval::EvalCase>) -> gauss_core::error::Result<()> {
+/// use gauss_core::eval::*;
+///
+/// let mut runner = EvalRunner::new()
+///     .with_concurrency(8)
+///     .with_rate_limit(120)
+///     .with_trials(3)
+///     .with_checkpoint("eval.checkpoint.jsonl");
+/// runner.add_scorer(std::sync::Arc::new(ExactMatchScorer));
+/// let report = runner
+///     .run(&dataset, |case| {
+///         let input = case.input.clone();
+///         async move { Ok(input) }
+///     })
+///     .await?;
+/// println!("{}", report.to_markdown());
+/// # Ok(())
+/// # }
+/// ```
+pub struct EvalRunner {
+    scorers: Vec<crate::Shared<dyn Scorer>>,
+    /// Minimum average score to pass (default 0.5).
+    pub pass_threshold: f64,
+    /// Cases evaluated at once (default 1).
+    pub concurrency: usize,
+    /// Times each case is run; scores are averaged (default 1).
+    pub trials: usize,
+    /// Maximum `eval_fn` calls per minute (`None` = unlimited).
+    pub rate_limit: Option<u32>,
+    /// JSONL file that finished results are appended to and resumed from.
+    pub checkpoint: Option<std::path::PathBuf>,
+    /// Rerun cases whose checkpointed result is an error (default true).
+    pub retry_errors: bool,
+}
+
+impl Default for EvalRunner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EvalRunner {
+    pub fn new() -> Self {
+        Self {
+            scorers: Vec::new(),
+            pass_threshold: 0.5,
+            concurrency: 1,
+            trials: 1,
+            rate_limit: None,
+            checkpoint: None,
+            retry_errors: true,
+        }
+    }
+
+    pub fn with_threshold(mut self, threshold: f64) -> Self {
+        self.pass_threshold = threshold;
+        self
+    }
+
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    pub fn with_trials(mut self, trials: usize) -> Self {
+        self.trials = trials.max(1);
+        self
+    }
+
+    pub fn with_rate_limit(mut self, calls_per_minute: u32) -> Self {
+        self.rate_limit = Some(calls_per_minute.max(1));
+        self
+    }
+
+    pub fn with_checkpoint(mut self, path: impl Into<std::path::PathBuf>) -> Self {
+        self.checkpoint = Some(path.into());
+        self
+    }
+
+    pub fn with_retry_errors(mut self, retry_errors: bool) -> Self {
+        self.retry_errors = retry_errors;
+        self
+    }
+
+    pub fn add_scorer(&mut self, scorer: crate::Shared<dyn Scorer>) {
+        self.scorers.push(scorer);
+    }
+
+    /// Evaluate a single case given the actual output.
+    pub async fn evaluate_case(
+        &self,
+        case: &EvalCase,
+        actual_output: &str,
+    ) -> error::Result<EvalResult> {
+        self.evaluate(case, &actual_output).await
+    }
+
+    /// Evaluate a single case given a full agent run; scorers see every step
+    /// through [`Scorer::score_agent`].
+    pub async fn evaluate_agent_output(
+        &self,
+        case: &EvalCase,
+        output: &AgentOutput,
+    ) -> error::Result<EvalResult> {
+        self.evaluate(case, output).await
+    }
+
+    async fn evaluate<O: EvalOutput>(
+        &self,
+        case: &EvalCase,
+        output: &O,
+    ) -> error::Result<EvalResult> {
+        let start = std::time::Instant::now();
+        let mut scores = HashMap::new();
+        let mut metadata = HashMap::new();
+
+        for scorer in &self.scorers {
+            let detail = match output.agent_output() {
+                Some(agent_output) => scorer.score_agent(case, agent_output).await?,
+                None => {
+                    scorer
+                        .score_detailed(
+                            &case.input,
+                            output.text(),
+                            case.expected_output.as_deref(),
+                            case.context.as_deref(),
+                        )
+                        .await?
+                }
+            };
+            scores.insert(scorer.name().to_string(), detail.score);
+            if detail.has_details() {
+                metadata.insert(
+                    scorer.name().to_string(),
+                    serde_json::to_value(&detail).unwrap_or_default(),
+                );
+            }
+        }
+
+        // Exceeding the trajectory's step budget fails the case whatever the scores.
+        let over_max_steps = output.agent_output().is_some_and(|agent_output| {
+            case.expected_trajectory
+                .as_ref()
+                .is_some_and(|t| t.exceeds_max_steps(agent_output.steps))
+        });
+
+        Ok(EvalResult {
+            case_id: case.id.clone(),
+            passed: self.passes(&scores) && !over_max_steps,
+            scores,
+            actual_output: output.text().to_string(),
+            error: None,
+            duration_ms: start.elapsed().as_millis() as u64,
+            metadata,
+            trial_stats: None,
+        })
+    }
+
+    fn passes(&self, scores: &HashMap<String, f64>) -> bool {
+        let avg = if scores.is_empty() {
+            0.0
+        } else {
+            scores.values().sum::<f64>() / scores.len() as f64
+        };
+        avg >= self.pass_threshold
+    }
+
+    /// Run evaluation on a dataset with a provided evaluation function.
+    pub async fn run<F, Fut>(&self, dataset: &[EvalCase], eval_fn: F) -> error::Result<EvalReport>
+    where
+        F: Fn(&EvalCase) -> Fut,
+        Fut: std::future::Future<Output = error::Result<String>>,
+    {
+        self.run_with(dataset, eval_fn).await
+    }
+
+    /// Like [`run`](Self::run), but `eval_fn` returns the full agent run so scorers
+    /// such as [`TrajectoryScorer`] can inspect tool calls and step counts.
+    pub async fn run_agent<F, Fut>(
+        &self,
+        dataset: &[EvalCase],
+        eval_fn: F,
+    ) -> error::Result<EvalReport>
+    where
+        F: Fn(&EvalCase) -> Fut,
+        Fut: std::future::Future<Output = error::Result<AgentOutput>>,
+    {
+        self.run_with(dataset, eval_fn).await
+    }
+
+    async fn run_with<F, Fut, O>(
+        &self,
+        dataset: &[EvalCase],
+        eval_fn: F,
+    ) -> error::Result<EvalReport>
+    where
+        F: Fn(&EvalCase) -> Fut,
+        Fut: std::future::Future<Output = error::Result<O>>,
+        O: EvalOutput,
+    {
+        use futures::StreamExt;
+        use std::io::Write;
+
+        let run_start = std::time::Instant::now();
+        let mut finished = self.load_checkpoint()?;
+        let pending: Vec<&EvalCase> = dataset
+            .iter()
+            .filter(|case| !finished.contains_key(&case.id))
+            .collect();
+
+        let mut checkpoint = match &self.checkpoint {
+            Some(path) => Some(
+                std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .map_err(|e| checkpoint_error(path, e))?,
+            ),
+            None => None,
+        };
+
+        let gate = self.rate_limit.map(RateGate::new);
+        let eval_fn = &eval_fn;
+        let mut stream = futures::stream::iter(pending)
+            .map(|case| self.run_case(case, eval_fn, gate.as_ref()))
+            .buffer_unordered(self.concurrency.max(1));
+
+        while let Some(result) = stream.next().await {
+            let result = result?;
+            if let (Some(file), Some(path)) = (checkpoint.as_mut(), &self.checkpoint) {
+                let line = serde_json::to_string(&result)
+                    .map_err(|e| error::GaussError::internal(e.to_string()))?;
+                writeln!(file, "{line}")
+                    .and_then(|_| file.flush())
+                    .map_err(|e| checkpoint_error(path, e))?;
+            }
+            finished.insert(result.case_id.clone(), result);
+        }
+
+        let results: Vec<EvalResult> = dataset
+            .iter()
+            .filter_map(|case| finished.remove(&case.id))
+            .collect();
+        let errored = results.iter().filter(|r| r.error.is_some()).count();
+        let passed = results.iter().filter(|r| r.passed).count();
+
+        // Compute average scores
+        let mut avg_scores: HashMap<String, f64> = HashMap::new();
+        let valid_results: Vec<&EvalResult> =
+            results.iter().filter(|r| r.error.is_none()).collect();
+        if !valid_results.is_empty() {
+            for r in &valid_results {
+                for (name, score) in &r.scores {
+                    *avg_scores.entry(name.clone()).or_insert(0.0) += score;
+                }
+            }
+            let count = valid_results.len() as f64;
+            for v in avg_scores.values_mut() {
+                *v /= count;
+            }
+        }
+
+        Ok(EvalReport {
+            total: dataset.len(),
+            passed,
+            failed: results.len() - passed - errored,
+            errored,
+            avg_scores,
+            results,
+            total_duration_ms: run_start.elapsed().as_millis() as u64,
+        })
+    }
+
+    /// Run all trials of one case and fold them into a single result.
+    async fn run_case<F, Fut, O>(
+        &self,
+        case: &EvalCase,
+        eval_fn: &F,
+        gate: Option<&RateGate>,
+    ) -> error::Result<EvalResult>
+    where
+        F: Fn(&EvalCase) -> Fut,
+        Fut: std::future::Future<Output = error::Result<O>>,
+        O: EvalOutput,
+    {
+        let start = std::time::Instant::now();
+        let mut trials = Vec::with_capacity(self.trials);
+        let mut last_error = None;
+        let mut errors = 0;
+
+        for _ in 0..self.trials.max(1) {
+            if let Some(gate) = gate {
+                gate.wait().await;
+            }
+            match eval_fn(case).await {
+                Ok(output) => trials.push(self.evaluate(case, &output).await?),
+                Err(e) => {
+                    errors += 1;
+                    last_error = Some(e.to_string());
+                }
+            }
+        }
+
+        if trials.is_empty() {
+            return Ok(EvalResult {
+                case_id: case.id.clone(),
+                scores: HashMap::new(),
+                passed: false,
+                actual_output: String::new(),
+                error: last_error,
+                duration_ms: start.elapsed().as_millis() as u64,
+                metadata: HashMap::new(),
+                trial_stats: None,
+            });
+        }
+        if self.trials <= 1 {
+            return Ok(trials.remove(0));
+        }
+
+        let stats = TrialStats::from_trials(&trials, errors);
+        let scores: HashMap<String, f64> = stats
+            .scores
+            .iter()
+            .map(|(name, s)| (name.clone(), s.mean))
+            .collect();
+        let first = trials.remove(0);
+        Ok(EvalResult {
+            case_id: first.case_id,
+            passed: self.passes(&scores),
+            scores,
+            actual_output: first.actual_output,
+            error: None,
+            duration_ms: start.elapsed().as_millis() as u64,
+            metadata: first.metadata,
+            trial_stats: Some(stats),
+        })
+    }
+
+    /// Results already recorded in the checkpoint file, keyed by case id.
+    ///
+    /// A run killed mid-write leaves a truncated last line; it is cut off so
+    /// the next appended record starts on a line of its own.
+    fn load_checkpoint(&self) -> error::Result<HashMap<String, EvalResult>> {
+        let Some(path) = &self.checkpoint else {
+            return Ok(HashMap::new());
+        };
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(HashMap::new()),
+            Err(e) => return Err(checkpoint_error(path, e)),
+        };
+        if !text.is_empty() && !text.ends_with('\n') {
+            let complete = text.rfind('\n').map_or(0, |i| i + 1);
+            std::fs::OpenOptions::new()
+                .write(true)
+                .open(path)
+                .and_then(|file| file.set_len(complete as u64))
+                .map_err(|e| checkpoint_error(path, e))?;
+        }
+        // Skip lines that still don't parse, e.g. from an older format.
+        Ok(text
+            .lines()
+            .filter_map(|line| serde_json::from_str::<EvalResult>(line).ok())
+            .filter(|r| !(self.retry_errors && r.error.is_some()))
+            .map(|r| (r.case_id.clone(), r))
+            .collect())
+    }
+}
+
+/// What an `eval_fn` produced: plain text, or a full agent run.
+trait EvalOutput {
+    fn text(&self) -> &str;
+
+    fn agent_output(&self) -> Option<&AgentOutput> {
+        None
+    }
+}
+
+impl EvalOutput for &str {
+    fn text(&self) -> &str {
+        self
+    }
+}
+
+impl EvalOutput for String {
+    fn text(&self) -> &str {
+        self
+    }
+}
+
+impl EvalOutput for AgentOutput {
+    fn text(&self) -> &str {
+        &self.text
+    }
+
+    fn agent_output(&self) -> Option<&AgentOutput> {
+        Some(self)
+    }
+}
+
+fn checkpoint_error(path: &std::path::Path, e: std::io::Error) -> error::GaussError {
+    error::GaussError::internal(format!("Eval checkpoint {}: {e}", path.display()))
+}
+
+/// Spaces out `eval_fn` calls to at most `calls_per_minute`.
+struct RateGate {
+    interval: std::time::Duration,
+    next: std::sync::Mutex<Option<std::time::Instant>>,
+}
+
+impl RateGate {
+    fn new(calls_per_minute: u32) -> Self {
+        Self {
+            interval: std::time::Duration::from_secs(60) / calls_per_minute.max(1),
+            next: std::sync::Mutex::new(None),
+        }
+    }
+
+    async fn wait(&self) {
+        let delay = {
+            let mut next = self.next.lock().unwrap_or_else(|e| e.into_inner());
+            let now = std::time::Instant::now();
+            let slot = next.map_or(now, |n| n.max(now));
+            *next = Some(slot + self.interval);
+            slot - now
+        };
+        if !delay.is_zero() {
+            crate::provider::retry::sleep(delay).await;
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Regression Diffing
+// ---------------------------------------------------------------------------
+
+/// Thresholds for [`EvalReport::compare_with`].
+#[derive(Debug, Clone, Copy)]
+pub struct CompareOptions {
+    /// Smallest per-case or per-scorer score change that counts (default 0.05).
+    pub min_delta: f64,
+    /// Paired z-score a scorer's mean change must reach to be significant (default 1.96,
+    /// ~95% two-sided).
+    pub z_threshold: f64,
+}
+
+impl Default for CompareOptions {
+    fn default() -> Self {
+        Self {
+            min_delta: 0.05,
+            z_threshold: 1.96,
+        }
+    }
+}
+
+/// How one case changed between two reports.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaseDiff {
+    pub case_id: String,
+    pub baseline_passed: bool,
+    pub current_passed: bool,
+    /// `current - baseline` for every scorer present in both runs.
+    pub score_deltas: HashMap<String, f64>,
+}
+
+/// How one scorer's mean changed over the cases present in both reports.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScorerDelta {
+    pub scorer: String,
+    pub baseline_mean: f64,
+    pub current_mean: f64,
+    pub delta: f64,
+    /// Number of paired cases.
+    pub cases: usize,
+    /// Paired z-score of the delta (`None` when it cannot be estimated).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub z_score: Option<f64>,
+    pub significant: bool,
+}
+
+impl ScorerDelta {
+    pub fn is_regression(&self) -> bool {
+        self.significant && self.delta < 0.0
+    }
+}
+
+/// Result of [`EvalReport::compare`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportComparison {
+    pub pass_rate_delta: f64,
+    pub regressions: Vec<CaseDiff>,
+    pub improvements: Vec<CaseDiff>,
+    pub scorers: Vec<ScorerDelta>,
+    /// Cases only in the current report.
+    pub new_cases: Vec<String>,
+    /// Cases only in the baseline.
+    pub missing_cases: Vec<String>,
+}
+
+impl ReportComparison {
+    /// Whether any case or scorer regressed — the usual CI gate.
+    pub fn has_regressions(&self) -> bool {
+        !self.regressions.is_empty() || self.scorers.iter().any(ScorerDelta::is_regression)
+    }
+
+    /// Render as a Markdown summary (for PR comments or CI job summaries).
+    pub fn to_markdown(&self) -> String {
+        let mut md = String::from("## Eval comparison\n\n");
+        md.push_str(&format!(
+            "Pass rate change: {:+.1}%\n\n",
+            self.pass_rate_delta * 100.0
+        ));
+        if !self.scorers.is_empty() {
+            md.push_str("| Scorer | Baseline | Current | Δ | z | |\n|---|---|---|---|---|---|\n");
+            for s in &self.scorers {
+                let flag = if s.is_regression() {
+                    "regressed"
+                } else if s.significant {
+                    "improved"
+                } else {
+                    ""
+                };
+                let z = s.z_score.map_or("-".into(), |z| format!("{z:.2}"));
+                md.push_str(&format!(
+                    "| {} | {:.3} | {:.3} | {:+.3} | {z} | {flag} |\n",
+                    markdown_cell(&s.scorer),
+                    s.baseline_mean,
+                    s.current_mean,
+                    s.delta,
+                ));
+            }
+            md.push('\n');
+        }
+        for (title, diffs) in [
+            ("Regressions", &self.regressions),
+            ("Improvements", &self.improvements),
+        ] {
+            if diffs.is_empty() {
+                continue;
+            }
+            md.push_str(&format!("### {title} ({})\n\n", diffs.len()));
+            for d in diffs {
+                let mut deltas: Vec<_> = d.score_deltas.iter().collect();
+                deltas.sort_by(|a, b| a.0.cmp(b.0));
+                let deltas = deltas
+                    .iter()
+                    .map(|(name, delta)| format!("{name} {delta:+.3}"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                md.push_str(&format!(
+                    "- `{}`: {} → {} ({deltas})\n",
+                    d.case_id,
+                    pass_label(d.baseline_passed),
+                    pass_label(d.current_passed),
+                ));
+            }
+            md.push('\n');
+        }
+        md
+    }
+}
+
+impl EvalReport {
+    /// Compare against a `baseline` run with default [`CompareOptions`].
+    pub fn compare(&self, baseline: &EvalReport) -> ReportComparison {
+        self.compare_with(baseline, &CompareOptions::default())
+    }
+
+    /// Per-case and per-scorer differences from `baseline`, matched by case id.
+    ///
+    /// A case regresses when it stops passing or any score drops by at least
+    /// `min_delta`. A scorer regresses when its mean drops by at least `min_delta`
+    /// and the paired z-score reaches `z_threshold`.
+    pub fn compare_with(
+        &self,
+        baseline: &EvalReport,
+        options: &CompareOptions,
+    ) -> ReportComparison {
+        let base: HashMap<&str, &EvalResult> = baseline
+            .results
+            .iter()
+            .map(|r| (r.case_id.as_str(), r))
+            .collect();
+        let current_ids: std::collections::HashSet<&str> =
+            self.results.iter().map(|r| r.case_id.as_str()).collect();
+
+        let mut regressions = Vec::new();
+        let mut improvements = Vec::new();
+        let mut new_cases = Vec::new();
+        let mut paired: HashMap<&str, Vec<(f64, f64)>> = HashMap::new();
+
+        for result in &self.results {
+            let Some(before) = base.get(result.case_id.as_str()) else {
+                new_cases.push(result.case_id.clone());
+                continue;
+            };
+            let mut score_deltas = HashMap::new();
+            for (name, score) in &result.scores {
+                if let Some(old) = before.scores.get(name) {
+                    score_deltas.insert(name.clone(), score - old);
+                    paired
+                        .entry(name.as_str())
+                        .or_default()
+                        .push((*old, *score));
+                }
+            }
+            let diff = CaseDiff {
+                case_id: result.case_id.clone(),
+                baseline_passed: before.passed,
+                current_passed: result.passed,
+                score_deltas,
+            };
+            let dropped = diff.score_deltas.values().any(|d| *d <= -options.min_delta);
+            let rose = diff.score_deltas.values().any(|d| *d >= options.min_delta);
+            if (before.passed && !result.passed) || dropped {
+                regressions.push(diff);
+            } else if (!before.passed && result.passed) || rose {
+                improvements.push(diff);
+            }
+        }
+
+        let mut scorers: Vec<ScorerDelta> = paired
+            .into_iter()
+            .map(|(name, pairs)| scorer_delta(name, &pairs, options))
+            .collect();
+        scorers.sort_by(|a, b| a.scorer.cmp(&b.scorer));
+
+        ReportComparison {
+            pass_rate_delta: self.pass_rate() - baseline.pass_rate(),
+            regressions,
+            improvements,
+            scorers,
+            new_cases,
+            missing_cases: baseline
+                .results
+                .iter()
+                .filter(|r| !current_ids.contains(r.case_id.as_str()))
+                .map(|r| r.case_id.clone())
+                .collect(),
+        }
+    }
+}
+
+/// Paired comparison of one scorer: z = mean(diff) / (sd(diff) / √n).
+fn scorer_delta(name: &str, pairs: &[(f64, f64)], options: &CompareOptions) -> ScorerDelta {
+    let n = pairs.len() as f64;
+    let baseline_mean = pairs.iter().map(|p| p.0).sum::<f64>() / n;
+    let current_mean = pairs.iter().map(|p| p.1).sum::<f64>() / n;
+    let diffs: Vec<f64> = pairs.iter().map(|(old, new)| new - old).collect();
+    let stats = ScoreStats::from_values(&diffs);
+    let delta = stats.mean;
+
+    let z_score = if pairs.len() < 2 {
+        None
+    } else if stats.std_dev == 0.0 {
+        // Every case moved by exactly the same amount.
+        (delta != 0.0).then(|| f64::INFINITY.copysign(delta))
+    } else {
+        Some(delta / (stats.std_dev / n.sqrt()))
+    };
+    let significant =
+        delta.abs() >= options.min_delta && z_score.is_some_and(|z| z.abs() >= options.z_threshold);
+
+    ScorerDelta {
+        scorer: name.to_string(),
+        baseline_mean,
+        current_mean,
+        delta,
+        cases: pairs.len(),
+        z_score,
+        significant,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Report Export
+// ---------------------------------------------------------------------------
+
+impl EvalReport {
+    /// Render as JUnit XML: one `<testcase>` per eval case, failed cases as
+    /// `<failure>` and errored cases as `<error>`.
+    pub fn to_junit_xml(&self, suite_name: &str) -> String {
+        let secs = |ms: u64| ms as f64 / 1000.0;
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str(&format!(
+            "<testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.3}\">\n",
+            xml_escape(suite_name),
+            self.total,
+            self.failed,
+            self.errored,
+            secs(self.total_duration_ms),
+        ));
+        for r in &self.results {
+            xml.push_str(&format!(
+                "  <testcase classname=\"{}\" name=\"{}\" time=\"{:.3}\"",
+                xml_escape(suite_name),
+                xml_escape(&r.case_id),
+                secs(r.duration_ms),
+            ));
+            if let Some(ref err) = r.error {
+                xml.push_str(&format!(
+                    ">\n    <error message=\"{}\"/>\n  </testcase>\n",
+                    xml_escape(err)
+                ));
+            } else if !r.passed {
+                xml.push_str(&format!(
+                    ">\n    <failure message=\"{}\">{}</failure>\n  </testcase>\n",
+                    xml_escape(&format_scores(&r.scores)),
+                    xml_escape(&r.actual_output),
+                ));
+            } else {
+                xml.push_str("/>\n");
+            }
+        }
+        xml.push_str("</testsuite>\n");
+        xml
+    }
+
+    /// Render as a Markdown summary with average scores and failing cases.
+    pub fn to_markdown(&self) -> String {
+        let mut md = String::from("## Eval report\n\n");
+        md.push_str(&format!(
+            "**{}/{} passed** ({:.1}%), {} failed, {} errored, {:.1}s\n\n",
+            self.passed,
+            self.total,
+            self.pass_rate() * 100.0,
+            self.failed,
+            self.errored,
+            self.total_duration_ms as f64 / 1000.0,
+        ));
+        if !self.avg_scores.is_empty() {
+            let mut names: Vec<_> = self.avg_scores.keys().collect();
+            names.sort();
+            md.push_str("| Scorer | Average |\n|---|---|\n");
+            for name in names {
+                md.push_str(&format!(
+                    "| {} | {:.3} |\n",
+                    markdown_cell(name),
+                    self.avg_scores[name]
+                ));
+            }
+            md.push('\n');
+        }
+        let failing: Vec<&EvalResult> = self.results.iter().filter(|r| !r.passed).collect();
+        if !failing.is_empty() {
+            md.push_str(&format!("### Failing cases ({})\n\n", failing.len()));
+            for r in failing {
+                let detail = match &r.error {
+                    Some(err) => format!("error: {err}"),
+                    None => format_scores(&r.scores),
+                };
+                md.push_str(&format!("- `{}`: {}\n", r.case_id, markdown_cell(&detail)));
+            }
+        }
+        md
+    }
+}
+
+fn format_scores(scores: &HashMap<String, f64>) -> String {
+    let mut scores: Vec<_> = scores.iter().collect();
+    scores.sort_by(|a, b| a.0.cmp(b.0));
+    scores
+        .iter()
+        .map(|(name, score)| format!("{name}={score:.3}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn pass_label(passed: bool) -> &'static str {
+    if passed { "pass" } else { "fail" }
+}
+
+fn markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+fn xml_escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            // Control characters other than tab/newline are not allowed in XML 1.0.
+            c if c.is_control() && c != '\t' && c != '\n' && c != '\r' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
@@ -5,8 +5,11 @@
 //! Forks run concurrent branches; consensus merges results.
 
 use crate::agent::Agent;
+use crate::cost::RunCost;
 use crate::error::{self, GaussError};
 use crate::message::Message;
+use crate::plugin::{OrchestratorRun, PluginRegistry};
+use crate::telemetry::{SpanBuilder, SpanRecord, SpanType, TelemetryCollector, TraceContext};
 use std::collections::HashMap;
 
 /// Output from a graph node.
@@ -22,6 +25,10 @@ pub struct NodeOutput {
 pub struct GraphResult {
     pub outputs: HashMap<String, NodeOutput>,
     pub final_output: Option<NodeOutput>,
+    /// Estimated cost of every agent run in the graph.
+    pub cost: RunCost,
+    /// Estimated cost per node (function nodes are not tracked).
+    pub node_costs: HashMap<String, RunCost>,
 }
 
 /// Consensus strategy for merging fork results.
@@ -96,6 +103,8 @@ pub struct Graph {
     edges: HashMap<String, Vec<String>>,
     entry_points: Vec<String>,
     terminal_nodes: Vec<String>,
+    telemetry: Option<crate::Shared<TelemetryCollector>>,
+    plugins: Option<crate::Shared<PluginRegistry>>,
 }
 
 impl Graph {
@@ -105,9 +114,36 @@ impl Graph {
 
     /// Execute the graph with a prompt. Returns all node outputs.
     pub async fn run(&self, prompt: impl Into<String>) -> error::Result<GraphResult> {
-        let prompt_str = prompt.into();
+        let lifecycle = OrchestratorRun::start(self.plugins.as_ref(), "graph", "graph").await;
+        let result = self.run_instrumented(prompt.into()).await;
+        lifecycle.finish(result.is_err()).await;
+        result
+    }
+
+    async fn run_instrumented(&self, prompt: String) -> error::Result<GraphResult> {
+        let Some(ref telemetry) = self.telemetry else {
+            return self.run_nodes(prompt, None, &mut Vec::new()).await;
+        };
+        let context = crate::telemetry::child_of_current();
+        let mut spans = Vec::new();
+        let result = self.run_nodes(prompt, Some(&context), &mut spans).await;
+        let span = SpanBuilder::new("graph", SpanType::Custom)
+            .context(context)
+            .attribute("nodes", self.nodes.len());
+        telemetry.record_run(crate::telemetry::finish_span(span, spans, &result));
+        result
+    }
+
+    async fn run_nodes(
+        &self,
+        prompt_str: String,
+        trace: Option<&TraceContext>,
+        spans: &mut Vec<SpanRecord>,
+    ) -> error::Result<GraphResult> {
         let initial_msgs = vec![Message::user(prompt_str)];
         let mut completed: HashMap<String, NodeOutput> = HashMap::new();
+        let mut cost = RunCost::default();
+        let mut node_costs: HashMap<String, RunCost> = HashMap::new();
         let mut ready: Vec<String> = self.entry_points.clone();
 
         while !ready.is_empty() {
@@ -136,12 +172,13 @@ impl Graph {
             {
                 let mut handles = Vec::new();
                 for node_id in &batch {
-                    let output = self
-                        .execute_node(node_id, &completed, &initial_msgs)
+                    let (output, node_cost) = self
+                        .execute_traced(node_id, &completed, &initial_msgs, trace, spans)
                         .await?;
-                    handles.push((node_id.clone(), output));
+                    handles.push((node_id.clone(), output, node_cost));
                 }
-                for (nid, output) in handles {
+                for (nid, output, node_cost) in handles {
+                    record_node_cost(&mut cost, &mut node_costs, &nid, node_cost);
                     completed.insert(nid.clone(), output);
                     self.discover_ready(&nid, &completed, &mut next_ready);
                 }
@@ -150,9 +187,10 @@ impl Graph {
             #[cfg(target_arch = "wasm32")]
             {
                 for node_id in &batch {
-                    let output = self
-                        .execute_node(node_id, &completed, &initial_msgs)
+                    let (output, node_cost) = self
+                        .execute_traced(node_id, &completed, &initial_msgs, trace, spans)
                         .await?;
+                    record_node_cost(&mut cost, &mut node_costs, node_id, node_cost);
                     completed.insert(node_id.clone(), output);
                     self.discover_ready(node_id, &completed, &mut next_ready);
                 }
@@ -178,16 +216,52 @@ impl Graph {
         Ok(GraphResult {
             outputs: completed,
             final_output,
+            cost,
+            node_costs,
         })
     }
 
+    /// Execute a node, wrapped in a node span when tracing.
+    async fn execute_traced(
+        &self,
+        node_id: &str,
+        completed: &HashMap<String, NodeOutput>,
+        initial_msgs: &[Message],
+        trace: Option<&TraceContext>,
+        spans: &mut Vec<SpanRecord>,
+    ) -> error::Result<(NodeOutput, Option<RunCost>)> {
+        let Some(trace) = trace else {
+            return self
+                .execute_node(node_id, completed, initial_msgs, None, &mut Vec::new())
+                .await;
+        };
+        let context = trace.child();
+        let mut children = Vec::new();
+        let result = self
+            .execute_node(
+                node_id,
+                completed,
+                initial_msgs,
+                Some(&context),
+                &mut children,
+            )
+            .await;
+        let span = SpanBuilder::new(format!("node {node_id}"), SpanType::WorkflowStep)
+            .context(context)
+            .attribute("node_id", node_id);
+        spans.push(crate::telemetry::finish_span(span, children, &result));
+        result
+    }
+
     /// Execute a single node.
     async fn execute_node(
         &self,
         node_id: &str,
         completed: &HashMap<String, NodeOutput>,
         initial_msgs: &[Message],
-    ) -> error::Result<NodeOutput> {
+        trace: Option<&TraceContext>,
+        spans: &mut Vec<SpanRecord>,
+    ) -> error::Result<(NodeOutput, Option<RunCost>)> {
         let node = self.nodes.get(node_id).ok_or_else(|| GaussError::Agent {
             message: format!("Graph node '{node_id}' not found"),
             source: None,
@@ -200,16 +274,30 @@ impl Graph {
                 } else {
                     input_fn(completed)
                 };
-                let result = agent.run(messages).await?;
-                Ok(NodeOutput {
-                    node_id: node_id.to_string(),
-                    text: result.text,
-                    data: result.structured_output,
-                })
+                let result = agent.run_child(messages, trace, spans).await?;
+                Ok((
+                    NodeOutput {
+                        node_id: node_id.to_string(),
+                        text: result.text,
+                        data: result.structured_output,
+                    },
+                    Some(result.cost),
+                ))
             }
-            GraphNode::Function { execute } => execute(completed.clone()).await,
+            GraphNode::Function { execute } => Ok((execute(completed.clone()).await?, None)),
             GraphNode::Fork { agents, consensus } => {
-                self.execute_fork(node_id, agents, consensus, completed, initial_msgs)
+                let messages = if completed.is_empty() {
+                    initial_msgs.to_vec()
+                } else {
+                    // Pass context from completed nodes
+                    let context = completed
+                        .values()
+                        .map(|o| format!("[{}]: {}", o.node_id, o.text))
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    vec![Message::user(context)]
+                };
+                self.execute_fork(node_id, agents, consensus, messages, trace, spans)
                     .await
             }
         }
@@ -221,25 +309,16 @@ impl Graph {
         node_id: &str,
         agents: &[(String, Box<Agent>)],
         consensus: &ConsensusStrategy,
-        completed: &HashMap<String, NodeOutput>,
-        initial_msgs: &[Message],
-    ) -> error::Result<NodeOutput> {
+        messages: Vec<Message>,
+        trace: Option<&TraceContext>,
+        spans: &mut Vec<SpanRecord>,
+    ) -> error::Result<(NodeOutput, Option<RunCost>)> {
         let mut results = Vec::new();
+        let mut cost = RunCost::default();
 
         for (branch_id, agent) in agents {
-            let messages = if completed.is_empty() {
-                initial_msgs.to_vec()
-            } else {
-                // Pass context from completed nodes
-                let context = completed
-                    .values()
-                    .map(|o| format!("[{}]: {}", o.node_id, o.text))
-                    .collect::<Vec<_>>()
-                    .join("\n");
-                vec![Message::user(context)]
-            };
-
-            let result = agent.run(messages).await?;
+            let result = agent.run_child(messages.clone(), trace, spans).await?;
+            cost.merge(&result.cost);
             results.push((branch_id.clone(), result.text, result.structured_output));
         }
 
@@ -270,11 +349,14 @@ impl Graph {
             }
         };
 
-        Ok(NodeOutput {
-            node_id: node_id.to_string(),
-            text: merged.0,
-            data: merged.1,
-        })
+        Ok((
+            NodeOutput {
+                node_id: node_id.to_string(),
+                text: merged.0,
+                data: merged.1,
+            },
+            Some(cost),
+        ))
     }
 
     /// Find nodes that become ready after a node completes.
@@ -296,6 +378,19 @@ impl Graph {
     }
 }
 
+/// Fold a node's cost into the graph total and the per-node breakdown.
+fn record_node_cost(
+    total: &mut RunCost,
+    node_costs: &mut HashMap<String, RunCost>,
+    node_id: &str,
+    node_cost: Option<RunCost>,
+) {
+    if let Some(node_cost) = node_cost {
+        total.merge(&node_cost);
+        node_costs.insert(node_id.to_string(), node_cost);
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Builder
 // ---------------------------------------------------------------------------
@@ -303,6 +398,8 @@ impl Graph {
 pub struct GraphBuilder {
     nodes: HashMap<String, GraphNode>,
     edges: HashMap<String, Vec<String>>,
+    telemetry: Option<crate::Shared<TelemetryCollector>>,
+    plugins: Option<crate::Shared<PluginRegistry>>,
 }
 
 impl GraphBuilder {
@@ -310,6 +407,8 @@ impl GraphBuilder {
         Self {
             nodes: HashMap::new(),
             edges: HashMap::new(),
+            telemetry: None,
+            plugins: None,
         }
     }
 
@@ -417,8 +516,35 @@ impl GraphBuilder {
         self
     }
 
+    /// Record a graph span with a span per node and the agent runs nested under it.
+    pub fn telemetry(mut self, collector: crate::Shared<TelemetryCollector>) -> Self {
+        self.telemetry = Some(collector);
+        self
+    }
+
+    /// Publish graph start/finish events, and node agents' events unless
+    /// they have their own registry.
+    pub fn plugins(mut self, registry: crate::Shared<PluginRegistry>) -> Self {
+        self.plugins = Some(registry);
+        self
+    }
+
     /// Build the graph.
-    pub fn build(self) -> Graph {
+    pub fn build(mut self) -> Graph {
+        if let Some(ref plugins) = self.plugins {
+            for node in self.nodes.values_mut() {
+                match node {
+                    GraphNode::Agent { agent, .. } => agent.inherit_plugins(plugins),
+                    GraphNode::Fork { agents, .. } => {
+                        for (_, agent) in agents {
+                            agent.inherit_plugins(plugins);
+                        }
+                    }
+                    GraphNode::Function { .. } => {}
+                }
+            }
+        }
+
         let entry_points: Vec<String> = self
             .nodes
             .keys()
@@ -438,6 +564,8 @@ impl GraphBuilder {
             edges: self.edges,
             entry_points,
             terminal_nodes,
+            telemetry: self.telemetry,
+            plugins: self.plugins,
         }
     }
 }
@@ -6,6 +6,7 @@
 
 use crate::error::{self, GaussError};
 use crate::message::Message;
+use crate::plugin::{GaussEvent, PluginRegistry};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -168,6 +169,7 @@ pub trait Guardrail {
 /// Ordered guardrail execution pipeline. Short-circuits on Block.
 pub struct GuardrailChain {
     guardrails: Vec<crate::Shared<dyn Guardrail>>,
+    plugins: Option<crate::Shared<PluginRegistry>>,
 }
 
 impl Default for GuardrailChain {
@@ -188,9 +190,36 @@ impl GuardrailChain {
     pub fn new() -> Self {
         Self {
             guardrails: Vec::new(),
+            plugins: None,
         }
     }
 
+    /// Publish a `GuardrailDecision` event for every guardrail evaluated.
+    pub fn with_plugins(mut self, registry: crate::Shared<PluginRegistry>) -> Self {
+        self.plugins = Some(registry);
+        self
+    }
+
+    async fn record_decision(&self, stage: &str, name: &str, result: &GuardrailResult) {
+        let Some(ref plugins) = self.plugins else {
+            return;
+        };
+        let (action, reason) = match &result.action {
+            GuardrailAction::Allow => ("allow", None),
+            GuardrailAction::Warn { reason, .. } => ("warn", Some(reason.clone())),
+            GuardrailAction::Rewrite { reason, .. } => ("rewrite", Some(reason.clone())),
+            GuardrailAction::Block { reason, .. } => ("block", Some(reason.clone())),
+        };
+        plugins
+            .dispatch(&GaussEvent::GuardrailDecision {
+                guardrail: name.to_string(),
+                stage: stage.to_string(),
+                action: action.to_string(),
+                reason,
+            })
+            .await;
+    }
+
     /// Add a guardrail to the chain.
     pub fn add(&mut self, guardrail: crate::Shared<dyn Guardrail>) {
         self.guardrails.push(guardrail);
@@ -220,6 +249,8 @@ impl GuardrailChain {
                 .validate_input(messages)
                 .await?
                 .with_guardrail_name(guardrail.name());
+            self.record_decision("input", guardrail.name(), &result)
+                .await;
             let blocked = result.action.is_blocked();
             results.push(result);
             if blocked {
@@ -243,6 +274,8 @@ impl GuardrailChain {
                 .validate_output(text)
                 .await?
                 .with_guardrail_name(guardrail.name());
+            self.record_decision("output", guardrail.name(), &result)
+                .await;
             let blocked = result.action.is_blocked();
             results.push(result);
             if blocked {
@@ -399,31 +432,38 @@ pub enum PiiAction {
     Redact,
 }
 
+/// Regexes for common PII with their type name and redaction placeholder.
+pub(crate) fn pii_patterns() -> Vec<(regex::Regex, &'static str, &'static str)> {
+    vec![
+        (
+            regex::Regex::new(r"[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}").unwrap(),
+            "email",
+            "[EMAIL_REDACTED]",
+        ),
+        (
+            regex::Regex::new(r"\b\d{3}[-.]?\d{3}[-.]?\d{4}\b").unwrap(),
+            "phone",
+            "[PHONE_REDACTED]",
+        ),
+        (
+            regex::Regex::new(r"\b\d{3}-\d{2}-\d{4}\b").unwrap(),
+            "ssn",
+            "[SSN_REDACTED]",
+        ),
+        (
+            regex::Regex::new(r"\b(?:\d[ -]*?){13,16}\b").unwrap(),
+            "credit_card",
+            "[CC_REDACTED]",
+        ),
+    ]
+}
+
 impl PiiDetectionGuardrail {
     pub fn new(action: PiiAction) -> Self {
-        let patterns = vec![
-            (
-                regex::Regex::new(r"[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}").unwrap(),
-                "email",
-                "[EMAIL_REDACTED]",
-            ),
-            (
-                regex::Regex::new(r"\b\d{3}[-.]?\d{3}[-.]?\d{4}\b").unwrap(),
-                "phone",
-                "[PHONE_REDACTED]",
-            ),
-            (
-                regex::Regex::new(r"\b\d{3}-\d{2}-\d{4}\b").unwrap(),
-                "ssn",
-                "[SSN_REDACTED]",
-            ),
-            (
-                regex::Regex::new(r"\b(?:\d[ -]*?){13,16}\b").unwrap(),
-                "credit_card",
-                "[CC_REDACTED]",
-            ),
-        ];
-        Self { action, patterns }
+        Self {
+            action,
+            patterns: pii_patterns(),
+        }
     }
 
     fn check_text(&self, text: &str) -> GuardrailResult {
@@ -4,6 +4,7 @@
 //! for checkpoint/resume, and workflow-level human review steps.
 
 use crate::error;
+use crate::plugin::{GaussEvent, PluginRegistry};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -212,6 +213,7 @@ impl CheckpointStore for InMemoryCheckpointStore {
 #[derive(Debug, Default)]
 pub struct ApprovalManager {
     pending: std::sync::Mutex<HashMap<String, ApprovalRequest>>,
+    plugins: Option<crate::Shared<PluginRegistry>>,
 }
 
 impl ApprovalManager {
@@ -219,6 +221,12 @@ impl ApprovalManager {
         Self::default()
     }
 
+    /// Publish an `ApprovalRequest` event for every new request.
+    pub fn with_plugins(mut self, registry: crate::Shared<PluginRegistry>) -> Self {
+        self.plugins = Some(registry);
+        self
+    }
+
     /// Create a new approval request.
     pub fn request_approval(
         &self,
@@ -247,6 +255,16 @@ impl ApprovalManager {
             .map_err(|e| error::GaussError::internal(e.to_string()))?
             .insert(request.id.clone(), request.clone());
 
+        if let Some(ref plugins) = self.plugins {
+            plugins.emit(&GaussEvent::ApprovalRequest {
+                request_id: request.id.clone(),
+                tool_name: request.tool_name.clone(),
+                arguments: request.args.clone(),
+                session_id: request.session_id.clone(),
+                step_index: request.step_index,
+            });
+        }
+
         Ok(request)
     }
 
@@ -10,8 +10,15 @@ pub mod a2a_client;
 pub mod a2a_server;
 pub mod agent;
 pub mod agents_md;
+pub mod catalog;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod code_execution;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod code_session;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod code_wasm;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod code_workspace;
 pub mod config;
 pub mod context;
 pub mod cost;
@@ -23,8 +30,11 @@ pub mod hitl;
 pub mod mcp;
 pub mod memory;
 pub mod message;
+pub mod metrics;
 pub mod middleware;
 pub mod network;
+#[cfg(all(feature = "otel", not(target_arch = "wasm32")))]
+pub mod otel;
 pub mod patterns;
 pub mod plugin;
 pub mod provider;
@@ -36,6 +46,8 @@ pub mod streaming;
 pub mod team;
 pub mod telemetry;
 pub mod tool;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod wasm_plugin;
 pub mod workflow;
 
 /// Platform-aware shared pointer: `Arc` on native, `Rc` on WASM.
@@ -44,15 +56,24 @@ pub type Shared<T> = std::sync::Arc<T>;
 #[cfg(target_arch = "wasm32")]
 pub type Shared<T> = std::rc::Rc<T>;
 
-pub use agent::{Agent, AgentBuilder, AgentOutput};
+pub use agent::{Agent, AgentBuilder, AgentOutput, StopReason};
 pub use agents_md::{AgentSpec, AgentToolSpec};
+pub use catalog::{ModelCatalog, ModelInfo};
 #[cfg(not(target_arch = "wasm32"))]
 pub use code_execution::{
     BashRuntime, CodeExecutionConfig, CodeExecutionConfigBuilder, CodeExecutionOrchestrator,
     CodeRuntime, ExecutionResult, JavaScriptRuntime, PythonRuntime, RuntimeConfig, SandboxConfig,
     code_execution_tool,
 };
-pub use cost::CostEstimate;
+#[cfg(not(target_arch = "wasm32"))]
+pub use code_session::{CodeSession, SessionConfig, SessionManager};
+#[cfg(all(not(target_arch = "wasm32"), feature = "wasm-runtime"))]
+pub use code_wasm::WasmRuntime;
+#[cfg(not(target_arch = "wasm32"))]
+pub use code_wasm::WasmRuntimeConfig;
+#[cfg(not(target_arch = "wasm32"))]
+pub use code_workspace::{Artifact, InputFile, WorkspaceConfig};
+pub use cost::{CostEstimate, RunCost};
 pub use error::GaussError;
 pub use graph::{ConsensusStrategy, Graph, GraphBuilder, GraphResult};
 pub use message::{
@@ -1001,11 +1001,12 @@ impl HttpTransport {
 #[async_trait]
 impl McpTransport for HttpTransport {
     async fn send(&self, message: &JsonRpcMessage) -> error::Result<()> {
-        let resp = self
+        let req = self
             .client
             .post(&self.endpoint)
             .header("Content-Type", "application/json")
-            .json(message)
+            .json(message);
+        let resp = crate::telemetry::inject_trace_context(req)
             .send()
             .await
             .map_err(|e| error::GaussError::tool("mcp", format!("HTTP send error: {e}")))?;
@@ -46,6 +46,13 @@ pub enum Content {
     },
     Reasoning {
         text: String,
+        /// Provider item id, needed to send the reasoning back on a later turn.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        id: Option<String>,
+        /// Opaque encrypted reasoning state (OpenAI Responses `encrypted_content`,
+        /// Bedrock reasoning `signature`).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        encrypted_content: Option<String>,
     },
     File {
         #[serde(skip_serializing_if = "Option::is_none")]
@@ -87,7 +94,8 @@ pub enum Content {
 /// A citation referencing a source document.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Citation {
-    /// Citation type: "char_location", "page_location", "content_block_location".
+    /// Citation type: "char_location", "page_location", "content_block_location",
+    /// "url_citation".
     pub citation_type: String,
     /// The cited text from the source document.
     pub cited_text: String,
@@ -100,6 +108,9 @@ pub struct Citation {
     /// End index.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub end: Option<u64>,
+    /// Source URL (web citations).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
 }
 
 /// A web search result from grounding (Google Search).
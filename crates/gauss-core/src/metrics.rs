@@ -0,0 +1,547 @@
+//! Metrics — Prometheus-style counters, gauges and histograms.
+//!
+//! A [`MetricsRegistry`] aggregates labeled series for agents, providers and
+//! tools. It is fed from finished runs via
+//! [`TelemetryCollector::with_registry`](crate::telemetry::TelemetryCollector::with_registry),
+//! from [`RetryProvider`](crate::provider::retry::RetryProvider) retries and
+//! from [`CircuitBreaker`](crate::resilience::CircuitBreaker) state changes,
+//! and rendered in the Prometheus text exposition format.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::Mutex;
+
+use crate::telemetry::{SpanRecord, SpanStatus, SpanType};
+
+/// Default latency buckets, in seconds.
+pub const DEFAULT_LATENCY_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0,
+];
+
+/// Circuit-breaker states, as reported by `CircuitBreaker::state_name`.
+const CIRCUIT_STATES: &[&str] = &["closed", "open", "half_open"];
+
+type Labels = Vec<(String, String)>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MetricKind {
+    Counter,
+    Gauge,
+    Histogram,
+}
+
+impl MetricKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Counter => "counter",
+            Self::Gauge => "gauge",
+            Self::Histogram => "histogram",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Series {
+    Value(f64),
+    Histogram {
+        /// Cumulative count per bucket upper bound.
+        buckets: Vec<u64>,
+        sum: f64,
+        count: u64,
+    },
+}
+
+#[derive(Debug)]
+struct Family {
+    help: String,
+    kind: MetricKind,
+    series: BTreeMap<Labels, Series>,
+}
+
+/// Thread-safe registry of labeled metric series.
+#[derive(Debug)]
+pub struct MetricsRegistry {
+    families: Mutex<BTreeMap<String, Family>>,
+    buckets: Vec<f64>,
+}
+
+impl Default for MetricsRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetricsRegistry {
+    pub fn new() -> Self {
+        Self {
+            families: Mutex::new(BTreeMap::new()),
+            buckets: DEFAULT_LATENCY_BUCKETS.to_vec(),
+        }
+    }
+
+    /// Use custom histogram bucket upper bounds (sorted ascending).
+    pub fn with_buckets(mut self, mut buckets: Vec<f64>) -> Self {
+        buckets.sort_by(f64::total_cmp);
+        buckets.dedup();
+        self.buckets = buckets;
+        self
+    }
+
+    /// Add `value` to a counter.
+    pub fn inc_counter(&self, name: &str, help: &str, labels: &[(&str, &str)], value: f64) {
+        self.update(name, help, MetricKind::Counter, labels, |series| {
+            if let Series::Value(v) = series {
+                *v += value;
+            }
+        });
+    }
+
+    /// Set a gauge.
+    pub fn set_gauge(&self, name: &str, help: &str, labels: &[(&str, &str)], value: f64) {
+        self.update(name, help, MetricKind::Gauge, labels, |series| {
+            if let Series::Value(v) = series {
+                *v = value;
+            }
+        });
+    }
+
+    /// Record an observation in a histogram.
+    pub fn observe(&self, name: &str, help: &str, labels: &[(&str, &str)], value: f64) {
+        let bounds = &self.buckets;
+        self.update(name, help, MetricKind::Histogram, labels, |series| {
+            if let Series::Histogram {
+                buckets,
+                sum,
+                count,
+            } = series
+            {
+                for (bucket, bound) in buckets.iter_mut().zip(bounds) {
+                    if value <= *bound {
+                        *bucket += 1;
+                    }
+                }
+                *sum += value;
+                *count += 1;
+            }
+        });
+    }
+
+    /// Current value of a counter or gauge, or the observation count of a
+    /// histogram. Labels may be given in any order.
+    pub fn value(&self, name: &str, labels: &[(&str, &str)]) -> Option<f64> {
+        let families = self.families.lock().ok()?;
+        match families.get(name)?.series.get(&label_key(labels))? {
+            Series::Value(v) => Some(*v),
+            Series::Histogram { count, .. } => Some(*count as f64),
+        }
+    }
+
+    /// Clear all series.
+    pub fn reset(&self) {
+        if let Ok(mut families) = self.families.lock() {
+            families.clear();
+        }
+    }
+
+    fn update(
+        &self,
+        name: &str,
+        help: &str,
+        kind: MetricKind,
+        labels: &[(&str, &str)],
+        apply: impl FnOnce(&mut Series),
+    ) {
+        let Ok(mut families) = self.families.lock() else {
+            return;
+        };
+        let family = families.entry(name.to_string()).or_insert_with(|| Family {
+            help: help.to_string(),
+            kind,
+            series: BTreeMap::new(),
+        });
+        if family.kind != kind {
+            tracing::warn!(metric = name, "Metric recorded with a conflicting type");
+            return;
+        }
+        let series = family
+            .series
+            .entry(label_key(labels))
+            .or_insert_with(|| match kind {
+                MetricKind::Histogram => Series::Histogram {
+                    buckets: vec![0; self.buckets.len()],
+                    sum: 0.0,
+                    count: 0,
+                },
+                _ => Series::Value(0.0),
+            });
+        apply(series);
+    }
+
+    // -----------------------------------------------------------------------
+    // Domain recorders
+    // -----------------------------------------------------------------------
+
+    /// Record a finished run from its span tree: agent runs and steps, model
+    /// calls (requests, errors by error type, latency, tokens, cost) and tool
+    /// calls. Orchestrator spans are walked through to reach nested agents.
+    pub fn record_run(&self, span: &SpanRecord) {
+        self.record_span(span, "");
+    }
+
+    fn record_span(&self, span: &SpanRecord, agent: &str) {
+        let attr = |key: &str| span.attributes.get(key).and_then(|v| v.as_str());
+        let number = |key: &str| {
+            span.attributes
+                .get(key)
+                .and_then(serde_json::Value::as_f64)
+                .unwrap_or(0.0)
+        };
+        let seconds = span.duration_ms as f64 / 1000.0;
+        let failed = span.status == SpanStatus::Error;
+        let error_type = attr("error.type").unwrap_or("unknown");
+
+        let agent = match span.span_type {
+            SpanType::AgentRun => {
+                let agent = attr("agent_name").unwrap_or(agent);
+                let status = if failed { "error" } else { "ok" };
+                self.inc_counter(
+                    "gauss_agent_runs_total",
+                    "Agent runs.",
+                    &[("agent", agent), ("status", status)],
+                    1.0,
+                );
+                self.observe(
+                    "gauss_agent_run_duration_seconds",
+                    "Agent run latency in seconds.",
+                    &[("agent", agent)],
+                    seconds,
+                );
+                agent
+            }
+            SpanType::AgentStep => {
+                self.inc_counter(
+                    "gauss_agent_steps_total",
+                    "Agent loop steps.",
+                    &[("agent", agent)],
+                    1.0,
+                );
+                agent
+            }
+            SpanType::ModelCall => {
+                let provider = attr("provider").unwrap_or("unknown");
+                let model = attr("model").unwrap_or("unknown");
+                let labels = [("agent", agent), ("model", model), ("provider", provider)];
+                self.inc_counter(
+                    "gauss_model_requests_total",
+                    "Model requests.",
+                    &labels,
+                    1.0,
+                );
+                self.observe(
+                    "gauss_model_request_duration_seconds",
+                    "Model request latency in seconds.",
+                    &labels,
+                    seconds,
+                );
+                if failed {
+                    self.inc_counter(
+                        "gauss_model_errors_total",
+                        "Failed model requests by error type.",
+                        &[
+                            ("agent", agent),
+                            ("error", error_type),
+                            ("model", model),
+                            ("provider", provider),
+                        ],
+                        1.0,
+                    );
+                }
+                for (key, kind) in [
+                    ("input_tokens", "input"),
+                    ("output_tokens", "output"),
+                    ("reasoning_tokens", "reasoning"),
+                    ("cache_read_tokens", "cache_read"),
+                    ("cache_creation_tokens", "cache_creation"),
+                ] {
+                    let tokens = number(key);
+                    if tokens > 0.0 {
+                        self.inc_counter(
+                            "gauss_tokens_total",
+                            "Tokens processed by type.",
+                            &[
+                                ("agent", agent),
+                                ("model", model),
+                                ("provider", provider),
+                                ("type", kind),
+                            ],
+                            tokens,
+                        );
+                    }
+                }
+                let cost = number("cost_usd");
+                if cost > 0.0 {
+                    self.inc_counter(
+                        "gauss_cost_usd_total",
+                        "Estimated spend in USD.",
+                        &labels,
+                        cost,
+                    );
+                }
+                agent
+            }
+            SpanType::ToolCall => {
+                let tool = attr("tool_name").unwrap_or("unknown");
+                let labels = [("agent", agent), ("tool", tool)];
+                self.inc_counter("gauss_tool_calls_total", "Tool calls.", &labels, 1.0);
+                self.observe(
+                    "gauss_tool_call_duration_seconds",
+                    "Tool call latency in seconds.",
+                    &labels,
+                    seconds,
+                );
+                if failed {
+                    self.inc_counter(
+                        "gauss_tool_errors_total",
+                        "Failed tool calls by error type.",
+                        &[("agent", agent), ("error", error_type), ("tool", tool)],
+                        1.0,
+                    );
+                }
+                agent
+            }
+            _ => agent,
+        };
+
+        for child in &span.children {
+            self.record_span(child, agent);
+        }
+    }
+
+    /// Count a retry of a provider request.
+    pub fn record_retry(&self, provider: &str, model: &str, error: &str) {
+        self.inc_counter(
+            "gauss_provider_retries_total",
+            "Provider request retries by triggering error type.",
+            &[("error", error), ("model", model), ("provider", provider)],
+            1.0,
+        );
+    }
+
+    /// Publish a circuit breaker's state: `1` for the current state and `0`
+    /// for the others.
+    pub fn record_circuit_state(&self, provider: &str, model: &str, state: &str) {
+        for candidate in CIRCUIT_STATES {
+            self.set_gauge(
+                "gauss_circuit_breaker_state",
+                "Circuit breaker state (1 for the current state).",
+                &[
+                    ("model", model),
+                    ("provider", provider),
+                    ("state", candidate),
+                ],
+                if *candidate == state { 1.0 } else { 0.0 },
+            );
+        }
+    }
+
+    // -----------------------------------------------------------------------
+    // Exposition
+    // -----------------------------------------------------------------------
+
+    /// Render all series in the Prometheus text exposition format (0.0.4).
+    pub fn render(&self) -> String {
+        let Ok(families) = self.families.lock() else {
+            return String::new();
+        };
+        let mut out = String::new();
+        for (name, family) in families.iter() {
+            let _ = writeln!(out, "# HELP {name} {}", escape_help(&family.help));
+            let _ = writeln!(out, "# TYPE {name} {}", family.kind.as_str());
+            for (labels, series) in &family.series {
+                match series {
+                    Series::Value(v) => {
+                        let _ = writeln!(out, "{name}{} {}", format_labels(labels, None), v);
+                    }
+                    Series::Histogram {
+                        buckets,
+                        sum,
+                        count,
+                    } => {
+                        for (bound, n) in self.buckets.iter().zip(buckets) {
+                            let le = bound.to_string();
+                            let _ = writeln!(
+                                out,
+                                "{name}_bucket{} {n}",
+                                format_labels(labels, Some(&le))
+                            );
+                        }
+                        let _ = writeln!(
+                            out,
+                            "{name}_bucket{} {count}",
+                            format_labels(labels, Some("+Inf"))
+                        );
+                        let _ = writeln!(out, "{name}_sum{} {sum}", format_labels(labels, None));
+                        let _ =
+                            writeln!(out, "{name}_count{} {count}", format_labels(labels, None));
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+fn label_key(labels: &[(&str, &str)]) -> Labels {
+    let mut key: Labels = labels
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    key.sort();
+    key
+}
+
+fn format_labels(labels: &Labels, le: Option<&str>) -> String {
+    let mut parts: Vec<String> = labels
+        .iter()
+        .map(|(k, v)| format!("{k}=\"{}\"", escape_label(v)))
+        .collect();
+    if let Some(le) = le {
+        parts.push(format!("le=\"{le}\""));
+    }
+    if parts.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", parts.join(","))
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn escape_help(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+// ---------------------------------------------------------------------------
+// HTTP endpoint
+// ---------------------------------------------------------------------------
+
+/// Minimal HTTP server exposing a registry at `GET /metrics`.
+#[cfg(all(feature = "native", not(target_arch = "wasm32")))]
+pub struct MetricsServer {
+    listener: tokio::net::TcpListener,
+    registry: crate::Shared<MetricsRegistry>,
+}
+
+#[cfg(all(feature = "native", not(target_arch = "wasm32")))]
+impl MetricsServer {
+    /// Bind the endpoint. Use port `0` to pick a free port.
+    pub async fn bind(
+        addr: impl tokio::net::ToSocketAddrs,
+        registry: crate::Shared<MetricsRegistry>,
+    ) -> crate::error::Result<Self> {
+        let listener = tokio::net::TcpListener::bind(addr)
+            .await
+            .map_err(|e| crate::error::GaussError::internal(format!("Metrics bind failed: {e}")))?;
+        Ok(Self { listener, registry })
+    }
+
+    pub fn local_addr(&self) -> crate::error::Result<std::net::SocketAddr> {
+        self.listener
+            .local_addr()
+            .map_err(|e| crate::error::GaussError::internal(e.to_string()))
+    }
+
+    /// Serve scrapes until the task is dropped.
+    pub async fn serve(self) -> crate::error::Result<()> {
+        loop {
+            let (stream, _) = self
+                .listener
+                .accept()
+                .await
+                .map_err(|e| crate::error::GaussError::internal(e.to_string()))?;
+            let registry = self.registry.clone();
+            tokio::spawn(async move {
+                if let Err(e) = respond(stream, &registry).await {
+                    tracing::debug!(error = %e, "Metrics scrape failed");
+                }
+            });
+        }
+    }
+}
+
+#[cfg(all(feature = "native", not(target_arch = "wasm32")))]
+async fn respond(
+    mut stream: tokio::net::TcpStream,
+    registry: &MetricsRegistry,
+) -> std::io::Result<()> {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    // Read the request head; bodies are not expected.
+    let mut head = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") && head.len() < 8192 {
+        let n = stream.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        head.extend_from_slice(&buf[..n]);
+    }
+    let request_line = String::from_utf8_lossy(&head);
+    let mut parts = request_line.split_whitespace();
+    let (method, target) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+    let path = target.split('?').next().unwrap_or("");
+
+    let (status, content_type, body) = match (method, path) {
+        ("GET", "/metrics") => (
+            "200 OK",
+            "text/plain; version=0.0.4; charset=utf-8",
+            registry.render(),
+        ),
+        ("GET", _) => ("404 Not Found", "text/plain", "Not Found\n".to_string()),
+        _ => (
+            "405 Method Not Allowed",
+            "text/plain",
+            "Method Not Allowed\n".to_string(),
+        ),
+    };
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_counters_and_histograms() {
+        let registry = MetricsRegistry::new().with_buckets(vec![1.0, 0.1]);
+        registry.inc_counter("jobs_total", "Jobs.", &[("queue", "a\"b")], 2.0);
+        registry.observe("latency_seconds", "Latency.", &[], 0.05);
+        registry.observe("latency_seconds", "Latency.", &[], 0.5);
+
+        let text = registry.render();
+        assert!(text.contains("# TYPE jobs_total counter\njobs_total{queue=\"a\\\"b\"} 2\n"));
+        assert!(text.contains("latency_seconds_bucket{le=\"0.1\"} 1\n"));
+        assert!(text.contains("latency_seconds_bucket{le=\"1\"} 2\n"));
+        assert!(text.contains("latency_seconds_bucket{le=\"+Inf\"} 2\n"));
+        assert!(text.contains("latency_seconds_sum 0.55\n"));
+        assert!(text.contains("latency_seconds_count 2\n"));
+    }
+
+    #[test]
+    fn test_conflicting_kind_is_ignored() {
+        let registry = MetricsRegistry::new();
+        registry.inc_counter("x", "X.", &[], 1.0);
+        registry.set_gauge("x", "X.", &[], 5.0);
+        assert_eq!(registry.value("x", &[]), Some(1.0));
+    }
+}
@@ -437,6 +437,10 @@ impl Middleware for LoggingMiddleware {
 // ---------------------------------------------------------------------------
 
 /// LLM response caching middleware with TTL.
+///
+/// Caches final agent text only. To cache provider responses keyed by
+/// messages, tools and options, wrap the provider in
+/// [`CachedProvider`](crate::provider::cache::CachedProvider).
 #[derive(Debug)]
 pub struct CachingMiddleware {
     cache: std::sync::Mutex<HashMap<String, CacheEntry>>,
@@ -534,6 +538,7 @@ impl Middleware for RateLimitMiddleware {
         }
         Err(error::GaussError::RateLimited {
             provider: "middleware:rate_limit".to_string(),
+            message: format!("Rate limit exceeded for key '{key}'"),
             retry_after_ms,
         })
     }
@@ -6,6 +6,7 @@
 use crate::agent::Agent;
 use crate::error;
 use crate::message::Message;
+use crate::plugin::{OrchestratorRun, PluginRegistry};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -75,6 +76,7 @@ pub struct AgentNetwork {
     nodes: HashMap<String, AgentNode>,
     /// Optional supervisor agent name.
     supervisor: Option<String>,
+    plugins: Option<crate::Shared<PluginRegistry>>,
 }
 
 impl Default for AgentNetwork {
@@ -88,14 +90,28 @@ impl AgentNetwork {
         Self {
             nodes: HashMap::new(),
             supervisor: None,
+            plugins: None,
         }
     }
 
     /// Add an agent to the network.
-    pub fn add_agent(&mut self, node: AgentNode) {
+    pub fn add_agent(&mut self, mut node: AgentNode) {
+        if let Some(ref plugins) = self.plugins {
+            node.agent.inherit_plugins(plugins);
+        }
         self.nodes.insert(node.card.name.clone(), node);
     }
 
+    /// Publish network start/finish events for supervised runs and
+    /// broadcasts, and member agents' events unless they have their own
+    /// registry.
+    pub fn set_plugins(&mut self, registry: crate::Shared<PluginRegistry>) {
+        for node in self.nodes.values_mut() {
+            node.agent.inherit_plugins(&registry);
+        }
+        self.plugins = Some(registry);
+    }
+
     /// Remove an agent from the network.
     pub fn remove_agent(&mut self, name: &str) -> Option<AgentNode> {
         let node = self.nodes.remove(name);
@@ -161,6 +177,14 @@ impl AgentNetwork {
 
     /// Run the supervisor pattern: supervisor agent decides which sub-agent to delegate to.
     pub async fn run_supervised(&self, messages: Vec<Message>) -> error::Result<DelegationResult> {
+        let lifecycle =
+            OrchestratorRun::start(self.plugins.as_ref(), "network", "supervised").await;
+        let result = self.supervise(messages).await;
+        lifecycle.finish(result.is_err()).await;
+        result
+    }
+
+    async fn supervise(&self, messages: Vec<Message>) -> error::Result<DelegationResult> {
         let supervisor_name = self.supervisor.as_deref().ok_or_else(|| {
             error::GaussError::internal("No supervisor agent configured".to_string())
         })?;
@@ -220,8 +244,15 @@ impl AgentNetwork {
     }
 
     /// Run all agents in parallel and aggregate results.
-    #[cfg(not(target_arch = "wasm32"))]
     pub async fn broadcast(&self, messages: Vec<Message>) -> error::Result<Vec<DelegationResult>> {
+        let lifecycle = OrchestratorRun::start(self.plugins.as_ref(), "network", "broadcast").await;
+        let result = self.broadcast_all(messages).await;
+        lifecycle.finish(result.is_err()).await;
+        result
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn broadcast_all(&self, messages: Vec<Message>) -> error::Result<Vec<DelegationResult>> {
         let mut handles = Vec::new();
 
         for name in self.nodes.keys() {
@@ -240,7 +271,7 @@ impl AgentNetwork {
     }
 
     #[cfg(target_arch = "wasm32")]
-    pub async fn broadcast(&self, messages: Vec<Message>) -> error::Result<Vec<DelegationResult>> {
+    async fn broadcast_all(&self, messages: Vec<Message>) -> error::Result<Vec<DelegationResult>> {
         let mut results = Vec::new();
         for name in self.nodes.keys() {
             results.push(self.delegate(name, messages.clone()).await?);
@@ -281,6 +312,11 @@ impl AgentNetworkBuilder {
         self
     }
 
+    pub fn plugins(mut self, registry: crate::Shared<PluginRegistry>) -> Self {
+        self.network.set_plugins(registry);
+        self
+    }
+
     pub fn build(self) -> AgentNetwork {
         self.network
     }
@@ -0,0 +1,382 @@
+//! OpenTelemetry export — ships [`SpanRecord`]s to an OTLP/HTTP collector
+//! using the GenAI semantic conventions.
+//!
+//! Spans are encoded with the OTLP JSON mapping and POSTed to
+//! `{endpoint}/v1/traces`. Shorthand attributes (`model`, `input_tokens`,
+//! `tool_name`, …) are renamed to their `gen_ai.*` equivalents. Prompt and
+//! response content is dropped unless capture is enabled, and captured
+//! content passes through the configured redaction rules first.
+
+use serde_json::{Value, json};
+use std::collections::HashMap;
+
+use crate::error::{self, GaussError};
+use crate::telemetry::{SpanRecord, SpanStatus, SpanType, TelemetryCollector, TraceContext};
+
+/// Default OTLP/HTTP endpoint of a local collector.
+pub const DEFAULT_OTLP_ENDPOINT: &str = "http://localhost:4318";
+
+/// Attributes holding prompt/response content, exported only when
+/// content capture is enabled.
+const CONTENT_ATTRIBUTES: &[&str] = &[
+    "gen_ai.input.messages",
+    "gen_ai.output.messages",
+    "gen_ai.system_instructions",
+    "gen_ai.tool.call.arguments",
+    "gen_ai.tool.call.result",
+];
+
+/// Shorthand attribute keys mapped to GenAI semantic convention names.
+const ATTRIBUTE_ALIASES: &[(&str, &str)] = &[
+    ("model", "gen_ai.request.model"),
+    ("response_model", "gen_ai.response.model"),
+    ("provider", "gen_ai.provider.name"),
+    ("input_tokens", "gen_ai.usage.input_tokens"),
+    ("output_tokens", "gen_ai.usage.output_tokens"),
+    ("temperature", "gen_ai.request.temperature"),
+    ("max_tokens", "gen_ai.request.max_tokens"),
+    ("finish_reason", "gen_ai.response.finish_reasons"),
+    ("agent", "gen_ai.agent.name"),
+    ("agent_name", "gen_ai.agent.name"),
+    ("tool", "gen_ai.tool.name"),
+    ("tool_name", "gen_ai.tool.name"),
+    ("tool_call_id", "gen_ai.tool.call.id"),
+    ("prompt", "gen_ai.input.messages"),
+    ("input", "gen_ai.input.messages"),
+    ("response", "gen_ai.output.messages"),
+    ("output", "gen_ai.output.messages"),
+    ("args", "gen_ai.tool.call.arguments"),
+    ("arguments", "gen_ai.tool.call.arguments"),
+    ("result", "gen_ai.tool.call.result"),
+];
+
+/// OTLP exporter configuration.
+#[derive(Debug, Clone)]
+pub struct OtelConfig {
+    /// Collector base URL, or a full URL ending in `/v1/traces`.
+    pub endpoint: String,
+    pub service_name: String,
+    /// Extra request headers (e.g. auth for a hosted collector).
+    pub headers: HashMap<String, String>,
+    /// Export prompt/response content attributes.
+    pub capture_content: bool,
+    /// Patterns replaced in captured content before export.
+    pub redactions: Vec<(regex::Regex, String)>,
+    pub timeout_ms: Option<u64>,
+}
+
+impl Default for OtelConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: DEFAULT_OTLP_ENDPOINT.to_string(),
+            service_name: "gauss".to_string(),
+            headers: HashMap::new(),
+            capture_content: false,
+            redactions: Vec::new(),
+            timeout_ms: None,
+        }
+    }
+}
+
+impl OtelConfig {
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Read the standard `OTEL_*` environment variables:
+    /// `OTEL_EXPORTER_OTLP_TRACES_ENDPOINT` / `OTEL_EXPORTER_OTLP_ENDPOINT`,
+    /// `OTEL_EXPORTER_OTLP_HEADERS`, `OTEL_SERVICE_NAME` and
+    /// `OTEL_INSTRUMENTATION_GENAI_CAPTURE_MESSAGE_CONTENT`.
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+        if let Ok(endpoint) = std::env::var("OTEL_EXPORTER_OTLP_TRACES_ENDPOINT")
+            .or_else(|_| std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT"))
+        {
+            config.endpoint = endpoint;
+        }
+        if let Ok(name) = std::env::var("OTEL_SERVICE_NAME") {
+            config.service_name = name;
+        }
+        if let Ok(headers) = std::env::var("OTEL_EXPORTER_OTLP_HEADERS") {
+            for pair in headers.split(',') {
+                if let Some((k, v)) = pair.split_once('=') {
+                    config
+                        .headers
+                        .insert(k.trim().to_string(), v.trim().to_string());
+                }
+            }
+        }
+        if let Ok(capture) = std::env::var("OTEL_INSTRUMENTATION_GENAI_CAPTURE_MESSAGE_CONTENT") {
+            config.capture_content = capture.eq_ignore_ascii_case("true");
+        }
+        config
+    }
+
+    pub fn service_name(mut self, name: impl Into<String>) -> Self {
+        self.service_name = name.into();
+        self
+    }
+
+    pub fn header(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn capture_content(mut self, capture: bool) -> Self {
+        self.capture_content = capture;
+        self
+    }
+
+    /// Replace matches of `pattern` in captured content.
+    pub fn redact(mut self, pattern: &str, replacement: impl Into<String>) -> error::Result<Self> {
+        let re = regex::Regex::new(pattern).map_err(|e| GaussError::Config {
+            message: format!("Invalid redaction pattern '{pattern}': {e}"),
+        })?;
+        self.redactions.push((re, replacement.into()));
+        Ok(self)
+    }
+
+    /// Redact emails, phone numbers, SSNs and card numbers in captured content.
+    pub fn redact_pii(mut self) -> Self {
+        for (re, _, replacement) in crate::guardrail::pii_patterns() {
+            self.redactions.push((re, replacement.to_string()));
+        }
+        self
+    }
+
+    pub fn timeout_ms(mut self, ms: u64) -> Self {
+        self.timeout_ms = Some(ms);
+        self
+    }
+
+    fn traces_url(&self) -> String {
+        let endpoint = self.endpoint.trim_end_matches('/');
+        if endpoint.ends_with("/v1/traces") {
+            endpoint.to_string()
+        } else {
+            format!("{endpoint}/v1/traces")
+        }
+    }
+}
+
+/// Exports spans to an OTLP/HTTP collector.
+pub struct OtlpExporter {
+    config: OtelConfig,
+    client: reqwest::Client,
+}
+
+impl OtlpExporter {
+    pub fn new(config: OtelConfig) -> Self {
+        let client = crate::provider::build_client(config.timeout_ms);
+        Self { config, client }
+    }
+
+    pub fn config(&self) -> &OtelConfig {
+        &self.config
+    }
+
+    /// Send spans (and their children) to the collector.
+    pub async fn export(&self, spans: &[SpanRecord]) -> error::Result<()> {
+        if spans.is_empty() {
+            return Ok(());
+        }
+
+        let mut req = self
+            .client
+            .post(self.config.traces_url())
+            .header("Content-Type", "application/json");
+        for (k, v) in &self.config.headers {
+            req = req.header(k, v);
+        }
+
+        let resp = req
+            .json(&self.encode(spans))
+            .send()
+            .await
+            .map_err(|e| GaussError::provider("otlp", format!("Export request failed: {e}")))?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            return Err(GaussError::provider(
+                "otlp",
+                format!("Collector returned {status}: {body}"),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Drain the collector's spans and export them. Spans are put back if
+    /// the export fails.
+    pub async fn flush(&self, collector: &TelemetryCollector) -> error::Result<usize> {
+        let spans = collector.drain_spans();
+        let count = spans.len();
+        if let Err(e) = self.export(&spans).await {
+            for span in spans {
+                collector.record_span(span);
+            }
+            return Err(e);
+        }
+        Ok(count)
+    }
+
+    /// Encode spans as an OTLP `ExportTraceServiceRequest` (JSON mapping).
+    pub fn encode(&self, spans: &[SpanRecord]) -> Value {
+        let mut encoded = Vec::new();
+        for span in spans {
+            self.encode_span(span, None, &mut encoded);
+        }
+
+        json!({
+            "resourceSpans": [{
+                "resource": {
+                    "attributes": [
+                        key_value("service.name", any_value(&json!(self.config.service_name))),
+                        key_value("telemetry.sdk.name", any_value(&json!("gauss"))),
+                        key_value("telemetry.sdk.language", any_value(&json!("rust"))),
+                        key_value(
+                            "telemetry.sdk.version",
+                            any_value(&json!(env!("CARGO_PKG_VERSION"))),
+                        ),
+                    ]
+                },
+                "scopeSpans": [{
+                    "scope": { "name": "gauss-core", "version": env!("CARGO_PKG_VERSION") },
+                    "spans": encoded,
+                }]
+            }]
+        })
+    }
+
+    fn encode_span(&self, span: &SpanRecord, parent: Option<&TraceContext>, out: &mut Vec<Value>) {
+        let context = match (&span.context, parent) {
+            (Some(ctx), _) => ctx.clone(),
+            (None, Some(parent)) => parent.child(),
+            (None, None) => TraceContext::new_root(),
+        };
+        let parent_span_id = parent
+            .map(|p| p.span_id.clone())
+            .or_else(|| span.parent_span_id.clone())
+            .unwrap_or_default();
+
+        let duration_nanos = span.duration_ms.saturating_mul(1_000_000);
+        let start = if span.start_unix_nano > 0 {
+            span.start_unix_nano
+        } else {
+            crate::telemetry::unix_nanos().saturating_sub(duration_nanos)
+        };
+
+        let status = match span.status {
+            SpanStatus::Ok => json!({ "code": 1 }),
+            SpanStatus::Error => json!({
+                "code": 2,
+                "message": span.error.clone().unwrap_or_default(),
+            }),
+        };
+        let kind = match span.span_type {
+            SpanType::ModelCall | SpanType::Embedding => 3, // CLIENT
+            _ => 1,                                         // INTERNAL
+        };
+
+        let attributes: Vec<Value> = self
+            .attributes(span)
+            .into_iter()
+            .map(|(k, v)| key_value(&k, v))
+            .collect();
+
+        out.push(json!({
+            "traceId": context.trace_id,
+            "spanId": context.span_id,
+            "parentSpanId": parent_span_id,
+            "name": span.name,
+            "kind": kind,
+            "startTimeUnixNano": start.to_string(),
+            "endTimeUnixNano": start.saturating_add(duration_nanos).to_string(),
+            "attributes": attributes,
+            "status": status,
+        }));
+
+        for child in &span.children {
+            self.encode_span(child, Some(&context), out);
+        }
+    }
+
+    /// GenAI-mapped, content-filtered attributes in key order.
+    fn attributes(&self, span: &SpanRecord) -> Vec<(String, Value)> {
+        let mut attrs: HashMap<String, Value> = HashMap::new();
+
+        let operation = match span.span_type {
+            SpanType::AgentRun => Some("invoke_agent"),
+            SpanType::ModelCall => Some("chat"),
+            SpanType::ToolCall => Some("execute_tool"),
+            SpanType::Embedding => Some("embeddings"),
+            _ => None,
+        };
+        if let Some(op) = operation {
+            attrs.insert("gen_ai.operation.name".into(), any_value(&json!(op)));
+        }
+        let span_type = serde_json::to_value(span.span_type).unwrap_or(Value::Null);
+        attrs.insert("gauss.span_type".into(), any_value(&span_type));
+        if let Some(ref error) = span.error {
+            attrs.insert("error.type".into(), any_value(&json!(error)));
+        }
+
+        for (key, value) in &span.attributes {
+            let key = ATTRIBUTE_ALIASES
+                .iter()
+                .find(|(alias, _)| alias == key)
+                .map(|(_, name)| name.to_string())
+                .unwrap_or_else(|| key.clone());
+
+            let encoded = if CONTENT_ATTRIBUTES.contains(&key.as_str()) {
+                if !self.config.capture_content {
+                    continue;
+                }
+                any_value(&json!(self.redact(value)))
+            } else if key == "gen_ai.response.finish_reasons" && !value.is_array() {
+                any_value(&json!([value]))
+            } else {
+                any_value(value)
+            };
+            attrs.insert(key, encoded);
+        }
+
+        let mut attrs: Vec<(String, Value)> = attrs.into_iter().collect();
+        attrs.sort_by(|a, b| a.0.cmp(&b.0));
+        attrs
+    }
+
+    fn redact(&self, value: &Value) -> String {
+        let mut text = match value {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        for (re, replacement) in &self.config.redactions {
+            text = re.replace_all(&text, replacement.as_str()).into_owned();
+        }
+        text
+    }
+}
+
+fn key_value(key: &str, value: Value) -> Value {
+    json!({ "key": key, "value": value })
+}
+
+/// Encode a JSON value as an OTLP `AnyValue`.
+fn any_value(value: &Value) -> Value {
+    match value {
+        Value::String(s) => json!({ "stringValue": s }),
+        Value::Bool(b) => json!({ "boolValue": b }),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => json!({ "intValue": i.to_string() }),
+            None => json!({ "doubleValue": n.as_f64().unwrap_or_default() }),
+        },
+        Value::Array(items) => json!({
+            "arrayValue": { "values": items.iter().map(any_value).collect::<Vec<_>>() }
+        }),
+        Value::Null => json!({ "stringValue": "" }),
+        Value::Object(_) => json!({ "stringValue": value.to_string() }),
+    }
+}
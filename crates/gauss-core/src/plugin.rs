@@ -7,6 +7,8 @@ use crate::error::{self, GaussError};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
 
 // ---------------------------------------------------------------------------
 // GaussEvent — typed events for the bus
@@ -26,6 +28,21 @@ pub enum GaussEvent {
         agent_name: String,
         session_id: String,
         result_text: String,
+        #[serde(default)]
+        steps: usize,
+        #[serde(default)]
+        duration_ms: u64,
+    },
+    /// One iteration of the agent loop finished.
+    StepFinish {
+        agent_name: String,
+        session_id: String,
+        step_index: usize,
+        finish_reason: String,
+        tool_calls: usize,
+        input_tokens: u64,
+        output_tokens: u64,
+        duration_ms: u64,
     },
     /// Tool call started.
     ToolCallStart {
@@ -39,6 +56,52 @@ pub enum GaussEvent {
         duration_ms: u64,
         is_error: bool,
     },
+    /// A provider request is being retried after an error.
+    ProviderRetry {
+        provider: String,
+        model: String,
+        attempt: u32,
+        delay_ms: u64,
+        error: String,
+    },
+    /// A fallback chain moved on to the next provider.
+    ProviderFallback {
+        from_provider: String,
+        to_provider: String,
+        error: String,
+    },
+    /// A guardrail validated input or output.
+    GuardrailDecision {
+        guardrail: String,
+        /// `"input"` or `"output"`.
+        stage: String,
+        /// `"allow"`, `"warn"`, `"rewrite"` or `"block"`.
+        action: String,
+        reason: Option<String>,
+    },
+    /// A tool call is waiting for human approval.
+    ApprovalRequest {
+        request_id: String,
+        tool_name: String,
+        arguments: serde_json::Value,
+        session_id: String,
+        step_index: usize,
+    },
+    /// A team, graph, workflow or network run started.
+    OrchestratorStart {
+        /// `"team"`, `"graph"`, `"workflow"` or `"network"`.
+        kind: String,
+        name: String,
+        session_id: String,
+    },
+    /// A team, graph, workflow or network run finished.
+    OrchestratorFinish {
+        kind: String,
+        name: String,
+        session_id: String,
+        duration_ms: u64,
+        is_error: bool,
+    },
     /// An error occurred.
     Error { source: String, message: String },
     /// Custom user-defined event.
@@ -56,6 +119,13 @@ impl GaussEvent {
             Self::AgentFinish { .. } => "agent_finish",
             Self::ToolCallStart { .. } => "tool_call_start",
             Self::ToolCallFinish { .. } => "tool_call_finish",
+            Self::StepFinish { .. } => "step_finish",
+            Self::ProviderRetry { .. } => "provider_retry",
+            Self::ProviderFallback { .. } => "provider_fallback",
+            Self::GuardrailDecision { .. } => "guardrail_decision",
+            Self::ApprovalRequest { .. } => "approval_request",
+            Self::OrchestratorStart { .. } => "orchestrator_start",
+            Self::OrchestratorFinish { .. } => "orchestrator_finish",
             Self::Error { .. } => "error",
             Self::Custom { name, .. } => name,
         }
@@ -72,10 +142,27 @@ pub type EventHandler = Box<dyn Fn(&GaussEvent) + Send + Sync>;
 #[cfg(target_arch = "wasm32")]
 pub type EventHandler = Box<dyn Fn(&GaussEvent)>;
 
+/// Async handler function type for event bus, for handlers that do I/O.
+#[cfg(not(target_arch = "wasm32"))]
+pub type AsyncEventHandler =
+    Box<dyn Fn(GaussEvent) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
+#[cfg(target_arch = "wasm32")]
+pub type AsyncEventHandler = Box<dyn Fn(GaussEvent) -> Pin<Box<dyn Future<Output = ()>>>>;
+
+#[cfg(not(target_arch = "wasm32"))]
+type HandlerFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+#[cfg(target_arch = "wasm32")]
+type HandlerFuture = Pin<Box<dyn Future<Output = ()>>>;
+
+enum Subscriber {
+    Sync(EventHandler),
+    Async(AsyncEventHandler),
+}
+
 /// Publish/subscribe event bus with typed events.
 pub struct EventBus {
     /// Handlers keyed by event type pattern. "*" matches all events.
-    handlers: HashMap<String, Vec<(String, EventHandler)>>,
+    handlers: HashMap<String, Vec<(String, Subscriber)>>,
     next_id: u32,
 }
 
@@ -106,12 +193,27 @@ impl EventBus {
     /// Subscribe to a specific event type. Use "*" for all events.
     /// Returns a subscription ID for unsubscribing.
     pub fn subscribe(&mut self, event_type: impl Into<String>, handler: EventHandler) -> String {
+        self.add(event_type.into(), Subscriber::Sync(handler))
+    }
+
+    /// Subscribe an async handler. [`dispatch`](Self::dispatch), which is what
+    /// the agent runtime uses, and [`publish`](Self::publish) spawn it on the
+    /// current tokio runtime; [`publish_async`](Self::publish_async) awaits it.
+    pub fn subscribe_async(
+        &mut self,
+        event_type: impl Into<String>,
+        handler: AsyncEventHandler,
+    ) -> String {
+        self.add(event_type.into(), Subscriber::Async(handler))
+    }
+
+    fn add(&mut self, event_type: String, subscriber: Subscriber) -> String {
         let id = format!("sub_{}", self.next_id);
         self.next_id += 1;
         self.handlers
-            .entry(event_type.into())
+            .entry(event_type)
             .or_default()
-            .push((id.clone(), handler));
+            .push((id.clone(), subscriber));
         id
     }
 
@@ -128,23 +230,57 @@ impl EventBus {
         found
     }
 
-    /// Publish an event to all matching handlers.
+    /// Specific handlers first, then wildcard handlers.
+    fn matching(&self, event: &GaussEvent) -> impl Iterator<Item = &Subscriber> {
+        let specific = self.handlers.get(event.event_type()).into_iter().flatten();
+        let wildcard = self.handlers.get("*").into_iter().flatten();
+        specific.chain(wildcard).map(|(_, subscriber)| subscriber)
+    }
+
+    /// Publish an event to all matching handlers. Sync handlers run inline;
+    /// async handlers are spawned on the current tokio runtime (and skipped
+    /// when there is none).
     pub fn publish(&self, event: &GaussEvent) {
-        let event_type = event.event_type();
+        for subscriber in self.matching(event) {
+            match subscriber {
+                Subscriber::Sync(handler) => handler(event),
+                Subscriber::Async(handler) => {
+                    if spawn(handler(event.clone())).is_some() {
+                        tracing::debug!(
+                            event_type = event.event_type(),
+                            "No runtime for async event handler; use publish_async"
+                        );
+                    }
+                }
+            }
+        }
+    }
 
-        // Specific handlers
-        if let Some(handlers) = self.handlers.get(event_type) {
-            for (_, handler) in handlers {
-                handler(event);
+    /// Publish an event without waiting on slow handlers. Sync handlers run
+    /// inline; async handlers are spawned on the current tokio runtime, or
+    /// awaited when there is none to spawn on (e.g. on wasm).
+    pub async fn dispatch(&self, event: &GaussEvent) {
+        let mut pending = Vec::new();
+        for subscriber in self.matching(event) {
+            match subscriber {
+                Subscriber::Sync(handler) => handler(event),
+                Subscriber::Async(handler) => pending.extend(spawn(handler(event.clone()))),
             }
         }
+        futures::future::join_all(pending).await;
+    }
 
-        // Wildcard handlers
-        if let Some(handlers) = self.handlers.get("*") {
-            for (_, handler) in handlers {
-                handler(event);
+    /// Publish an event, running sync handlers inline and awaiting all async
+    /// handlers concurrently.
+    pub async fn publish_async(&self, event: &GaussEvent) {
+        let mut pending = Vec::new();
+        for subscriber in self.matching(event) {
+            match subscriber {
+                Subscriber::Sync(handler) => handler(event),
+                Subscriber::Async(handler) => pending.push(handler(event.clone())),
             }
         }
+        futures::future::join_all(pending).await;
     }
 
     /// Get the number of subscriptions.
@@ -153,6 +289,66 @@ impl EventBus {
     }
 }
 
+/// Spawn `handler` on the current tokio runtime, handing it back if there is
+/// none.
+fn spawn(handler: HandlerFuture) -> Option<HandlerFuture> {
+    #[cfg(all(feature = "native", not(target_arch = "wasm32")))]
+    if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+        runtime.spawn(handler);
+        return None;
+    }
+    Some(handler)
+}
+
+/// Start/finish events around an orchestrator run.
+pub(crate) struct OrchestratorRun<'a> {
+    plugins: Option<&'a crate::Shared<PluginRegistry>>,
+    kind: &'static str,
+    name: String,
+    session_id: String,
+    started: std::time::Instant,
+}
+
+impl<'a> OrchestratorRun<'a> {
+    pub(crate) async fn start(
+        plugins: Option<&'a crate::Shared<PluginRegistry>>,
+        kind: &'static str,
+        name: &str,
+    ) -> Self {
+        let run = Self {
+            plugins,
+            kind,
+            name: name.to_string(),
+            session_id: uuid::Uuid::new_v4().to_string(),
+            started: std::time::Instant::now(),
+        };
+        if let Some(plugins) = run.plugins {
+            plugins
+                .dispatch(&GaussEvent::OrchestratorStart {
+                    kind: run.kind.to_string(),
+                    name: run.name.clone(),
+                    session_id: run.session_id.clone(),
+                })
+                .await;
+        }
+        run
+    }
+
+    pub(crate) async fn finish(self, is_error: bool) {
+        if let Some(plugins) = self.plugins {
+            plugins
+                .dispatch(&GaussEvent::OrchestratorFinish {
+                    kind: self.kind.to_string(),
+                    name: self.name,
+                    session_id: self.session_id,
+                    duration_ms: self.started.elapsed().as_millis() as u64,
+                    is_error,
+                })
+                .await;
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // PluginContext
 // ---------------------------------------------------------------------------
@@ -235,6 +431,10 @@ pub trait Plugin {
 // ---------------------------------------------------------------------------
 
 /// Manages plugin lifecycle and dependency ordering.
+///
+/// Initialize it, then share it (`crate::Shared<PluginRegistry>`) with agents,
+/// orchestrators, providers, guardrail chains and approval managers so they
+/// publish their lifecycle events on its bus.
 pub struct PluginRegistry {
     plugins: Vec<crate::Shared<dyn Plugin>>,
     initialized: Vec<String>,
@@ -242,6 +442,16 @@ pub struct PluginRegistry {
     pub ctx: PluginContext,
 }
 
+impl std::fmt::Debug for PluginRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PluginRegistry")
+            .field("plugins", &self.list())
+            .field("initialized", &self.initialized)
+            .field("bus", &self.bus)
+            .finish()
+    }
+}
+
 impl Default for PluginRegistry {
     fn default() -> Self {
         Self::new()
@@ -299,6 +509,17 @@ impl PluginRegistry {
         self.bus.publish(event);
     }
 
+    /// Publish an event to the bus, awaiting async handlers.
+    pub async fn emit_async(&self, event: &GaussEvent) {
+        self.bus.publish_async(event).await;
+    }
+
+    /// Publish an event to the bus without waiting on async handlers; see
+    /// [`EventBus::dispatch`].
+    pub async fn dispatch(&self, event: &GaussEvent) {
+        self.bus.dispatch(event).await;
+    }
+
     fn topological_sort(&self) -> error::Result<Vec<crate::Shared<dyn Plugin>>> {
         let mut sorted: Vec<crate::Shared<dyn Plugin>> = Vec::new();
         let mut visited: std::collections::HashSet<String> = std::collections::HashSet::new();
@@ -414,6 +635,7 @@ impl Plugin for MemoryPlugin {
                     agent_name,
                     session_id,
                     result_text,
+                    ..
                 } = event
                 {
                     tracing::debug!(
@@ -293,6 +293,7 @@ impl AnthropicProvider {
                                         .as_u64()
                                         .or_else(|| cit["end_page"].as_u64())
                                         .or_else(|| cit["end_block_index"].as_u64()),
+                                    url: None,
                                 });
                             }
                         }
@@ -340,6 +341,7 @@ impl AnthropicProvider {
             thinking: thinking_text,
             citations,
             grounding_metadata: None,
+            routing: None,
         })
     }
 }
@@ -356,16 +358,19 @@ impl Provider for AnthropicProvider {
     }
 
     fn capabilities(&self) -> ProviderCapabilities {
-        ProviderCapabilities {
-            streaming: true,
-            tool_use: true,
-            vision: true,
-            extended_thinking: true,
-            citations: true,
-            cache_control: true,
-            structured_output: true,
-            ..Default::default()
-        }
+        crate::catalog::read().capabilities(
+            &self.model,
+            ProviderCapabilities {
+                streaming: true,
+                tool_use: true,
+                vision: true,
+                extended_thinking: true,
+                citations: true,
+                cache_control: true,
+                structured_output: true,
+                ..Default::default()
+            },
+        )
     }
 
     async fn generate(
@@ -394,7 +399,7 @@ impl Provider for AnthropicProvider {
             req = req.header(k, v);
         }
 
-        let resp = req
+        let resp = crate::telemetry::inject_trace_context(req)
             .json(&body)
             .send()
             .await
@@ -406,6 +411,8 @@ impl Provider for AnthropicProvider {
             })?;
 
         let status = resp.status();
+        let headers = resp.headers().clone();
+        crate::provider::rate_limit::observe("anthropic", &self.model, &headers);
         let resp_body: serde_json::Value = resp.json().await.map_err(|e| {
             GaussError::provider("anthropic", format!("Failed to parse response: {e}"))
         })?;
@@ -416,7 +423,11 @@ impl Provider for AnthropicProvider {
                 .unwrap_or("Unknown error");
 
             return match status.as_u16() {
-                429 => Err(GaussError::rate_limited("anthropic", msg)),
+                429 => Err(crate::provider::rate_limit::rate_limited_error(
+                    "anthropic",
+                    msg,
+                    &headers,
+                )),
                 401 => Err(GaussError::authentication("anthropic", msg)),
                 _ => Err(GaussError::Provider {
                     message: msg.to_string(),
@@ -457,7 +468,7 @@ impl Provider for AnthropicProvider {
             req = req.header(k, v);
         }
 
-        let resp = req
+        let resp = crate::telemetry::inject_trace_context(req)
             .json(&body)
             .send()
             .await
@@ -468,10 +479,19 @@ impl Provider for AnthropicProvider {
                 source: Some(Box::new(e)),
             })?;
 
+        crate::provider::rate_limit::observe("anthropic", &self.model, resp.headers());
         if !resp.status().is_success() {
             let status = resp.status();
+            let headers = resp.headers().clone();
             let body: serde_json::Value = resp.json().await.unwrap_or(json!({}));
             let msg = body["error"]["message"].as_str().unwrap_or("Unknown error");
+            if status.as_u16() == 429 {
+                return Err(crate::provider::rate_limit::rate_limited_error(
+                    "anthropic",
+                    msg,
+                    &headers,
+                ));
+            }
             return Err(GaussError::Provider {
                 message: msg.to_string(),
                 status: Some(status.as_u16()),
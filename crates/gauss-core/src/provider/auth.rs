@@ -0,0 +1,435 @@
+//! OAuth2 bearer tokens for providers that don't take static API keys:
+//! Azure OpenAI with Entra ID and Google Vertex AI.
+
+use async_trait::async_trait;
+
+use crate::error;
+
+/// Source of OAuth2 access tokens. Implementations cache and refresh as
+/// needed; callers ask for a token before every request.
+#[cfg(not(target_arch = "wasm32"))]
+#[async_trait]
+pub trait AccessTokenSource: Send + Sync {
+    async fn access_token(&self) -> error::Result<String>;
+}
+
+#[cfg(target_arch = "wasm32")]
+#[async_trait(?Send)]
+pub trait AccessTokenSource {
+    async fn access_token(&self) -> error::Result<String>;
+}
+
+/// A token obtained elsewhere, e.g. from `az account get-access-token`.
+#[derive(Clone)]
+pub struct StaticToken(String);
+
+impl StaticToken {
+    pub fn new(token: impl Into<String>) -> Self {
+        Self(token.into())
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl AccessTokenSource for StaticToken {
+    async fn access_token(&self) -> error::Result<String> {
+        Ok(self.0.clone())
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use entra::EntraTokenSource;
+#[cfg(not(target_arch = "wasm32"))]
+pub use service_account::{ServiceAccountKey, ServiceAccountTokenSource};
+
+/// Token endpoint requests and caching shared by the refreshing sources.
+#[cfg(not(target_arch = "wasm32"))]
+mod refresh {
+    use std::sync::Mutex;
+    use std::time::{Duration, SystemTime};
+
+    use serde_json::json;
+
+    use crate::error::{self, GaussError};
+
+    pub(super) struct CachedToken {
+        token: String,
+        expires_at: SystemTime,
+    }
+
+    /// The last token fetched, reused until `margin` before it expires.
+    #[derive(Default)]
+    pub(super) struct TokenCache(Mutex<Option<CachedToken>>);
+
+    impl TokenCache {
+        pub(super) async fn get(
+            &self,
+            margin: Duration,
+            fetch: impl Future<Output = error::Result<CachedToken>>,
+        ) -> error::Result<String> {
+            {
+                let cached = self.0.lock().unwrap_or_else(|e| e.into_inner());
+                if let Some(ref cached) = *cached
+                    && SystemTime::now() + margin < cached.expires_at
+                {
+                    return Ok(cached.token.clone());
+                }
+            }
+            let fresh = fetch.await?;
+            let token = fresh.token.clone();
+            *self.0.lock().unwrap_or_else(|e| e.into_inner()) = Some(fresh);
+            Ok(token)
+        }
+    }
+
+    /// POST `form` to an OAuth2 token endpoint and read the access token
+    /// out of the response. `flow` names the exchange in error messages.
+    pub(super) async fn exchange(
+        client: &reqwest::Client,
+        url: &str,
+        form: &[(&str, &str)],
+        provider: &str,
+        flow: &str,
+    ) -> error::Result<CachedToken> {
+        let now = SystemTime::now();
+        let resp = client
+            .post(url)
+            .form(form)
+            .send()
+            .await
+            .map_err(|e| GaussError::authentication(provider, e.to_string()))?;
+
+        let status = resp.status();
+        let body: serde_json::Value = resp.json().await.unwrap_or(json!({}));
+        let Some(token) = body["access_token"]
+            .as_str()
+            .filter(|_| status.is_success())
+        else {
+            let reason = body["error_description"]
+                .as_str()
+                .or_else(|| body["error"].as_str())
+                .unwrap_or("no access token in response");
+            return Err(GaussError::Provider {
+                message: format!("{flow} failed: {reason}"),
+                status: Some(status.as_u16()),
+                provider: provider.to_string(),
+                source: None,
+            });
+        };
+        let expires_in = body["expires_in"].as_u64().unwrap_or(3600);
+        Ok(CachedToken {
+            token: token.to_string(),
+            expires_at: now + Duration::from_secs(expires_in),
+        })
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod service_account {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use async_trait::async_trait;
+    use base64::Engine as _;
+    use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+    use ring::rand::SystemRandom;
+    use ring::signature::{RSA_PKCS1_SHA256, RsaKeyPair};
+    use serde::Deserialize;
+    use serde_json::json;
+
+    use super::AccessTokenSource;
+    use super::refresh::{CachedToken, TokenCache, exchange};
+    use crate::error::{self, GaussError};
+
+    const DEFAULT_TOKEN_URI: &str = "https://oauth2.googleapis.com/token";
+    const CLOUD_PLATFORM_SCOPE: &str = "https://www.googleapis.com/auth/cloud-platform";
+    const JWT_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:jwt-bearer";
+    /// Lifetime requested for the signed assertion (Google's maximum).
+    const ASSERTION_LIFETIME: Duration = Duration::from_secs(3600);
+
+    /// The fields of a Google service-account JSON key that the JWT flow needs.
+    #[derive(Clone, Deserialize)]
+    pub struct ServiceAccountKey {
+        pub client_email: String,
+        pub private_key: String,
+        #[serde(default)]
+        pub private_key_id: Option<String>,
+        #[serde(default)]
+        pub project_id: Option<String>,
+        #[serde(default = "default_token_uri")]
+        pub token_uri: String,
+    }
+
+    fn default_token_uri() -> String {
+        DEFAULT_TOKEN_URI.to_string()
+    }
+
+    fn config_error(message: impl Into<String>) -> GaussError {
+        GaussError::Config {
+            message: message.into(),
+        }
+    }
+
+    impl std::fmt::Debug for ServiceAccountKey {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("ServiceAccountKey")
+                .field("client_email", &self.client_email)
+                .field("private_key", &"<redacted>")
+                .field("project_id", &self.project_id)
+                .field("token_uri", &self.token_uri)
+                .finish()
+        }
+    }
+
+    impl ServiceAccountKey {
+        pub fn from_json(json: &str) -> error::Result<Self> {
+            serde_json::from_str(json)
+                .map_err(|e| config_error(format!("Invalid service account key: {e}")))
+        }
+
+        pub fn from_file(path: impl AsRef<std::path::Path>) -> error::Result<Self> {
+            let path = path.as_ref();
+            let json = std::fs::read_to_string(path).map_err(|e| {
+                config_error(format!(
+                    "Failed to read service account key {}: {e}",
+                    path.display()
+                ))
+            })?;
+            Self::from_json(&json)
+        }
+
+        /// Read the key named by `GOOGLE_APPLICATION_CREDENTIALS`.
+        pub fn from_env() -> error::Result<Self> {
+            let path = std::env::var("GOOGLE_APPLICATION_CREDENTIALS")
+                .map_err(|_| config_error("GOOGLE_APPLICATION_CREDENTIALS is not set"))?;
+            Self::from_file(path)
+        }
+
+        /// Decode the PEM `private_key` (PKCS#8) into an RSA signing key.
+        fn signing_key(&self) -> error::Result<RsaKeyPair> {
+            let body: String = self
+                .private_key
+                .lines()
+                .filter(|line| !line.starts_with("-----"))
+                .collect();
+            let der = STANDARD
+                .decode(body.trim())
+                .map_err(|e| config_error(format!("Invalid service account private key: {e}")))?;
+            RsaKeyPair::from_pkcs8(&der)
+                .map_err(|e| config_error(format!("Invalid service account private key: {e}")))
+        }
+    }
+
+    /// OAuth2 JWT-bearer flow for a Google service account: signs an RS256
+    /// assertion, exchanges it at the key's `token_uri` and caches the access
+    /// token until shortly before it expires.
+    pub struct ServiceAccountTokenSource {
+        key: ServiceAccountKey,
+        signing_key: RsaKeyPair,
+        scopes: Vec<String>,
+        refresh_margin: Duration,
+        client: reqwest::Client,
+        cache: TokenCache,
+    }
+
+    impl ServiceAccountTokenSource {
+        /// Defaults to the `cloud-platform` scope.
+        pub fn new(key: ServiceAccountKey) -> error::Result<Self> {
+            Ok(Self {
+                signing_key: key.signing_key()?,
+                key,
+                scopes: vec![CLOUD_PLATFORM_SCOPE.to_string()],
+                refresh_margin: Duration::from_secs(60),
+                client: crate::provider::build_client(Some(30_000)),
+                cache: TokenCache::default(),
+            })
+        }
+
+        pub fn with_scopes(mut self, scopes: Vec<String>) -> Self {
+            self.scopes = scopes;
+            self
+        }
+
+        /// Refresh tokens this long before they expire (default 60s).
+        pub fn with_refresh_margin(mut self, margin: Duration) -> Self {
+            self.refresh_margin = margin;
+            self
+        }
+
+        pub fn key(&self) -> &ServiceAccountKey {
+            &self.key
+        }
+
+        /// The signed JWT assertion exchanged for an access token.
+        pub fn assertion(&self, now: SystemTime) -> error::Result<String> {
+            let iat = now
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            let mut header = json!({"alg": "RS256", "typ": "JWT"});
+            if let Some(ref kid) = self.key.private_key_id {
+                header["kid"] = json!(kid);
+            }
+            let claims = json!({
+                "iss": self.key.client_email,
+                "scope": self.scopes.join(" "),
+                "aud": self.key.token_uri,
+                "iat": iat,
+                "exp": iat + ASSERTION_LIFETIME.as_secs(),
+            });
+            let signing_input = format!(
+                "{}.{}",
+                URL_SAFE_NO_PAD.encode(header.to_string()),
+                URL_SAFE_NO_PAD.encode(claims.to_string())
+            );
+
+            let mut signature = vec![0; self.signing_key.public().modulus_len()];
+            self.signing_key
+                .sign(
+                    &RSA_PKCS1_SHA256,
+                    &SystemRandom::new(),
+                    signing_input.as_bytes(),
+                    &mut signature,
+                )
+                .map_err(|_| GaussError::internal("Failed to sign service account assertion"))?;
+            Ok(format!(
+                "{signing_input}.{}",
+                URL_SAFE_NO_PAD.encode(signature)
+            ))
+        }
+
+        async fn fetch(&self) -> error::Result<CachedToken> {
+            let assertion = self.assertion(SystemTime::now())?;
+            exchange(
+                &self.client,
+                &self.key.token_uri,
+                &[("grant_type", JWT_GRANT_TYPE), ("assertion", &assertion)],
+                "google",
+                "Service account token exchange",
+            )
+            .await
+        }
+    }
+
+    #[async_trait]
+    impl AccessTokenSource for ServiceAccountTokenSource {
+        async fn access_token(&self) -> error::Result<String> {
+            self.cache.get(self.refresh_margin, self.fetch()).await
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod entra {
+    use std::time::Duration;
+
+    use async_trait::async_trait;
+
+    use super::AccessTokenSource;
+    use super::refresh::{CachedToken, TokenCache, exchange};
+    use crate::error::{self, GaussError};
+
+    const DEFAULT_AUTHORITY: &str = "https://login.microsoftonline.com";
+    const COGNITIVE_SERVICES_SCOPE: &str = "https://cognitiveservices.azure.com/.default";
+
+    /// OAuth2 client-credentials flow for a Microsoft Entra ID app
+    /// registration: exchanges the client secret at the tenant's token
+    /// endpoint and caches the access token until shortly before it expires.
+    pub struct EntraTokenSource {
+        tenant_id: String,
+        client_id: String,
+        client_secret: String,
+        authority: String,
+        scope: String,
+        refresh_margin: Duration,
+        client: reqwest::Client,
+        cache: TokenCache,
+    }
+
+    impl std::fmt::Debug for EntraTokenSource {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("EntraTokenSource")
+                .field("tenant_id", &self.tenant_id)
+                .field("client_id", &self.client_id)
+                .field("client_secret", &"<redacted>")
+                .field("authority", &self.authority)
+                .field("scope", &self.scope)
+                .finish()
+        }
+    }
+
+    impl EntraTokenSource {
+        /// Defaults to the Azure OpenAI (`cognitiveservices`) scope on the
+        /// public cloud.
+        pub fn new(
+            tenant_id: impl Into<String>,
+            client_id: impl Into<String>,
+            client_secret: impl Into<String>,
+        ) -> Self {
+            Self {
+                tenant_id: tenant_id.into(),
+                client_id: client_id.into(),
+                client_secret: client_secret.into(),
+                authority: DEFAULT_AUTHORITY.to_string(),
+                scope: COGNITIVE_SERVICES_SCOPE.to_string(),
+                refresh_margin: Duration::from_secs(60),
+                client: crate::provider::build_client(Some(30_000)),
+                cache: TokenCache::default(),
+            }
+        }
+
+        /// Read `AZURE_TENANT_ID`, `AZURE_CLIENT_ID` and `AZURE_CLIENT_SECRET`.
+        pub fn from_env() -> error::Result<Self> {
+            let var = |name: &str| {
+                std::env::var(name).map_err(|_| GaussError::Config {
+                    message: format!("{name} is not set"),
+                })
+            };
+            Ok(Self::new(
+                var("AZURE_TENANT_ID")?,
+                var("AZURE_CLIENT_ID")?,
+                var("AZURE_CLIENT_SECRET")?,
+            ))
+        }
+
+        /// Token authority, e.g. `https://login.microsoftonline.us` for
+        /// Azure Government.
+        pub fn with_authority(mut self, authority: impl Into<String>) -> Self {
+            self.authority = authority.into().trim_end_matches('/').to_string();
+            self
+        }
+
+        pub fn with_scope(mut self, scope: impl Into<String>) -> Self {
+            self.scope = scope.into();
+            self
+        }
+
+        /// Refresh tokens this long before they expire (default 60s).
+        pub fn with_refresh_margin(mut self, margin: Duration) -> Self {
+            self.refresh_margin = margin;
+            self
+        }
+
+        async fn fetch(&self) -> error::Result<CachedToken> {
+            exchange(
+                &self.client,
+                &format!("{}/{}/oauth2/v2.0/token", self.authority, self.tenant_id),
+                &[
+                    ("grant_type", "client_credentials"),
+                    ("client_id", &self.client_id),
+                    ("client_secret", &self.client_secret),
+                    ("scope", &self.scope),
+                ],
+                "azure_openai",
+                "Entra ID token request",
+            )
+            .await
+        }
+    }
+
+    #[async_trait]
+    impl AccessTokenSource for EntraTokenSource {
+        async fn access_token(&self) -> error::Result<String> {
+            self.cache.get(self.refresh_margin, self.fetch()).await
+        }
+    }
+}
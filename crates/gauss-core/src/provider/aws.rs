@@ -0,0 +1,509 @@
+//! AWS plumbing shared by AWS-hosted providers: credentials, Signature
+//! Version 4 request signing and the `application/vnd.amazon.eventstream`
+//! binary framing used by streaming APIs.
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::collections::HashMap;
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use url::Url;
+
+use crate::error::{self, GaussError};
+
+/// Static AWS credentials.
+#[derive(Clone)]
+pub struct AwsCredentials {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    pub session_token: Option<String>,
+}
+
+impl fmt::Debug for AwsCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AwsCredentials")
+            .field("access_key_id", &self.access_key_id)
+            .field("secret_access_key", &"<redacted>")
+            .field(
+                "session_token",
+                &self.session_token.as_ref().map(|_| "<redacted>"),
+            )
+            .finish()
+    }
+}
+
+impl AwsCredentials {
+    pub fn new(access_key_id: impl Into<String>, secret_access_key: impl Into<String>) -> Self {
+        Self {
+            access_key_id: access_key_id.into(),
+            secret_access_key: secret_access_key.into(),
+            session_token: None,
+        }
+    }
+
+    /// Attach a session token for temporary (STS) credentials.
+    pub fn with_session_token(mut self, token: impl Into<String>) -> Self {
+        self.session_token = Some(token.into());
+        self
+    }
+
+    /// Read `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY` and the optional
+    /// `AWS_SESSION_TOKEN`.
+    pub fn from_env() -> error::Result<Self> {
+        let (Some(key), Some(secret)) = (
+            non_empty_env("AWS_ACCESS_KEY_ID"),
+            non_empty_env("AWS_SECRET_ACCESS_KEY"),
+        ) else {
+            return Err(config_error(
+                "AWS_ACCESS_KEY_ID and AWS_SECRET_ACCESS_KEY must be set",
+            ));
+        };
+        Ok(Self {
+            access_key_id: key,
+            secret_access_key: secret,
+            session_token: non_empty_env("AWS_SESSION_TOKEN"),
+        })
+    }
+
+    /// Read a named profile from the shared credentials file
+    /// (`AWS_SHARED_CREDENTIALS_FILE`, default `~/.aws/credentials`), falling
+    /// back to the config file (`AWS_CONFIG_FILE`, default `~/.aws/config`).
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_profile(profile: &str) -> error::Result<Self> {
+        let sections = [
+            read_profile(SharedFile::Credentials, profile),
+            read_profile(SharedFile::Config, profile),
+        ];
+        for section in sections.into_iter().flatten() {
+            if let (Some(key), Some(secret)) = (
+                section.get("aws_access_key_id"),
+                section.get("aws_secret_access_key"),
+            ) {
+                return Ok(Self {
+                    access_key_id: key.clone(),
+                    secret_access_key: secret.clone(),
+                    session_token: section.get("aws_session_token").cloned(),
+                });
+            }
+        }
+        Err(config_error(format!(
+            "No static credentials found for AWS profile '{profile}'"
+        )))
+    }
+
+    /// Environment variables first, then the profile named by `AWS_PROFILE`
+    /// (default `default`).
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load() -> error::Result<Self> {
+        Self::from_env().or_else(|_| Self::from_profile(&profile_name()))
+    }
+}
+
+/// Resolve the region from `AWS_REGION`, `AWS_DEFAULT_REGION` or the
+/// `region` key of the active profile in the config file.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn region_from_env() -> Option<String> {
+    non_empty_env("AWS_REGION")
+        .or_else(|| non_empty_env("AWS_DEFAULT_REGION"))
+        .or_else(|| read_profile(SharedFile::Config, &profile_name())?.remove("region"))
+}
+
+fn non_empty_env(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|v| !v.is_empty())
+}
+
+fn config_error(message: impl Into<String>) -> GaussError {
+    GaussError::Config {
+        message: message.into(),
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn profile_name() -> String {
+    non_empty_env("AWS_PROFILE").unwrap_or_else(|| "default".to_string())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Clone, Copy)]
+enum SharedFile {
+    Credentials,
+    Config,
+}
+
+/// Key/value pairs of one profile section, or `None` if the file or section
+/// doesn't exist.
+#[cfg(not(target_arch = "wasm32"))]
+fn read_profile(file: SharedFile, profile: &str) -> Option<HashMap<String, String>> {
+    let (var, default) = match file {
+        SharedFile::Credentials => ("AWS_SHARED_CREDENTIALS_FILE", "credentials"),
+        SharedFile::Config => ("AWS_CONFIG_FILE", "config"),
+    };
+    let path = match non_empty_env(var) {
+        Some(path) => std::path::PathBuf::from(path),
+        None => std::path::PathBuf::from(non_empty_env("HOME")?)
+            .join(".aws")
+            .join(default),
+    };
+    let text = std::fs::read_to_string(path).ok()?;
+    // The config file prefixes every profile but `default` with "profile ".
+    let header = match file {
+        SharedFile::Config if profile != "default" => format!("profile {profile}"),
+        _ => profile.to_string(),
+    };
+    parse_ini(&text).remove(&header)
+}
+
+/// Minimal INI parser for the AWS shared files: `[section]` headers,
+/// `key = value` pairs, `#`/`;` comments.
+#[cfg(not(target_arch = "wasm32"))]
+fn parse_ini(text: &str) -> HashMap<String, HashMap<String, String>> {
+    let mut sections: HashMap<String, HashMap<String, String>> = HashMap::new();
+    let mut current: Option<String> = None;
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+            sections.entry(name.clone()).or_default();
+            current = Some(name);
+        } else if let (Some(section), Some((key, value))) = (&current, line.split_once('=')) {
+            sections
+                .entry(section.clone())
+                .or_default()
+                .insert(key.trim().to_string(), value.trim().to_string());
+        }
+    }
+    sections
+}
+
+// ---------------------------------------------------------------------------
+// Signature Version 4
+// ---------------------------------------------------------------------------
+
+const ALGORITHM: &str = "AWS4-HMAC-SHA256";
+
+/// What a SigV4 signature is scoped to.
+#[derive(Debug, Clone)]
+pub struct SigningParams<'a> {
+    pub credentials: &'a AwsCredentials,
+    pub region: &'a str,
+    pub service: &'a str,
+    pub time: SystemTime,
+}
+
+/// Sign a request with AWS Signature Version 4.
+///
+/// `headers` are the headers that will be sent and should be signed (the
+/// `host` header is derived from `url` when absent). Returns the headers to
+/// add to the request: `x-amz-date`, `x-amz-security-token` for temporary
+/// credentials, and `authorization`.
+pub fn sign(
+    method: &str,
+    url: &Url,
+    headers: &[(&str, &str)],
+    payload: &[u8],
+    params: &SigningParams<'_>,
+) -> Vec<(String, String)> {
+    let (date, amz_date) = format_amz_date(params.time);
+
+    let mut signed: Vec<(String, String)> = headers
+        .iter()
+        .map(|(k, v)| (k.to_ascii_lowercase(), normalize_header_value(v)))
+        .collect();
+    if !signed.iter().any(|(k, _)| k == "host") {
+        let host = match url.port() {
+            Some(port) => format!("{}:{port}", url.host_str().unwrap_or_default()),
+            None => url.host_str().unwrap_or_default().to_string(),
+        };
+        signed.push(("host".to_string(), host));
+    }
+    let mut extra = vec![("x-amz-date".to_string(), amz_date.clone())];
+    if let Some(ref token) = params.credentials.session_token {
+        extra.push(("x-amz-security-token".to_string(), token.clone()));
+    }
+    signed.extend(extra.iter().cloned());
+    signed.sort();
+
+    let canonical_headers: String = signed.iter().map(|(k, v)| format!("{k}:{v}\n")).collect();
+    let signed_headers = signed
+        .iter()
+        .map(|(k, _)| k.as_str())
+        .collect::<Vec<_>>()
+        .join(";");
+
+    let canonical_request = format!(
+        "{method}\n{}\n{}\n{canonical_headers}\n{signed_headers}\n{}",
+        canonical_uri(url),
+        canonical_query(url),
+        hex(&Sha256::digest(payload)),
+    );
+    let scope = format!("{date}/{}/{}/aws4_request", params.region, params.service);
+    let string_to_sign = format!(
+        "{ALGORITHM}\n{amz_date}\n{scope}\n{}",
+        hex(&Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let secret = format!("AWS4{}", params.credentials.secret_access_key);
+    let mut key = hmac_sha256(secret.as_bytes(), date.as_bytes());
+    for part in [params.region, params.service, "aws4_request"] {
+        key = hmac_sha256(&key, part.as_bytes());
+    }
+    let signature = hex(&hmac_sha256(&key, string_to_sign.as_bytes()));
+
+    extra.push((
+        "authorization".to_string(),
+        format!(
+            "{ALGORITHM} Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+            params.credentials.access_key_id
+        ),
+    ));
+    extra
+}
+
+/// Percent-encode everything but RFC 3986 unreserved characters.
+pub fn uri_encode(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    out
+}
+
+/// Every service but S3 signs the already-encoded path encoded once more.
+fn canonical_uri(url: &Url) -> String {
+    let path = url.path();
+    if path.is_empty() || path == "/" {
+        return "/".to_string();
+    }
+    path.split('/')
+        .map(uri_encode)
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn canonical_query(url: &Url) -> String {
+    let mut pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(k, v)| (uri_encode(&k), uri_encode(&v)))
+        .collect();
+    pairs.sort();
+    pairs
+        .iter()
+        .map(|(k, v)| format!("{k}={v}"))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+fn normalize_header_value(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// `(YYYYMMDD, YYYYMMDD'T'HHMMSS'Z')` in UTC.
+fn format_amz_date(time: SystemTime) -> (String, String) {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0) as i64;
+    let (year, month, day) = civil_from_days(secs.div_euclid(86_400));
+    let secs_of_day = secs.rem_euclid(86_400);
+    let date = format!("{year:04}{month:02}{day:02}");
+    let stamp = format!(
+        "{date}T{:02}{:02}{:02}Z",
+        secs_of_day / 3600,
+        secs_of_day % 3600 / 60,
+        secs_of_day % 60
+    );
+    (date, stamp)
+}
+
+/// Proleptic Gregorian date for a count of days since 1970-01-01.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+// ---------------------------------------------------------------------------
+// Event stream framing
+// ---------------------------------------------------------------------------
+
+/// Prelude (total length, headers length, prelude CRC) plus trailing CRC.
+const PRELUDE_LEN: usize = 12;
+const TRAILER_LEN: usize = 4;
+const STRING_HEADER: u8 = 7;
+
+/// One message of an `application/vnd.amazon.eventstream` response.
+///
+/// Only string-valued headers are kept; the others (`:message-type`,
+/// `:event-type`, `:exception-type`, ...) are all strings in practice.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EventStreamMessage {
+    pub headers: Vec<(String, String)>,
+    pub payload: Vec<u8>,
+}
+
+impl EventStreamMessage {
+    /// A JSON event with `:message-type: event` and the given `:event-type`.
+    pub fn event(event_type: &str, payload: &serde_json::Value) -> Self {
+        Self {
+            headers: vec![
+                (":event-type".to_string(), event_type.to_string()),
+                (":content-type".to_string(), "application/json".to_string()),
+                (":message-type".to_string(), "event".to_string()),
+            ],
+            payload: payload.to_string().into_bytes(),
+        }
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Serialize to the binary frame format.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut headers = Vec::new();
+        for (name, value) in &self.headers {
+            headers.push(name.len() as u8);
+            headers.extend_from_slice(name.as_bytes());
+            headers.push(STRING_HEADER);
+            headers.extend_from_slice(&(value.len() as u16).to_be_bytes());
+            headers.extend_from_slice(value.as_bytes());
+        }
+        let total = PRELUDE_LEN + headers.len() + self.payload.len() + TRAILER_LEN;
+
+        let mut frame = Vec::with_capacity(total);
+        frame.extend_from_slice(&(total as u32).to_be_bytes());
+        frame.extend_from_slice(&(headers.len() as u32).to_be_bytes());
+        frame.extend_from_slice(&crc32fast::hash(&frame).to_be_bytes());
+        frame.extend_from_slice(&headers);
+        frame.extend_from_slice(&self.payload);
+        frame.extend_from_slice(&crc32fast::hash(&frame).to_be_bytes());
+        frame
+    }
+}
+
+/// Incremental decoder: feed network chunks with [`push`](Self::push) and
+/// drain complete messages with [`next_message`](Self::next_message).
+#[derive(Debug, Default)]
+pub struct EventStreamDecoder {
+    buffer: Vec<u8>,
+}
+
+impl EventStreamDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, chunk: &[u8]) {
+        self.buffer.extend_from_slice(chunk);
+    }
+
+    /// Bytes received that don't yet form a complete message.
+    pub fn buffered(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// The next complete message, `None` if more bytes are needed, or an
+    /// error if the frame is corrupt.
+    pub fn next_message(&mut self) -> error::Result<Option<EventStreamMessage>> {
+        if self.buffer.len() < PRELUDE_LEN {
+            return Ok(None);
+        }
+        let total = read_u32(&self.buffer, 0) as usize;
+        let headers_len = read_u32(&self.buffer, 4) as usize;
+        if crc32fast::hash(&self.buffer[..8]) != read_u32(&self.buffer, 8) {
+            return Err(stream_error("event stream prelude checksum mismatch"));
+        }
+        if total < PRELUDE_LEN + headers_len + TRAILER_LEN {
+            return Err(stream_error("event stream frame length is too small"));
+        }
+        if self.buffer.len() < total {
+            return Ok(None);
+        }
+
+        let frame: Vec<u8> = self.buffer.drain(..total).collect();
+        if crc32fast::hash(&frame[..total - TRAILER_LEN]) != read_u32(&frame, total - TRAILER_LEN) {
+            return Err(stream_error("event stream message checksum mismatch"));
+        }
+        let headers = parse_headers(&frame[PRELUDE_LEN..PRELUDE_LEN + headers_len])?;
+        let payload = frame[PRELUDE_LEN + headers_len..total - TRAILER_LEN].to_vec();
+        Ok(Some(EventStreamMessage { headers, payload }))
+    }
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> u32 {
+    u32::from_be_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]])
+}
+
+fn stream_error(message: &str) -> GaussError {
+    GaussError::Stream {
+        message: message.to_string(),
+        source: None,
+    }
+}
+
+fn parse_headers(mut bytes: &[u8]) -> error::Result<Vec<(String, String)>> {
+    let truncated = || stream_error("truncated event stream header");
+    let mut headers = Vec::new();
+    while !bytes.is_empty() {
+        let name_len = bytes[0] as usize;
+        let name = bytes.get(1..1 + name_len).ok_or_else(truncated)?;
+        let name = String::from_utf8_lossy(name).into_owned();
+        let value_type = *bytes.get(1 + name_len).ok_or_else(truncated)?;
+        let rest = &bytes[2 + name_len..];
+
+        // Fixed sizes for bool, byte, short, int, long, timestamp and uuid;
+        // byte arrays and strings carry a u16 length.
+        let (value_len, offset) = match value_type {
+            0 | 1 => (0, 0),
+            2 => (1, 0),
+            3 => (2, 0),
+            4 => (4, 0),
+            5 | 8 => (8, 0),
+            9 => (16, 0),
+            6 | STRING_HEADER => {
+                let len = rest.get(..2).ok_or_else(truncated)?;
+                (u16::from_be_bytes([len[0], len[1]]) as usize, 2)
+            }
+            other => {
+                return Err(stream_error(&format!(
+                    "unknown event stream header type {other}"
+                )));
+            }
+        };
+        let value = rest.get(offset..offset + value_len).ok_or_else(truncated)?;
+        if value_type == STRING_HEADER {
+            headers.push((name, String::from_utf8_lossy(value).into_owned()));
+        }
+        bytes = &rest[offset + value_len..];
+    }
+    Ok(headers)
+}
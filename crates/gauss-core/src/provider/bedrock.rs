@@ -0,0 +1,686 @@
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use reqwest::Client;
+use serde_json::{Value, json};
+use tracing::debug;
+
+use crate::error::{self, GaussError};
+use crate::message::{Content, Message, Role, Usage};
+use crate::provider::aws::{self, AwsCredentials, EventStreamDecoder, EventStreamMessage};
+use crate::provider::{
+    BoxStream, FinishReason, GenerateOptions, GenerateResult, Provider, ProviderCapabilities,
+    rate_limit,
+};
+use crate::streaming::StreamEvent;
+use crate::tool::{Tool, ToolChoice};
+
+const PROVIDER: &str = "bedrock";
+const SERVICE: &str = "bedrock";
+
+/// AWS Bedrock provider speaking the model-agnostic Converse API
+/// (`/model/{id}/converse` and `/converse-stream`).
+///
+/// Requests are signed with SigV4; no AWS SDK is required.
+///
+/// ```no_run
+/// use gauss_core::provider::aws::AwsCredentials;
+/// use gauss_core::provider::bedrock::BedrockProvider;
+///
+/// let provider = BedrockProvider::new(
+///     "anthropic.claude-3-5-sonnet-20240620-v1:0",
+///     "us-east-1",
+///     AwsCredentials::new("AKIA...", "secret"),
+/// );
+/// ```
+pub struct BedrockProvider {
+    model: String,
+    region: String,
+    credentials: AwsCredentials,
+    endpoint: Option<String>,
+    headers: Vec<(String, String)>,
+    client: Client,
+}
+
+impl BedrockProvider {
+    pub fn new(
+        model: impl Into<String>,
+        region: impl Into<String>,
+        credentials: AwsCredentials,
+    ) -> Self {
+        Self {
+            model: model.into(),
+            region: region.into(),
+            credentials,
+            endpoint: None,
+            headers: Vec::new(),
+            client: crate::provider::build_client(Some(60_000)),
+        }
+    }
+
+    /// Credentials from the environment or shared profile files, region from
+    /// `AWS_REGION`/`AWS_DEFAULT_REGION` or the profile.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_env(model: impl Into<String>) -> error::Result<Self> {
+        let region = aws::region_from_env().ok_or_else(|| GaussError::Config {
+            message: "No AWS region configured (set AWS_REGION)".to_string(),
+        })?;
+        Ok(Self::new(model, region, AwsCredentials::load()?))
+    }
+
+    /// Override the runtime endpoint, e.g. for VPC endpoints or tests.
+    /// Defaults to `https://bedrock-runtime.{region}.amazonaws.com`.
+    pub fn endpoint(mut self, url: impl Into<String>) -> Self {
+        self.endpoint = Some(url.into());
+        self
+    }
+
+    pub fn timeout_ms(mut self, timeout_ms: u64) -> Self {
+        self.client = crate::provider::build_client(Some(timeout_ms));
+        self
+    }
+
+    /// Extra (unsigned) headers sent with every request.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    fn url(&self, operation: &str) -> error::Result<url::Url> {
+        let base = match self.endpoint {
+            Some(ref endpoint) => endpoint.trim_end_matches('/').to_string(),
+            None => format!("https://bedrock-runtime.{}.amazonaws.com", self.region),
+        };
+        let url = format!("{base}/model/{}/{operation}", aws::uri_encode(&self.model));
+        url::Url::parse(&url).map_err(|e| GaussError::Config {
+            message: format!("Invalid Bedrock endpoint '{base}': {e}"),
+        })
+    }
+
+    async fn send(&self, operation: &str, body: &Value) -> error::Result<reqwest::Response> {
+        let url = self.url(operation)?;
+        let payload = body.to_string().into_bytes();
+        let signed = aws::sign(
+            "POST",
+            &url,
+            &[("content-type", "application/json")],
+            &payload,
+            &aws::SigningParams {
+                credentials: &self.credentials,
+                region: &self.region,
+                service: SERVICE,
+                time: SystemTime::now(),
+            },
+        );
+
+        debug!(model = %self.model, url = %url, "Bedrock {operation}");
+
+        let mut req = self
+            .client
+            .post(url)
+            .header("content-type", "application/json");
+        for (k, v) in signed.iter().chain(&self.headers) {
+            req = req.header(k, v);
+        }
+
+        let resp = crate::telemetry::inject_trace_context(req)
+            .body(payload)
+            .send()
+            .await
+            .map_err(|e| GaussError::Provider {
+                message: e.to_string(),
+                status: e.status().map(|s| s.as_u16()),
+                provider: PROVIDER.to_string(),
+                source: Some(Box::new(e)),
+            })?;
+
+        rate_limit::observe(PROVIDER, &self.model, resp.headers());
+        let status = resp.status().as_u16();
+        if resp.status().is_success() {
+            return Ok(resp);
+        }
+
+        let headers = resp.headers().clone();
+        let body: Value = resp.json().await.unwrap_or(json!({}));
+        let message = body["message"]
+            .as_str()
+            .or_else(|| body["Message"].as_str())
+            .unwrap_or("Unknown error");
+        Err(match status {
+            429 => rate_limit::rate_limited_error(PROVIDER, message, &headers),
+            // Bad or expired signatures come back as 403.
+            401 | 403 => GaussError::authentication(PROVIDER, message),
+            _ => GaussError::Provider {
+                message: message.to_string(),
+                status: Some(status),
+                provider: PROVIDER.to_string(),
+                source: None,
+            },
+        })
+    }
+
+    fn build_request_body(
+        &self,
+        messages: &[Message],
+        tools: &[Tool],
+        options: &GenerateOptions,
+    ) -> error::Result<Value> {
+        let mut system = Vec::new();
+        let mut turns: Vec<(&str, Vec<Value>)> = Vec::new();
+
+        for message in messages {
+            let role = match message.role {
+                Role::System => {
+                    system.extend(message.content.iter().filter_map(|c| match c {
+                        Content::Text { text } => Some(json!({"text": text})),
+                        _ => None,
+                    }));
+                    continue;
+                }
+                Role::User | Role::Tool => "user",
+                Role::Assistant => "assistant",
+            };
+            let blocks = message
+                .content
+                .iter()
+                .map(convert_content)
+                .collect::<error::Result<Vec<_>>>()?
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>();
+            if blocks.is_empty() {
+                continue;
+            }
+            // Converse requires alternating roles; tool results following a
+            // user turn (or vice versa) are merged into one message.
+            match turns.last_mut() {
+                Some((last, content)) if *last == role => content.extend(blocks),
+                _ => turns.push((role, blocks)),
+            }
+        }
+
+        let mut body = json!({
+            "messages": turns
+                .into_iter()
+                .map(|(role, content)| json!({"role": role, "content": content}))
+                .collect::<Vec<_>>(),
+        });
+
+        if !system.is_empty() {
+            if options.cache_control {
+                system.push(json!({"cachePoint": {"type": "default"}}));
+            }
+            body["system"] = json!(system);
+        }
+
+        let mut inference = serde_json::Map::new();
+        if let Some(max) = options.max_tokens {
+            inference.insert("maxTokens".into(), json!(max));
+        }
+        if let Some(t) = options.temperature {
+            inference.insert("temperature".into(), json!(t));
+        }
+        if let Some(tp) = options.top_p {
+            inference.insert("topP".into(), json!(tp));
+        }
+        if let Some(ref stops) = options.stop_sequences {
+            inference.insert("stopSequences".into(), json!(stops));
+        }
+        if !inference.is_empty() {
+            body["inferenceConfig"] = Value::Object(inference);
+        }
+
+        // Model-specific knobs Converse doesn't standardize.
+        let mut additional = serde_json::Map::new();
+        if let Some(tk) = options.top_k {
+            additional.insert("top_k".into(), json!(tk));
+        }
+        if let Some(budget) = options.thinking_budget {
+            additional.insert(
+                "thinking".into(),
+                json!({"type": "enabled", "budget_tokens": budget}),
+            );
+        }
+        if !additional.is_empty() {
+            body["additionalModelRequestFields"] = Value::Object(additional);
+        }
+
+        if !tools.is_empty() {
+            let mut specs: Vec<Value> = tools
+                .iter()
+                .map(|t| {
+                    json!({"toolSpec": {
+                        "name": t.name,
+                        "description": t.description,
+                        "inputSchema": {"json": t.parameters},
+                    }})
+                })
+                .collect();
+            if options.cache_control {
+                specs.push(json!({"cachePoint": {"type": "default"}}));
+            }
+            let mut config = json!({"tools": specs});
+            // Converse has no "none" mode; omitting the choice leaves it to the model.
+            match options.tool_choice {
+                Some(ToolChoice::Auto) => config["toolChoice"] = json!({"auto": {}}),
+                Some(ToolChoice::Required) => config["toolChoice"] = json!({"any": {}}),
+                Some(ToolChoice::Specific { ref name }) => {
+                    config["toolChoice"] = json!({"tool": {"name": name}})
+                }
+                Some(ToolChoice::None) | None => {}
+            }
+            body["toolConfig"] = config;
+        }
+
+        Ok(body)
+    }
+}
+
+/// Map one content part onto a Converse content block. Parts Converse has
+/// no block for (audio, code execution output) are dropped.
+fn convert_content(content: &Content) -> error::Result<Option<Value>> {
+    Ok(Some(match content {
+        Content::Text { text } => json!({"text": text}),
+        Content::Image {
+            url,
+            base64,
+            media_type,
+        } => json!({"image": {
+            "format": image_format(media_type.as_deref()),
+            "source": binary_source(url.as_deref(), base64.as_deref(), "image")?,
+        }}),
+        Content::Document {
+            source_type,
+            data,
+            media_type,
+            title,
+            ..
+        } => {
+            let data = data.as_deref().unwrap_or_default();
+            let (format, source) = match source_type.as_str() {
+                "text" => ("txt", json!({"text": data})),
+                "url" => (
+                    document_format(media_type.as_deref()),
+                    binary_source(Some(data), None, "document")?,
+                ),
+                _ => (
+                    document_format(media_type.as_deref()),
+                    json!({"bytes": data}),
+                ),
+            };
+            json!({"document": {
+                "format": format,
+                "name": document_name(title.as_deref()),
+                "source": source,
+            }})
+        }
+        Content::File {
+            url,
+            base64,
+            media_type,
+        } => json!({"document": {
+            "format": document_format(media_type.as_deref()),
+            "name": "file",
+            "source": binary_source(url.as_deref(), base64.as_deref(), "file")?,
+        }}),
+        Content::ToolCall {
+            id,
+            name,
+            arguments,
+        } => json!({"toolUse": {"toolUseId": id, "name": name, "input": arguments}}),
+        Content::ToolResult {
+            tool_call_id,
+            content,
+            is_error,
+        } => {
+            let block = match content {
+                Value::String(text) => json!({"text": text}),
+                Value::Object(_) => json!({"json": content}),
+                other => json!({"text": other.to_string()}),
+            };
+            let mut result = json!({"toolUseId": tool_call_id, "content": [block]});
+            if *is_error == Some(true) {
+                result["status"] = json!("error");
+            }
+            json!({"toolResult": result})
+        }
+        Content::Reasoning {
+            text,
+            encrypted_content,
+            ..
+        } => {
+            let mut reasoning = json!({"text": text});
+            if let Some(signature) = encrypted_content {
+                reasoning["signature"] = json!(signature);
+            }
+            json!({"reasoningContent": {"reasoningText": reasoning}})
+        }
+        Content::Audio { .. }
+        | Content::ExecutableCode { .. }
+        | Content::CodeExecutionResult { .. }
+        | Content::GeneratedImage { .. } => return Ok(None),
+    }))
+}
+
+/// Converse takes inline bytes (base64 in JSON) or S3 locations.
+fn binary_source(url: Option<&str>, base64: Option<&str>, kind: &str) -> error::Result<Value> {
+    if let Some(data) = base64 {
+        return Ok(json!({"bytes": data}));
+    }
+    match url {
+        Some(uri) if uri.starts_with("s3://") => Ok(json!({"s3Location": {"uri": uri}})),
+        Some(uri) => match uri
+            .strip_prefix("data:")
+            .and_then(|u| u.split_once(";base64,"))
+        {
+            Some((_, data)) => Ok(json!({"bytes": data})),
+            None => Err(GaussError::provider(
+                PROVIDER,
+                format!("Bedrock {kind}s must be inline base64 or s3:// URLs, got {uri}"),
+            )),
+        },
+        None => Err(GaussError::provider(
+            PROVIDER,
+            format!("{kind} has no data"),
+        )),
+    }
+}
+
+fn image_format(media_type: Option<&str>) -> &str {
+    match media_type.and_then(|m| m.strip_prefix("image/")) {
+        Some("jpg" | "jpeg") => "jpeg",
+        Some(format @ ("png" | "gif" | "webp")) => format,
+        _ => "png",
+    }
+}
+
+fn document_format(media_type: Option<&str>) -> &'static str {
+    match media_type.unwrap_or("application/pdf") {
+        "text/csv" => "csv",
+        "application/msword" => "doc",
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.document" => "docx",
+        "application/vnd.ms-excel" => "xls",
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet" => "xlsx",
+        "text/html" => "html",
+        "text/markdown" => "md",
+        "text/plain" => "txt",
+        _ => "pdf",
+    }
+}
+
+/// Document names may only contain alphanumerics, single spaces, hyphens,
+/// parentheses and square brackets.
+fn document_name(title: Option<&str>) -> String {
+    let name: String = title
+        .unwrap_or("document")
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '-' | '(' | ')' | '[' | ']') {
+                c
+            } else {
+                ' '
+            }
+        })
+        .collect();
+    let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+    if name.is_empty() {
+        "document".to_string()
+    } else {
+        name
+    }
+}
+
+fn finish_reason(stop_reason: &str) -> FinishReason {
+    match stop_reason {
+        "end_turn" | "stop_sequence" => FinishReason::Stop,
+        "tool_use" => FinishReason::ToolCalls,
+        "max_tokens" => FinishReason::Length,
+        "content_filtered" | "guardrail_intervened" => FinishReason::ContentFilter,
+        other => FinishReason::Other(other.to_string()),
+    }
+}
+
+fn parse_usage(usage: &Value) -> Usage {
+    Usage {
+        input_tokens: usage["inputTokens"].as_u64().unwrap_or(0),
+        output_tokens: usage["outputTokens"].as_u64().unwrap_or(0),
+        reasoning_tokens: None,
+        cache_read_tokens: usage["cacheReadInputTokens"].as_u64(),
+        cache_creation_tokens: usage["cacheWriteInputTokens"].as_u64(),
+    }
+}
+
+fn parse_response(body: &Value) -> error::Result<GenerateResult> {
+    let mut content = Vec::new();
+    let mut thinking = Vec::new();
+
+    for block in body["output"]["message"]["content"]
+        .as_array()
+        .into_iter()
+        .flatten()
+    {
+        if let Some(text) = block["text"].as_str() {
+            content.push(Content::Text {
+                text: text.to_string(),
+            });
+        } else if let Some(tool) = block.get("toolUse") {
+            content.push(Content::ToolCall {
+                id: tool["toolUseId"].as_str().unwrap_or("").to_string(),
+                name: tool["name"].as_str().unwrap_or("").to_string(),
+                arguments: tool["input"].clone(),
+            });
+        } else if let Some(reasoning) = block["reasoningContent"].get("reasoningText") {
+            let text = reasoning["text"].as_str().unwrap_or("").to_string();
+            thinking.push(text.clone());
+            content.push(Content::Reasoning {
+                text,
+                id: None,
+                encrypted_content: reasoning["signature"].as_str().map(String::from),
+            });
+        }
+    }
+
+    Ok(GenerateResult {
+        message: Message {
+            role: Role::Assistant,
+            content,
+            name: None,
+        },
+        usage: parse_usage(&body["usage"]),
+        finish_reason: finish_reason(body["stopReason"].as_str().unwrap_or("end_turn")),
+        provider_metadata: json!({"metrics": body["metrics"]}),
+        thinking: (!thinking.is_empty()).then(|| thinking.join("\n")),
+        citations: Vec::new(),
+        grounding_metadata: None,
+        routing: None,
+    })
+}
+
+/// Maps ConverseStream events onto [`StreamEvent`]s.
+#[derive(Default)]
+struct StreamState {
+    /// `contentBlockIndex` of each tool use block → tool call index.
+    tool_calls: HashMap<u64, usize>,
+    done: bool,
+}
+
+impl StreamState {
+    fn handle(&mut self, message: &EventStreamMessage) -> Vec<error::Result<StreamEvent>> {
+        let payload: Value = serde_json::from_slice(&message.payload).unwrap_or(json!({}));
+
+        match message.header(":message-type") {
+            Some("exception") => {
+                self.done = true;
+                let kind = message.header(":exception-type").unwrap_or("exception");
+                let text = payload["message"].as_str().unwrap_or("Stream error");
+                let err = if kind == "throttlingException" {
+                    GaussError::rate_limited(PROVIDER, text)
+                } else {
+                    GaussError::provider(PROVIDER, format!("{kind}: {text}"))
+                };
+                return vec![Err(err)];
+            }
+            Some("error") => {
+                self.done = true;
+                let text = message.header(":error-message").unwrap_or("Stream error");
+                return vec![Err(GaussError::provider(PROVIDER, text))];
+            }
+            _ => {}
+        }
+
+        let block_index = payload["contentBlockIndex"].as_u64().unwrap_or(0);
+        match message.header(":event-type").unwrap_or("") {
+            "contentBlockStart" => match payload["start"].get("toolUse") {
+                Some(tool) => {
+                    let index = self.tool_calls.len();
+                    self.tool_calls.insert(block_index, index);
+                    vec![Ok(StreamEvent::ToolCallDelta {
+                        index,
+                        id: tool["toolUseId"].as_str().map(String::from),
+                        name: tool["name"].as_str().map(String::from),
+                        arguments_delta: None,
+                    })]
+                }
+                None => vec![],
+            },
+            "contentBlockDelta" => {
+                let delta = &payload["delta"];
+                if let Some(text) = delta["text"].as_str() {
+                    vec![Ok(StreamEvent::TextDelta(text.to_string()))]
+                } else if let Some(input) = delta["toolUse"]["input"].as_str() {
+                    match self.tool_calls.get(&block_index) {
+                        Some(&index) => vec![Ok(StreamEvent::ToolCallDelta {
+                            index,
+                            id: None,
+                            name: None,
+                            arguments_delta: Some(input.to_string()),
+                        })],
+                        None => vec![],
+                    }
+                } else if let Some(text) = delta["reasoningContent"]["text"].as_str() {
+                    vec![Ok(StreamEvent::ReasoningDelta(text.to_string()))]
+                } else if let Some(signature) = delta["reasoningContent"]["signature"].as_str() {
+                    // Needed to send the reasoning back on the next turn.
+                    vec![Ok(StreamEvent::ReasoningItem {
+                        id: None,
+                        encrypted_content: Some(signature.to_string()),
+                    })]
+                } else {
+                    vec![]
+                }
+            }
+            "messageStop" => vec![Ok(StreamEvent::FinishReason(finish_reason(
+                payload["stopReason"].as_str().unwrap_or("end_turn"),
+            )))],
+            // Sent last, after `messageStop`.
+            "metadata" => {
+                self.done = true;
+                vec![
+                    Ok(StreamEvent::Usage(parse_usage(&payload["usage"]))),
+                    Ok(StreamEvent::Done),
+                ]
+            }
+            _ => vec![],
+        }
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Provider for BedrockProvider {
+    fn name(&self) -> &str {
+        PROVIDER
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        crate::catalog::read().capabilities(
+            &self.model,
+            ProviderCapabilities {
+                streaming: true,
+                tool_use: true,
+                vision: true,
+                extended_thinking: true,
+                cache_control: true,
+                ..Default::default()
+            },
+        )
+    }
+
+    async fn generate(
+        &self,
+        messages: &[Message],
+        tools: &[Tool],
+        options: &GenerateOptions,
+    ) -> error::Result<GenerateResult> {
+        let body = self.build_request_body(messages, tools, options)?;
+        let response: Value = self
+            .send("converse", &body)
+            .await?
+            .json()
+            .await
+            .map_err(|e| {
+                GaussError::provider(PROVIDER, format!("Failed to parse response: {e}"))
+            })?;
+        parse_response(&response)
+    }
+
+    async fn stream(
+        &self,
+        messages: &[Message],
+        tools: &[Tool],
+        options: &GenerateOptions,
+    ) -> error::Result<BoxStream> {
+        let body = self.build_request_body(messages, tools, options)?;
+        let resp = self.send("converse-stream", &body).await?;
+
+        let mut chunks = resp.bytes_stream();
+        let stream = async_stream::stream! {
+            let mut decoder = EventStreamDecoder::new();
+            let mut state = StreamState::default();
+            'read: while let Some(chunk) = chunks.next().await {
+                let chunk = match chunk {
+                    Ok(chunk) => chunk,
+                    Err(e) => {
+                        yield Err(GaussError::provider(PROVIDER, format!("Stream error: {e}")));
+                        return;
+                    }
+                };
+                decoder.push(&chunk);
+                loop {
+                    match decoder.next_message() {
+                        Ok(Some(message)) => {
+                            for out in state.handle(&message) {
+                                yield out;
+                            }
+                            if state.done {
+                                break 'read;
+                            }
+                        }
+                        Ok(None) => break,
+                        Err(e) => {
+                            yield Err(e);
+                            return;
+                        }
+                    }
+                }
+            }
+            // Without the closing `metadata` event the response was cut short.
+            if !state.done {
+                let message = if decoder.buffered() > 0 {
+                    "Stream ended mid-message"
+                } else {
+                    "Stream ended before the metadata event"
+                };
+                yield Err(GaussError::provider(PROVIDER, message));
+            }
+        };
+
+        Ok(Box::new(Box::pin(stream)))
+    }
+}
@@ -0,0 +1,815 @@
+//! Response caching at the provider level.
+//!
+//! [`CachedProvider`] wraps any provider and serves repeated requests from a
+//! [`CacheStore`]. Requests are keyed by a canonical hash of the model,
+//! messages, tools and [`GenerateOptions`], so changing any of them is a
+//! miss. `stream` calls are cached as their event sequence and replayed.
+//!
+//! With [`CachedProvider::semantic`], a miss falls back to comparing the
+//! embedding of the last user message against earlier requests that share
+//! everything else (model, tools, options and prior turns), and reuses the
+//! most similar answer above the threshold.
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//! use gauss_core::provider::ProviderConfig;
+//! use gauss_core::provider::cache::{CachedProvider, DiskCacheStore};
+//! use gauss_core::provider::openai::OpenAiProvider;
+//!
+//! let openai = Arc::new(OpenAiProvider::new("gpt-4o-mini", ProviderConfig::new("sk-...")));
+//! let provider = CachedProvider::new(openai, Arc::new(DiskCacheStore::new(".gauss/cache")))
+//!     .with_ttl(Duration::from_secs(24 * 3600));
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::{debug, warn};
+
+use crate::error::{self, GaussError};
+use crate::message::{Message, Role};
+use crate::provider::{BoxStream, GenerateOptions, GenerateResult, Provider, ProviderCapabilities};
+use crate::rag::{Embedding, cosine_similarity};
+use crate::streaming::StreamEvent;
+use crate::tool::Tool;
+
+/// A cached response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CachedResponse {
+    Generate {
+        result: Box<GenerateResult>,
+    },
+    /// A complete event sequence from `stream`.
+    Stream {
+        events: Vec<StreamEvent>,
+    },
+}
+
+/// Embedding of a request's last user message, for semantic lookups.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SemanticKey {
+    /// Hash of everything but the last user message.
+    pub context: String,
+    pub embedding: Vec<f32>,
+}
+
+/// A stored cache entry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheEntry {
+    pub response: CachedResponse,
+    /// Unix time in milliseconds when the entry was stored.
+    pub created_at_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub semantic: Option<SemanticKey>,
+}
+
+// ---------------------------------------------------------------------------
+// Cache Store Trait
+// ---------------------------------------------------------------------------
+
+#[cfg(not(target_arch = "wasm32"))]
+#[async_trait]
+pub trait CacheStore: Send + Sync {
+    async fn get(&self, key: &str) -> error::Result<Option<CacheEntry>>;
+    async fn put(&self, key: &str, entry: CacheEntry) -> error::Result<()>;
+    async fn remove(&self, key: &str) -> error::Result<()>;
+    /// Keys and semantic keys of all entries, for building the semantic index.
+    async fn semantic_keys(&self) -> error::Result<Vec<(String, SemanticKey)>>;
+    async fn clear(&self) -> error::Result<()>;
+}
+
+#[cfg(target_arch = "wasm32")]
+#[async_trait(?Send)]
+pub trait CacheStore {
+    async fn get(&self, key: &str) -> error::Result<Option<CacheEntry>>;
+    async fn put(&self, key: &str, entry: CacheEntry) -> error::Result<()>;
+    async fn remove(&self, key: &str) -> error::Result<()>;
+    async fn semantic_keys(&self) -> error::Result<Vec<(String, SemanticKey)>>;
+    async fn clear(&self) -> error::Result<()>;
+}
+
+// ---------------------------------------------------------------------------
+// In-Memory LRU Store
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Default)]
+struct Lru {
+    entries: HashMap<String, (CacheEntry, u64)>,
+    /// Last-use tick → key, oldest first.
+    order: BTreeMap<u64, String>,
+    tick: u64,
+}
+
+impl Lru {
+    fn touch(&mut self, key: &str) {
+        self.tick += 1;
+        let tick = self.tick;
+        if let Some((_, used)) = self.entries.get_mut(key) {
+            self.order.remove(used);
+            *used = tick;
+            self.order.insert(tick, key.to_string());
+        }
+    }
+}
+
+/// In-memory store that evicts the least recently used entry when full.
+#[derive(Debug)]
+pub struct MemoryCacheStore {
+    capacity: usize,
+    lru: Mutex<Lru>,
+}
+
+impl MemoryCacheStore {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            lru: Mutex::new(Lru::default()),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.lru
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entries
+            .len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for MemoryCacheStore {
+    fn default() -> Self {
+        Self::new(1000)
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl CacheStore for MemoryCacheStore {
+    async fn get(&self, key: &str) -> error::Result<Option<CacheEntry>> {
+        let mut lru = self.lru.lock().unwrap_or_else(|e| e.into_inner());
+        lru.touch(key);
+        Ok(lru.entries.get(key).map(|(entry, _)| entry.clone()))
+    }
+
+    async fn put(&self, key: &str, entry: CacheEntry) -> error::Result<()> {
+        let mut lru = self.lru.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((_, used)) = lru.entries.remove(key) {
+            lru.order.remove(&used);
+        } else if lru.entries.len() >= self.capacity
+            && let Some((_, oldest)) = lru.order.pop_first()
+        {
+            lru.entries.remove(&oldest);
+        }
+        lru.entries.insert(key.to_string(), (entry, 0));
+        lru.touch(key);
+        Ok(())
+    }
+
+    async fn remove(&self, key: &str) -> error::Result<()> {
+        let mut lru = self.lru.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((_, used)) = lru.entries.remove(key) {
+            lru.order.remove(&used);
+        }
+        Ok(())
+    }
+
+    async fn semantic_keys(&self) -> error::Result<Vec<(String, SemanticKey)>> {
+        let lru = self.lru.lock().unwrap_or_else(|e| e.into_inner());
+        Ok(lru
+            .entries
+            .iter()
+            .filter_map(|(key, (entry, _))| Some((key.clone(), entry.semantic.clone()?)))
+            .collect())
+    }
+
+    async fn clear(&self) -> error::Result<()> {
+        *self.lru.lock().unwrap_or_else(|e| e.into_inner()) = Lru::default();
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// On-Disk Store
+// ---------------------------------------------------------------------------
+
+/// Stores one JSON file per entry in a directory, so the cache survives
+/// restarts and can be shared between processes.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone)]
+pub struct DiskCacheStore {
+    dir: std::path::PathBuf,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl DiskCacheStore {
+    pub fn new(dir: impl Into<std::path::PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &std::path::Path {
+        &self.dir
+    }
+
+    fn path(&self, key: &str) -> std::path::PathBuf {
+        self.dir.join(format!("{key}.json"))
+    }
+
+    fn io_error(&self, action: &str, e: impl std::fmt::Display) -> GaussError {
+        GaussError::Config {
+            message: format!("Failed to {action} cache '{}': {e}", self.dir.display()),
+        }
+    }
+
+    /// Run blocking file I/O on tokio's blocking pool when there is a
+    /// runtime, so it doesn't stall the async worker threads.
+    async fn blocking<T: Send + 'static>(
+        &self,
+        io: impl FnOnce(&Self) -> error::Result<T> + Send + 'static,
+    ) -> error::Result<T> {
+        #[cfg(feature = "native")]
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            let store = self.clone();
+            return handle
+                .spawn_blocking(move || io(&store))
+                .await
+                .map_err(|e| self.io_error("access", e))?;
+        }
+        io(self)
+    }
+
+    fn read_entry(&self, key: &str) -> error::Result<Option<CacheEntry>> {
+        let content = match std::fs::read_to_string(self.path(key)) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(self.io_error("read", e)),
+        };
+        match serde_json::from_str(&content) {
+            Ok(entry) => Ok(Some(entry)),
+            Err(e) => {
+                warn!(key, error = %e, "Ignoring unreadable cache entry");
+                Ok(None)
+            }
+        }
+    }
+
+    fn write_entry(&self, key: &str, entry: &CacheEntry) -> error::Result<()> {
+        std::fs::create_dir_all(&self.dir).map_err(|e| self.io_error("create", e))?;
+        let json = serde_json::to_string(entry).map_err(|e| self.io_error("serialize", e))?;
+        // Write then rename, so readers never see a partial entry.
+        let tmp = self
+            .dir
+            .join(format!(".{key}.{}.tmp", uuid::Uuid::new_v4().simple()));
+        std::fs::write(&tmp, json).map_err(|e| self.io_error("write", e))?;
+        std::fs::rename(&tmp, self.path(key)).map_err(|e| self.io_error("write", e))
+    }
+
+    fn remove_entry(&self, key: &str) -> error::Result<()> {
+        match std::fs::remove_file(self.path(key)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(self.io_error("remove", e)),
+            _ => Ok(()),
+        }
+    }
+
+    fn read_semantic_keys(&self) -> error::Result<Vec<(String, SemanticKey)>> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(self.io_error("read", e)),
+        };
+        let mut keys = Vec::new();
+        for path in entries.flatten().map(|e| e.path()) {
+            let Some(key) = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .and_then(|n| n.strip_suffix(".json"))
+            else {
+                continue;
+            };
+            if let Some(semantic) = self.read_entry(key)?.and_then(|e| e.semantic) {
+                keys.push((key.to_string(), semantic));
+            }
+        }
+        Ok(keys)
+    }
+
+    fn remove_all(&self) -> error::Result<()> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(self.io_error("clear", e)),
+        };
+        for path in entries.flatten().map(|e| e.path()) {
+            let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            let ours = name.ends_with(".json") && !name.starts_with('.')
+                || name.starts_with('.') && name.ends_with(".tmp");
+            if !ours || !path.is_file() {
+                continue;
+            }
+            match std::fs::remove_file(&path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    return Err(self.io_error("clear", e));
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[async_trait]
+impl CacheStore for DiskCacheStore {
+    async fn get(&self, key: &str) -> error::Result<Option<CacheEntry>> {
+        let key = key.to_string();
+        self.blocking(move |store| store.read_entry(&key)).await
+    }
+
+    async fn put(&self, key: &str, entry: CacheEntry) -> error::Result<()> {
+        let key = key.to_string();
+        self.blocking(move |store| store.write_entry(&key, &entry))
+            .await
+    }
+
+    async fn remove(&self, key: &str) -> error::Result<()> {
+        let key = key.to_string();
+        self.blocking(move |store| store.remove_entry(&key)).await
+    }
+
+    async fn semantic_keys(&self) -> error::Result<Vec<(String, SemanticKey)>> {
+        self.blocking(Self::read_semantic_keys).await
+    }
+
+    /// Removes the entries and leftover temporary files this store wrote;
+    /// anything else in the directory is left alone.
+    async fn clear(&self) -> error::Result<()> {
+        self.blocking(Self::remove_all).await
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Cache Keys
+// ---------------------------------------------------------------------------
+
+/// 128-bit FNV-1a: stable across builds and platforms, and wide enough that
+/// distinct requests don't collide in practice.
+fn fnv1a_128(bytes: &[u8]) -> u128 {
+    bytes
+        .iter()
+        .fold(0x6c62_272e_07bb_0142_62b8_2175_6295_c58d, |hash, &byte| {
+            (hash ^ u128::from(byte)).wrapping_mul(0x0000_0000_0100_0000_0000_0000_0000_013b)
+        })
+}
+
+fn hash_json(value: &serde_json::Value) -> String {
+    // serde_json maps are sorted, so the serialized form is canonical.
+    format!("{:032x}", fnv1a_128(value.to_string().as_bytes()))
+}
+
+fn tools_json(tools: &[Tool]) -> serde_json::Value {
+    tools
+        .iter()
+        .map(|t| json!({"name": t.name, "description": t.description, "parameters": t.parameters}))
+        .collect()
+}
+
+/// Canonical cache key for a request. `stream` is part of the key because
+/// streamed and non-streamed responses are cached separately.
+pub fn cache_key(
+    provider: &dyn Provider,
+    stream: bool,
+    messages: &[Message],
+    tools: &[Tool],
+    options: &GenerateOptions,
+) -> String {
+    hash_json(&json!({
+        "provider": provider.name(),
+        "model": provider.model(),
+        "stream": stream,
+        "messages": messages,
+        "tools": tools_json(tools),
+        "options": options,
+    }))
+}
+
+/// The last user message's text and a hash of everything else, or `None`
+/// when the request doesn't end with a user text message.
+fn semantic_parts<'a>(
+    provider: &dyn Provider,
+    stream: bool,
+    messages: &'a [Message],
+    tools: &[Tool],
+    options: &GenerateOptions,
+) -> Option<(&'a str, String)> {
+    let (last, history) = messages.split_last()?;
+    if last.role != Role::User {
+        return None;
+    }
+    let prompt = last.text()?;
+    let context = hash_json(&json!({
+        "provider": provider.name(),
+        "model": provider.model(),
+        "stream": stream,
+        "history": history,
+        "tools": tools_json(tools),
+        "options": options,
+    }));
+    Some((prompt, context))
+}
+
+fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+// ---------------------------------------------------------------------------
+// CachedProvider
+// ---------------------------------------------------------------------------
+
+/// Hit and miss counts for a [`CachedProvider`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub semantic_hits: u64,
+    pub misses: u64,
+}
+
+/// Loaded from the store on first use, then kept in sync with `put`s.
+type SemanticIndex = Mutex<Option<Vec<(String, SemanticKey)>>>;
+
+struct Semantic {
+    embedder: crate::Shared<dyn Embedding>,
+    threshold: f32,
+    index: crate::Shared<SemanticIndex>,
+}
+
+/// Outcome of a cache lookup.
+struct Lookup {
+    key: String,
+    /// The entry and `provider_metadata.cache` for a hit.
+    hit: Option<(CacheEntry, serde_json::Value)>,
+    /// Semantic key to store with the response on a miss.
+    semantic: Option<SemanticKey>,
+}
+
+/// Serves repeated requests from a [`CacheStore`].
+///
+/// Hits carry `provider_metadata.cache` (`"exact"` or `"semantic"` with the
+/// similarity), in a [`StreamEvent::ProviderMetadata`] event for streams;
+/// errors and streams that end before `Done` are never cached.
+pub struct CachedProvider {
+    inner: crate::Shared<dyn Provider>,
+    store: crate::Shared<dyn CacheStore>,
+    ttl: Option<Duration>,
+    semantic: Option<Semantic>,
+    hits: AtomicU64,
+    semantic_hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl CachedProvider {
+    pub fn new(inner: crate::Shared<dyn Provider>, store: crate::Shared<dyn CacheStore>) -> Self {
+        Self {
+            inner,
+            store,
+            ttl: None,
+            semantic: None,
+            hits: AtomicU64::new(0),
+            semantic_hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Treat entries older than `ttl` as misses.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Reuse answers for prompts whose embeddings have cosine similarity of
+    /// at least `threshold` (e.g. 0.95) with a cached one.
+    pub fn semantic(mut self, embedder: crate::Shared<dyn Embedding>, threshold: f32) -> Self {
+        self.semantic = Some(Semantic {
+            embedder,
+            threshold,
+            index: crate::Shared::new(Mutex::new(None)),
+        });
+        self
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            semantic_hits: self.semantic_hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    fn expired(&self, entry: &CacheEntry) -> bool {
+        self.ttl.is_some_and(|ttl| {
+            now_millis().saturating_sub(entry.created_at_ms) >= ttl.as_millis() as u64
+        })
+    }
+
+    /// A live entry for `key`. Expired entries are removed, and store errors
+    /// are logged and treated as misses.
+    async fn fresh(&self, key: &str) -> Option<CacheEntry> {
+        match self.store.get(key).await {
+            Ok(Some(entry)) if self.expired(&entry) => {
+                if let Err(e) = self.store.remove(key).await {
+                    warn!(error = %e, "Failed to remove expired cache entry");
+                }
+                None
+            }
+            Ok(entry) => entry,
+            Err(e) => {
+                warn!(error = %e, "Failed to read cache entry");
+                None
+            }
+        }
+    }
+
+    async fn semantic_index(
+        &self,
+        semantic: &Semantic,
+    ) -> error::Result<Vec<(String, SemanticKey)>> {
+        if let Some(index) = semantic
+            .index
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+        {
+            return Ok(index);
+        }
+        let index = self.store.semantic_keys().await?;
+        *semantic.index.lock().unwrap_or_else(|e| e.into_inner()) = Some(index.clone());
+        Ok(index)
+    }
+
+    /// The most similar cached entry for `embedding` within `context`.
+    async fn nearest(
+        &self,
+        semantic: &Semantic,
+        context: &str,
+        embedding: &[f32],
+    ) -> error::Result<Option<(CacheEntry, f32)>> {
+        let best = self
+            .semantic_index(semantic)
+            .await?
+            .into_iter()
+            .filter(|(_, k)| k.context == context)
+            .map(|(key, k)| (cosine_similarity(embedding, &k.embedding), key))
+            .filter(|(similarity, _)| *similarity >= semantic.threshold)
+            .max_by(|a, b| a.0.total_cmp(&b.0));
+        let Some((similarity, key)) = best else {
+            return Ok(None);
+        };
+        if let Some(entry) = self.fresh(&key).await {
+            return Ok(Some((entry, similarity)));
+        }
+        // Evicted or expired since the index was built.
+        if let Some(index) = semantic
+            .index
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_mut()
+        {
+            index.retain(|(k, _)| *k != key);
+        }
+        Ok(None)
+    }
+
+    /// Look up a request: exact key first, then the nearest semantic match.
+    async fn lookup(
+        &self,
+        stream: bool,
+        messages: &[Message],
+        tools: &[Tool],
+        options: &GenerateOptions,
+    ) -> Lookup {
+        let key = cache_key(self.inner.as_ref(), stream, messages, tools, options);
+        if let Some(entry) = self.fresh(&key).await {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Lookup {
+                key,
+                hit: Some((entry, json!({"hit": "exact"}))),
+                semantic: None,
+            };
+        }
+
+        let mut semantic_key = None;
+        if let Some(ref semantic) = self.semantic
+            && let Some((prompt, context)) =
+                semantic_parts(self.inner.as_ref(), stream, messages, tools, options)
+        {
+            let nearest = match semantic.embedder.embed(prompt).await {
+                Ok(embedding) => {
+                    let nearest = self.nearest(semantic, &context, &embedding).await;
+                    semantic_key = Some(SemanticKey { context, embedding });
+                    nearest
+                }
+                Err(e) => Err(e),
+            };
+            match nearest {
+                Ok(Some((entry, similarity))) => {
+                    self.semantic_hits.fetch_add(1, Ordering::Relaxed);
+                    debug!(similarity, "Semantic cache hit");
+                    return Lookup {
+                        key,
+                        hit: Some((entry, json!({"hit": "semantic", "similarity": similarity}))),
+                        semantic: None,
+                    };
+                }
+                Ok(None) => {}
+                Err(e) => warn!(error = %e, "Semantic cache lookup failed"),
+            }
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        Lookup {
+            key,
+            hit: None,
+            semantic: semantic_key,
+        }
+    }
+
+    /// Remove every entry from the store.
+    pub async fn clear(&self) -> error::Result<()> {
+        if let Some(ref semantic) = self.semantic {
+            *semantic.index.lock().unwrap_or_else(|e| e.into_inner()) = None;
+        }
+        self.store.clear().await
+    }
+}
+
+/// Store an entry and add it to the semantic index, if one is loaded.
+/// Failures are logged: a broken cache must not fail the request.
+async fn store_entry(
+    store: &dyn CacheStore,
+    index: Option<&SemanticIndex>,
+    key: String,
+    response: CachedResponse,
+    semantic: Option<SemanticKey>,
+) {
+    let entry = CacheEntry {
+        response,
+        created_at_ms: now_millis(),
+        semantic: semantic.clone(),
+    };
+    if let Err(e) = store.put(&key, entry).await {
+        warn!(error = %e, "Failed to store cache entry");
+        return;
+    }
+    if let (Some(index), Some(semantic)) = (index, semantic)
+        && let Some(index) = index.lock().unwrap_or_else(|e| e.into_inner()).as_mut()
+    {
+        index.retain(|(k, _)| *k != key);
+        index.push((key, semantic));
+    }
+}
+
+fn annotate(metadata: &mut serde_json::Value, meta: serde_json::Value) {
+    match metadata {
+        serde_json::Value::Object(map) => {
+            map.insert("cache".into(), meta);
+        }
+        _ => *metadata = json!({ "cache": meta }),
+    }
+}
+
+/// Mark replayed stream events as a hit: on the recorded
+/// [`StreamEvent::ProviderMetadata`] if there is one, or a new one before
+/// `Done` otherwise.
+fn annotate_events(events: &mut Vec<StreamEvent>, meta: serde_json::Value) {
+    for event in events.iter_mut() {
+        if let StreamEvent::ProviderMetadata(metadata) = event {
+            annotate(metadata, meta);
+            return;
+        }
+    }
+    let done = events
+        .iter()
+        .position(StreamEvent::is_done)
+        .unwrap_or(events.len());
+    events.insert(
+        done,
+        StreamEvent::ProviderMetadata(json!({ "cache": meta })),
+    );
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Provider for CachedProvider {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn model(&self) -> &str {
+        self.inner.model()
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        self.inner.capabilities()
+    }
+
+    async fn generate(
+        &self,
+        messages: &[Message],
+        tools: &[Tool],
+        options: &GenerateOptions,
+    ) -> error::Result<GenerateResult> {
+        let Lookup { key, hit, semantic } = self.lookup(false, messages, tools, options).await;
+        if let Some((
+            CacheEntry {
+                response: CachedResponse::Generate { mut result },
+                ..
+            },
+            meta,
+        )) = hit
+        {
+            annotate(&mut result.provider_metadata, meta);
+            return Ok(*result);
+        }
+
+        let result = self.inner.generate(messages, tools, options).await?;
+        store_entry(
+            self.store.as_ref(),
+            self.semantic.as_ref().map(|s| s.index.as_ref()),
+            key,
+            CachedResponse::Generate {
+                result: Box::new(result.clone()),
+            },
+            semantic,
+        )
+        .await;
+        Ok(result)
+    }
+
+    async fn stream(
+        &self,
+        messages: &[Message],
+        tools: &[Tool],
+        options: &GenerateOptions,
+    ) -> error::Result<BoxStream> {
+        let Lookup { key, hit, semantic } = self.lookup(true, messages, tools, options).await;
+        if let Some((
+            CacheEntry {
+                response: CachedResponse::Stream { mut events },
+                ..
+            },
+            meta,
+        )) = hit
+        {
+            annotate_events(&mut events, meta);
+            return Ok(Box::new(futures::stream::iter(events.into_iter().map(Ok))));
+        }
+
+        let mut inner = self.inner.stream(messages, tools, options).await?;
+        let store = self.store.clone();
+        let index = self.semantic.as_ref().map(|s| s.index.clone());
+        // Stored when `Done` arrives without an earlier error, before it is
+        // yielded: the agent stops reading there.
+        let stream = async_stream::stream! {
+            let mut events = Vec::new();
+            while let Some(item) = inner.next().await {
+                match &item {
+                    Ok(event) if event.is_done() => {
+                        events.push(event.clone());
+                        store_entry(
+                            store.as_ref(),
+                            index.as_deref(),
+                            key,
+                            CachedResponse::Stream { events },
+                            semantic,
+                        )
+                        .await;
+                        yield item;
+                        return;
+                    }
+                    Ok(event) => events.push(event.clone()),
+                    Err(_) => {
+                        yield item;
+                        return;
+                    }
+                }
+                yield item;
+            }
+        };
+        Ok(Box::new(Box::pin(stream)))
+    }
+}
@@ -0,0 +1,779 @@
+//! Record/replay providers for deterministic, offline tests.
+//!
+//! [`RecordingProvider`] wraps a real provider and writes every `generate` and
+//! `stream` call — request and response — to a JSON cassette file.
+//! [`ReplayProvider`] serves those responses back without touching the
+//! network, matching requests by a canonical hash at a configurable
+//! [`MatchStrictness`] and reproducing stream chunk timing.
+//!
+//! ```no_run
+//! # async fn example() -> gauss_core::error::Result<()> {
+//! use std::sync::Arc;
+//! use gauss_core::Agent;
+//! use gauss_core::provider::cassette::{MatchStrictness, ReplayProvider, ReplayTiming};
+//!
+//! let provider = ReplayProvider::from_file("tests/cassettes/weather.json")?
+//!     .strictness(MatchStrictness::IgnoreOptions)
+//!     .timing(ReplayTiming::Instant);
+//! let agent = Agent::builder("weather", Arc::new(provider)).build();
+//! # let _ = agent;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{self, GaussError};
+use crate::message::Message;
+use crate::provider::retry::sleep;
+use crate::provider::{BoxStream, GenerateOptions, GenerateResult, Provider, ProviderCapabilities};
+use crate::streaming::StreamEvent;
+use crate::tool::{Tool, ToolParameters};
+
+/// Current cassette file format version.
+pub const CASSETTE_VERSION: u32 = 1;
+
+/// Which provider method an interaction went through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RequestKind {
+    Generate,
+    Stream,
+}
+
+/// A tool definition as sent to the provider (its executor is not recorded).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedTool {
+    pub name: String,
+    pub description: String,
+    pub parameters: ToolParameters,
+}
+
+impl From<&Tool> for RecordedTool {
+    fn from(tool: &Tool) -> Self {
+        Self {
+            name: tool.name.clone(),
+            description: tool.description.clone(),
+            parameters: tool.parameters.clone(),
+        }
+    }
+}
+
+/// A recorded provider request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub kind: RequestKind,
+    pub provider: String,
+    pub model: String,
+    pub messages: Vec<Message>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<RecordedTool>,
+    #[serde(default)]
+    pub options: GenerateOptions,
+}
+
+impl RecordedRequest {
+    pub fn new(
+        kind: RequestKind,
+        provider: &dyn Provider,
+        messages: &[Message],
+        tools: &[Tool],
+        options: &GenerateOptions,
+    ) -> Self {
+        Self {
+            kind,
+            provider: provider.name().to_string(),
+            model: provider.model().to_string(),
+            messages: messages.to_vec(),
+            tools: tools.iter().map(RecordedTool::from).collect(),
+            options: options.clone(),
+        }
+    }
+
+    /// Canonical hash of the parts of the request `strictness` compares, or
+    /// `None` for [`MatchStrictness::Sequential`].
+    pub fn hash(&self, strictness: MatchStrictness) -> Option<String> {
+        let key = match strictness {
+            MatchStrictness::Exact => serde_json::json!({
+                "kind": self.kind,
+                "model": self.model,
+                "messages": self.messages,
+                "tools": self.tools,
+                "options": self.options,
+            }),
+            MatchStrictness::IgnoreOptions => serde_json::json!({
+                "kind": self.kind,
+                "model": self.model,
+                "messages": self.messages,
+                "tools": self.tools,
+            }),
+            MatchStrictness::MessagesOnly => serde_json::json!({
+                "kind": self.kind,
+                "messages": self.messages,
+            }),
+            MatchStrictness::Sequential => return None,
+        };
+        // serde_json maps are sorted, so the serialized form is canonical.
+        Some(format!("{:016x}", fnv1a(key.to_string().as_bytes())))
+    }
+}
+
+/// 64-bit FNV-1a: stable across builds and platforms, unlike `DefaultHasher`.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// A provider error, kept with enough detail to rebuild an equivalent one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedError {
+    /// [`GaussError::kind`] of the original error.
+    pub kind: String,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_after_ms: Option<u64>,
+}
+
+impl From<&GaussError> for RecordedError {
+    fn from(error: &GaussError) -> Self {
+        let (message, status, retry_after_ms) = match error {
+            GaussError::Provider {
+                message, status, ..
+            } => (message.clone(), *status, None),
+            GaussError::Stream { message, .. } => (message.clone(), None, None),
+            GaussError::RateLimited {
+                message,
+                retry_after_ms,
+                ..
+            } => (message.clone(), None, *retry_after_ms),
+            other => (other.to_string(), None, None),
+        };
+        Self {
+            kind: error.kind().to_string(),
+            message,
+            status,
+            retry_after_ms,
+        }
+    }
+}
+
+impl RecordedError {
+    /// Rebuild the error as if `provider` had returned it.
+    pub fn to_error(&self, provider: &str) -> GaussError {
+        match self.kind.as_str() {
+            "rate_limited" => GaussError::RateLimited {
+                provider: provider.to_string(),
+                message: self.message.clone(),
+                retry_after_ms: self.retry_after_ms,
+            },
+            "authentication" => GaussError::Authentication {
+                provider: provider.to_string(),
+            },
+            "stream" => GaussError::Stream {
+                message: self.message.clone(),
+                source: None,
+            },
+            _ => GaussError::Provider {
+                message: self.message.clone(),
+                status: self.status,
+                provider: provider.to_string(),
+                source: None,
+            },
+        }
+    }
+}
+
+/// A stream event and when it arrived, relative to the start of the stream.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedEvent {
+    pub offset_ms: u64,
+    pub event: StreamEvent,
+}
+
+/// A recorded provider response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RecordedResponse {
+    Generate {
+        result: Box<GenerateResult>,
+    },
+    /// The full event sequence; `error` is set when the stream ended with one.
+    Stream {
+        events: Vec<RecordedEvent>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error: Option<RecordedError>,
+    },
+    /// The call failed before producing a result or stream.
+    Error {
+        error: RecordedError,
+    },
+}
+
+/// One request/response pair.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interaction {
+    /// [`MatchStrictness::Exact`] hash of the request, for reading diffs.
+    pub hash: String,
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+    pub duration_ms: u64,
+}
+
+impl Interaction {
+    pub fn new(request: RecordedRequest, response: RecordedResponse, duration: Duration) -> Self {
+        Self {
+            hash: request.hash(MatchStrictness::Exact).unwrap_or_default(),
+            request,
+            response,
+            duration_ms: duration.as_millis() as u64,
+        }
+    }
+}
+
+/// A sequence of recorded interactions, stored as pretty-printed JSON.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Cassette {
+    pub version: u32,
+    /// Capabilities of the recorded provider, reported again on replay.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capabilities: Option<ProviderCapabilities>,
+    pub interactions: Vec<Interaction>,
+}
+
+impl Default for Cassette {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cassette {
+    pub fn new() -> Self {
+        Self {
+            version: CASSETTE_VERSION,
+            capabilities: None,
+            interactions: Vec::new(),
+        }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> error::Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|e| GaussError::Config {
+            message: format!("Failed to read cassette '{}': {e}", path.display()),
+        })?;
+        let cassette: Self = serde_json::from_str(&content).map_err(|e| GaussError::Config {
+            message: format!("Invalid cassette '{}': {e}", path.display()),
+        })?;
+        if cassette.version > CASSETTE_VERSION {
+            return Err(GaussError::Config {
+                message: format!(
+                    "Cassette '{}' has version {}, newer than supported {CASSETTE_VERSION}",
+                    path.display(),
+                    cassette.version
+                ),
+            });
+        }
+        Ok(cassette)
+    }
+
+    /// Write the cassette, creating parent directories as needed. The file is
+    /// replaced atomically, so a crash mid-save leaves the previous one intact.
+    pub fn save(&self, path: impl AsRef<Path>) -> error::Result<()> {
+        let path = path.as_ref();
+        let write_err = |e: std::io::Error| GaussError::Config {
+            message: format!("Failed to write cassette '{}': {e}", path.display()),
+        };
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).map_err(write_err)?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(|e| GaussError::Config {
+            message: format!("Failed to serialize cassette: {e}"),
+        })?;
+        let name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("cassette");
+        let tmp = path.with_file_name(format!(".{name}.{}.tmp", uuid::Uuid::new_v4().simple()));
+        std::fs::write(&tmp, json)
+            .and_then(|_| std::fs::rename(&tmp, path))
+            .map_err(|e| {
+                let _ = std::fs::remove_file(&tmp);
+                write_err(e)
+            })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// RecordingProvider
+// ---------------------------------------------------------------------------
+
+/// Wraps a provider and records every call to a cassette file.
+///
+/// Interactions are written in the background, off the async executor:
+/// interactions recorded while a write is in flight are batched into the
+/// next one. Call [`flush`](Self::flush) before reading the file; dropping
+/// the recorder flushes too. Write failures are logged and never fail the
+/// call being recorded. A stream is recorded once it has been consumed to
+/// the end (or to its first error); streams dropped early are not recorded.
+pub struct RecordingProvider {
+    inner: crate::Shared<dyn Provider>,
+    recorder: Arc<Recorder>,
+}
+
+impl RecordingProvider {
+    /// Record into a new cassette at `path`, replacing any existing file.
+    pub fn new(inner: crate::Shared<dyn Provider>, path: impl Into<PathBuf>) -> Self {
+        Self::with_cassette(inner, path.into(), Cassette::new())
+    }
+
+    /// Record into `path`, keeping the interactions already in it.
+    pub fn append(
+        inner: crate::Shared<dyn Provider>,
+        path: impl Into<PathBuf>,
+    ) -> error::Result<Self> {
+        let path = path.into();
+        let cassette = if path.exists() {
+            Cassette::load(&path)?
+        } else {
+            Cassette::new()
+        };
+        Ok(Self::with_cassette(inner, path, cassette))
+    }
+
+    fn with_cassette(
+        inner: crate::Shared<dyn Provider>,
+        path: PathBuf,
+        mut cassette: Cassette,
+    ) -> Self {
+        cassette.capabilities = Some(inner.capabilities());
+        Self {
+            inner,
+            recorder: Arc::new(Recorder {
+                path,
+                state: Mutex::new(RecorderState {
+                    cassette,
+                    dirty: false,
+                    writing: false,
+                }),
+                write_lock: Mutex::new(()),
+            }),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.recorder.path
+    }
+
+    /// A snapshot of what has been recorded so far.
+    pub fn cassette(&self) -> Cassette {
+        self.recorder.state().cassette.clone()
+    }
+
+    /// Write every recorded interaction to the cassette file now.
+    pub fn flush(&self) -> error::Result<()> {
+        self.recorder.flush()
+    }
+}
+
+impl Drop for RecordingProvider {
+    fn drop(&mut self) {
+        if let Err(e) = self.recorder.flush() {
+            tracing::warn!(error = %e, "Failed to write cassette");
+        }
+    }
+}
+
+/// The cassette being recorded and its pending writes.
+struct Recorder {
+    path: PathBuf,
+    state: Mutex<RecorderState>,
+    /// Held while writing the file, so writes land in order.
+    write_lock: Mutex<()>,
+}
+
+struct RecorderState {
+    cassette: Cassette,
+    /// Interactions were added since the last write started.
+    dirty: bool,
+    /// A background write is scheduled or running.
+    writing: bool,
+}
+
+impl Recorder {
+    fn state(&self) -> std::sync::MutexGuard<'_, RecorderState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Add an interaction and schedule a write unless one is already pending.
+    fn record(self: &Arc<Self>, interaction: Interaction) {
+        let schedule = {
+            let mut state = self.state();
+            state.cassette.interactions.push(interaction);
+            state.dirty = true;
+            !std::mem::replace(&mut state.writing, true)
+        };
+        if !schedule {
+            return;
+        }
+        #[cfg(feature = "native")]
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            let recorder = self.clone();
+            handle.spawn_blocking(move || recorder.write_pending());
+            return;
+        }
+        self.write_pending();
+    }
+
+    /// Background writer: save until no new interactions arrived meanwhile.
+    fn write_pending(&self) {
+        loop {
+            let _write = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+            let snapshot = {
+                let mut state = self.state();
+                if !state.dirty {
+                    state.writing = false;
+                    return;
+                }
+                state.dirty = false;
+                state.cassette.clone()
+            };
+            if let Err(e) = snapshot.save(&self.path) {
+                tracing::warn!(error = %e, "Failed to write cassette");
+            }
+        }
+    }
+
+    fn flush(&self) -> error::Result<()> {
+        let _write = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let snapshot = {
+            let mut state = self.state();
+            if !state.dirty {
+                return Ok(());
+            }
+            state.dirty = false;
+            state.cassette.clone()
+        };
+        snapshot.save(&self.path)
+    }
+}
+
+#[async_trait]
+impl Provider for RecordingProvider {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn model(&self) -> &str {
+        self.inner.model()
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        self.inner.capabilities()
+    }
+
+    async fn generate(
+        &self,
+        messages: &[Message],
+        tools: &[Tool],
+        options: &GenerateOptions,
+    ) -> error::Result<GenerateResult> {
+        let request = RecordedRequest::new(
+            RequestKind::Generate,
+            self.inner.as_ref(),
+            messages,
+            tools,
+            options,
+        );
+        let started = Instant::now();
+        let result = self.inner.generate(messages, tools, options).await;
+        let response = match &result {
+            Ok(result) => RecordedResponse::Generate {
+                result: Box::new(result.clone()),
+            },
+            Err(e) => RecordedResponse::Error { error: e.into() },
+        };
+        self.recorder
+            .record(Interaction::new(request, response, started.elapsed()));
+        result
+    }
+
+    async fn stream(
+        &self,
+        messages: &[Message],
+        tools: &[Tool],
+        options: &GenerateOptions,
+    ) -> error::Result<BoxStream> {
+        let request = RecordedRequest::new(
+            RequestKind::Stream,
+            self.inner.as_ref(),
+            messages,
+            tools,
+            options,
+        );
+        let started = Instant::now();
+        let mut inner = match self.inner.stream(messages, tools, options).await {
+            Ok(stream) => stream,
+            Err(e) => {
+                let response = RecordedResponse::Error { error: (&e).into() };
+                self.recorder
+                    .record(Interaction::new(request, response, started.elapsed()));
+                return Err(e);
+            }
+        };
+
+        let recorder = self.recorder.clone();
+        // Recorded at `Done` or the first error, before it is yielded: the agent
+        // and orchestrators stop reading there.
+        let stream = async_stream::stream! {
+            let mut events = Vec::new();
+            let mut request = Some(request);
+            while let Some(item) = inner.next().await {
+                let offset_ms = started.elapsed().as_millis() as u64;
+                let error = match &item {
+                    Ok(event) => {
+                        events.push(RecordedEvent {
+                            offset_ms,
+                            event: event.clone(),
+                        });
+                        None
+                    }
+                    Err(e) => Some(RecordedError::from(e)),
+                };
+                let end = error.is_some() || item.as_ref().is_ok_and(StreamEvent::is_done);
+                if end && let Some(request) = request.take() {
+                    let events = std::mem::take(&mut events);
+                    let response = RecordedResponse::Stream { events, error };
+                    recorder.record(Interaction::new(request, response, started.elapsed()));
+                }
+                yield item;
+                if end {
+                    break;
+                }
+            }
+            // Ran dry without `Done`: record what arrived.
+            if let Some(request) = request {
+                let response = RecordedResponse::Stream { events, error: None };
+                recorder.record(Interaction::new(request, response, started.elapsed()));
+            }
+        };
+        Ok(Box::new(Box::pin(stream)))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ReplayProvider
+// ---------------------------------------------------------------------------
+
+/// How much of a request must match a recorded one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MatchStrictness {
+    /// Method, model, messages, tools and options.
+    #[default]
+    Exact,
+    /// Like `Exact`, but generation options (temperature, seed, ...) may differ.
+    IgnoreOptions,
+    /// Method and messages only.
+    MessagesOnly,
+    /// Ignore request content; serve interactions in recorded order.
+    Sequential,
+}
+
+/// How recorded stream timing is reproduced.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum ReplayTiming {
+    /// Wait between events as long as the recording did.
+    #[default]
+    Recorded,
+    /// Emit all events immediately.
+    Instant,
+    /// Recorded delays multiplied by a factor (e.g. `0.1` for ten times faster).
+    Scaled(f64),
+}
+
+/// Serves recorded responses from a cassette.
+///
+/// Each recorded interaction is used once, in order; when every match for a
+/// request has been used, the last one is served again. A request with no
+/// match fails with a provider error naming its hash.
+pub struct ReplayProvider {
+    name: String,
+    model: String,
+    cassette: Cassette,
+    strictness: MatchStrictness,
+    timing: ReplayTiming,
+    used: Mutex<Vec<bool>>,
+}
+
+impl ReplayProvider {
+    pub fn new(cassette: Cassette) -> Self {
+        let first = cassette.interactions.first().map(|i| &i.request);
+        Self {
+            name: first.map_or_else(|| "replay".to_string(), |r| r.provider.clone()),
+            model: first.map_or_else(String::new, |r| r.model.clone()),
+            used: Mutex::new(vec![false; cassette.interactions.len()]),
+            cassette,
+            strictness: MatchStrictness::default(),
+            timing: ReplayTiming::default(),
+        }
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> error::Result<Self> {
+        Ok(Self::new(Cassette::load(path)?))
+    }
+
+    pub fn strictness(mut self, strictness: MatchStrictness) -> Self {
+        self.strictness = strictness;
+        self
+    }
+
+    pub fn timing(mut self, timing: ReplayTiming) -> Self {
+        self.timing = timing;
+        self
+    }
+
+    /// Interactions not served yet.
+    pub fn remaining(&self) -> usize {
+        self.used
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .filter(|used| !**used)
+            .count()
+    }
+
+    fn lookup(&self, request: &RecordedRequest) -> error::Result<&RecordedResponse> {
+        let hash = request.hash(self.strictness);
+        let matches: Vec<usize> = self
+            .cassette
+            .interactions
+            .iter()
+            .enumerate()
+            .filter(|(_, i)| {
+                i.request.kind == request.kind
+                    && (hash.is_none() || i.request.hash(self.strictness) == hash)
+            })
+            .map(|(index, _)| index)
+            .collect();
+
+        let mut used = self.used.lock().unwrap_or_else(|e| e.into_inner());
+        let index = match matches.iter().copied().find(|&i| !used[i]) {
+            Some(index) => index,
+            None if hash.is_some() => *matches.last().ok_or_else(|| {
+                GaussError::provider(
+                    &self.name,
+                    format!(
+                        "No recorded {:?} interaction matches request {} ({:?})",
+                        request.kind,
+                        hash.as_deref().unwrap_or_default(),
+                        self.strictness
+                    ),
+                )
+            })?,
+            None => {
+                return Err(GaussError::provider(
+                    &self.name,
+                    format!("Cassette has no more {:?} interactions", request.kind),
+                ));
+            }
+        };
+        used[index] = true;
+        Ok(&self.cassette.interactions[index].response)
+    }
+
+    fn delay(&self, millis: u64) -> Option<Duration> {
+        let millis = match self.timing {
+            ReplayTiming::Recorded => millis as f64,
+            ReplayTiming::Instant => return None,
+            ReplayTiming::Scaled(factor) => millis as f64 * factor.max(0.0),
+        };
+        (millis >= 1.0).then(|| Duration::from_millis(millis as u64))
+    }
+}
+
+#[async_trait]
+impl Provider for ReplayProvider {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        match &self.cassette.capabilities {
+            Some(capabilities) => capabilities.clone(),
+            None => ProviderCapabilities {
+                streaming: true,
+                tool_use: true,
+                ..Default::default()
+            },
+        }
+    }
+
+    async fn generate(
+        &self,
+        messages: &[Message],
+        tools: &[Tool],
+        options: &GenerateOptions,
+    ) -> error::Result<GenerateResult> {
+        let request = RecordedRequest::new(RequestKind::Generate, self, messages, tools, options);
+        match self.lookup(&request)? {
+            RecordedResponse::Generate { result } => Ok((**result).clone()),
+            RecordedResponse::Error { error } => Err(error.to_error(&self.name)),
+            RecordedResponse::Stream { .. } => Err(GaussError::provider(
+                &self.name,
+                "Recorded response is a stream, expected a generate result",
+            )),
+        }
+    }
+
+    async fn stream(
+        &self,
+        messages: &[Message],
+        tools: &[Tool],
+        options: &GenerateOptions,
+    ) -> error::Result<BoxStream> {
+        let request = RecordedRequest::new(RequestKind::Stream, self, messages, tools, options);
+        let (events, error) = match self.lookup(&request)? {
+            RecordedResponse::Stream { events, error } => (events.clone(), error.clone()),
+            RecordedResponse::Error { error } => return Err(error.to_error(&self.name)),
+            RecordedResponse::Generate { .. } => {
+                return Err(GaussError::provider(
+                    &self.name,
+                    "Recorded response is a generate result, expected a stream",
+                ));
+            }
+        };
+
+        let delays: Vec<Option<Duration>> = events
+            .iter()
+            .scan(0, |previous, event| {
+                let gap = event.offset_ms.saturating_sub(*previous);
+                *previous = event.offset_ms;
+                Some(self.delay(gap))
+            })
+            .collect();
+        let name = self.name.clone();
+        let stream = async_stream::stream! {
+            for (event, delay) in events.into_iter().zip(delays) {
+                if let Some(delay) = delay {
+                    sleep(delay).await;
+                }
+                yield Ok(event.event);
+            }
+            if let Some(error) = error {
+                yield Err(error.to_error(&name));
+            }
+        };
+        Ok(Box::new(Box::pin(stream)))
+    }
+}
@@ -0,0 +1,31 @@
+//! A monotonic clock for the rate limiter, router and resilience wrappers.
+
+/// Monotonic milliseconds; follows tokio's clock on native so paused-time
+/// tests are deterministic.
+pub(crate) struct Clock {
+    #[cfg(all(feature = "native", not(target_arch = "wasm32")))]
+    start: tokio::time::Instant,
+}
+
+impl Clock {
+    pub(crate) fn new() -> Self {
+        Self {
+            #[cfg(all(feature = "native", not(target_arch = "wasm32")))]
+            start: tokio::time::Instant::now(),
+        }
+    }
+
+    pub(crate) fn now_ms(&self) -> u64 {
+        #[cfg(all(feature = "native", not(target_arch = "wasm32")))]
+        {
+            self.start.elapsed().as_millis() as u64
+        }
+        #[cfg(not(all(feature = "native", not(target_arch = "wasm32"))))]
+        {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64
+        }
+    }
+}
@@ -0,0 +1,531 @@
+//! Ensembles: query several providers and combine their answers.
+//!
+//! [`EnsembleProvider`] sends every request to all of its providers
+//! concurrently and hands the successful responses to an [`Aggregator`],
+//! which picks the answer to return. [`MajorityVote`] compares structured
+//! output (or tool calls, or normalized text); [`JudgeAggregator`] asks a
+//! judge model to pick the best answer.
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//! use gauss_core::provider::ProviderConfig;
+//! use gauss_core::provider::anthropic::AnthropicProvider;
+//! use gauss_core::provider::ensemble::{EnsembleProvider, JudgeAggregator};
+//! use gauss_core::provider::openai::OpenAiProvider;
+//!
+//! let judge = Arc::new(OpenAiProvider::new("gpt-4o", ProviderConfig::new("sk-...")));
+//! let ensemble = EnsembleProvider::new(vec![
+//!     Arc::new(OpenAiProvider::new("gpt-4o-mini", ProviderConfig::new("sk-..."))),
+//!     Arc::new(AnthropicProvider::new("claude-3-5-haiku", ProviderConfig::new("sk-ant-..."))),
+//! ])
+//! .with_aggregator(Arc::new(JudgeAggregator::new(judge)));
+//! ```
+
+use async_trait::async_trait;
+use serde_json::{Value, json};
+use tracing::warn;
+
+use crate::cost::ModelUsage;
+use crate::error::{self, GaussError};
+use crate::message::{Content, Message, Usage};
+use crate::provider::router::{RouteRequest, RoutingDecision};
+use crate::provider::{BoxStream, GenerateOptions, GenerateResult, Provider, ProviderCapabilities};
+use crate::streaming::StreamEvent;
+use crate::tool::Tool;
+
+/// One provider's successful answer.
+#[derive(Debug, Clone)]
+pub struct EnsembleResponse {
+    /// Index of the provider in the ensemble.
+    pub index: usize,
+    pub provider: String,
+    pub model: String,
+    pub result: GenerateResult,
+}
+
+/// What an [`Aggregator`] decided.
+#[derive(Debug, Clone, Default)]
+pub struct Aggregation {
+    /// Index into the responses passed to the aggregator.
+    pub chosen: usize,
+    /// Aggregator-specific details, recorded under `provider_metadata.ensemble`.
+    pub metadata: Value,
+    /// Tokens the aggregator itself spent (e.g. a judge call).
+    pub usage: Usage,
+    /// The model that spent `usage`, for pricing it.
+    pub model: Option<String>,
+}
+
+/// Combines the answers of an ensemble into one.
+#[cfg(not(target_arch = "wasm32"))]
+#[async_trait]
+pub trait Aggregator: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// Pick one of `responses` (never empty) for the conversation `messages`.
+    async fn aggregate(
+        &self,
+        messages: &[Message],
+        responses: &[EnsembleResponse],
+    ) -> error::Result<Aggregation>;
+}
+
+#[cfg(target_arch = "wasm32")]
+#[async_trait(?Send)]
+pub trait Aggregator {
+    fn name(&self) -> &str;
+
+    async fn aggregate(
+        &self,
+        messages: &[Message],
+        responses: &[EnsembleResponse],
+    ) -> error::Result<Aggregation>;
+}
+
+// ---------------------------------------------------------------------------
+// MajorityVote
+// ---------------------------------------------------------------------------
+
+/// Picks the most common answer; ties go to the earlier provider.
+///
+/// Answers are compared as JSON when the text parses as JSON (so key order
+/// and whitespace don't matter), as name/arguments pairs when the model
+/// called tools, and otherwise as whitespace-normalized text.
+#[derive(Debug, Clone, Default)]
+pub struct MajorityVote {
+    min_votes: usize,
+}
+
+impl MajorityVote {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fail unless the winning answer has at least this many votes.
+    pub fn with_min_votes(mut self, votes: usize) -> Self {
+        self.min_votes = votes;
+        self
+    }
+
+    /// The value answers are compared by.
+    pub fn ballot(result: &GenerateResult) -> Value {
+        let calls = result.tool_calls();
+        if !calls.is_empty() {
+            return Value::Array(
+                calls
+                    .into_iter()
+                    .map(|(_, name, arguments)| json!([name, arguments]))
+                    .collect(),
+            );
+        }
+        let text = result.text().unwrap_or_default().trim();
+        serde_json::from_str(text).unwrap_or_else(|_| {
+            Value::String(text.split_whitespace().collect::<Vec<_>>().join(" "))
+        })
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Aggregator for MajorityVote {
+    fn name(&self) -> &str {
+        "majority_vote"
+    }
+
+    async fn aggregate(
+        &self,
+        _messages: &[Message],
+        responses: &[EnsembleResponse],
+    ) -> error::Result<Aggregation> {
+        // (ballot, first response index, votes)
+        let mut tally: Vec<(Value, usize, usize)> = Vec::new();
+        for (i, response) in responses.iter().enumerate() {
+            let ballot = Self::ballot(&response.result);
+            match tally.iter_mut().find(|(b, _, _)| *b == ballot) {
+                Some((_, _, votes)) => *votes += 1,
+                None => tally.push((ballot, i, 1)),
+            }
+        }
+        // max_by_key keeps the last maximum, so scan in reverse to favor earlier answers.
+        let (_, chosen, votes) = tally
+            .iter()
+            .rev()
+            .max_by_key(|(_, _, votes)| *votes)
+            .cloned()
+            .ok_or_else(|| GaussError::internal("No responses to vote on"))?;
+        if votes < self.min_votes {
+            return Err(GaussError::internal(format!(
+                "No answer reached {} votes (best had {votes} of {})",
+                self.min_votes,
+                responses.len()
+            )));
+        }
+        Ok(Aggregation {
+            chosen,
+            metadata: json!({
+                "votes": votes,
+                "distinct_answers": tally.len(),
+            }),
+            usage: Usage::default(),
+            model: None,
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// JudgeAggregator
+// ---------------------------------------------------------------------------
+
+/// Asks a judge model which answer is best.
+pub struct JudgeAggregator {
+    judge: crate::Shared<dyn Provider>,
+    criteria: Option<String>,
+    options: GenerateOptions,
+}
+
+impl JudgeAggregator {
+    pub fn new(judge: crate::Shared<dyn Provider>) -> Self {
+        Self {
+            judge,
+            criteria: None,
+            options: GenerateOptions {
+                temperature: Some(0.0),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// What "best" means (default: correct, complete and concise).
+    pub fn with_criteria(mut self, criteria: impl Into<String>) -> Self {
+        self.criteria = Some(criteria.into());
+        self
+    }
+
+    /// Generation options for the judge call (default: temperature 0).
+    pub fn with_options(mut self, options: GenerateOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// JSON schema the judge must answer with.
+    pub fn output_schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "best": { "type": "integer", "minimum": 1 },
+                "reasoning": { "type": "string" }
+            },
+            "required": ["best", "reasoning"]
+        })
+    }
+
+    fn prompt(messages: &[Message], responses: &[EnsembleResponse]) -> String {
+        let mut prompt = String::from("CONVERSATION:\n");
+        for message in messages {
+            if let Some(text) = message.text() {
+                prompt.push_str(&format!("[{:?}] {text}\n", message.role));
+            }
+        }
+        for (i, response) in responses.iter().enumerate() {
+            let answer = match response.result.tool_calls().as_slice() {
+                [] => response.result.text().unwrap_or_default().to_string(),
+                calls => calls
+                    .iter()
+                    .map(|(_, name, arguments)| format!("call {name}({arguments})"))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            };
+            prompt.push_str(&format!("\nANSWER {}:\n{answer}\n", i + 1));
+        }
+        prompt
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Aggregator for JudgeAggregator {
+    fn name(&self) -> &str {
+        "judge"
+    }
+
+    async fn aggregate(
+        &self,
+        messages: &[Message],
+        responses: &[EnsembleResponse],
+    ) -> error::Result<Aggregation> {
+        let criteria = self
+            .criteria
+            .as_deref()
+            .unwrap_or("Prefer the answer that is correct, complete and concise.");
+        let system = format!(
+            "You compare candidate answers to the last message of a conversation and pick the \
+             best one. {criteria} Reply with JSON: the number of the best answer and your reasoning."
+        );
+        let mut options = self.options.clone();
+        options.output_schema = Some(Self::output_schema());
+        let judge_messages = [
+            Message::system(system),
+            Message::user(Self::prompt(messages, responses)),
+        ];
+        let result = self.judge.generate(&judge_messages, &[], &options).await?;
+        let text = result.text().unwrap_or_default();
+        let verdict = crate::eval::parse_judge_json(text)
+            .ok_or_else(|| GaussError::internal(format!("Judge returned invalid JSON: {text}")))?;
+        let best = verdict["best"]
+            .as_u64()
+            .filter(|&n| n >= 1 && n as usize <= responses.len())
+            .ok_or_else(|| {
+                GaussError::internal(format!("Judge picked no valid answer: {verdict}"))
+            })?;
+        Ok(Aggregation {
+            chosen: best as usize - 1,
+            metadata: json!({
+                "judge": self.judge.model(),
+                "reasoning": verdict["reasoning"],
+            }),
+            model: Some(
+                result
+                    .routing
+                    .map_or_else(|| self.judge.model().to_string(), |routing| routing.model),
+            ),
+            usage: result.usage,
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// EnsembleProvider
+// ---------------------------------------------------------------------------
+
+/// Queries every provider concurrently and returns the aggregated answer.
+///
+/// The returned usage is the sum over all providers and the aggregator, broken
+/// down per model under `provider_metadata.usage_by_model` (see
+/// [`ModelUsage`]) so each is priced at its own rate.
+/// `provider_metadata.ensemble` records which answer was chosen, and `routing`
+/// names the chosen provider and model.
+pub struct EnsembleProvider {
+    providers: Vec<crate::Shared<dyn Provider>>,
+    aggregator: crate::Shared<dyn Aggregator>,
+    min_responses: usize,
+}
+
+impl EnsembleProvider {
+    pub fn new(providers: Vec<crate::Shared<dyn Provider>>) -> Self {
+        Self {
+            providers,
+            aggregator: crate::Shared::new(MajorityVote::new()),
+            min_responses: 1,
+        }
+    }
+
+    pub fn with_aggregator(mut self, aggregator: crate::Shared<dyn Aggregator>) -> Self {
+        self.aggregator = aggregator;
+        self
+    }
+
+    /// Fail unless at least this many providers answer (default 1).
+    pub fn with_min_responses(mut self, n: usize) -> Self {
+        self.min_responses = n.max(1);
+        self
+    }
+}
+
+/// What every one of `providers` supports.
+pub(crate) fn common_capabilities(
+    providers: &[crate::Shared<dyn Provider>],
+) -> ProviderCapabilities {
+    let mut caps = providers.iter().map(|p| p.capabilities());
+    let Some(first) = caps.next() else {
+        return ProviderCapabilities::default();
+    };
+    caps.fold(first, |a, b| ProviderCapabilities {
+        streaming: a.streaming && b.streaming,
+        tool_use: a.tool_use && b.tool_use,
+        vision: a.vision && b.vision,
+        audio: a.audio && b.audio,
+        extended_thinking: a.extended_thinking && b.extended_thinking,
+        citations: a.citations && b.citations,
+        cache_control: a.cache_control && b.cache_control,
+        structured_output: a.structured_output && b.structured_output,
+        reasoning_effort: a.reasoning_effort && b.reasoning_effort,
+        image_generation: a.image_generation && b.image_generation,
+        grounding: a.grounding && b.grounding,
+        code_execution: a.code_execution && b.code_execution,
+        web_search: a.web_search && b.web_search,
+    })
+}
+
+fn add_usage(total: &mut Usage, usage: &Usage) {
+    let add = |a: Option<u64>, b: Option<u64>| match (a, b) {
+        (None, None) => None,
+        (a, b) => Some(a.unwrap_or(0) + b.unwrap_or(0)),
+    };
+    total.input_tokens += usage.input_tokens;
+    total.output_tokens += usage.output_tokens;
+    total.reasoning_tokens = add(total.reasoning_tokens, usage.reasoning_tokens);
+    total.cache_read_tokens = add(total.cache_read_tokens, usage.cache_read_tokens);
+    total.cache_creation_tokens = add(total.cache_creation_tokens, usage.cache_creation_tokens);
+}
+
+/// Replays a finished result as stream events.
+fn result_events(result: GenerateResult) -> Vec<StreamEvent> {
+    let mut events: Vec<StreamEvent> = result
+        .routing
+        .map(StreamEvent::Routing)
+        .into_iter()
+        .collect();
+    let mut index = 0;
+    for content in result.message.content {
+        match content {
+            Content::Text { text } => events.push(StreamEvent::TextDelta(text)),
+            Content::Reasoning { text, .. } => events.push(StreamEvent::ReasoningDelta(text)),
+            Content::ToolCall {
+                id,
+                name,
+                arguments,
+            } => {
+                events.push(StreamEvent::ToolCallDelta {
+                    index,
+                    id: Some(id),
+                    name: Some(name),
+                    arguments_delta: Some(arguments.to_string()),
+                });
+                index += 1;
+            }
+            _ => {}
+        }
+    }
+    events.push(StreamEvent::FinishReason(result.finish_reason));
+    events.push(StreamEvent::ProviderMetadata(result.provider_metadata));
+    events.push(StreamEvent::Usage(result.usage));
+    events.push(StreamEvent::Done);
+    events
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Provider for EnsembleProvider {
+    fn name(&self) -> &str {
+        "ensemble"
+    }
+
+    /// The first provider's model. The model whose answer was chosen is in
+    /// [`GenerateResult::routing`].
+    fn model(&self) -> &str {
+        self.providers
+            .first()
+            .map(|p| p.model())
+            .unwrap_or("unknown")
+    }
+
+    /// What every provider supports, since each one sees the request.
+    fn capabilities(&self) -> ProviderCapabilities {
+        common_capabilities(&self.providers)
+    }
+
+    async fn generate(
+        &self,
+        messages: &[Message],
+        tools: &[Tool],
+        options: &GenerateOptions,
+    ) -> error::Result<GenerateResult> {
+        let results = futures::future::join_all(
+            self.providers
+                .iter()
+                .map(|p| p.generate(messages, tools, options)),
+        )
+        .await;
+
+        let mut responses = Vec::new();
+        let mut errors = Vec::new();
+        let mut usage = Usage::default();
+        let mut usage_by_model = Vec::new();
+        for (index, (provider, result)) in self.providers.iter().zip(results).enumerate() {
+            match result {
+                Ok(result) => {
+                    add_usage(&mut usage, &result.usage);
+                    usage_by_model.push(ModelUsage {
+                        model: result
+                            .routing
+                            .as_ref()
+                            .map_or(provider.model(), |r| r.model.as_str())
+                            .to_string(),
+                        usage: result.usage.clone(),
+                    });
+                    responses.push(EnsembleResponse {
+                        index,
+                        provider: provider.name().to_string(),
+                        model: provider.model().to_string(),
+                        result,
+                    });
+                }
+                Err(e) => {
+                    warn!(provider = provider.name(), error = %e, "Ensemble member failed");
+                    errors.push(e);
+                }
+            }
+        }
+        if responses.len() < self.min_responses {
+            let mut errors = errors.into_iter();
+            return Err(match (responses.len(), errors.next()) {
+                (0, Some(e)) => e,
+                _ => GaussError::internal(format!(
+                    "Ensemble needs {} responses, got {}",
+                    self.min_responses,
+                    responses.len()
+                )),
+            });
+        }
+
+        let aggregation = self.aggregator.aggregate(messages, &responses).await?;
+        let chosen = responses
+            .get(aggregation.chosen)
+            .ok_or_else(|| GaussError::internal("Aggregator chose a missing response"))?;
+        add_usage(&mut usage, &aggregation.usage);
+        let spent = aggregation.usage.input_tokens + aggregation.usage.output_tokens;
+        if aggregation.model.is_some() || spent > 0 {
+            usage_by_model.push(ModelUsage {
+                model: aggregation
+                    .model
+                    .clone()
+                    .unwrap_or_else(|| chosen.model.clone()),
+                usage: aggregation.usage.clone(),
+            });
+        }
+
+        let mut result = chosen.result.clone();
+        result.usage = usage;
+        // A chosen router already knows the exact model.
+        result.routing.get_or_insert_with(|| {
+            let request = RouteRequest::new(messages, tools, options);
+            let provider = self.providers[chosen.index].as_ref();
+            RoutingDecision::picked(chosen.index, provider, "ensemble", &request)
+        });
+        let summary = json!({
+            "aggregator": self.aggregator.name(),
+            "chosen": { "index": chosen.index, "provider": chosen.provider, "model": chosen.model },
+            "responses": responses.len(),
+            "failed": errors.len(),
+            "details": aggregation.metadata,
+        });
+        if !result.provider_metadata.is_object() {
+            result.provider_metadata = json!({});
+        }
+        result.provider_metadata["ensemble"] = summary;
+        result.provider_metadata[ModelUsage::METADATA_KEY] = json!(usage_by_model);
+        Ok(result)
+    }
+
+    /// Aggregation needs complete answers, so the chosen one is replayed as a stream.
+    async fn stream(
+        &self,
+        messages: &[Message],
+        tools: &[Tool],
+        options: &GenerateOptions,
+    ) -> error::Result<BoxStream> {
+        let result = self.generate(messages, tools, options).await?;
+        let events = result_events(result).into_iter().map(Ok);
+        Ok(Box::new(futures::stream::iter(events)))
+    }
+}
@@ -5,6 +5,7 @@ use tracing::debug;
 
 use crate::error::GaussError;
 use crate::message::{Content, Message, Role, Usage};
+use crate::provider::auth::AccessTokenSource;
 use crate::provider::{FinishReason, GenerateOptions, GenerateResult, Provider, ProviderCapabilities, ProviderConfig};
 use crate::streaming::StreamEvent;
 use crate::tool::Tool;
@@ -12,10 +13,21 @@ use crate::tool::Tool;
 const DEFAULT_BASE_URL: &str = "https://generativelanguage.googleapis.com/v1beta";
 
 /// Google Generative AI (Gemini) provider.
+///
+/// Also serves Gemini on Vertex AI via [`vertex`](Self::vertex), which
+/// authenticates with OAuth2 access tokens instead of an API key.
 pub struct GoogleProvider {
     model: String,
     config: ProviderConfig,
     client: reqwest::Client,
+    vertex: Option<VertexLocation>,
+}
+
+/// Vertex AI project/location and credentials.
+struct VertexLocation {
+    project: String,
+    location: String,
+    token_source: crate::Shared<dyn AccessTokenSource>,
 }
 
 impl GoogleProvider {
@@ -26,13 +38,76 @@ impl GoogleProvider {
             model: model.into(),
             config,
             client,
+            vertex: None,
         }
     }
 
+    /// Send requests to Vertex AI in `project` and `location` (e.g.
+    /// `us-central1` or `global`), authenticating with tokens from
+    /// `token_source`, typically a
+    /// [`ServiceAccountTokenSource`](crate::provider::auth::ServiceAccountTokenSource).
+    /// The config's API key is ignored; its `base_url` overrides the regional
+    /// endpoint.
+    pub fn vertex(
+        mut self,
+        project: impl Into<String>,
+        location: impl Into<String>,
+        token_source: crate::Shared<dyn AccessTokenSource>,
+    ) -> Self {
+        self.vertex = Some(VertexLocation {
+            project: project.into(),
+            location: location.into(),
+            token_source,
+        });
+        self
+    }
+
     fn base_url(&self) -> &str {
         self.config.base_url.as_deref().unwrap_or(DEFAULT_BASE_URL)
     }
 
+    /// URL of a model method such as `generateContent`, with extra query
+    /// parameters (`alt=sse`).
+    fn url(&self, model: &str, method: &str, query: Option<&str>) -> String {
+        let Some(ref vertex) = self.vertex else {
+            let query = query.map(|q| format!("{q}&")).unwrap_or_default();
+            return format!(
+                "{}/models/{model}:{method}?{query}key={}",
+                self.base_url(),
+                self.config.api_key
+            );
+        };
+        let base = match self.config.base_url {
+            Some(ref base) => base.trim_end_matches('/').to_string(),
+            None if vertex.location == "global" => {
+                "https://aiplatform.googleapis.com/v1".to_string()
+            }
+            None => format!("https://{}-aiplatform.googleapis.com/v1", vertex.location),
+        };
+        let query = query.map(|q| format!("?{q}")).unwrap_or_default();
+        format!(
+            "{base}/projects/{}/locations/{}/publishers/google/models/{model}:{method}{query}",
+            vertex.project, vertex.location
+        )
+    }
+
+    async fn authorize(
+        &self,
+        mut req: reqwest::RequestBuilder,
+    ) -> crate::error::Result<reqwest::RequestBuilder> {
+        req = req.header("content-type", "application/json");
+        if let Some(ref vertex) = self.vertex {
+            let token = vertex.token_source.access_token().await?;
+            req = req.header("Authorization", format!("Bearer {token}"));
+        }
+
+        for (k, v) in &self.config.headers {
+            req = req.header(k, v);
+        }
+
+        Ok(crate::telemetry::inject_trace_context(req))
+    }
+
     fn build_request_body(
         &self,
         messages: &[Message],
@@ -71,10 +146,16 @@ impl GoogleProvider {
                                 ..
                             } = c
                             {
+                                // Vertex only accepts a JSON object as the response.
+                                let response = if self.vertex.is_some() && !content.is_object() {
+                                    json!({"content": content})
+                                } else {
+                                    content.clone()
+                                };
                                 Some(json!({
                                     "functionResponse": {
                                         "name": tool_call_id,
-                                        "response": content
+                                        "response": response
                                     }
                                 }))
                             } else {
@@ -85,7 +166,13 @@ impl GoogleProvider {
                     if parts.is_empty() {
                         None
                     } else {
-                        Some(json!({"role": "function", "parts": parts}))
+                        // Vertex has no "function" role; results come from the user.
+                        let role = if self.vertex.is_some() {
+                            "user"
+                        } else {
+                            "function"
+                        };
+                        Some(json!({"role": role, "parts": parts}))
                     }
                 }
             })
@@ -178,6 +265,17 @@ impl GoogleProvider {
             .iter()
             .filter_map(|c| match c {
                 Content::Text { text } => Some(json!({"text": text})),
+                // Vertex reads Cloud Storage objects directly.
+                Content::Image {
+                    url: Some(uri),
+                    base64: None,
+                    media_type,
+                } if self.vertex.is_some() && uri.starts_with("gs://") => Some(json!({
+                    "fileData": {
+                        "mimeType": media_type.as_deref().unwrap_or("image/png"),
+                        "fileUri": uri
+                    }
+                })),
                 Content::Image {
                     base64, media_type, ..
                 } => Some(json!({
@@ -210,10 +308,20 @@ impl GoogleProvider {
     }
 
     fn parse_response(&self, body: &serde_json::Value) -> crate::error::Result<GenerateResult> {
+        // A blocked prompt yields no candidates, only `promptFeedback`.
+        if let Some(reason) = body["promptFeedback"]["blockReason"].as_str()
+            && body["candidates"].as_array().is_none_or(|c| c.is_empty())
+        {
+            return Err(GaussError::Guardrail {
+                reason: format!("Prompt blocked: {reason}"),
+                guardrail: Some(format!("{}_safety", self.name())),
+            });
+        }
+
         let candidate = body["candidates"]
             .as_array()
             .and_then(|c| c.first())
-            .ok_or_else(|| GaussError::provider("google", "No candidates in response"))?;
+            .ok_or_else(|| GaussError::provider(self.name(), "No candidates in response"))?;
 
         let mut content = Vec::new();
 
@@ -265,7 +373,9 @@ impl GoogleProvider {
         let finish_reason = match candidate["finishReason"].as_str() {
             Some("STOP") | None => FinishReason::Stop,
             Some("MAX_TOKENS") => FinishReason::Length,
-            Some("SAFETY") => FinishReason::ContentFilter,
+            Some("SAFETY" | "BLOCKLIST" | "PROHIBITED_CONTENT" | "SPII") => {
+                FinishReason::ContentFilter
+            }
             Some(other) => FinishReason::Other(other.to_string()),
         };
 
@@ -327,6 +437,7 @@ impl GoogleProvider {
             thinking: None,
             citations: vec![],
             grounding_metadata,
+            routing: None,
         })
     }
 }
@@ -335,7 +446,11 @@ impl GoogleProvider {
 #[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
 impl Provider for GoogleProvider {
     fn name(&self) -> &str {
-        "google"
+        if self.vertex.is_some() {
+            "vertex_ai"
+        } else {
+            "google"
+        }
     }
 
     fn model(&self) -> &str {
@@ -343,17 +458,20 @@ impl Provider for GoogleProvider {
     }
 
     fn capabilities(&self) -> ProviderCapabilities {
-        ProviderCapabilities {
-            streaming: true,
-            tool_use: true,
-            vision: true,
-            structured_output: true,
-            grounding: true,
-            code_execution: true,
-            web_search: true,
-            image_generation: true,
-            ..Default::default()
-        }
+        crate::catalog::read().capabilities(
+            &self.model,
+            ProviderCapabilities {
+                streaming: true,
+                tool_use: true,
+                vision: true,
+                structured_output: true,
+                grounding: true,
+                code_execution: true,
+                web_search: true,
+                image_generation: true,
+                ..Default::default()
+            },
+        )
     }
 
     async fn generate_image(
@@ -374,39 +492,29 @@ impl Provider for GoogleProvider {
         }
 
         let messages = vec![crate::message::Message::user(prompt)];
-        let url = format!(
-            "{}/models/{}:generateContent?key={}",
-            self.base_url(),
-            model,
-            self.config.api_key
-        );
+        let url = self.url(model, "generateContent", None);
         let body = self.build_request_body(&messages, &[], &options);
 
-        let mut req = self
-            .client
-            .post(&url)
-            .header("content-type", "application/json");
-
-        for (k, v) in &self.config.headers {
-            req = req.header(k, v);
-        }
-
-        let resp = req
+        let resp = self
+            .authorize(self.client.post(&url))
+            .await?
             .json(&body)
             .send()
             .await
-            .map_err(|e| GaussError::provider("google", format!("Image generation error: {e}")))?;
+            .map_err(|e| {
+                GaussError::provider(self.name(), format!("Image generation error: {e}"))
+            })?;
 
         let status = resp.status();
         let resp_body: serde_json::Value = resp.json().await.map_err(|e| {
-            GaussError::provider("google", format!("Failed to parse image response: {e}"))
+            GaussError::provider(self.name(), format!("Failed to parse image response: {e}"))
         })?;
 
         if !status.is_success() {
             let msg = resp_body["error"]["message"]
                 .as_str()
                 .unwrap_or("Unknown error");
-            return Err(GaussError::provider("google", msg));
+            return Err(GaussError::provider(self.name(), msg));
         }
 
         let result = self.parse_response(&resp_body)?;
@@ -434,39 +542,29 @@ impl Provider for GoogleProvider {
         tools: &[Tool],
         options: &GenerateOptions,
     ) -> crate::error::Result<GenerateResult> {
-        let url = format!(
-            "{}/models/{}:generateContent?key={}",
-            self.base_url(),
-            self.model,
-            self.config.api_key
-        );
+        let url = self.url(&self.model, "generateContent", None);
         let body = self.build_request_body(messages, tools, options);
 
-        debug!(model = %self.model, "Google generate");
-
-        let mut req = self
-            .client
-            .post(&url)
-            .header("content-type", "application/json");
-
-        for (k, v) in &self.config.headers {
-            req = req.header(k, v);
-        }
+        debug!(model = %self.model, provider = self.name(), "Google generate");
 
-        let resp = req
+        let resp = self
+            .authorize(self.client.post(&url))
+            .await?
             .json(&body)
             .send()
             .await
             .map_err(|e| GaussError::Provider {
                 message: e.to_string(),
                 status: e.status().map(|s| s.as_u16()),
-                provider: "google".to_string(),
+                provider: self.name().to_string(),
                 source: Some(Box::new(e)),
             })?;
 
         let status = resp.status();
+        let headers = resp.headers().clone();
+        crate::provider::rate_limit::observe(self.name(), &self.model, &headers);
         let resp_body: serde_json::Value = resp.json().await.map_err(|e| {
-            GaussError::provider("google", format!("Failed to parse response: {e}"))
+            GaussError::provider(self.name(), format!("Failed to parse response: {e}"))
         })?;
 
         if !status.is_success() {
@@ -475,12 +573,16 @@ impl Provider for GoogleProvider {
                 .unwrap_or("Unknown error");
 
             return match status.as_u16() {
-                429 => Err(GaussError::rate_limited("google", msg)),
-                401 | 403 => Err(GaussError::authentication("google", msg)),
+                429 => Err(crate::provider::rate_limit::rate_limited_error(
+                    self.name(),
+                    msg,
+                    &headers,
+                )),
+                401 | 403 => Err(GaussError::authentication(self.name(), msg)),
                 _ => Err(GaussError::Provider {
                     message: msg.to_string(),
                     status: Some(status.as_u16()),
-                    provider: "google".to_string(),
+                    provider: self.name().to_string(),
                     source: None,
                 }),
             };
@@ -495,44 +597,41 @@ impl Provider for GoogleProvider {
         tools: &[Tool],
         options: &GenerateOptions,
     ) -> crate::error::Result<crate::provider::BoxStream> {
-        let url = format!(
-            "{}/models/{}:streamGenerateContent?alt=sse&key={}",
-            self.base_url(),
-            self.model,
-            self.config.api_key
-        );
+        let url = self.url(&self.model, "streamGenerateContent", Some("alt=sse"));
         let body = self.build_request_body(messages, tools, options);
 
-        debug!(model = %self.model, "Google stream");
+        debug!(model = %self.model, provider = self.name(), "Google stream");
 
-        let mut req = self
-            .client
-            .post(&url)
-            .header("content-type", "application/json");
-
-        for (k, v) in &self.config.headers {
-            req = req.header(k, v);
-        }
-
-        let resp = req
+        let resp = self
+            .authorize(self.client.post(&url))
+            .await?
             .json(&body)
             .send()
             .await
             .map_err(|e| GaussError::Provider {
                 message: e.to_string(),
                 status: e.status().map(|s| s.as_u16()),
-                provider: "google".to_string(),
+                provider: self.name().to_string(),
                 source: Some(Box::new(e)),
             })?;
 
+        crate::provider::rate_limit::observe(self.name(), &self.model, resp.headers());
         if !resp.status().is_success() {
             let status = resp.status();
+            let headers = resp.headers().clone();
             let body: serde_json::Value = resp.json().await.unwrap_or(json!({}));
             let msg = body["error"]["message"].as_str().unwrap_or("Unknown error");
+            if status.as_u16() == 429 {
+                return Err(crate::provider::rate_limit::rate_limited_error(
+                    self.name(),
+                    msg,
+                    &headers,
+                ));
+            }
             return Err(GaussError::Provider {
                 message: msg.to_string(),
                 status: Some(status.as_u16()),
-                provider: "google".to_string(),
+                provider: self.name().to_string(),
                 source: None,
             });
         }
@@ -571,7 +670,9 @@ impl Provider for GoogleProvider {
                                     let fr = match reason {
                                         "STOP" => FinishReason::Stop,
                                         "MAX_TOKENS" => FinishReason::Length,
-                                        "SAFETY" => FinishReason::ContentFilter,
+                                        "SAFETY" | "BLOCKLIST" | "PROHIBITED_CONTENT" | "SPII" => {
+                                            FinishReason::ContentFilter
+                                        }
                                         other => FinishReason::Other(other.to_string()),
                                     };
                                     events.push(Ok(StreamEvent::FinishReason(fr)));
@@ -0,0 +1,542 @@
+//! Scriptable mock provider for tests (feature `testing`).
+//!
+//! [`MockProvider`] plays back a script of responses, one per call, and keeps
+//! every request it receives for assertions.
+//!
+//! ```
+//! # async fn example() -> gauss_core::error::Result<()> {
+//! use std::sync::Arc;
+//! use gauss_core::{Agent, Message};
+//! use gauss_core::provider::mock::{MockError, MockProvider};
+//!
+//! let mock = Arc::new(
+//!     MockProvider::new()
+//!         .error(MockError::status(500, "upstream down"))
+//!         .tool_call("get_weather", serde_json::json!({"city": "Paris"}))
+//!         .text("It is sunny in Paris."),
+//! );
+//! let agent = Agent::builder("weather", mock.clone()).build();
+//! # let _ = agent;
+//! assert_eq!(mock.remaining(), 3);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use reqwest::header::HeaderMap;
+
+use crate::error::{self, GaussError};
+use crate::message::{Content, Message, Role, Usage};
+use crate::provider::retry::sleep;
+use crate::provider::{
+    BoxStream, FinishReason, GenerateOptions, GenerateResult, Provider, ProviderCapabilities,
+};
+use crate::streaming::StreamEvent;
+use crate::tool::Tool;
+
+/// An error the mock returns in place of a response.
+#[derive(Debug, Clone)]
+pub enum MockError {
+    /// `GaussError::RateLimited`.
+    RateLimited { retry_after_ms: Option<u64> },
+    /// `GaussError::Provider` with an HTTP status.
+    Status { status: u16, message: String },
+    /// `GaussError::Authentication`.
+    Authentication,
+    /// `GaussError::Timeout`.
+    Timeout { timeout_ms: u64 },
+    /// `GaussError::Stream`.
+    Stream(String),
+}
+
+impl MockError {
+    pub fn rate_limited(retry_after_ms: Option<u64>) -> Self {
+        Self::RateLimited { retry_after_ms }
+    }
+
+    pub fn status(status: u16, message: impl Into<String>) -> Self {
+        Self::Status {
+            status,
+            message: message.into(),
+        }
+    }
+
+    fn to_error(&self, provider: &str) -> GaussError {
+        match self {
+            Self::RateLimited { retry_after_ms } => GaussError::RateLimited {
+                provider: provider.to_string(),
+                message: "Rate limit exceeded".to_string(),
+                retry_after_ms: *retry_after_ms,
+            },
+            Self::Status { status, message } => GaussError::Provider {
+                message: message.clone(),
+                status: Some(*status),
+                provider: provider.to_string(),
+                source: None,
+            },
+            Self::Authentication => GaussError::Authentication {
+                provider: provider.to_string(),
+            },
+            Self::Timeout { timeout_ms } => GaussError::Timeout {
+                timeout_ms: *timeout_ms,
+            },
+            Self::Stream(message) => GaussError::Stream {
+                message: message.clone(),
+                source: None,
+            },
+        }
+    }
+}
+
+/// One scripted response.
+#[derive(Debug, Clone)]
+pub enum MockResponse {
+    /// An assistant text reply.
+    Text(String),
+    /// Tool calls as `(name, arguments)`, finishing with `ToolCalls`.
+    ToolCalls(Vec<(String, serde_json::Value)>),
+    /// A JSON reply, as returned for structured output.
+    Structured(serde_json::Value),
+    /// A failed call.
+    Error(MockError),
+    /// An exact stream event sequence. `generate` folds it into a result.
+    Stream(Vec<StreamEvent>),
+    /// A complete result, returned as is.
+    Result(Box<GenerateResult>),
+}
+
+/// A request the mock received.
+#[derive(Debug, Clone)]
+pub struct MockRequest {
+    /// Whether it came through `stream` rather than `generate`.
+    pub stream: bool,
+    pub messages: Vec<Message>,
+    pub tools: Vec<Tool>,
+    pub options: GenerateOptions,
+}
+
+impl MockRequest {
+    /// Text of the last user message.
+    pub fn last_user_text(&self) -> Option<&str> {
+        self.messages
+            .iter()
+            .rev()
+            .find(|m| m.role == Role::User)
+            .and_then(Message::text)
+    }
+
+    pub fn tool_names(&self) -> Vec<&str> {
+        self.tools.iter().map(|t| t.name.as_str()).collect()
+    }
+}
+
+/// A [`Provider`] that replays a script of responses, one per call.
+///
+/// When the script runs out, calls fail with a provider error unless a
+/// fallback was set with [`MockProvider::repeat`].
+pub struct MockProvider {
+    name: String,
+    model: String,
+    capabilities: ProviderCapabilities,
+    usage: Usage,
+    latency: Duration,
+    chunk_delay: Duration,
+    chunk_size: usize,
+    rate_limit_headers: Option<HeaderMap>,
+    script: Mutex<VecDeque<MockResponse>>,
+    fallback: Option<MockResponse>,
+    requests: Mutex<Vec<MockRequest>>,
+}
+
+impl Default for MockProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockProvider {
+    pub fn new() -> Self {
+        Self {
+            name: "mock".to_string(),
+            model: "mock-model".to_string(),
+            capabilities: ProviderCapabilities {
+                streaming: true,
+                tool_use: true,
+                structured_output: true,
+                ..Default::default()
+            },
+            usage: Usage {
+                input_tokens: 10,
+                output_tokens: 5,
+                ..Default::default()
+            },
+            latency: Duration::ZERO,
+            chunk_delay: Duration::ZERO,
+            chunk_size: 0,
+            rate_limit_headers: None,
+            script: Mutex::new(VecDeque::new()),
+            fallback: None,
+            requests: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = model.into();
+        self
+    }
+
+    pub fn with_capabilities(mut self, capabilities: ProviderCapabilities) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    /// Usage reported with every response.
+    pub fn usage(mut self, usage: Usage) -> Self {
+        self.usage = usage;
+        self
+    }
+
+    /// Delay before each response (and before the first stream event).
+    pub fn latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    /// Delay between stream events.
+    pub fn chunk_delay(mut self, delay: Duration) -> Self {
+        self.chunk_delay = delay;
+        self
+    }
+
+    /// Split streamed text into deltas of this many characters (0 = one delta).
+    pub fn chunk_size(mut self, chars: usize) -> Self {
+        self.chunk_size = chars;
+        self
+    }
+
+    /// Rate-limit headers recorded on every call, as HTTP providers do with
+    /// [`rate_limit::observe`](crate::provider::rate_limit::observe).
+    pub fn rate_limit_headers(mut self, headers: HeaderMap) -> Self {
+        self.rate_limit_headers = Some(headers);
+        self
+    }
+
+    /// Append a response to the script.
+    pub fn respond(self, response: MockResponse) -> Self {
+        self.script
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push_back(response);
+        self
+    }
+
+    pub fn text(self, text: impl Into<String>) -> Self {
+        self.respond(MockResponse::Text(text.into()))
+    }
+
+    pub fn tool_call(self, name: impl Into<String>, arguments: serde_json::Value) -> Self {
+        self.respond(MockResponse::ToolCalls(vec![(name.into(), arguments)]))
+    }
+
+    pub fn tool_calls<I, S>(self, calls: I) -> Self
+    where
+        I: IntoIterator<Item = (S, serde_json::Value)>,
+        S: Into<String>,
+    {
+        let calls = calls
+            .into_iter()
+            .map(|(name, args)| (name.into(), args))
+            .collect();
+        self.respond(MockResponse::ToolCalls(calls))
+    }
+
+    pub fn structured(self, value: serde_json::Value) -> Self {
+        self.respond(MockResponse::Structured(value))
+    }
+
+    pub fn error(self, error: MockError) -> Self {
+        self.respond(MockResponse::Error(error))
+    }
+
+    pub fn stream_events(self, events: Vec<StreamEvent>) -> Self {
+        self.respond(MockResponse::Stream(events))
+    }
+
+    /// Serve `response` for every call after the script runs out.
+    pub fn repeat(mut self, response: MockResponse) -> Self {
+        self.fallback = Some(response);
+        self
+    }
+
+    /// Every request received so far.
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.requests
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    pub fn call_count(&self) -> usize {
+        self.requests
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .len()
+    }
+
+    /// Scripted responses not served yet.
+    pub fn remaining(&self) -> usize {
+        self.script.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    /// Record the request and take the next response.
+    fn next(
+        &self,
+        stream: bool,
+        messages: &[Message],
+        tools: &[Tool],
+        options: &GenerateOptions,
+    ) -> error::Result<MockResponse> {
+        self.requests
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(MockRequest {
+                stream,
+                messages: messages.to_vec(),
+                tools: tools.to_vec(),
+                options: options.clone(),
+            });
+        if let Some(ref headers) = self.rate_limit_headers {
+            crate::provider::rate_limit::observe(&self.name, &self.model, headers);
+        }
+        let next = self
+            .script
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .pop_front();
+        next.or_else(|| self.fallback.clone()).ok_or_else(|| {
+            GaussError::provider(
+                &self.name,
+                format!(
+                    "MockProvider script exhausted after {} calls",
+                    self.call_count() - 1
+                ),
+            )
+        })
+    }
+
+    fn result(&self, message: Message, finish_reason: FinishReason) -> GenerateResult {
+        GenerateResult {
+            message,
+            usage: self.usage.clone(),
+            finish_reason,
+            provider_metadata: serde_json::Value::Null,
+            thinking: None,
+            citations: Vec::new(),
+            grounding_metadata: None,
+            routing: None,
+        }
+    }
+
+    fn text_deltas(&self, text: &str) -> Vec<StreamEvent> {
+        if self.chunk_size == 0 || text.is_empty() {
+            return vec![StreamEvent::TextDelta(text.to_string())];
+        }
+        let chars: Vec<char> = text.chars().collect();
+        chars
+            .chunks(self.chunk_size)
+            .map(|chunk| StreamEvent::TextDelta(chunk.iter().collect()))
+            .collect()
+    }
+
+    /// The stream events for a non-error response.
+    fn events(&self, response: MockResponse) -> Vec<StreamEvent> {
+        let (mut events, finish_reason) = match response {
+            MockResponse::Stream(events) => return events,
+            MockResponse::Text(text) => (self.text_deltas(&text), FinishReason::Stop),
+            MockResponse::Structured(value) => {
+                (self.text_deltas(&value.to_string()), FinishReason::Stop)
+            }
+            MockResponse::ToolCalls(calls) => {
+                let events = calls
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, (name, arguments))| StreamEvent::ToolCallDelta {
+                        index,
+                        id: Some(format!("call_{index}")),
+                        name: Some(name),
+                        arguments_delta: Some(arguments.to_string()),
+                    })
+                    .collect();
+                (events, FinishReason::ToolCalls)
+            }
+            MockResponse::Result(result) => {
+                let mut events = Vec::new();
+                let mut index = 0;
+                for content in result.message.content {
+                    match content {
+                        Content::Text { text } => events.extend(self.text_deltas(&text)),
+                        Content::ToolCall {
+                            id,
+                            name,
+                            arguments,
+                        } => {
+                            events.push(StreamEvent::ToolCallDelta {
+                                index,
+                                id: Some(id),
+                                name: Some(name),
+                                arguments_delta: Some(arguments.to_string()),
+                            });
+                            index += 1;
+                        }
+                        _ => {}
+                    }
+                }
+                events.push(StreamEvent::FinishReason(result.finish_reason));
+                events.push(StreamEvent::Usage(result.usage));
+                events.push(StreamEvent::Done);
+                return events;
+            }
+            // Errors are returned before any stream exists.
+            MockResponse::Error(_) => return Vec::new(),
+        };
+        events.push(StreamEvent::FinishReason(finish_reason));
+        events.push(StreamEvent::Usage(self.usage.clone()));
+        events.push(StreamEvent::Done);
+        events
+    }
+}
+
+/// Fold a stream event sequence into the equivalent `generate` result.
+fn fold_events(events: Vec<StreamEvent>, mut usage: Usage) -> GenerateResult {
+    let mut text = String::new();
+    let mut calls: Vec<(String, String, String)> = Vec::new();
+    let mut finish_reason = FinishReason::Stop;
+    for event in events {
+        match event {
+            StreamEvent::TextDelta(delta) => text.push_str(&delta),
+            StreamEvent::ToolCallDelta {
+                index,
+                id,
+                name,
+                arguments_delta,
+            } => {
+                if calls.len() <= index {
+                    calls.resize(index + 1, Default::default());
+                }
+                let call = &mut calls[index];
+                if let Some(id) = id {
+                    call.0 = id;
+                }
+                if let Some(name) = name {
+                    call.1 = name;
+                }
+                if let Some(delta) = arguments_delta {
+                    call.2.push_str(&delta);
+                }
+            }
+            StreamEvent::FinishReason(reason) => finish_reason = reason,
+            StreamEvent::Usage(u) => usage = u,
+            _ => {}
+        }
+    }
+
+    let mut content = Vec::new();
+    if !text.is_empty() {
+        content.push(Content::Text { text });
+    }
+    content.extend(calls.into_iter().map(|(id, name, args)| Content::ToolCall {
+        id,
+        name,
+        arguments: serde_json::from_str(&args).unwrap_or(serde_json::Value::String(args)),
+    }));
+    GenerateResult {
+        message: Message {
+            role: Role::Assistant,
+            content,
+            name: None,
+        },
+        usage,
+        finish_reason,
+        provider_metadata: serde_json::Value::Null,
+        thinking: None,
+        citations: Vec::new(),
+        grounding_metadata: None,
+        routing: None,
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Provider for MockProvider {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        self.capabilities.clone()
+    }
+
+    async fn generate(
+        &self,
+        messages: &[Message],
+        tools: &[Tool],
+        options: &GenerateOptions,
+    ) -> error::Result<GenerateResult> {
+        let response = self.next(false, messages, tools, options)?;
+        if !self.latency.is_zero() {
+            sleep(self.latency).await;
+        }
+        match response {
+            MockResponse::Error(e) => Err(e.to_error(&self.name)),
+            MockResponse::Text(text) => {
+                Ok(self.result(Message::assistant(text), FinishReason::Stop))
+            }
+            MockResponse::Structured(value) => {
+                Ok(self.result(Message::assistant(value.to_string()), FinishReason::Stop))
+            }
+            MockResponse::Result(result) => Ok(*result),
+            other => Ok(fold_events(self.events(other), self.usage.clone())),
+        }
+    }
+
+    async fn stream(
+        &self,
+        messages: &[Message],
+        tools: &[Tool],
+        options: &GenerateOptions,
+    ) -> error::Result<BoxStream> {
+        let response = self.next(true, messages, tools, options)?;
+        if !self.latency.is_zero() {
+            sleep(self.latency).await;
+        }
+        if let MockResponse::Error(e) = response {
+            return Err(e.to_error(&self.name));
+        }
+
+        let events = self.events(response);
+        let delay = self.chunk_delay;
+        let stream = futures::stream::iter(events.into_iter().enumerate()).then(
+            move |(index, event)| async move {
+                if index > 0 && !delay.is_zero() {
+                    sleep(delay).await;
+                }
+                Ok(event)
+            },
+        );
+        Ok(Box::new(Box::pin(stream)))
+    }
+}
@@ -64,6 +64,10 @@ pub struct GenerateOptions {
     /// Image configuration for Gemini image generation.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub image_config: Option<crate::message::ImageGenerationConfig>,
+    /// OpenAI Responses: continue from this stored response; only messages
+    /// added after it need to be sent.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_response_id: Option<String>,
 }
 
 /// Provider configuration.
@@ -101,18 +105,26 @@ impl ProviderConfig {
 }
 
 /// Result from a non-streaming generation.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GenerateResult {
     pub message: Message,
+    #[serde(default)]
     pub usage: Usage,
     pub finish_reason: FinishReason,
+    #[serde(default)]
     pub provider_metadata: serde_json::Value,
     /// Anthropic extended thinking output (if enabled).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub thinking: Option<String>,
     /// Citations from document-aware responses (Anthropic).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub citations: Vec<crate::message::Citation>,
     /// Grounding metadata from Google Search (Gemini).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub grounding_metadata: Option<crate::message::GroundingMetadata>,
+    /// Which target a [`RouterProvider`](router::RouterProvider) chose, and why.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub routing: Option<router::RoutingDecision>,
 }
 
 impl GenerateResult {
@@ -250,16 +262,30 @@ pub fn build_client(timeout_ms: Option<u64>) -> reqwest::Client {
 }
 
 pub mod anthropic;
+pub mod auth;
+pub mod aws;
+pub mod bedrock;
+pub mod cache;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod cassette;
+pub(crate) mod clock;
 pub mod deepseek;
+pub mod ensemble;
 pub mod fireworks;
 pub mod google;
 pub mod groq;
 pub mod mistral;
+#[cfg(feature = "testing")]
+pub mod mock;
 pub mod ollama;
 pub mod openai;
 pub mod openai_compatible;
+pub mod openai_responses;
 pub mod openrouter;
 pub mod perplexity;
+pub mod rate_limit;
+pub mod rate_limited;
 pub mod retry;
+pub mod router;
 pub mod together;
 pub mod xai;
@@ -1,19 +1,944 @@
-//! Ollama provider — uses OpenAI-compatible API.
+//! Ollama provider — speaks the native API (`/api/chat`, `/api/generate`,
+//! `/api/embed`) and manages local models (`/api/tags`, `/api/pull`,
+//! `/api/show`).
 
-use crate::provider::ProviderConfig;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+
+use crate::catalog::{DEFAULT_CONTEXT_WINDOW, ModelCapabilities, ModelInfo, TokenizerFamily};
+use crate::error::{self, GaussError};
+use crate::message::{Content, Message, Role, Usage};
 use crate::provider::openai::OpenAiProvider;
 use crate::provider::openai_compatible::create_openai_compatible;
+use crate::provider::{
+    BoxStream, FinishReason, GenerateOptions, GenerateResult, Provider, ProviderCapabilities,
+    ProviderConfig, ReasoningEffort, rate_limit,
+};
+use crate::rag::Embedding;
+use crate::streaming::StreamEvent;
+use crate::tool::{Tool, ToolChoice};
 
+const DEFAULT_BASE_URL: &str = "http://localhost:11434";
 const OLLAMA_BASE_URL: &str = "http://localhost:11434/v1";
+const PROVIDER: &str = "ollama";
 
-/// Ollama provider — wraps OpenAI provider with Ollama's local base URL.
-pub struct OllamaProvider;
+/// Ollama provider over the native `/api/chat` endpoint.
+///
+/// `ProviderConfig::base_url` is the server root (`http://host:11434`); a
+/// trailing `/v1` is ignored. Ollama needs no API key — any string works.
+///
+/// ```no_run
+/// use gauss_core::provider::ProviderConfig;
+/// use gauss_core::provider::ollama::OllamaProvider;
+///
+/// let provider = OllamaProvider::new("llama3.3", ProviderConfig::new("ollama"))
+///     .num_ctx(32_768)
+///     .keep_alive("30m");
+/// ```
+pub struct OllamaProvider {
+    model: String,
+    client: OllamaClient,
+    options: Map<String, Value>,
+    keep_alive: Option<String>,
+}
 
 impl OllamaProvider {
-    /// Create an Ollama provider.
+    pub fn new(model: impl Into<String>, config: ProviderConfig) -> Self {
+        Self {
+            model: model.into(),
+            client: OllamaClient::new(config),
+            options: Map::new(),
+            keep_alive: None,
+        }
+    }
+
+    /// Create a provider for Ollama's OpenAI-compatible `/v1` endpoint.
     /// Models: llama3.3, codellama, mistral, phi3, etc.
     /// Ollama does not require an API key — pass any string.
     pub fn create(model: impl Into<String>, config: ProviderConfig) -> OpenAiProvider {
         create_openai_compatible(model, config, OLLAMA_BASE_URL)
     }
+
+    /// Context length to load the model with. Ollama's default is small
+    /// (2–4K tokens) regardless of what the model supports.
+    pub fn num_ctx(self, tokens: u32) -> Self {
+        self.option("num_ctx", json!(tokens))
+    }
+
+    /// Set a model option (`num_gpu`, `repeat_penalty`, `mirostat`, …).
+    /// Per-request [`GenerateOptions`] take precedence over these.
+    pub fn option(mut self, key: impl Into<String>, value: Value) -> Self {
+        self.options.insert(key.into(), value);
+        self
+    }
+
+    /// How long the model stays loaded after a request, as a duration string
+    /// (`"10m"`, `"1h"`). `"0"` unloads immediately, a negative duration keeps
+    /// it loaded indefinitely.
+    pub fn keep_alive(mut self, duration: impl Into<String>) -> Self {
+        self.keep_alive = Some(duration.into());
+        self
+    }
+
+    fn model_options(&self, options: &GenerateOptions) -> Map<String, Value> {
+        let mut map = self.options.clone();
+        if let Some(t) = options.temperature {
+            map.insert("temperature".into(), json!(t));
+        }
+        if let Some(tp) = options.top_p {
+            map.insert("top_p".into(), json!(tp));
+        }
+        if let Some(tk) = options.top_k {
+            map.insert("top_k".into(), json!(tk));
+        }
+        if let Some(mt) = options.max_tokens {
+            map.insert("num_predict".into(), json!(mt));
+        }
+        if let Some(fp) = options.frequency_penalty {
+            map.insert("frequency_penalty".into(), json!(fp));
+        }
+        if let Some(pp) = options.presence_penalty {
+            map.insert("presence_penalty".into(), json!(pp));
+        }
+        if let Some(seed) = options.seed {
+            map.insert("seed".into(), json!(seed));
+        }
+        if let Some(ref stop) = options.stop_sequences {
+            map.insert("stop".into(), json!(stop));
+        }
+        map
+    }
+
+    /// Fields shared by `/api/chat` and `/api/generate`.
+    fn apply_options(&self, body: &mut Value, options: &GenerateOptions) {
+        let model_options = self.model_options(options);
+        if !model_options.is_empty() {
+            body["options"] = Value::Object(model_options);
+        }
+        if let Some(ref keep_alive) = self.keep_alive {
+            body["keep_alive"] = json!(keep_alive);
+        }
+        if let Some(ref schema) = options.output_schema {
+            body["format"] = schema.clone();
+        }
+        // Thinking models take a level for reasoning effort, `true` otherwise.
+        if let Some(ref re) = options.reasoning_effort {
+            body["think"] = json!(match re {
+                ReasoningEffort::Low => "low",
+                ReasoningEffort::Medium => "medium",
+                ReasoningEffort::High => "high",
+            });
+        } else if options.thinking_budget.is_some() {
+            body["think"] = json!(true);
+        }
+    }
+
+    fn build_chat_body(
+        &self,
+        messages: &[Message],
+        tools: &[Tool],
+        options: &GenerateOptions,
+        stream: bool,
+    ) -> error::Result<Value> {
+        let mut body = json!({
+            "model": self.model,
+            "messages": convert_messages(messages)?,
+            "stream": stream,
+        });
+
+        // Ollama has no tool_choice; the model always decides.
+        let tools_disabled = matches!(options.tool_choice, Some(ToolChoice::None));
+        if !tools.is_empty() && !tools_disabled {
+            let tool_defs: Vec<Value> = tools
+                .iter()
+                .map(|t| {
+                    json!({
+                        "type": "function",
+                        "function": {
+                            "name": t.name,
+                            "description": t.description,
+                            "parameters": t.parameters,
+                        }
+                    })
+                })
+                .collect();
+            body["tools"] = json!(tool_defs);
+        }
+
+        self.apply_options(&mut body, options);
+        Ok(body)
+    }
+
+    /// Single-prompt completion over `/api/generate`. `suffix` enables
+    /// fill-in-the-middle on models that support it.
+    pub async fn complete(
+        &self,
+        prompt: &str,
+        suffix: Option<&str>,
+        options: &GenerateOptions,
+    ) -> error::Result<GenerateResult> {
+        let mut body = json!({
+            "model": self.model,
+            "prompt": prompt,
+            "stream": false,
+        });
+        if let Some(suffix) = suffix {
+            body["suffix"] = json!(suffix);
+        }
+        self.apply_options(&mut body, options);
+
+        let response = self
+            .client
+            .post_json(&self.model, "/api/generate", &body)
+            .await?;
+        let mut content = Vec::new();
+        let thinking = non_empty(&response["thinking"]);
+        if let Some(ref text) = thinking {
+            content.push(Content::Reasoning {
+                text: text.clone(),
+                id: None,
+                encrypted_content: None,
+            });
+        }
+        if let Some(text) = non_empty(&response["response"]) {
+            content.push(Content::Text { text });
+        }
+
+        Ok(GenerateResult {
+            message: Message {
+                role: Role::Assistant,
+                content,
+                name: None,
+            },
+            usage: parse_usage(&response),
+            finish_reason: finish_reason(&response, false),
+            provider_metadata: metadata(&response),
+            thinking,
+            citations: Vec::new(),
+            grounding_metadata: None,
+            routing: None,
+        })
+    }
+
+    /// Models available locally (`/api/tags`).
+    pub async fn list_models(&self) -> error::Result<Vec<OllamaModel>> {
+        let resp = self
+            .client
+            .send(
+                &self.model,
+                self.client.http.get(self.client.url("/api/tags")),
+            )
+            .await?;
+        let body: Value = parse_json(resp).await?;
+        serde_json::from_value(body["models"].clone())
+            .map_err(|e| GaussError::provider(PROVIDER, format!("Failed to parse model list: {e}")))
+    }
+
+    /// Download a model (`/api/pull`), reporting progress as layers arrive.
+    /// Returns once Ollama reports `success`.
+    pub async fn pull(
+        &self,
+        model: &str,
+        mut on_progress: impl FnMut(&PullProgress),
+    ) -> error::Result<()> {
+        let req = self
+            .client
+            .transfer_http()
+            .post(self.client.url("/api/pull"))
+            .json(&json!({"model": model, "stream": true}));
+        let resp = self.client.send(&self.model, req).await?;
+
+        let mut lines = Box::pin(ndjson(resp));
+        while let Some(line) = lines.next().await {
+            let line = line?;
+            if let Some(message) = line["error"].as_str() {
+                return Err(GaussError::provider(PROVIDER, message));
+            }
+            let progress: PullProgress = serde_json::from_value(line).map_err(|e| {
+                GaussError::provider(PROVIDER, format!("Failed to parse pull progress: {e}"))
+            })?;
+            on_progress(&progress);
+            if progress.status == "success" {
+                return Ok(());
+            }
+        }
+        Err(GaussError::provider(
+            PROVIDER,
+            format!("Pull of '{model}' ended before completing"),
+        ))
+    }
+
+    /// Model details (`/api/show`): family, context length and capabilities.
+    pub async fn show(&self, model: &str) -> error::Result<OllamaModelInfo> {
+        let body = self
+            .client
+            .post_json(&self.model, "/api/show", &json!({"model": model}))
+            .await?;
+        Ok(OllamaModelInfo::from_response(&body))
+    }
+
+    /// Look this provider's model up with [`show`](Self::show) and record its
+    /// context window and capabilities in the global model catalog, so
+    /// routing, context tracking and [`Provider::capabilities`] see them.
+    /// A configured [`num_ctx`](Self::num_ctx) caps the context window.
+    pub async fn register_model(&self) -> error::Result<ModelInfo> {
+        let shown = self.show(&self.model).await?;
+        let mut info = shown.to_model_info(&self.model);
+        if let Some(num_ctx) = self.options.get("num_ctx").and_then(Value::as_u64) {
+            info.context_window = info.context_window.min(num_ctx as usize);
+        }
+        crate::catalog::global()
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(info.clone());
+        Ok(info)
+    }
+}
+
+/// HTTP plumbing shared by [`OllamaProvider`] and [`OllamaEmbedding`].
+struct OllamaClient {
+    config: ProviderConfig,
+    base_url: String,
+    http: Client,
+}
+
+impl OllamaClient {
+    fn new(config: ProviderConfig) -> Self {
+        let base_url = config
+            .base_url
+            .as_deref()
+            .unwrap_or(DEFAULT_BASE_URL)
+            .trim_end_matches('/');
+        let base_url = base_url.strip_suffix("/v1").unwrap_or(base_url).to_string();
+
+        Self {
+            http: crate::provider::build_client(config.timeout_ms),
+            config,
+            base_url,
+        }
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{path}", self.base_url)
+    }
+
+    /// A client for long transfers such as pulls, which can run far past
+    /// the configured request timeout: only connecting and each read are
+    /// bounded by it.
+    fn transfer_http(&self) -> Client {
+        let builder = Client::builder();
+        #[cfg(not(target_arch = "wasm32"))]
+        let builder = {
+            let timeout =
+                std::time::Duration::from_millis(self.config.timeout_ms.unwrap_or(60_000));
+            builder.connect_timeout(timeout).read_timeout(timeout)
+        };
+        builder.build().expect("Failed to build HTTP client")
+    }
+
+    async fn send(
+        &self,
+        model: &str,
+        mut req: reqwest::RequestBuilder,
+    ) -> error::Result<reqwest::Response> {
+        // Ollama ignores the key, but authenticating proxies in front of it don't.
+        if !self.config.api_key.is_empty() {
+            req = req.header("Authorization", format!("Bearer {}", self.config.api_key));
+        }
+        for (k, v) in &self.config.headers {
+            req = req.header(k, v);
+        }
+
+        let resp = crate::telemetry::inject_trace_context(req)
+            .send()
+            .await
+            .map_err(|e| GaussError::Provider {
+                message: e.to_string(),
+                status: e.status().map(|s| s.as_u16()),
+                provider: PROVIDER.to_string(),
+                source: Some(Box::new(e)),
+            })?;
+
+        rate_limit::observe(PROVIDER, model, resp.headers());
+        let status = resp.status().as_u16();
+        if resp.status().is_success() {
+            return Ok(resp);
+        }
+
+        let headers = resp.headers().clone();
+        let body: Value = resp.json().await.unwrap_or(json!({}));
+        let message = body["error"]
+            .as_str()
+            .or_else(|| body["error"]["message"].as_str())
+            .unwrap_or("Unknown error");
+        Err(match status {
+            429 => rate_limit::rate_limited_error(PROVIDER, message, &headers),
+            401 | 403 => GaussError::Authentication {
+                provider: PROVIDER.to_string(),
+            },
+            _ => GaussError::Provider {
+                message: message.to_string(),
+                status: Some(status),
+                provider: PROVIDER.to_string(),
+                source: None,
+            },
+        })
+    }
+
+    async fn post(
+        &self,
+        model: &str,
+        path: &str,
+        body: &Value,
+    ) -> error::Result<reqwest::Response> {
+        self.send(model, self.http.post(self.url(path)).json(body))
+            .await
+    }
+
+    async fn post_json(&self, model: &str, path: &str, body: &Value) -> error::Result<Value> {
+        parse_json(self.post(model, path, body).await?).await
+    }
+}
+
+async fn parse_json(resp: reqwest::Response) -> error::Result<Value> {
+    resp.json()
+        .await
+        .map_err(|e| GaussError::provider(PROVIDER, format!("Failed to parse response: {e}")))
+}
+
+/// Split a newline-delimited JSON body into values as it arrives.
+fn ndjson(resp: reqwest::Response) -> impl futures::Stream<Item = error::Result<Value>> {
+    let mut chunks = resp.bytes_stream();
+    async_stream::stream! {
+        let mut buffer: Vec<u8> = Vec::new();
+        'read: while let Some(chunk) = chunks.next().await {
+            let chunk = match chunk {
+                Ok(chunk) => chunk,
+                Err(e) => {
+                    yield Err(GaussError::provider(PROVIDER, format!("Stream error: {e}")));
+                    break;
+                }
+            };
+            buffer.extend_from_slice(&chunk);
+            while let Some(newline) = buffer.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = buffer.drain(..=newline).collect();
+                if let Some(value) = parse_line(&line) {
+                    let failed = value.is_err();
+                    yield value;
+                    if failed {
+                        break 'read;
+                    }
+                }
+            }
+        }
+        if let Some(value) = parse_line(&buffer) {
+            yield value;
+        }
+    }
+}
+
+fn parse_line(line: &[u8]) -> Option<error::Result<Value>> {
+    let line = line.trim_ascii();
+    if line.is_empty() {
+        return None;
+    }
+    Some(
+        serde_json::from_slice(line).map_err(|e| GaussError::Stream {
+            message: format!("Invalid NDJSON line: {e}"),
+            source: Some(Box::new(e)),
+        }),
+    )
+}
+
+fn non_empty(value: &Value) -> Option<String> {
+    value.as_str().filter(|s| !s.is_empty()).map(String::from)
+}
+
+/// Ollama only takes inline base64 image data.
+fn image_data(url: &Option<String>, base64: &Option<String>) -> error::Result<String> {
+    if let Some(b64) = base64 {
+        return Ok(b64.clone());
+    }
+    if let Some(url) = url
+        && let Some((_, data)) = url
+            .strip_prefix("data:")
+            .and_then(|rest| rest.split_once(";base64,"))
+    {
+        return Ok(data.to_string());
+    }
+    Err(GaussError::provider(
+        PROVIDER,
+        "Ollama only accepts inline (base64) images, not URLs",
+    ))
+}
+
+fn convert_messages(messages: &[Message]) -> error::Result<Vec<Value>> {
+    let mut result = Vec::new();
+    // Ollama tool results are matched to calls by function name, not id.
+    let mut tool_names: HashMap<&str, &str> = HashMap::new();
+
+    for msg in messages {
+        if msg.role == Role::Tool {
+            for c in &msg.content {
+                if let Content::ToolResult {
+                    tool_call_id,
+                    content,
+                    ..
+                } = c
+                {
+                    let text = match content {
+                        Value::String(s) => s.clone(),
+                        other => other.to_string(),
+                    };
+                    let mut m = json!({"role": "tool", "content": text});
+                    if let Some(name) = tool_names.get(tool_call_id.as_str()) {
+                        m["tool_name"] = json!(name);
+                    }
+                    result.push(m);
+                }
+            }
+            continue;
+        }
+
+        let role = match msg.role {
+            Role::System => "system",
+            Role::Assistant => "assistant",
+            _ => "user",
+        };
+        let mut text = Vec::new();
+        let mut thinking = Vec::new();
+        let mut images = Vec::new();
+        let mut tool_calls = Vec::new();
+        for c in &msg.content {
+            match c {
+                Content::Text { text: t } => text.push(t.as_str()),
+                Content::Reasoning { text: t, .. } if !t.is_empty() => thinking.push(t.as_str()),
+                Content::Image { url, base64, .. } => images.push(image_data(url, base64)?),
+                Content::ToolCall {
+                    id,
+                    name,
+                    arguments,
+                } => {
+                    tool_names.insert(id, name);
+                    tool_calls.push(json!({
+                        "type": "function",
+                        "function": {"name": name, "arguments": arguments},
+                    }));
+                }
+                _ => {}
+            }
+        }
+
+        let mut m = json!({"role": role, "content": text.join("\n")});
+        if !thinking.is_empty() {
+            m["thinking"] = json!(thinking.join("\n"));
+        }
+        if !images.is_empty() {
+            m["images"] = json!(images);
+        }
+        if !tool_calls.is_empty() {
+            m["tool_calls"] = json!(tool_calls);
+        }
+        result.push(m);
+    }
+
+    Ok(result)
+}
+
+/// Ollama's tool calls carry no id on older servers; fall back to the index.
+fn tool_call_id(call: &Value, index: usize) -> String {
+    call["id"]
+        .as_str()
+        .map(String::from)
+        .unwrap_or_else(|| format!("call_{index}"))
+}
+
+fn tool_call_arguments(call: &Value) -> Value {
+    match &call["function"]["arguments"] {
+        Value::String(s) => serde_json::from_str(s).unwrap_or(json!({})),
+        Value::Null => json!({}),
+        other => other.clone(),
+    }
+}
+
+fn parse_usage(body: &Value) -> Usage {
+    Usage {
+        input_tokens: body["prompt_eval_count"].as_u64().unwrap_or(0),
+        output_tokens: body["eval_count"].as_u64().unwrap_or(0),
+        ..Default::default()
+    }
+}
+
+fn finish_reason(body: &Value, has_tool_calls: bool) -> FinishReason {
+    match body["done_reason"].as_str() {
+        Some("stop") | None if has_tool_calls => FinishReason::ToolCalls,
+        Some("stop") | None => FinishReason::Stop,
+        Some("length") => FinishReason::Length,
+        Some(other) => FinishReason::Other(other.to_string()),
+    }
+}
+
+/// Timings are in nanoseconds.
+fn metadata(body: &Value) -> Value {
+    json!({
+        "model": body["model"],
+        "created_at": body["created_at"],
+        "total_duration": body["total_duration"],
+        "load_duration": body["load_duration"],
+        "prompt_eval_duration": body["prompt_eval_duration"],
+        "eval_duration": body["eval_duration"],
+    })
+}
+
+fn parse_chat_response(body: &Value) -> GenerateResult {
+    let message = &body["message"];
+    let mut content = Vec::new();
+
+    let thinking = non_empty(&message["thinking"]);
+    if let Some(ref text) = thinking {
+        content.push(Content::Reasoning {
+            text: text.clone(),
+            id: None,
+            encrypted_content: None,
+        });
+    }
+    if let Some(text) = non_empty(&message["content"]) {
+        content.push(Content::Text { text });
+    }
+    let calls = message["tool_calls"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or(&[]);
+    for (index, call) in calls.iter().enumerate() {
+        content.push(Content::ToolCall {
+            id: tool_call_id(call, index),
+            name: call["function"]["name"].as_str().unwrap_or("").to_string(),
+            arguments: tool_call_arguments(call),
+        });
+    }
+
+    GenerateResult {
+        message: Message {
+            role: Role::Assistant,
+            content,
+            name: None,
+        },
+        usage: parse_usage(body),
+        finish_reason: finish_reason(body, !calls.is_empty()),
+        provider_metadata: metadata(body),
+        thinking,
+        citations: Vec::new(),
+        grounding_metadata: None,
+        routing: None,
+    }
+}
+
+/// Maps `/api/chat` NDJSON chunks onto [`StreamEvent`]s.
+#[derive(Default)]
+struct StreamState {
+    tool_calls: usize,
+    done: bool,
+}
+
+impl StreamState {
+    fn handle(&mut self, chunk: &Value) -> Vec<error::Result<StreamEvent>> {
+        if let Some(message) = chunk["error"].as_str() {
+            self.done = true;
+            return vec![Err(GaussError::provider(PROVIDER, message))];
+        }
+
+        let mut events = Vec::new();
+        let message = &chunk["message"];
+        if let Some(thinking) = non_empty(&message["thinking"]) {
+            events.push(Ok(StreamEvent::ReasoningDelta(thinking)));
+        }
+        if let Some(text) = non_empty(&message["content"]) {
+            events.push(Ok(StreamEvent::TextDelta(text)));
+        }
+        // Tool calls arrive whole, one chunk each.
+        for call in message["tool_calls"].as_array().into_iter().flatten() {
+            let index = self.tool_calls;
+            self.tool_calls += 1;
+            events.push(Ok(StreamEvent::ToolCallDelta {
+                index,
+                id: Some(tool_call_id(call, index)),
+                name: call["function"]["name"].as_str().map(String::from),
+                arguments_delta: Some(tool_call_arguments(call).to_string()),
+            }));
+        }
+
+        if chunk["done"].as_bool() == Some(true) {
+            self.done = true;
+            events.push(Ok(StreamEvent::FinishReason(finish_reason(
+                chunk,
+                self.tool_calls > 0,
+            ))));
+            events.push(Ok(StreamEvent::Usage(parse_usage(chunk))));
+            events.push(Ok(StreamEvent::Done));
+        }
+        events
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Provider for OllamaProvider {
+    fn name(&self) -> &str {
+        PROVIDER
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        crate::catalog::read().capabilities(
+            &self.model,
+            ProviderCapabilities {
+                streaming: true,
+                tool_use: true,
+                vision: true,
+                structured_output: true,
+                ..Default::default()
+            },
+        )
+    }
+
+    async fn generate(
+        &self,
+        messages: &[Message],
+        tools: &[Tool],
+        options: &GenerateOptions,
+    ) -> error::Result<GenerateResult> {
+        let body = self.build_chat_body(messages, tools, options, false)?;
+        let response = self
+            .client
+            .post_json(&self.model, "/api/chat", &body)
+            .await?;
+        Ok(parse_chat_response(&response))
+    }
+
+    async fn stream(
+        &self,
+        messages: &[Message],
+        tools: &[Tool],
+        options: &GenerateOptions,
+    ) -> error::Result<BoxStream> {
+        let body = self.build_chat_body(messages, tools, options, true)?;
+        let resp = self.client.post(&self.model, "/api/chat", &body).await?;
+
+        let mut lines = Box::pin(ndjson(resp));
+        let stream = async_stream::stream! {
+            let mut state = StreamState::default();
+            while let Some(line) = lines.next().await {
+                let line = match line {
+                    Ok(line) => line,
+                    Err(e) => {
+                        yield Err(e);
+                        break;
+                    }
+                };
+                for out in state.handle(&line) {
+                    yield out;
+                }
+                if state.done {
+                    break;
+                }
+            }
+        };
+
+        Ok(Box::new(Box::pin(stream)))
+    }
+}
+
+/// A locally available model, as listed by `/api/tags`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OllamaModel {
+    pub name: String,
+    pub modified_at: Option<String>,
+    /// Size on disk in bytes.
+    pub size: u64,
+    pub digest: String,
+    pub details: OllamaModelDetails,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OllamaModelDetails {
+    pub format: String,
+    pub family: String,
+    pub families: Option<Vec<String>>,
+    pub parameter_size: String,
+    pub quantization_level: String,
+}
+
+/// One progress update from `/api/pull`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PullProgress {
+    /// `"pulling manifest"`, `"pulling <digest>"`, `"verifying sha256 digest"`,
+    /// …, `"success"`.
+    pub status: String,
+    pub digest: Option<String>,
+    pub total: Option<u64>,
+    pub completed: Option<u64>,
+}
+
+impl PullProgress {
+    /// Fraction of the current layer downloaded, when known.
+    pub fn fraction(&self) -> Option<f64> {
+        match (self.completed, self.total) {
+            (Some(completed), Some(total)) if total > 0 => Some(completed as f64 / total as f64),
+            _ => None,
+        }
+    }
+}
+
+/// Model details from `/api/show`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OllamaModelInfo {
+    pub details: OllamaModelDetails,
+    /// `completion`, `tools`, `vision`, `thinking`, `insert`, `embedding`, …
+    pub capabilities: Vec<String>,
+    /// The model's trained context length (`<arch>.context_length`).
+    pub context_length: Option<usize>,
+    /// Modelfile parameters, one per line.
+    pub parameters: Option<String>,
+    pub template: Option<String>,
+    /// Raw GGUF metadata.
+    pub model_info: Value,
+}
+
+impl OllamaModelInfo {
+    fn from_response(body: &Value) -> Self {
+        let model_info = body["model_info"].clone();
+        let context_length = model_info.as_object().and_then(|info| {
+            info.iter()
+                .find(|(key, _)| key.ends_with(".context_length"))
+                .and_then(|(_, value)| value.as_u64())
+                .map(|n| n as usize)
+        });
+
+        Self {
+            details: serde_json::from_value(body["details"].clone()).unwrap_or_default(),
+            capabilities: serde_json::from_value(body["capabilities"].clone()).unwrap_or_default(),
+            context_length,
+            parameters: body["parameters"].as_str().map(String::from),
+            template: body["template"].as_str().map(String::from),
+            model_info,
+        }
+    }
+
+    pub fn has_capability(&self, capability: &str) -> bool {
+        self.capabilities.iter().any(|c| c == capability)
+    }
+
+    /// A catalog entry for this model under `id`.
+    pub fn to_model_info(&self, id: &str) -> ModelInfo {
+        let mut info = ModelInfo::new(id, self.context_length.unwrap_or(DEFAULT_CONTEXT_WINDOW));
+        info.provider = Some(PROVIDER.to_string());
+        info.capabilities = Some(ModelCapabilities {
+            tool_use: Some(self.has_capability("tools")),
+            vision: Some(self.has_capability("vision")),
+            audio: Some(false),
+            // `/api/show` doesn't report it; the provider default applies.
+            structured_output: None,
+            extended_thinking: Some(self.has_capability("thinking")),
+            reasoning_effort: Some(false),
+        });
+        if self.details.family.contains("llama") {
+            info.tokenizer = TokenizerFamily::Llama;
+        }
+        info
+    }
+}
+
+/// Embeddings over Ollama's `/api/embed`.
+///
+/// [`dimensions`](Embedding::dimensions) reports the size of the last vectors
+/// returned; before the first call it is whatever was set with
+/// [`with_dimensions`](Self::with_dimensions), or 0.
+pub struct OllamaEmbedding {
+    model: String,
+    client: OllamaClient,
+    keep_alive: Option<String>,
+    dimensions: AtomicUsize,
+}
+
+impl OllamaEmbedding {
+    pub fn new(model: impl Into<String>, config: ProviderConfig) -> Self {
+        Self {
+            model: model.into(),
+            client: OllamaClient::new(config),
+            keep_alive: None,
+            dimensions: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn with_dimensions(self, dimensions: usize) -> Self {
+        self.dimensions.store(dimensions, Ordering::Relaxed);
+        self
+    }
+
+    /// See [`OllamaProvider::keep_alive`].
+    pub fn keep_alive(mut self, duration: impl Into<String>) -> Self {
+        self.keep_alive = Some(duration.into());
+        self
+    }
+
+    async fn request(&self, input: &[&str]) -> error::Result<Vec<Vec<f32>>> {
+        let mut body = json!({"model": self.model, "input": input});
+        if let Some(ref keep_alive) = self.keep_alive {
+            body["keep_alive"] = json!(keep_alive);
+        }
+
+        let response = self
+            .client
+            .post_json(&self.model, "/api/embed", &body)
+            .await?;
+        let embeddings: Vec<Vec<f32>> = serde_json::from_value(response["embeddings"].clone())
+            .map_err(|e| {
+                GaussError::provider(PROVIDER, format!("Failed to parse embeddings: {e}"))
+            })?;
+        if embeddings.len() != input.len() {
+            return Err(GaussError::provider(
+                PROVIDER,
+                format!(
+                    "Expected {} embeddings, got {}",
+                    input.len(),
+                    embeddings.len()
+                ),
+            ));
+        }
+        if let Some(first) = embeddings.first() {
+            self.dimensions.store(first.len(), Ordering::Relaxed);
+        }
+        Ok(embeddings)
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Embedding for OllamaEmbedding {
+    async fn embed(&self, text: &str) -> error::Result<Vec<f32>> {
+        Ok(self.request(&[text]).await?.remove(0))
+    }
+
+    async fn embed_batch(&self, texts: &[&str]) -> error::Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.request(texts).await
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dimensions.load(Ordering::Relaxed)
+    }
 }
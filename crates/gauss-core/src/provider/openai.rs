@@ -1,10 +1,11 @@
 use async_trait::async_trait;
 use futures::StreamExt;
-use reqwest::Client;
+use reqwest::{Client, RequestBuilder};
 use serde_json::json;
 
 use crate::error::{self, GaussError};
 use crate::message::{Content, Message, Role, Usage};
+use crate::provider::auth::AccessTokenSource;
 use crate::provider::{
     FinishReason, GenerateOptions, GenerateResult, Provider, ProviderCapabilities, ProviderConfig, ReasoningEffort,
 };
@@ -12,12 +13,34 @@ use crate::streaming::StreamEvent;
 use crate::tool::Tool;
 
 const DEFAULT_BASE_URL: &str = "https://api.openai.com/v1";
+const DEFAULT_AZURE_API_VERSION: &str = "2024-10-21";
 
 /// OpenAI Chat Completions provider.
+///
+/// Also serves Azure OpenAI deployments via [`azure`](Self::azure):
+///
+/// ```no_run
+/// use gauss_core::provider::ProviderConfig;
+/// use gauss_core::provider::openai::OpenAiProvider;
+///
+/// let provider = OpenAiProvider::new("gpt-4o", ProviderConfig::new("azure-key"))
+///     .azure("https://my-resource.openai.azure.com", "gpt-4o-prod");
+/// ```
 pub struct OpenAiProvider {
     config: ProviderConfig,
     model: String,
     client: Client,
+    azure: Option<AzureDeployment>,
+    azure_api_version: String,
+    /// Entra ID bearer tokens; `None` authenticates Azure requests with the
+    /// `api-key` header.
+    entra: Option<crate::Shared<dyn AccessTokenSource>>,
+}
+
+/// Where to reach an Azure OpenAI deployment.
+struct AzureDeployment {
+    endpoint: String,
+    deployment: String,
 }
 
 impl OpenAiProvider {
@@ -28,13 +51,80 @@ impl OpenAiProvider {
             config,
             model: model.into(),
             client,
+            azure: None,
+            azure_api_version: DEFAULT_AZURE_API_VERSION.to_string(),
+            entra: None,
         }
     }
 
+    /// Send requests to an Azure OpenAI deployment at `endpoint` (e.g.
+    /// `https://my-resource.openai.azure.com`) instead of the public API.
+    /// `model` should still name the deployed model; it drives capability
+    /// lookup and parameter naming. Authenticates with the config's API key
+    /// unless [`entra`](Self::entra) is set.
+    pub fn azure(mut self, endpoint: impl Into<String>, deployment: impl Into<String>) -> Self {
+        self.azure = Some(AzureDeployment {
+            endpoint: endpoint.into().trim_end_matches('/').to_string(),
+            deployment: deployment.into(),
+        });
+        self
+    }
+
+    /// Azure `api-version` query parameter. May be set before or after
+    /// [`azure`](Self::azure); only Azure requests use it.
+    pub fn api_version(mut self, version: impl Into<String>) -> Self {
+        self.azure_api_version = version.into();
+        self
+    }
+
+    /// Authenticate to Azure with Entra ID bearer tokens (e.g.
+    /// [`EntraTokenSource`](crate::provider::auth::EntraTokenSource)) instead
+    /// of an API key. May be set before or after [`azure`](Self::azure); only
+    /// Azure requests use it.
+    pub fn entra(mut self, token_source: crate::Shared<dyn AccessTokenSource>) -> Self {
+        self.entra = Some(token_source);
+        self
+    }
+
     fn base_url(&self) -> &str {
         self.config.base_url.as_deref().unwrap_or(DEFAULT_BASE_URL)
     }
 
+    /// URL of an API operation such as `chat/completions`.
+    fn url(&self, operation: &str) -> String {
+        match self.azure {
+            Some(ref azure) => format!(
+                "{}/openai/deployments/{}/{operation}?api-version={}",
+                azure.endpoint, azure.deployment, self.azure_api_version
+            ),
+            None => format!("{}/{operation}", self.base_url()),
+        }
+    }
+
+    async fn authorize(&self, mut req: RequestBuilder) -> error::Result<RequestBuilder> {
+        req = match (&self.azure, &self.entra) {
+            (Some(_), Some(source)) => req.header(
+                "Authorization",
+                format!("Bearer {}", source.access_token().await?),
+            ),
+            (Some(_), None) => req.header("api-key", &self.config.api_key),
+            (None, _) => {
+                req = req.header("Authorization", format!("Bearer {}", self.config.api_key));
+                if let Some(ref org) = self.config.organization {
+                    req = req.header("OpenAI-Organization", org);
+                }
+                req
+            }
+        };
+
+        req = req.header("Content-Type", "application/json");
+        for (k, v) in &self.config.headers {
+            req = req.header(k, v);
+        }
+
+        Ok(crate::telemetry::inject_trace_context(req))
+    }
+
     /// Returns true if this model requires `max_completion_tokens` instead of `max_tokens`.
     /// Newer OpenAI models (o-series reasoning, gpt-5.x) use the new parameter name.
     fn uses_max_completion_tokens(&self) -> bool {
@@ -56,6 +146,12 @@ impl OpenAiProvider {
             "model": self.model,
             "messages": self.convert_messages(messages),
         });
+        // Azure routes by deployment; the model is implied by the URL.
+        if self.azure.is_some()
+            && let Some(obj) = body.as_object_mut()
+        {
+            obj.remove("model");
+        }
 
         if stream {
             body["stream"] = json!(true);
@@ -246,11 +342,37 @@ impl OpenAiProvider {
             .collect()
     }
 
+    fn error_from(
+        &self,
+        status: u16,
+        body: &serde_json::Value,
+        headers: &reqwest::header::HeaderMap,
+    ) -> GaussError {
+        let msg = body["error"]["message"].as_str().unwrap_or("Unknown error");
+        match status {
+            429 => crate::provider::rate_limit::rate_limited_error(self.name(), msg, headers),
+            401 => GaussError::Authentication {
+                provider: self.name().to_string(),
+            },
+            // Azure rejects prompts its content filter flags with a 400.
+            400 if body["error"]["code"] == "content_filter" => GaussError::Guardrail {
+                reason: msg.to_string(),
+                guardrail: Some("azure_content_filter".to_string()),
+            },
+            _ => GaussError::Provider {
+                message: msg.to_string(),
+                status: Some(status),
+                provider: self.name().to_string(),
+                source: None,
+            },
+        }
+    }
+
     fn parse_response(&self, body: &serde_json::Value) -> error::Result<GenerateResult> {
         let choice = body["choices"]
             .as_array()
             .and_then(|c| c.first())
-            .ok_or_else(|| GaussError::provider("openai", "No choices in response"))?;
+            .ok_or_else(|| GaussError::provider(self.name(), "No choices in response"))?;
 
         let msg = &choice["message"];
         let mut content = Vec::new();
@@ -281,6 +403,8 @@ impl OpenAiProvider {
         if let Some(reasoning) = msg.get("reasoning_content").and_then(|r| r.as_str()) {
             content.push(Content::Reasoning {
                 text: reasoning.to_string(),
+                id: None,
+                encrypted_content: None,
             });
         }
 
@@ -310,13 +434,22 @@ impl OpenAiProvider {
             },
             usage,
             finish_reason,
-            provider_metadata: body
-                .get("system_fingerprint")
-                .cloned()
-                .unwrap_or(json!(null)),
+            provider_metadata: if self.azure.is_some() {
+                // Azure annotates prompts and completions with content filter verdicts.
+                json!({
+                    "system_fingerprint": body["system_fingerprint"],
+                    "prompt_filter_results": body["prompt_filter_results"],
+                    "content_filter_results": choice["content_filter_results"],
+                })
+            } else {
+                body.get("system_fingerprint")
+                    .cloned()
+                    .unwrap_or(json!(null))
+            },
             thinking: None,
             citations: vec![],
             grounding_metadata: None,
+            routing: None,
         })
     }
 }
@@ -325,7 +458,11 @@ impl OpenAiProvider {
 #[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
 impl Provider for OpenAiProvider {
     fn name(&self) -> &str {
-        "openai"
+        if self.azure.is_some() {
+            "azure_openai"
+        } else {
+            "openai"
+        }
     }
 
     fn model(&self) -> &str {
@@ -333,15 +470,18 @@ impl Provider for OpenAiProvider {
     }
 
     fn capabilities(&self) -> ProviderCapabilities {
-        ProviderCapabilities {
-            streaming: true,
-            tool_use: true,
-            vision: true,
-            structured_output: true,
-            reasoning_effort: true,
-            image_generation: true,
-            ..Default::default()
-        }
+        crate::catalog::read().capabilities(
+            &self.model,
+            ProviderCapabilities {
+                streaming: true,
+                tool_use: true,
+                vision: true,
+                structured_output: true,
+                reasoning_effort: true,
+                image_generation: true,
+                ..Default::default()
+            },
+        )
     }
 
     async fn generate_image(
@@ -349,7 +489,7 @@ impl Provider for OpenAiProvider {
         prompt: &str,
         config: &crate::message::ImageGenerationConfig,
     ) -> error::Result<crate::message::ImageGenerationResult> {
-        let url = format!("{}/images/generations", self.base_url());
+        let url = self.url("images/generations");
 
         let model = config.model.as_deref().unwrap_or("dall-e-3");
         let mut body = json!({
@@ -366,32 +506,26 @@ impl Provider for OpenAiProvider {
             body["style"] = json!(s);
         }
 
-        let mut req = self
-            .client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", self.config.api_key))
-            .header("Content-Type", "application/json");
-
-        if let Some(ref org) = self.config.organization {
-            req = req.header("OpenAI-Organization", org);
-        }
-
-        let resp = req
+        let resp = self
+            .authorize(self.client.post(&url))
+            .await?
             .json(&body)
             .send()
             .await
-            .map_err(|e| GaussError::provider("openai", format!("Image generation error: {e}")))?;
+            .map_err(|e| {
+                GaussError::provider(self.name(), format!("Image generation error: {e}"))
+            })?;
 
         let status = resp.status();
         let resp_body: serde_json::Value = resp.json().await.map_err(|e| {
-            GaussError::provider("openai", format!("Failed to parse image response: {e}"))
+            GaussError::provider(self.name(), format!("Failed to parse image response: {e}"))
         })?;
 
         if !status.is_success() {
             let msg = resp_body["error"]["message"]
                 .as_str()
                 .unwrap_or("Unknown error");
-            return Err(GaussError::provider("openai", msg));
+            return Err(GaussError::provider(self.name(), msg));
         }
 
         let mut images = Vec::new();
@@ -423,62 +557,30 @@ impl Provider for OpenAiProvider {
         options: &GenerateOptions,
     ) -> error::Result<GenerateResult> {
         let body = self.build_request_body(messages, tools, options, false);
-        let url = format!("{}/chat/completions", self.base_url());
-
-        let mut req = self
-            .client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", self.config.api_key))
-            .header("Content-Type", "application/json");
-
-        if let Some(ref org) = self.config.organization {
-            req = req.header("OpenAI-Organization", org);
-        }
-
-        for (k, v) in &self.config.headers {
-            req = req.header(k, v);
-        }
+        let url = self.url("chat/completions");
 
-        let resp = req
+        let resp = self
+            .authorize(self.client.post(&url))
+            .await?
             .json(&body)
             .send()
             .await
             .map_err(|e| GaussError::Provider {
                 message: e.to_string(),
                 status: e.status().map(|s| s.as_u16()),
-                provider: "openai".to_string(),
+                provider: self.name().to_string(),
                 source: Some(Box::new(e)),
             })?;
 
         let status = resp.status();
+        let headers = resp.headers().clone();
+        crate::provider::rate_limit::observe(self.name(), &self.model, &headers);
         let resp_body: serde_json::Value = resp.json().await.map_err(|e| {
-            GaussError::provider("openai", format!("Failed to parse response: {e}"))
+            GaussError::provider(self.name(), format!("Failed to parse response: {e}"))
         })?;
 
         if !status.is_success() {
-            let error_msg = resp_body["error"]["message"]
-                .as_str()
-                .unwrap_or("Unknown error");
-
-            if status.as_u16() == 429 {
-                return Err(GaussError::RateLimited {
-                    provider: "openai".to_string(),
-                    retry_after_ms: None,
-                });
-            }
-
-            if status.as_u16() == 401 {
-                return Err(GaussError::Authentication {
-                    provider: "openai".to_string(),
-                });
-            }
-
-            return Err(GaussError::Provider {
-                message: error_msg.to_string(),
-                status: Some(status.as_u16()),
-                provider: "openai".to_string(),
-                source: None,
-            });
+            return Err(self.error_from(status.as_u16(), &resp_body, &headers));
         }
 
         self.parse_response(&resp_body)
@@ -491,43 +593,27 @@ impl Provider for OpenAiProvider {
         options: &GenerateOptions,
     ) -> error::Result<crate::provider::BoxStream> {
         let body = self.build_request_body(messages, tools, options, true);
-        let url = format!("{}/chat/completions", self.base_url());
+        let url = self.url("chat/completions");
 
-        let mut req = self
-            .client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", self.config.api_key))
-            .header("Content-Type", "application/json");
-
-        if let Some(ref org) = self.config.organization {
-            req = req.header("OpenAI-Organization", org);
-        }
-
-        for (k, v) in &self.config.headers {
-            req = req.header(k, v);
-        }
-
-        let resp = req
+        let resp = self
+            .authorize(self.client.post(&url))
+            .await?
             .json(&body)
             .send()
             .await
             .map_err(|e| GaussError::Provider {
                 message: e.to_string(),
                 status: e.status().map(|s| s.as_u16()),
-                provider: "openai".to_string(),
+                provider: self.name().to_string(),
                 source: Some(Box::new(e)),
             })?;
 
+        crate::provider::rate_limit::observe(self.name(), &self.model, resp.headers());
         if !resp.status().is_success() {
             let status = resp.status();
+            let headers = resp.headers().clone();
             let body: serde_json::Value = resp.json().await.unwrap_or(json!({}));
-            let msg = body["error"]["message"].as_str().unwrap_or("Unknown error");
-            return Err(GaussError::Provider {
-                message: msg.to_string(),
-                status: Some(status.as_u16()),
-                provider: "openai".to_string(),
-                source: None,
-            });
+            return Err(self.error_from(status.as_u16(), &body, &headers));
         }
 
         let byte_stream = resp.bytes_stream();
@@ -0,0 +1,722 @@
+//! OpenAI Responses API provider (`/v1/responses`).
+//!
+//! Stored responses report their id as `provider_metadata.response_id`.
+//! Passing it back as [`GenerateOptions::previous_response_id`] continues
+//! the conversation server-side, so only the messages added since need to
+//! be sent; [`Agent`](crate::agent::Agent) chains its steps this way when
+//! [`chain_responses`](crate::agent::AgentBuilder::chain_responses) is on.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use eventsource_stream::Eventsource;
+use futures::StreamExt;
+use reqwest::{Client, RequestBuilder};
+use serde_json::{Value, json};
+
+use crate::error::{self, GaussError};
+use crate::message::{Citation, Content, Message, Role, Usage};
+use crate::provider::{
+    BoxStream, FinishReason, GenerateOptions, GenerateResult, Provider, ProviderCapabilities,
+    ProviderConfig, ReasoningEffort, rate_limit,
+};
+use crate::streaming::StreamEvent;
+use crate::tool::{Tool, ToolChoice};
+
+const DEFAULT_BASE_URL: &str = "https://api.openai.com/v1";
+const PROVIDER: &str = "openai";
+
+/// OpenAI Responses API provider (`/v1/responses`).
+///
+/// Unlike [`OpenAiProvider`](crate::provider::openai::OpenAiProvider) this
+/// speaks the item-based Responses API, which adds hosted tools (web and
+/// file search), reasoning summaries, server-side conversation state and
+/// background execution.
+///
+/// ```no_run
+/// use gauss_core::provider::ProviderConfig;
+/// use gauss_core::provider::openai_responses::OpenAiResponsesProvider;
+///
+/// let provider = OpenAiResponsesProvider::new("o4-mini", ProviderConfig::new("sk-..."))
+///     .web_search(true)
+///     .encrypted_reasoning(true);
+/// ```
+pub struct OpenAiResponsesProvider {
+    config: ProviderConfig,
+    model: String,
+    client: Client,
+    web_search: bool,
+    file_search: Vec<String>,
+    store: Option<bool>,
+    encrypted_reasoning: bool,
+    background: Option<Duration>,
+}
+
+impl OpenAiResponsesProvider {
+    pub fn new(model: impl Into<String>, config: ProviderConfig) -> Self {
+        let client = crate::provider::build_client(config.timeout_ms);
+
+        Self {
+            config,
+            model: model.into(),
+            client,
+            web_search: false,
+            file_search: Vec::new(),
+            store: None,
+            encrypted_reasoning: false,
+            background: None,
+        }
+    }
+
+    /// Enable the hosted `web_search` tool. `GenerateOptions::grounding`
+    /// enables it for a single request.
+    pub fn web_search(mut self, enabled: bool) -> Self {
+        self.web_search = enabled;
+        self
+    }
+
+    /// Enable the hosted `file_search` tool over the given vector stores.
+    pub fn file_search(mut self, vector_store_ids: Vec<String>) -> Self {
+        self.file_search = vector_store_ids;
+        self
+    }
+
+    /// Whether OpenAI should store responses server-side (API default: true).
+    pub fn store(mut self, store: bool) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Request encrypted reasoning items so reasoning state can be carried
+    /// across turns without server-side storage. Implies `store(false)`.
+    pub fn encrypted_reasoning(mut self, enabled: bool) -> Self {
+        self.encrypted_reasoning = enabled;
+        if enabled {
+            self.store = Some(false);
+        }
+        self
+    }
+
+    /// Run requests in background mode, polling every `poll_interval` until
+    /// the response reaches a terminal status.
+    pub fn background(mut self, poll_interval: Duration) -> Self {
+        self.background = Some(poll_interval);
+        self
+    }
+
+    fn base_url(&self) -> &str {
+        self.config.base_url.as_deref().unwrap_or(DEFAULT_BASE_URL)
+    }
+
+    fn authorize(&self, mut req: RequestBuilder) -> RequestBuilder {
+        req = req
+            .header("Authorization", format!("Bearer {}", self.config.api_key))
+            .header("Content-Type", "application/json");
+
+        if let Some(ref org) = self.config.organization {
+            req = req.header("OpenAI-Organization", org);
+        }
+
+        for (k, v) in &self.config.headers {
+            req = req.header(k, v);
+        }
+
+        crate::telemetry::inject_trace_context(req)
+    }
+
+    async fn send(&self, req: RequestBuilder) -> error::Result<reqwest::Response> {
+        let resp = self
+            .authorize(req)
+            .send()
+            .await
+            .map_err(|e| GaussError::Provider {
+                message: e.to_string(),
+                status: e.status().map(|s| s.as_u16()),
+                provider: PROVIDER.to_string(),
+                source: Some(Box::new(e)),
+            })?;
+
+        rate_limit::observe(PROVIDER, &self.model, resp.headers());
+        let status = resp.status().as_u16();
+        if resp.status().is_success() {
+            return Ok(resp);
+        }
+
+        let headers = resp.headers().clone();
+        let body: Value = resp.json().await.unwrap_or(json!({}));
+        let message = body["error"]["message"].as_str().unwrap_or("Unknown error");
+        Err(match status {
+            429 => rate_limit::rate_limited_error(PROVIDER, message, &headers),
+            401 => GaussError::Authentication {
+                provider: PROVIDER.to_string(),
+            },
+            _ => GaussError::Provider {
+                message: message.to_string(),
+                status: Some(status),
+                provider: PROVIDER.to_string(),
+                source: None,
+            },
+        })
+    }
+
+    async fn fetch(&self, id: &str) -> error::Result<Value> {
+        let url = format!("{}/responses/{id}", self.base_url());
+        self.send(self.client.get(&url))
+            .await?
+            .json()
+            .await
+            .map_err(|e| GaussError::provider(PROVIDER, format!("Failed to parse response: {e}")))
+    }
+
+    /// Fetch a stored response by id, e.g. one started in background mode.
+    /// A response that is still running has no content and a finish reason
+    /// of `Other("in_progress")` or `Other("queued")`.
+    pub async fn retrieve(&self, id: &str) -> error::Result<GenerateResult> {
+        parse_response(&self.fetch(id).await?)
+    }
+
+    fn build_request_body(
+        &self,
+        messages: &[Message],
+        tools: &[Tool],
+        options: &GenerateOptions,
+        stream: bool,
+    ) -> Value {
+        let mut body = json!({
+            "model": self.model,
+            "input": convert_messages(messages),
+        });
+
+        if stream {
+            body["stream"] = json!(true);
+        }
+        if let Some(store) = self.store {
+            body["store"] = json!(store);
+        }
+        if self.encrypted_reasoning {
+            body["include"] = json!(["reasoning.encrypted_content"]);
+        }
+        if let Some(ref id) = options.previous_response_id {
+            body["previous_response_id"] = json!(id);
+        }
+        if self.background.is_some() {
+            body["background"] = json!(true);
+        }
+
+        let mut tool_defs: Vec<Value> = tools
+            .iter()
+            .map(|t| {
+                json!({
+                    "type": "function",
+                    "name": t.name,
+                    "description": t.description,
+                    "parameters": t.parameters,
+                })
+            })
+            .collect();
+        if self.web_search || options.grounding {
+            tool_defs.push(json!({"type": "web_search"}));
+        }
+        if !self.file_search.is_empty() {
+            tool_defs.push(json!({
+                "type": "file_search",
+                "vector_store_ids": self.file_search,
+            }));
+        }
+        if !tool_defs.is_empty() {
+            body["tools"] = json!(tool_defs);
+
+            if let Some(ref tc) = options.tool_choice {
+                body["tool_choice"] = match tc {
+                    ToolChoice::Auto => json!("auto"),
+                    ToolChoice::None => json!("none"),
+                    ToolChoice::Required => json!("required"),
+                    ToolChoice::Specific { name } => json!({"type": "function", "name": name}),
+                };
+            }
+        }
+
+        if let Some(t) = options.temperature {
+            body["temperature"] = json!(t);
+        }
+        if let Some(tp) = options.top_p {
+            body["top_p"] = json!(tp);
+        }
+        if let Some(mt) = options.max_tokens {
+            body["max_output_tokens"] = json!(mt);
+        }
+        if let Some(ref re) = options.reasoning_effort {
+            body["reasoning"] = json!({
+                "effort": match re {
+                    ReasoningEffort::Low => "low",
+                    ReasoningEffort::Medium => "medium",
+                    ReasoningEffort::High => "high",
+                },
+                "summary": "auto",
+            });
+        }
+        if let Some(ref schema) = options.output_schema {
+            body["text"] = json!({
+                "format": {
+                    "type": "json_schema",
+                    "name": "output",
+                    "schema": schema,
+                    "strict": true,
+                }
+            });
+        }
+
+        body
+    }
+}
+
+fn convert_messages(messages: &[Message]) -> Vec<Value> {
+    let mut items = Vec::new();
+
+    for msg in messages {
+        match msg.role {
+            Role::Tool => {
+                for c in &msg.content {
+                    if let Content::ToolResult {
+                        tool_call_id,
+                        content,
+                        ..
+                    } = c
+                    {
+                        let output = match content {
+                            Value::String(s) => s.clone(),
+                            other => other.to_string(),
+                        };
+                        items.push(json!({
+                            "type": "function_call_output",
+                            "call_id": tool_call_id,
+                            "output": output,
+                        }));
+                    }
+                }
+            }
+            Role::Assistant => {
+                for c in &msg.content {
+                    match c {
+                        Content::Text { text } => items.push(json!({
+                            "type": "message",
+                            "role": "assistant",
+                            "content": [{"type": "output_text", "text": text}],
+                        })),
+                        Content::ToolCall {
+                            id,
+                            name,
+                            arguments,
+                        } => items.push(json!({
+                            "type": "function_call",
+                            "call_id": id,
+                            "name": name,
+                            "arguments": arguments.to_string(),
+                        })),
+                        // Reasoning can only be replayed by item id.
+                        Content::Reasoning {
+                            text,
+                            id: Some(id),
+                            encrypted_content,
+                        } => {
+                            let summary: Vec<Value> = if text.is_empty() {
+                                vec![]
+                            } else {
+                                vec![json!({"type": "summary_text", "text": text})]
+                            };
+                            let mut item = json!({
+                                "type": "reasoning",
+                                "id": id,
+                                "summary": summary,
+                            });
+                            if let Some(enc) = encrypted_content {
+                                item["encrypted_content"] = json!(enc);
+                            }
+                            items.push(item);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Role::System | Role::User => {
+                let role = if msg.role == Role::System {
+                    "system"
+                } else {
+                    "user"
+                };
+                let parts: Vec<Value> = msg
+                    .content
+                    .iter()
+                    .filter_map(|c| match c {
+                        Content::Text { text } => Some(json!({"type": "input_text", "text": text})),
+                        Content::Image {
+                            url,
+                            base64,
+                            media_type,
+                        } => {
+                            let image_url = match (url, base64) {
+                                (Some(url), _) => url.clone(),
+                                (None, Some(b64)) => {
+                                    let mt = media_type.as_deref().unwrap_or("image/png");
+                                    format!("data:{mt};base64,{b64}")
+                                }
+                                (None, None) => return None,
+                            };
+                            Some(json!({"type": "input_image", "image_url": image_url}))
+                        }
+                        _ => None,
+                    })
+                    .collect();
+                items.push(json!({"role": role, "content": parts}));
+            }
+        }
+    }
+
+    items
+}
+
+fn parse_usage(usage: &Value) -> Usage {
+    Usage {
+        input_tokens: usage["input_tokens"].as_u64().unwrap_or(0),
+        output_tokens: usage["output_tokens"].as_u64().unwrap_or(0),
+        reasoning_tokens: usage["output_tokens_details"]["reasoning_tokens"].as_u64(),
+        cache_read_tokens: usage["input_tokens_details"]["cached_tokens"].as_u64(),
+        cache_creation_tokens: None,
+    }
+}
+
+fn finish_reason(response: &Value, has_tool_calls: bool) -> FinishReason {
+    match response["status"].as_str() {
+        Some("completed") | None if has_tool_calls => FinishReason::ToolCalls,
+        Some("completed") | None => FinishReason::Stop,
+        Some("incomplete") => match response["incomplete_details"]["reason"].as_str() {
+            Some("max_output_tokens") => FinishReason::Length,
+            Some("content_filter") => FinishReason::ContentFilter,
+            Some(other) => FinishReason::Other(other.to_string()),
+            None => FinishReason::Other("incomplete".to_string()),
+        },
+        Some(other) => FinishReason::Other(other.to_string()),
+    }
+}
+
+fn failure(response: &Value) -> GaussError {
+    let message = response["error"]["message"]
+        .as_str()
+        .unwrap_or("Response failed");
+    GaussError::provider(PROVIDER, message)
+}
+
+fn is_pending(response: &Value) -> bool {
+    matches!(response["status"].as_str(), Some("queued" | "in_progress"))
+}
+
+/// Slice `text` by character offsets, as used by `url_citation` annotations.
+fn char_slice(text: &str, start: Option<u64>, end: Option<u64>) -> String {
+    match (start, end) {
+        (Some(start), Some(end)) if end > start => text
+            .chars()
+            .skip(start as usize)
+            .take((end - start) as usize)
+            .collect(),
+        _ => String::new(),
+    }
+}
+
+fn parse_citation(annotation: &Value, text: &str) -> Option<Citation> {
+    if annotation["type"].as_str() != Some("url_citation") {
+        return None;
+    }
+    let start = annotation["start_index"].as_u64();
+    let end = annotation["end_index"].as_u64();
+    Some(Citation {
+        citation_type: "url_citation".to_string(),
+        cited_text: char_slice(text, start, end),
+        document_title: annotation["title"].as_str().map(String::from),
+        start,
+        end,
+        url: annotation["url"].as_str().map(String::from),
+    })
+}
+
+/// `provider_metadata` for a response; only stored responses can be
+/// continued, so only they report a `response_id`.
+fn metadata(response: &Value) -> Value {
+    let mut meta = json!({"status": response["status"]});
+    if response["store"].as_bool() != Some(false) {
+        meta["response_id"] = response["id"].clone();
+    }
+    meta
+}
+
+fn parse_response(body: &Value) -> error::Result<GenerateResult> {
+    if body["status"].as_str() == Some("failed") {
+        return Err(failure(body));
+    }
+
+    let mut content = Vec::new();
+    let mut citations = Vec::new();
+    let mut summaries = Vec::new();
+    let mut has_tool_calls = false;
+
+    for item in body["output"].as_array().into_iter().flatten() {
+        match item["type"].as_str() {
+            Some("message") => {
+                for part in item["content"].as_array().into_iter().flatten() {
+                    let text = match part["type"].as_str() {
+                        Some("output_text") => part["text"].as_str().unwrap_or(""),
+                        Some("refusal") => part["refusal"].as_str().unwrap_or(""),
+                        _ => continue,
+                    };
+                    citations.extend(
+                        part["annotations"]
+                            .as_array()
+                            .into_iter()
+                            .flatten()
+                            .filter_map(|ann| parse_citation(ann, text)),
+                    );
+                    if !text.is_empty() {
+                        content.push(Content::Text {
+                            text: text.to_string(),
+                        });
+                    }
+                }
+            }
+            Some("function_call") => {
+                has_tool_calls = true;
+                let args = item["arguments"].as_str().unwrap_or("{}");
+                content.push(Content::ToolCall {
+                    id: item["call_id"].as_str().unwrap_or("").to_string(),
+                    name: item["name"].as_str().unwrap_or("").to_string(),
+                    arguments: serde_json::from_str(args).unwrap_or(json!({})),
+                });
+            }
+            Some("reasoning") => {
+                let text = item["summary"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|s| s["text"].as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                if !text.is_empty() {
+                    summaries.push(text.clone());
+                }
+                content.push(Content::Reasoning {
+                    text,
+                    id: item["id"].as_str().map(String::from),
+                    encrypted_content: item["encrypted_content"].as_str().map(String::from),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    Ok(GenerateResult {
+        message: Message {
+            role: Role::Assistant,
+            content,
+            name: None,
+        },
+        usage: parse_usage(&body["usage"]),
+        finish_reason: finish_reason(body, has_tool_calls),
+        provider_metadata: metadata(body),
+        thinking: (!summaries.is_empty()).then(|| summaries.join("\n")),
+        citations,
+        grounding_metadata: None,
+        routing: None,
+    })
+}
+
+/// Maps typed Responses SSE events onto [`StreamEvent`]s.
+#[derive(Default)]
+struct StreamState {
+    /// `output_index` of each function call item → tool call index.
+    tool_calls: HashMap<u64, usize>,
+    /// Text so far per `(output_index, content_index)`, to resolve the spans
+    /// of citation annotations.
+    texts: HashMap<(u64, u64), String>,
+    done: bool,
+}
+
+impl StreamState {
+    fn text(&mut self, event: &Value) -> &mut String {
+        let key = (
+            event["output_index"].as_u64().unwrap_or(0),
+            event["content_index"].as_u64().unwrap_or(0),
+        );
+        self.texts.entry(key).or_default()
+    }
+
+    fn handle(&mut self, event: &Value) -> Vec<error::Result<StreamEvent>> {
+        let delta = || event["delta"].as_str().unwrap_or("").to_string();
+
+        match event["type"].as_str().unwrap_or("") {
+            "response.output_text.delta" => {
+                self.text(event).push_str(&delta());
+                vec![Ok(StreamEvent::TextDelta(delta()))]
+            }
+            "response.output_text.annotation.added" => {
+                let text = self.text(event).as_str();
+                parse_citation(&event["annotation"], text)
+                    .map(|c| Ok(StreamEvent::Citation(c)))
+                    .into_iter()
+                    .collect()
+            }
+            "response.output_item.done" if event["item"]["type"] == "reasoning" => {
+                let item = &event["item"];
+                vec![Ok(StreamEvent::ReasoningItem {
+                    id: item["id"].as_str().map(String::from),
+                    encrypted_content: item["encrypted_content"].as_str().map(String::from),
+                })]
+            }
+            "response.reasoning_summary_text.delta" => {
+                vec![Ok(StreamEvent::ReasoningDelta(delta()))]
+            }
+            "response.output_item.added" if event["item"]["type"] == "function_call" => {
+                let index = self.tool_calls.len();
+                let output_index = event["output_index"].as_u64().unwrap_or(0);
+                self.tool_calls.insert(output_index, index);
+                let item = &event["item"];
+                let arguments = item["arguments"].as_str().filter(|a| !a.is_empty());
+                vec![Ok(StreamEvent::ToolCallDelta {
+                    index,
+                    id: item["call_id"].as_str().map(String::from),
+                    name: item["name"].as_str().map(String::from),
+                    arguments_delta: arguments.map(String::from),
+                })]
+            }
+            "response.function_call_arguments.delta" => {
+                let output_index = event["output_index"].as_u64().unwrap_or(0);
+                match self.tool_calls.get(&output_index) {
+                    Some(&index) => vec![Ok(StreamEvent::ToolCallDelta {
+                        index,
+                        id: None,
+                        name: None,
+                        arguments_delta: Some(delta()),
+                    })],
+                    None => vec![],
+                }
+            }
+            "response.completed" | "response.incomplete" => {
+                self.done = true;
+                let response = &event["response"];
+                vec![
+                    Ok(StreamEvent::FinishReason(finish_reason(
+                        response,
+                        !self.tool_calls.is_empty(),
+                    ))),
+                    Ok(StreamEvent::Usage(parse_usage(&response["usage"]))),
+                    Ok(StreamEvent::ProviderMetadata(metadata(response))),
+                    Ok(StreamEvent::Done),
+                ]
+            }
+            "response.failed" => {
+                self.done = true;
+                vec![Err(failure(&event["response"]))]
+            }
+            "error" => {
+                self.done = true;
+                let message = event["message"].as_str().unwrap_or("Stream error");
+                vec![Err(GaussError::provider(PROVIDER, message))]
+            }
+            _ => vec![],
+        }
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Provider for OpenAiResponsesProvider {
+    fn name(&self) -> &str {
+        PROVIDER
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        crate::catalog::read().capabilities(
+            &self.model,
+            ProviderCapabilities {
+                streaming: true,
+                tool_use: true,
+                vision: true,
+                citations: true,
+                structured_output: true,
+                reasoning_effort: true,
+                web_search: true,
+                ..Default::default()
+            },
+        )
+    }
+
+    async fn generate(
+        &self,
+        messages: &[Message],
+        tools: &[Tool],
+        options: &GenerateOptions,
+    ) -> error::Result<GenerateResult> {
+        let body = self.build_request_body(messages, tools, options, false);
+        let url = format!("{}/responses", self.base_url());
+
+        let mut response: Value = self
+            .send(self.client.post(&url).json(&body))
+            .await?
+            .json()
+            .await
+            .map_err(|e| {
+                GaussError::provider(PROVIDER, format!("Failed to parse response: {e}"))
+            })?;
+
+        if let Some(interval) = self.background {
+            while is_pending(&response) {
+                let id = response["id"]
+                    .as_str()
+                    .ok_or_else(|| GaussError::provider(PROVIDER, "Background response has no id"))?
+                    .to_string();
+                crate::provider::retry::sleep(interval).await;
+                response = self.fetch(&id).await?;
+            }
+        }
+
+        parse_response(&response)
+    }
+
+    async fn stream(
+        &self,
+        messages: &[Message],
+        tools: &[Tool],
+        options: &GenerateOptions,
+    ) -> error::Result<BoxStream> {
+        let body = self.build_request_body(messages, tools, options, true);
+        let url = format!("{}/responses", self.base_url());
+        let resp = self.send(self.client.post(&url).json(&body)).await?;
+
+        let mut events = resp.bytes_stream().eventsource();
+        let stream = async_stream::stream! {
+            let mut state = StreamState::default();
+            while let Some(event) = events.next().await {
+                let event = match event {
+                    Ok(event) => event,
+                    Err(e) => {
+                        yield Err(GaussError::provider(PROVIDER, format!("Stream error: {e}")));
+                        break;
+                    }
+                };
+                let Ok(data) = serde_json::from_str::<Value>(&event.data) else {
+                    continue;
+                };
+                for out in state.handle(&data) {
+                    yield out;
+                }
+                if state.done {
+                    break;
+                }
+            }
+        };
+
+        Ok(Box::new(Box::pin(stream)))
+    }
+}
@@ -0,0 +1,319 @@
+//! Rate-limit header parsing.
+//!
+//! Providers report their limits in response headers: `retry-after` and
+//! `retry-after-ms` on throttled requests, plus `x-ratelimit-*` (OpenAI and
+//! compatible APIs) or `anthropic-ratelimit-*` on every response. The
+//! providers in this crate parse them into [`RateLimitInfo`], attach the
+//! retry delay to [`GaussError::RateLimited`] and publish the latest snapshot
+//! per provider/model through [`observe`], where [`latest`] can read it back.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use reqwest::header::HeaderMap;
+use serde::{Deserialize, Serialize};
+
+use crate::error::GaussError;
+
+/// Limit, remaining budget and time to reset for one quota.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimitWindow {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remaining: Option<u64>,
+    /// Milliseconds until the quota resets, relative to when the response arrived.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reset_ms: Option<u64>,
+}
+
+impl RateLimitWindow {
+    pub fn is_empty(&self) -> bool {
+        self.limit.is_none() && self.remaining.is_none() && self.reset_ms.is_none()
+    }
+
+    /// True when the provider reported no remaining budget.
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining == Some(0)
+    }
+}
+
+/// Rate-limit state reported by a provider response.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimitInfo {
+    /// Explicit `retry-after-ms` / `retry-after` delay.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_after_ms: Option<u64>,
+    pub requests: RateLimitWindow,
+    pub tokens: RateLimitWindow,
+    pub input_tokens: RateLimitWindow,
+    pub output_tokens: RateLimitWindow,
+}
+
+impl RateLimitInfo {
+    /// Parse every supported header family at the current time.
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        Self::from_headers_at(headers, SystemTime::now())
+    }
+
+    /// Parse relative to `now`, which absolute timestamps are measured against.
+    pub fn from_headers_at(headers: &HeaderMap, now: SystemTime) -> Self {
+        let get = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+        let number = |name: &str| get(name).and_then(|v| v.trim().parse::<u64>().ok());
+
+        let retry_after_ms = get("retry-after-ms")
+            .and_then(|v| v.trim().parse::<f64>().ok())
+            .filter(|ms| *ms >= 0.0)
+            .map(|ms| ms.ceil() as u64)
+            .or_else(|| get("retry-after").and_then(|v| parse_retry_after(v, now)));
+
+        // OpenAI: x-ratelimit-{limit,remaining,reset}-{requests,tokens}, resets as "6m0s".
+        let openai = |kind: &str| RateLimitWindow {
+            limit: number(&format!("x-ratelimit-limit-{kind}")),
+            remaining: number(&format!("x-ratelimit-remaining-{kind}")),
+            reset_ms: get(&format!("x-ratelimit-reset-{kind}")).and_then(parse_duration),
+        };
+        // Anthropic: anthropic-ratelimit-{kind}-{limit,remaining,reset}, resets as RFC 3339.
+        let anthropic = |kind: &str| RateLimitWindow {
+            limit: number(&format!("anthropic-ratelimit-{kind}-limit")),
+            remaining: number(&format!("anthropic-ratelimit-{kind}-remaining")),
+            reset_ms: get(&format!("anthropic-ratelimit-{kind}-reset"))
+                .and_then(|v| parse_rfc3339(v.trim()))
+                .map(|reset| millis_until(reset, now)),
+        };
+        let either = |kind: &str| {
+            let window = openai(kind);
+            if window.is_empty() {
+                anthropic(kind)
+            } else {
+                window
+            }
+        };
+
+        Self {
+            retry_after_ms,
+            requests: either("requests"),
+            tokens: either("tokens"),
+            input_tokens: anthropic("input-tokens"),
+            output_tokens: anthropic("output-tokens"),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.retry_after_ms.is_none()
+            && self.requests.is_empty()
+            && self.tokens.is_empty()
+            && self.input_tokens.is_empty()
+            && self.output_tokens.is_empty()
+    }
+
+    /// How long to wait before retrying: the explicit `retry-after`, or else
+    /// the latest reset among exhausted quotas.
+    pub fn retry_delay_ms(&self) -> Option<u64> {
+        self.retry_after_ms.or_else(|| {
+            [
+                self.requests,
+                self.tokens,
+                self.input_tokens,
+                self.output_tokens,
+            ]
+            .iter()
+            .filter(|w| w.is_exhausted())
+            .filter_map(|w| w.reset_ms)
+            .max()
+        })
+    }
+}
+
+/// Build a [`GaussError::RateLimited`] carrying the delay from `headers`.
+pub fn rate_limited_error(
+    provider: impl Into<String>,
+    message: impl Into<String>,
+    headers: &HeaderMap,
+) -> GaussError {
+    GaussError::RateLimited {
+        provider: provider.into(),
+        message: message.into(),
+        retry_after_ms: RateLimitInfo::from_headers(headers).retry_delay_ms(),
+    }
+}
+
+/// Parse a `retry-after` value: delay seconds or an HTTP date.
+pub fn parse_retry_after(value: &str, now: SystemTime) -> Option<u64> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<f64>() {
+        return (secs >= 0.0).then(|| (secs * 1000.0).ceil() as u64);
+    }
+    parse_http_date(value).map(|at| millis_until(at, now))
+}
+
+/// Parse a Go-style duration as used by OpenAI resets (`"1s"`, `"6m0s"`,
+/// `"20ms"`, `"1h2m3.5s"`) into milliseconds. A bare number is seconds.
+pub fn parse_duration(value: &str) -> Option<u64> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+    if let Ok(secs) = value.parse::<f64>() {
+        return (secs >= 0.0).then(|| (secs * 1000.0).ceil() as u64);
+    }
+
+    let mut total = 0.0;
+    let mut rest = value;
+    while !rest.is_empty() {
+        let split = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .filter(|&i| i > 0)?;
+        let amount: f64 = rest[..split].parse().ok()?;
+        rest = &rest[split..];
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(rest.len());
+        let scale = match &rest[..unit_len] {
+            "h" => 3_600_000.0,
+            "m" => 60_000.0,
+            "s" => 1_000.0,
+            "ms" => 1.0,
+            "us" | "µs" => 0.001,
+            "ns" => 0.000_001,
+            _ => return None,
+        };
+        total += amount * scale;
+        rest = &rest[unit_len..];
+    }
+    Some(total.ceil() as u64)
+}
+
+fn millis_until(at: SystemTime, now: SystemTime) -> u64 {
+    at.duration_since(now)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let mp = (month as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+fn to_system_time(days: i64, secs_of_day: i64, nanos: u32, offset_secs: i64) -> Option<SystemTime> {
+    let secs = days * 86_400 + secs_of_day - offset_secs;
+    let secs = u64::try_from(secs).ok()?;
+    Some(UNIX_EPOCH + std::time::Duration::new(secs, nanos))
+}
+
+fn parse_hms(value: &str) -> Option<(i64, u32)> {
+    let mut parts = value.splitn(3, ':');
+    let h: i64 = parts.next()?.parse().ok()?;
+    let m: i64 = parts.next()?.parse().ok()?;
+    let s = parts.next()?;
+    let (whole, frac) = s.split_once('.').unwrap_or((s, ""));
+    let s: i64 = whole.parse().ok()?;
+    if h > 23 || m > 59 || s > 60 {
+        return None;
+    }
+    let nanos = if frac.is_empty() {
+        0
+    } else {
+        let digits: String = frac.chars().take(9).collect();
+        let padded = format!("{digits:0<9}");
+        padded.parse().ok()?
+    };
+    Some((h * 3600 + m * 60 + s, nanos))
+}
+
+/// Parse an RFC 3339 timestamp such as `2024-05-01T12:00:30Z` or
+/// `2024-05-01T12:00:30.5+02:00`.
+fn parse_rfc3339(value: &str) -> Option<SystemTime> {
+    let (date, time) = value.split_once(['T', 't', ' '])?;
+    let mut date_parts = date.splitn(3, '-');
+    let year: i64 = date_parts.next()?.parse().ok()?;
+    let month: u32 = date_parts.next()?.parse().ok()?;
+    let day: u32 = date_parts.next()?.parse().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let (clock, offset_secs) = if let Some(clock) = time.strip_suffix(['Z', 'z']) {
+        (clock, 0)
+    } else {
+        let idx = time.rfind(['+', '-'])?;
+        let (clock, offset) = time.split_at(idx);
+        let sign = if offset.starts_with('-') { -1 } else { 1 };
+        let (oh, om) = offset[1..].split_once(':')?;
+        let oh: i64 = oh.parse().ok()?;
+        let om: i64 = om.parse().ok()?;
+        (clock, sign * (oh * 3600 + om * 60))
+    };
+    let (secs_of_day, nanos) = parse_hms(clock)?;
+    to_system_time(
+        days_from_civil(year, month, day),
+        secs_of_day,
+        nanos,
+        offset_secs,
+    )
+}
+
+/// Parse an IMF-fixdate HTTP date such as `Sun, 06 Nov 1994 08:49:37 GMT`.
+fn parse_http_date(value: &str) -> Option<SystemTime> {
+    let mut parts = value.split_whitespace();
+    let _weekday = parts.next()?;
+    let day: u32 = parts.next()?.parse().ok()?;
+    let month = match parts.next()? {
+        "Jan" => 1,
+        "Feb" => 2,
+        "Mar" => 3,
+        "Apr" => 4,
+        "May" => 5,
+        "Jun" => 6,
+        "Jul" => 7,
+        "Aug" => 8,
+        "Sep" => 9,
+        "Oct" => 10,
+        "Nov" => 11,
+        "Dec" => 12,
+        _ => return None,
+    };
+    let year: i64 = parts.next()?.parse().ok()?;
+    let (secs_of_day, _) = parse_hms(parts.next()?)?;
+    if parts.next()? != "GMT" {
+        return None;
+    }
+    to_system_time(days_from_civil(year, month, day), secs_of_day, 0, 0)
+}
+
+type Snapshots = Mutex<HashMap<(String, String), RateLimitInfo>>;
+
+fn snapshots() -> &'static Snapshots {
+    static SNAPSHOTS: OnceLock<Snapshots> = OnceLock::new();
+    SNAPSHOTS.get_or_init(Default::default)
+}
+
+/// Record the rate-limit headers of a response from `provider`/`model`.
+/// Responses without rate-limit headers leave the previous snapshot in place.
+pub fn observe(provider: &str, model: &str, headers: &HeaderMap) -> Option<RateLimitInfo> {
+    let info = RateLimitInfo::from_headers(headers);
+    if info.is_empty() {
+        return None;
+    }
+    snapshots()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert((provider.to_string(), model.to_string()), info);
+    Some(info)
+}
+
+/// The most recent rate-limit snapshot observed for `provider`/`model`.
+pub fn latest(provider: &str, model: &str) -> Option<RateLimitInfo> {
+    snapshots()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&(provider.to_string(), model.to_string()))
+        .copied()
+}
@@ -0,0 +1,540 @@
+//! Client-side request and token rate limiting.
+//!
+//! A [`RateLimiter`] keeps async token buckets for requests, input tokens and
+//! output tokens per provider/model. Wrap providers in [`RateLimitedProvider`]
+//! with a shared limiter to keep many concurrent agents under an
+//! organization's RPM/TPM limits. Callers wait in FIFO order for capacity
+//! instead of failing.
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//! use gauss_core::provider::ProviderConfig;
+//! use gauss_core::provider::openai::OpenAiProvider;
+//! use gauss_core::provider::rate_limited::{RateLimitedProvider, RateLimiter, RateLimits};
+//!
+//! let limiter = Arc::new(RateLimiter::new(RateLimits::default()).limit(
+//!     "openai",
+//!     "gpt-5.2",
+//!     RateLimits::new().requests_per_minute(500).input_tokens_per_minute(200_000),
+//! ));
+//! let provider = RateLimitedProvider::new(
+//!     Arc::new(OpenAiProvider::new("gpt-5.2", ProviderConfig::new("sk-..."))),
+//!     limiter.clone(),
+//! );
+//! ```
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use futures::channel::oneshot;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::debug;
+
+use crate::error::{self, GaussError};
+use crate::message::{Message, Usage};
+use crate::provider::clock::Clock;
+use crate::provider::rate_limit::{self, RateLimitInfo, RateLimitWindow};
+use crate::provider::retry::sleep;
+use crate::provider::{BoxStream, GenerateOptions, GenerateResult, Provider, ProviderCapabilities};
+use crate::streaming::StreamEvent;
+use crate::tool::Tool;
+
+/// Per-minute limits for one provider/model. `None` means unlimited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RateLimits {
+    pub requests_per_minute: Option<u64>,
+    pub input_tokens_per_minute: Option<u64>,
+    pub output_tokens_per_minute: Option<u64>,
+}
+
+impl RateLimits {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn requests_per_minute(mut self, rpm: u64) -> Self {
+        self.requests_per_minute = Some(rpm);
+        self
+    }
+
+    pub fn input_tokens_per_minute(mut self, tpm: u64) -> Self {
+        self.input_tokens_per_minute = Some(tpm);
+        self
+    }
+
+    pub fn output_tokens_per_minute(mut self, tpm: u64) -> Self {
+        self.output_tokens_per_minute = Some(tpm);
+        self
+    }
+}
+
+/// A continuously refilling bucket holding up to one minute of budget.
+/// The balance may go negative when actual usage exceeds the reservation.
+#[derive(Debug)]
+struct Bucket {
+    capacity: f64,
+    tokens: f64,
+    last_refill_ms: u64,
+}
+
+impl Bucket {
+    fn new(per_minute: u64, now: u64) -> Self {
+        Self {
+            capacity: per_minute as f64,
+            tokens: per_minute as f64,
+            last_refill_ms: now,
+        }
+    }
+
+    fn refill(&mut self, now: u64) {
+        let elapsed = now.saturating_sub(self.last_refill_ms) as f64;
+        self.tokens = (self.tokens + elapsed * self.capacity / 60_000.0).min(self.capacity);
+        self.last_refill_ms = now;
+    }
+
+    /// Milliseconds until `need` is available. Requests larger than the
+    /// bucket only wait for it to be full.
+    fn wait_ms(&self, need: f64) -> u64 {
+        let need = need.min(self.capacity);
+        if self.tokens >= need || self.capacity <= 0.0 {
+            0
+        } else {
+            ((need - self.tokens) * 60_000.0 / self.capacity).ceil() as u64
+        }
+    }
+
+    /// Apply what the provider reported: adopt a lower limit and never
+    /// assume more remaining budget than the provider does.
+    fn adjust(&mut self, window: &RateLimitWindow) {
+        if let Some(limit) = window.limit
+            && (limit as f64) < self.capacity
+        {
+            self.capacity = limit as f64;
+        }
+        if let Some(remaining) = window.remaining {
+            self.tokens = self.tokens.min(remaining as f64);
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Buckets {
+    requests: Option<Bucket>,
+    input: Option<Bucket>,
+    output: Option<Bucket>,
+    /// No requests before this time (provider said so).
+    blocked_until_ms: u64,
+    last_applied: Option<RateLimitInfo>,
+}
+
+impl Buckets {
+    fn iter_mut(&mut self) -> impl Iterator<Item = &mut Bucket> {
+        [&mut self.requests, &mut self.input, &mut self.output]
+            .into_iter()
+            .flatten()
+    }
+
+    fn block_for(&mut self, ms: u64, now: u64) {
+        self.blocked_until_ms = self.blocked_until_ms.max(now + ms);
+    }
+
+    fn apply(&mut self, info: RateLimitInfo, now: u64) {
+        if self.last_applied == Some(info) {
+            return;
+        }
+        self.last_applied = Some(info);
+
+        let input = if info.input_tokens.is_empty() {
+            info.tokens
+        } else {
+            info.input_tokens
+        };
+        for (bucket, window) in [
+            (&mut self.requests, info.requests),
+            (&mut self.input, input),
+            (&mut self.output, info.output_tokens),
+        ] {
+            if bucket.is_none()
+                && let Some(limit) = window.limit
+            {
+                *bucket = Some(Bucket::new(limit, now));
+            }
+            if let Some(bucket) = bucket {
+                bucket.refill(now);
+                bucket.adjust(&window);
+            }
+        }
+        if let Some(ms) = info.retry_delay_ms() {
+            self.block_for(ms, now);
+        }
+    }
+}
+
+#[derive(Default)]
+struct GateState {
+    busy: bool,
+    waiters: VecDeque<oneshot::Sender<()>>,
+}
+
+/// A FIFO async gate: one holder at a time, handed over in arrival order.
+#[derive(Default)]
+struct Gate {
+    state: Mutex<GateState>,
+}
+
+struct GateGuard<'a> {
+    gate: &'a Gate,
+}
+
+impl Gate {
+    async fn enter(&self) -> GateGuard<'_> {
+        let rx = {
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            if !state.busy {
+                state.busy = true;
+                return GateGuard { gate: self };
+            }
+            let (tx, rx) = oneshot::channel();
+            state.waiters.push_back(tx);
+            rx
+        };
+        let mut waiter = Waiter {
+            gate: self,
+            rx: Some(rx),
+        };
+        if let Some(rx) = waiter.rx.as_mut() {
+            // The sender is only dropped by `release`, which hands us the turn.
+            let _ = rx.await;
+        }
+        waiter.rx = None;
+        GateGuard { gate: self }
+    }
+
+    fn release(&self) {
+        Self::hand_over(&mut self.state.lock().unwrap_or_else(|e| e.into_inner()));
+    }
+
+    /// Pass the turn to the next live waiter, or mark the gate free.
+    fn hand_over(state: &mut GateState) {
+        while let Some(tx) = state.waiters.pop_front() {
+            if tx.send(()).is_ok() {
+                return;
+            }
+        }
+        state.busy = false;
+    }
+
+    fn waiting(&self) -> usize {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.waiters.iter().filter(|tx| !tx.is_canceled()).count()
+    }
+}
+
+impl Drop for GateGuard<'_> {
+    fn drop(&mut self) {
+        self.gate.release();
+    }
+}
+
+/// Pending turn; passes the turn on if cancelled after being handed it.
+struct Waiter<'a> {
+    gate: &'a Gate,
+    rx: Option<oneshot::Receiver<()>>,
+}
+
+impl Drop for Waiter<'_> {
+    fn drop(&mut self) {
+        let Some(mut rx) = self.rx.take() else {
+            return;
+        };
+        // `release` sends under the state lock, so with the lock held and the
+        // receiver closed no turn can arrive after the check below.
+        let mut state = self.gate.state.lock().unwrap_or_else(|e| e.into_inner());
+        rx.close();
+        if let Ok(Some(())) = rx.try_recv() {
+            Gate::hand_over(&mut state);
+        }
+    }
+}
+
+/// Buckets and queue for one provider/model.
+struct ModelLimiter {
+    clock: Clock,
+    gate: Gate,
+    buckets: Mutex<Buckets>,
+}
+
+impl ModelLimiter {
+    fn new(limits: RateLimits) -> Self {
+        let clock = Clock::new();
+        let now = clock.now_ms();
+        Self {
+            buckets: Mutex::new(Buckets {
+                requests: limits.requests_per_minute.map(|l| Bucket::new(l, now)),
+                input: limits.input_tokens_per_minute.map(|l| Bucket::new(l, now)),
+                output: limits.output_tokens_per_minute.map(|l| Bucket::new(l, now)),
+                ..Default::default()
+            }),
+            gate: Gate::default(),
+            clock,
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Buckets> {
+        self.buckets.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Wait in line until one request with the given token reservations fits.
+    async fn acquire(&self, input: u64, output: u64) {
+        let _turn = self.gate.enter().await;
+        loop {
+            let wait_ms = {
+                let mut guard = self.lock();
+                let buckets = &mut *guard;
+                let now = self.clock.now_ms();
+                for bucket in buckets.iter_mut() {
+                    bucket.refill(now);
+                }
+                let needs = [1.0, input as f64, output as f64];
+                let wait = [&buckets.requests, &buckets.input, &buckets.output]
+                    .into_iter()
+                    .zip(needs)
+                    .filter_map(|(bucket, need)| bucket.as_ref().map(|b| b.wait_ms(need)))
+                    .max()
+                    .unwrap_or(0)
+                    .max(buckets.blocked_until_ms.saturating_sub(now));
+                if wait == 0 {
+                    for (bucket, need) in [
+                        (&mut buckets.requests, 1.0),
+                        (&mut buckets.input, input as f64),
+                        (&mut buckets.output, output as f64),
+                    ] {
+                        if let Some(bucket) = bucket {
+                            bucket.tokens -= need;
+                        }
+                    }
+                    return;
+                }
+                wait
+            };
+            debug!(wait_ms, "Waiting for rate limit capacity");
+            sleep(Duration::from_millis(wait_ms)).await;
+        }
+    }
+
+    /// Take `input` and `output` more tokens (negative to give them back).
+    fn charge(&self, input: f64, output: f64) {
+        let mut buckets = self.lock();
+        if let Some(ref mut bucket) = buckets.input {
+            bucket.tokens -= input;
+        }
+        if let Some(ref mut bucket) = buckets.output {
+            bucket.tokens -= output;
+        }
+    }
+
+    /// Learn from the provider's latest rate-limit headers or error.
+    fn observe(&self, provider: &str, model: &str, error: Option<&GaussError>) {
+        let now = self.clock.now_ms();
+        let mut buckets = self.lock();
+        if let Some(info) = rate_limit::latest(provider, model) {
+            buckets.apply(info, now);
+        }
+        if let Some(GaussError::RateLimited {
+            retry_after_ms: Some(ms),
+            ..
+        }) = error
+        {
+            buckets.block_for(*ms, now);
+        }
+    }
+}
+
+/// One call's token reservations, settled against the usage it reports.
+///
+/// Usage events are running totals, so each one replaces what was charged
+/// before. A failed call gives everything back; a call that reports no
+/// usage gives back its output reservation when this is dropped.
+struct Reservation {
+    limiter: crate::Shared<ModelLimiter>,
+    input: u64,
+    output: u64,
+    settled: bool,
+}
+
+impl Reservation {
+    fn settle(&mut self, usage: &Usage) {
+        // Some providers report input and output tokens in separate events.
+        let input = if usage.input_tokens > 0 {
+            usage.input_tokens
+        } else {
+            self.input
+        };
+        self.limiter.charge(
+            input as f64 - self.input as f64,
+            usage.output_tokens as f64 - self.output as f64,
+        );
+        self.input = input;
+        self.output = usage.output_tokens;
+        self.settled = true;
+    }
+
+    fn refund(mut self) {
+        self.limiter
+            .charge(-(self.input as f64), -(self.output as f64));
+        self.settled = true;
+    }
+}
+
+impl Drop for Reservation {
+    fn drop(&mut self) {
+        if !self.settled {
+            self.limiter.charge(0.0, -(self.output as f64));
+        }
+    }
+}
+
+/// Shared token buckets per provider/model.
+///
+/// Limits not configured explicitly fall back to the default, and unlimited
+/// quotas are learned from the provider's rate-limit headers once seen.
+pub struct RateLimiter {
+    default: RateLimits,
+    limits: HashMap<(String, String), RateLimits>,
+    models: Mutex<HashMap<(String, String), crate::Shared<ModelLimiter>>>,
+}
+
+impl RateLimiter {
+    pub fn new(default: RateLimits) -> Self {
+        Self {
+            default,
+            limits: HashMap::new(),
+            models: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Set the limits for one provider/model.
+    pub fn limit(
+        mut self,
+        provider: impl Into<String>,
+        model: impl Into<String>,
+        limits: RateLimits,
+    ) -> Self {
+        self.limits.insert((provider.into(), model.into()), limits);
+        self
+    }
+
+    fn model(&self, provider: &str, model: &str) -> crate::Shared<ModelLimiter> {
+        let key = (provider.to_string(), model.to_string());
+        let mut models = self.models.lock().unwrap_or_else(|e| e.into_inner());
+        models
+            .entry(key)
+            .or_insert_with_key(|key| {
+                let limits = self.limits.get(key).copied().unwrap_or(self.default);
+                crate::Shared::new(ModelLimiter::new(limits))
+            })
+            .clone()
+    }
+
+    /// Number of callers queued for `provider`/`model`.
+    pub fn waiting(&self, provider: &str, model: &str) -> usize {
+        self.model(provider, model).gate.waiting()
+    }
+}
+
+/// A provider wrapper that waits for capacity in a shared [`RateLimiter`].
+///
+/// Input tokens are estimated up front with
+/// [`count_messages_tokens`](crate::context::count_messages_tokens); output
+/// tokens reserve `max_tokens` when set. Both are settled against the
+/// reported usage afterwards, and returned if the call fails.
+pub struct RateLimitedProvider {
+    inner: crate::Shared<dyn Provider>,
+    limiter: crate::Shared<RateLimiter>,
+}
+
+impl RateLimitedProvider {
+    pub fn new(inner: crate::Shared<dyn Provider>, limiter: crate::Shared<RateLimiter>) -> Self {
+        Self { inner, limiter }
+    }
+
+    /// Wait for capacity and reserve the call's estimated tokens.
+    async fn reserve(
+        limiter: &crate::Shared<ModelLimiter>,
+        messages: &[Message],
+        options: &GenerateOptions,
+    ) -> Reservation {
+        let input = crate::context::count_messages_tokens(messages) as u64;
+        let output = options.max_tokens.map(u64::from).unwrap_or(0);
+        limiter.acquire(input, output).await;
+        Reservation {
+            limiter: limiter.clone(),
+            input,
+            output,
+            settled: false,
+        }
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Provider for RateLimitedProvider {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn model(&self) -> &str {
+        self.inner.model()
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        self.inner.capabilities()
+    }
+
+    async fn generate(
+        &self,
+        messages: &[Message],
+        tools: &[Tool],
+        options: &GenerateOptions,
+    ) -> error::Result<GenerateResult> {
+        let (name, model) = (self.inner.name(), self.inner.model());
+        let limiter = self.limiter.model(name, model);
+        let mut reservation = Self::reserve(&limiter, messages, options).await;
+
+        let result = self.inner.generate(messages, tools, options).await;
+        limiter.observe(name, model, result.as_ref().err());
+        match result {
+            Ok(ref result) => reservation.settle(&result.usage),
+            Err(_) => reservation.refund(),
+        }
+        result
+    }
+
+    async fn stream(
+        &self,
+        messages: &[Message],
+        tools: &[Tool],
+        options: &GenerateOptions,
+    ) -> error::Result<BoxStream> {
+        let (name, model) = (self.inner.name(), self.inner.model());
+        let limiter = self.limiter.model(name, model);
+        let mut reservation = Self::reserve(&limiter, messages, options).await;
+
+        let stream = self.inner.stream(messages, tools, options).await;
+        limiter.observe(name, model, stream.as_ref().err());
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                reservation.refund();
+                return Err(e);
+            }
+        };
+        let stream = stream.inspect(move |event| {
+            if let Ok(StreamEvent::Usage(usage)) = event {
+                reservation.settle(usage);
+            }
+        });
+        Ok(Box::new(stream))
+    }
+}
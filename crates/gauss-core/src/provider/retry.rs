@@ -1,14 +1,17 @@
 use async_trait::async_trait;
 
+use std::sync::Mutex;
 use std::time::Duration;
 use tracing::{debug, warn};
 
 use crate::error::{self, GaussError};
 use crate::message::Message;
+use crate::metrics::MetricsRegistry;
+use crate::plugin::{GaussEvent, PluginRegistry};
 use crate::provider::{GenerateOptions, GenerateResult, Provider};
 use crate::tool::Tool;
 
-async fn sleep(duration: Duration) {
+pub(crate) async fn sleep(duration: Duration) {
     #[cfg(all(feature = "native", not(target_arch = "wasm32")))]
     {
         tokio::time::sleep(duration).await;
@@ -50,37 +53,241 @@ impl Default for RetryConfig {
     }
 }
 
+/// A retry budget shared by several [`RetryProvider`]s or calls.
+///
+/// Every request deposits `ratio` tokens and every retry withdraws one, so
+/// over time retries stay below `ratio` × requests. The balance starts at
+/// and is capped by `reserve`, which allows short bursts of retries. Once
+/// the budget is spent, failures are returned immediately instead of
+/// piling retries onto a struggling provider.
+#[derive(Debug)]
+pub struct RetryBudget {
+    ratio: f64,
+    reserve: f64,
+    balance: Mutex<f64>,
+}
+
+impl RetryBudget {
+    pub fn new(ratio: f64, reserve: u32) -> Self {
+        Self {
+            ratio: ratio.max(0.0),
+            reserve: reserve as f64,
+            balance: Mutex::new(reserve as f64),
+        }
+    }
+
+    /// Credit the budget for one request.
+    pub fn deposit(&self) {
+        let mut balance = self.balance.lock().unwrap_or_else(|e| e.into_inner());
+        *balance = (*balance + self.ratio).min(self.reserve);
+    }
+
+    /// Take one retry from the budget; false when it is spent.
+    pub fn try_withdraw(&self) -> bool {
+        let mut balance = self.balance.lock().unwrap_or_else(|e| e.into_inner());
+        if *balance >= 1.0 {
+            *balance -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Retries currently available.
+    pub fn balance(&self) -> f64 {
+        *self.balance.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for RetryBudget {
+    /// Retries limited to 20% of requests, with bursts of up to 10.
+    fn default() -> Self {
+        Self::new(0.2, 10)
+    }
+}
+
+/// Whether `error` is a transient provider failure: 5xx statuses (including
+/// Anthropic's 529 overloaded), 408/409, timeouts, rate limits and dropped
+/// connections.
+pub fn is_transient(error: &GaussError) -> bool {
+    match error {
+        GaussError::RateLimited { .. } | GaussError::Timeout { .. } | GaussError::Stream { .. } => {
+            true
+        }
+        GaussError::Provider {
+            status: Some(status),
+            ..
+        } => *status >= 500 || matches!(status, 408 | 409),
+        GaussError::Provider {
+            status: None,
+            message,
+            source,
+            ..
+        } => {
+            source
+                .as_deref()
+                .is_some_and(|source| is_connection_error(source))
+                || is_transient_message(message)
+        }
+        _ => false,
+    }
+}
+
+fn is_connection_error(error: &(dyn std::error::Error + 'static)) -> bool {
+    let mut current = Some(error);
+    while let Some(err) = current {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(e) = err.downcast_ref::<reqwest::Error>()
+            && (e.is_connect() || e.is_timeout())
+        {
+            return true;
+        }
+        if let Some(e) = err.downcast_ref::<std::io::Error>() {
+            use std::io::ErrorKind::*;
+            if matches!(
+                e.kind(),
+                ConnectionReset
+                    | ConnectionAborted
+                    | ConnectionRefused
+                    | BrokenPipe
+                    | TimedOut
+                    | UnexpectedEof
+            ) {
+                return true;
+            }
+        }
+        current = err.source();
+    }
+    false
+}
+
+fn is_transient_message(message: &str) -> bool {
+    let message = message.to_ascii_lowercase();
+    [
+        "overloaded",
+        "connection reset",
+        "connection closed",
+        "broken pipe",
+    ]
+    .iter()
+    .any(|needle| message.contains(needle))
+}
+
+/// Uniform random value in `0..=max`.
+fn jitter(max: u64) -> u64 {
+    (uuid::Uuid::new_v4().as_u128() % (max as u128 + 1)) as u64
+}
+
 /// A provider wrapper that adds retry logic with exponential backoff.
+///
+/// Backoff uses full jitter: each delay is drawn uniformly from zero up to
+/// the exponential ceiling. A rate-limit error that carries a delay from the
+/// provider's `retry-after` headers waits exactly that long instead, or is
+/// returned immediately when the delay exceeds `max_delay_ms`.
 pub struct RetryProvider {
     inner: crate::Shared<dyn Provider>,
     config: RetryConfig,
+    jitter: bool,
+    budget: Option<crate::Shared<RetryBudget>>,
+    metrics: Option<crate::Shared<MetricsRegistry>>,
+    plugins: Option<crate::Shared<PluginRegistry>>,
 }
 
 impl RetryProvider {
     pub fn new(inner: crate::Shared<dyn Provider>, config: RetryConfig) -> Self {
-        Self { inner, config }
+        Self {
+            inner,
+            config,
+            jitter: true,
+            budget: None,
+            metrics: None,
+            plugins: None,
+        }
     }
 
     pub fn wrap(inner: crate::Shared<dyn Provider>) -> Self {
         Self::new(inner, RetryConfig::default())
     }
 
+    /// Randomize backoff delays (full jitter). Enabled by default.
+    pub fn with_jitter(mut self, enabled: bool) -> Self {
+        self.jitter = enabled;
+        self
+    }
+
+    /// Draw retries from a budget shared with other providers or agents.
+    pub fn with_budget(mut self, budget: crate::Shared<RetryBudget>) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// Count retries in a metrics registry.
+    pub fn with_metrics(mut self, registry: crate::Shared<MetricsRegistry>) -> Self {
+        self.metrics = Some(registry);
+        self
+    }
+
+    /// Publish a `ProviderRetry` event for each retry.
+    pub fn with_plugins(mut self, registry: crate::Shared<PluginRegistry>) -> Self {
+        self.plugins = Some(registry);
+        self
+    }
+
+    async fn record_retry(&self, error: &GaussError, attempt: u32, delay: Duration) {
+        if let Some(ref metrics) = self.metrics {
+            metrics.record_retry(self.inner.name(), self.inner.model(), error.kind());
+        }
+        if let Some(ref plugins) = self.plugins {
+            plugins
+                .dispatch(&GaussEvent::ProviderRetry {
+                    provider: self.inner.name().to_string(),
+                    model: self.inner.model().to_string(),
+                    attempt,
+                    delay_ms: delay.as_millis() as u64,
+                    error: error.to_string(),
+                })
+                .await;
+        }
+    }
+
     fn should_retry(&self, error: &GaussError) -> bool {
-        match error {
-            GaussError::RateLimited { .. } => self.config.retry_on_rate_limit,
-            GaussError::Provider { status, .. } => {
-                self.config.retry_on_server_error && status.is_some_and(|s| s >= 500)
+        let retryable = match error {
+            GaussError::RateLimited { retry_after_ms, .. } => {
+                self.config.retry_on_rate_limit
+                    && retry_after_ms.is_none_or(|ms| ms <= self.config.max_delay_ms)
+            }
+            GaussError::Provider { .. } | GaussError::Timeout { .. } => {
+                self.config.retry_on_server_error && is_transient(error)
             }
             GaussError::Stream { .. } => true,
             _ => false,
+        };
+        if retryable
+            && let Some(ref budget) = self.budget
+            && !budget.try_withdraw()
+        {
+            warn!(provider = self.inner.name(), "Retry budget exhausted");
+            return false;
         }
+        retryable
     }
 
-    fn delay_for_attempt(&self, attempt: u32) -> Duration {
+    fn delay_for(&self, error: &GaussError, attempt: u32) -> Duration {
+        if let GaussError::RateLimited {
+            retry_after_ms: Some(ms),
+            ..
+        } = error
+        {
+            return Duration::from_millis(*ms);
+        }
         let delay = self.config.initial_delay_ms as f64
             * self.config.backoff_multiplier.powi(attempt as i32);
-        let clamped = delay.min(self.config.max_delay_ms as f64) as u64;
-        Duration::from_millis(clamped)
+        let ceiling = delay.min(self.config.max_delay_ms as f64) as u64;
+        Duration::from_millis(if self.jitter {
+            jitter(ceiling)
+        } else {
+            ceiling
+        })
     }
 }
 
@@ -106,13 +313,16 @@ impl Provider for RetryProvider {
         options: &GenerateOptions,
     ) -> error::Result<GenerateResult> {
         let mut last_error = None;
+        if let Some(ref budget) = self.budget {
+            budget.deposit();
+        }
 
         for attempt in 0..=self.config.max_retries {
             match self.inner.generate(messages, tools, options).await {
                 Ok(result) => return Ok(result),
                 Err(e) => {
                     if attempt < self.config.max_retries && self.should_retry(&e) {
-                        let delay = self.delay_for_attempt(attempt);
+                        let delay = self.delay_for(&e, attempt);
                         warn!(
                             provider = self.inner.name(),
                             attempt = attempt + 1,
@@ -121,6 +331,7 @@ impl Provider for RetryProvider {
                             error = %e,
                             "Retrying after error"
                         );
+                        self.record_retry(&e, attempt + 1, delay).await;
                         sleep(delay).await;
                         last_error = Some(e);
                     } else {
@@ -141,6 +352,9 @@ impl Provider for RetryProvider {
         options: &GenerateOptions,
     ) -> error::Result<crate::provider::BoxStream> {
         let mut last_error = None;
+        if let Some(ref budget) = self.budget {
+            budget.deposit();
+        }
 
         for attempt in 0..=self.config.max_retries {
             match self.inner.stream(messages, tools, options).await {
@@ -150,13 +364,14 @@ impl Provider for RetryProvider {
                 }
                 Err(e) => {
                     if attempt < self.config.max_retries && self.should_retry(&e) {
-                        let delay = self.delay_for_attempt(attempt);
+                        let delay = self.delay_for(&e, attempt);
                         warn!(
                             provider = self.inner.name(),
                             attempt = attempt + 1,
                             delay_ms = delay.as_millis() as u64,
                             "Retrying stream after error"
                         );
+                        self.record_retry(&e, attempt + 1, delay).await;
                         sleep(delay).await;
                         last_error = Some(e);
                     } else {
@@ -0,0 +1,558 @@
+//! Capability- and cost-aware routing across providers.
+//!
+//! [`RouterProvider`] picks one target per request. Targets that lack a
+//! required capability, whose context window is too small, whose estimated
+//! cost exceeds the ceiling or that are failing too often are ruled out, and
+//! a [`RoutingStrategy`] (or a custom function) chooses among the rest. The
+//! decision is recorded on [`GenerateResult::routing`], or sent first as a
+//! [`StreamEvent::Routing`] when streaming.
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//! use gauss_core::provider::ProviderConfig;
+//! use gauss_core::provider::anthropic::AnthropicProvider;
+//! use gauss_core::provider::openai::OpenAiProvider;
+//! use gauss_core::provider::router::{RouterProvider, RoutingPolicy, RoutingStrategy};
+//!
+//! let router = RouterProvider::new()
+//!     .target(Arc::new(OpenAiProvider::new("gpt-4o-mini", ProviderConfig::new("sk-..."))))
+//!     .target(Arc::new(AnthropicProvider::new("claude-sonnet-4", ProviderConfig::new("sk-ant-..."))))
+//!     .with_policy(RoutingPolicy {
+//!         strategy: RoutingStrategy::Cheapest,
+//!         max_cost_usd: Some(0.05),
+//!         ..Default::default()
+//!     });
+//! ```
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::debug;
+
+use crate::error::{self, GaussError};
+use crate::message::{Content, Message, Usage};
+use crate::provider::clock::Clock;
+use crate::provider::{BoxStream, GenerateOptions, GenerateResult, Provider, ProviderCapabilities};
+use crate::streaming::StreamEvent;
+use crate::tool::Tool;
+
+/// How to choose among eligible targets.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoutingStrategy {
+    /// The first eligible target in registration order.
+    #[default]
+    Priority,
+    /// The lowest estimated cost for this request.
+    Cheapest,
+    /// The lowest observed latency (EWMA); targets without samples go first.
+    LowestLatency,
+    /// Random choice proportional to each target's weight.
+    Weighted,
+}
+
+/// Limits and strategy applied to every request.
+#[derive(Debug, Clone)]
+pub struct RoutingPolicy {
+    pub strategy: RoutingStrategy,
+    /// Rule out targets whose estimated cost exceeds this many USD.
+    pub max_cost_usd: Option<f64>,
+    /// Rule out targets whose latency EWMA exceeds this many milliseconds.
+    pub max_latency_ms: Option<f64>,
+    /// Rule out targets whose error-rate EWMA exceeds this (0.0–1.0) until
+    /// `error_cooldown` has passed since their last failure.
+    pub max_error_rate: Option<f64>,
+    pub error_cooldown: Duration,
+    /// Output tokens assumed for cost estimates when `max_tokens` is unset.
+    pub expected_output_tokens: u64,
+    /// Smoothing factor for the latency and error-rate EWMAs.
+    pub ewma_alpha: f64,
+}
+
+impl Default for RoutingPolicy {
+    fn default() -> Self {
+        Self {
+            strategy: RoutingStrategy::default(),
+            max_cost_usd: None,
+            max_latency_ms: None,
+            max_error_rate: None,
+            error_cooldown: Duration::from_secs(30),
+            expected_output_tokens: 1024,
+            ewma_alpha: 0.2,
+        }
+    }
+}
+
+/// What a request needs from its target.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RouteRequest {
+    pub vision: bool,
+    pub audio: bool,
+    pub tool_use: bool,
+    pub structured_output: bool,
+    pub estimated_input_tokens: u64,
+    pub max_output_tokens: Option<u64>,
+}
+
+impl RouteRequest {
+    pub fn new(messages: &[Message], tools: &[Tool], options: &GenerateOptions) -> Self {
+        let parts = || messages.iter().flat_map(|m| &m.content);
+        Self {
+            vision: parts().any(|c| matches!(c, Content::Image { .. })),
+            audio: parts().any(|c| matches!(c, Content::Audio { .. })),
+            tool_use: !tools.is_empty(),
+            structured_output: options.output_schema.is_some(),
+            estimated_input_tokens: crate::context::count_messages_tokens(messages) as u64,
+            max_output_tokens: options.max_tokens.map(u64::from),
+        }
+    }
+}
+
+/// An eligible target as seen by the strategy or a custom route function.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RouteCandidate {
+    /// Index in registration order.
+    pub index: usize,
+    pub provider: String,
+    pub model: String,
+    pub weight: f64,
+    pub context_window: usize,
+    pub estimated_cost_usd: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_ewma_ms: Option<f64>,
+    pub error_rate: f64,
+}
+
+/// A target that was ruled out, and why.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RejectedTarget {
+    pub provider: String,
+    pub model: String,
+    pub reason: String,
+}
+
+/// The routing decision for one request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoutingDecision {
+    pub index: usize,
+    pub provider: String,
+    pub model: String,
+    /// Strategy name, or `"custom"` for a route function.
+    pub strategy: String,
+    pub estimated_input_tokens: u64,
+    pub estimated_cost_usd: f64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rejected: Vec<RejectedTarget>,
+}
+
+impl RoutingDecision {
+    /// A target picked by other means than a routing policy, such as the
+    /// winner of a hedged race or the answer an ensemble chose. Output tokens
+    /// only count towards the cost estimate when `max_tokens` bounds them.
+    pub fn picked(
+        index: usize,
+        provider: &dyn Provider,
+        strategy: impl Into<String>,
+        request: &RouteRequest,
+    ) -> Self {
+        let usage = Usage {
+            input_tokens: request.estimated_input_tokens,
+            output_tokens: request.max_output_tokens.unwrap_or(0),
+            ..Default::default()
+        };
+        Self {
+            index,
+            provider: provider.name().to_string(),
+            model: provider.model().to_string(),
+            strategy: strategy.into(),
+            estimated_input_tokens: request.estimated_input_tokens,
+            estimated_cost_usd: crate::catalog::read()
+                .estimate_cost(provider.model(), &usage)
+                .total_cost_usd,
+            rejected: Vec::new(),
+        }
+    }
+}
+
+/// Custom routing: returns the index (into `candidates`) of the chosen
+/// target, or `None` to defer to the policy's strategy.
+#[cfg(not(target_arch = "wasm32"))]
+pub type RouteFn = dyn Fn(&RouteRequest, &[RouteCandidate]) -> Option<usize> + Send + Sync;
+#[cfg(target_arch = "wasm32")]
+pub type RouteFn = dyn Fn(&RouteRequest, &[RouteCandidate]) -> Option<usize>;
+
+/// Observed health of one target.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TargetStats {
+    pub provider: String,
+    pub model: String,
+    pub requests: u64,
+    pub errors: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_ewma_ms: Option<f64>,
+    pub error_rate: f64,
+    #[serde(skip)]
+    last_error_ms: Option<u64>,
+}
+
+struct Target {
+    provider: crate::Shared<dyn Provider>,
+    weight: f64,
+    stats: Mutex<TargetStats>,
+}
+
+/// Uniform random value in `[0, 1)`.
+fn random_unit() -> f64 {
+    (uuid::Uuid::new_v4().as_u128() >> 75) as f64 / (1u64 << 53) as f64
+}
+
+fn union(a: ProviderCapabilities, b: ProviderCapabilities) -> ProviderCapabilities {
+    ProviderCapabilities {
+        streaming: a.streaming || b.streaming,
+        tool_use: a.tool_use || b.tool_use,
+        vision: a.vision || b.vision,
+        audio: a.audio || b.audio,
+        extended_thinking: a.extended_thinking || b.extended_thinking,
+        citations: a.citations || b.citations,
+        cache_control: a.cache_control || b.cache_control,
+        structured_output: a.structured_output || b.structured_output,
+        reasoning_effort: a.reasoning_effort || b.reasoning_effort,
+        image_generation: a.image_generation || b.image_generation,
+        grounding: a.grounding || b.grounding,
+        code_execution: a.code_execution || b.code_execution,
+        web_search: a.web_search || b.web_search,
+    }
+}
+
+/// Routes each request to one of several providers.
+pub struct RouterProvider {
+    targets: Vec<Target>,
+    policy: RoutingPolicy,
+    route_fn: Option<Box<RouteFn>>,
+    clock: Clock,
+}
+
+impl Default for RouterProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RouterProvider {
+    pub fn new() -> Self {
+        Self {
+            targets: Vec::new(),
+            policy: RoutingPolicy::default(),
+            route_fn: None,
+            clock: Clock::new(),
+        }
+    }
+
+    /// Add a target with weight 1.
+    pub fn target(self, provider: crate::Shared<dyn Provider>) -> Self {
+        self.weighted_target(provider, 1.0)
+    }
+
+    /// Add a target with a load-balancing weight.
+    pub fn weighted_target(mut self, provider: crate::Shared<dyn Provider>, weight: f64) -> Self {
+        let stats = TargetStats {
+            provider: provider.name().to_string(),
+            model: provider.model().to_string(),
+            ..Default::default()
+        };
+        self.targets.push(Target {
+            provider,
+            weight: weight.max(0.0),
+            stats: Mutex::new(stats),
+        });
+        self
+    }
+
+    pub fn with_policy(mut self, policy: RoutingPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Use a custom route function ahead of the policy's strategy.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_route_fn(
+        mut self,
+        f: impl Fn(&RouteRequest, &[RouteCandidate]) -> Option<usize> + Send + Sync + 'static,
+    ) -> Self {
+        self.route_fn = Some(Box::new(f));
+        self
+    }
+
+    /// Use a custom route function ahead of the policy's strategy.
+    #[cfg(target_arch = "wasm32")]
+    pub fn with_route_fn(
+        mut self,
+        f: impl Fn(&RouteRequest, &[RouteCandidate]) -> Option<usize> + 'static,
+    ) -> Self {
+        self.route_fn = Some(Box::new(f));
+        self
+    }
+
+    /// Observed latency and error rates, in registration order.
+    pub fn stats(&self) -> Vec<TargetStats> {
+        self.targets
+            .iter()
+            .map(|t| t.stats.lock().unwrap_or_else(|e| e.into_inner()).clone())
+            .collect()
+    }
+
+    fn rejection(&self, target: &Target, request: &RouteRequest, cost: f64) -> Option<String> {
+        let caps = target.provider.capabilities();
+        let missing = [
+            (request.vision && !caps.vision, "vision"),
+            (request.audio && !caps.audio, "audio"),
+            (request.tool_use && !caps.tool_use, "tool use"),
+            (
+                request.structured_output && !caps.structured_output,
+                "structured output",
+            ),
+        ];
+        if let Some((_, capability)) = missing.iter().find(|(missing, _)| *missing) {
+            return Some(format!("missing capability: {capability}"));
+        }
+
+        let context_window = crate::catalog::read().context_window(target.provider.model()) as u64;
+        let needed = request.estimated_input_tokens + request.max_output_tokens.unwrap_or(0);
+        if needed > context_window {
+            return Some(format!(
+                "needs ~{needed} tokens, context window is {context_window}"
+            ));
+        }
+
+        if let Some(max) = self.policy.max_cost_usd
+            && cost > max
+        {
+            return Some(format!("estimated cost ${cost:.4} exceeds ${max:.4}"));
+        }
+
+        let stats = target.stats.lock().unwrap_or_else(|e| e.into_inner());
+        if let (Some(max), Some(latency)) = (self.policy.max_latency_ms, stats.latency_ewma_ms)
+            && latency > max
+        {
+            return Some(format!("latency {latency:.0}ms exceeds {max:.0}ms"));
+        }
+        if let Some(max) = self.policy.max_error_rate
+            && stats.error_rate > max
+            && stats.last_error_ms.is_some_and(|at| {
+                self.clock.now_ms().saturating_sub(at)
+                    < self.policy.error_cooldown.as_millis() as u64
+            })
+        {
+            return Some(format!(
+                "error rate {:.2} exceeds {max:.2}",
+                stats.error_rate
+            ));
+        }
+        None
+    }
+
+    fn estimate_cost(&self, model: &str, request: &RouteRequest) -> f64 {
+        let usage = Usage {
+            input_tokens: request.estimated_input_tokens,
+            output_tokens: request
+                .max_output_tokens
+                .unwrap_or(self.policy.expected_output_tokens),
+            ..Default::default()
+        };
+        crate::catalog::read()
+            .estimate_cost(model, &usage)
+            .total_cost_usd
+    }
+
+    fn choose(&self, request: &RouteRequest, candidates: &[RouteCandidate]) -> (usize, String) {
+        if let Some(ref route_fn) = self.route_fn
+            && let Some(i) = route_fn(request, candidates).filter(|&i| i < candidates.len())
+        {
+            return (i, "custom".to_string());
+        }
+
+        let strategy = self.policy.strategy;
+        let by = |key: fn(&RouteCandidate) -> f64| {
+            candidates
+                .iter()
+                .enumerate()
+                .min_by(|(_, a), (_, b)| key(a).total_cmp(&key(b)))
+                .map(|(i, _)| i)
+                .unwrap_or(0)
+        };
+        let index = match strategy {
+            RoutingStrategy::Priority => 0,
+            RoutingStrategy::Cheapest => by(|c| c.estimated_cost_usd),
+            RoutingStrategy::LowestLatency => by(|c| c.latency_ewma_ms.unwrap_or(0.0)),
+            RoutingStrategy::Weighted => {
+                let total: f64 = candidates.iter().map(|c| c.weight).sum();
+                let mut point = random_unit() * total;
+                candidates
+                    .iter()
+                    .position(|c| {
+                        point -= c.weight;
+                        point < 0.0
+                    })
+                    .unwrap_or(0)
+            }
+        };
+        let name = serde_json::to_value(strategy)
+            .ok()
+            .and_then(|v| v.as_str().map(String::from))
+            .unwrap_or_default();
+        (index, name)
+    }
+
+    /// Pick a target for a request without sending it.
+    pub fn route(
+        &self,
+        messages: &[Message],
+        tools: &[Tool],
+        options: &GenerateOptions,
+    ) -> error::Result<RoutingDecision> {
+        let request = RouteRequest::new(messages, tools, options);
+        let mut candidates = Vec::new();
+        let mut rejected = Vec::new();
+
+        for (index, target) in self.targets.iter().enumerate() {
+            let model = target.provider.model();
+            let cost = self.estimate_cost(model, &request);
+            if let Some(reason) = self.rejection(target, &request, cost) {
+                rejected.push(RejectedTarget {
+                    provider: target.provider.name().to_string(),
+                    model: model.to_string(),
+                    reason,
+                });
+                continue;
+            }
+            let stats = target.stats.lock().unwrap_or_else(|e| e.into_inner());
+            candidates.push(RouteCandidate {
+                index,
+                provider: target.provider.name().to_string(),
+                model: model.to_string(),
+                weight: target.weight,
+                context_window: crate::catalog::read().context_window(model),
+                estimated_cost_usd: cost,
+                latency_ewma_ms: stats.latency_ewma_ms,
+                error_rate: stats.error_rate,
+            });
+        }
+
+        if candidates.is_empty() {
+            let reasons: Vec<String> = rejected
+                .iter()
+                .map(|r| format!("{}/{}: {}", r.provider, r.model, r.reason))
+                .collect();
+            return Err(GaussError::provider(
+                "router",
+                format!("No eligible target ({})", reasons.join("; ")),
+            ));
+        }
+
+        let (chosen, strategy) = self.choose(&request, &candidates);
+        let candidate = &candidates[chosen];
+        let decision = RoutingDecision {
+            index: candidate.index,
+            provider: candidate.provider.clone(),
+            model: candidate.model.clone(),
+            strategy,
+            estimated_input_tokens: request.estimated_input_tokens,
+            estimated_cost_usd: candidate.estimated_cost_usd,
+            rejected,
+        };
+        debug!(
+            provider = %decision.provider,
+            model = %decision.model,
+            strategy = %decision.strategy,
+            "Routed request"
+        );
+        Ok(decision)
+    }
+
+    fn record(&self, index: usize, started_ms: u64, failed: bool) {
+        let elapsed_ms = self.clock.now_ms().saturating_sub(started_ms) as f64;
+        let alpha = self.policy.ewma_alpha.clamp(0.0, 1.0);
+        let mut stats = self.targets[index]
+            .stats
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        stats.requests += 1;
+        stats.error_rate =
+            alpha * if failed { 1.0 } else { 0.0 } + (1.0 - alpha) * stats.error_rate;
+        if failed {
+            stats.errors += 1;
+            stats.last_error_ms = Some(self.clock.now_ms());
+        } else {
+            stats.latency_ewma_ms = Some(match stats.latency_ewma_ms {
+                Some(prev) => alpha * elapsed_ms + (1.0 - alpha) * prev,
+                None => elapsed_ms,
+            });
+        }
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Provider for RouterProvider {
+    fn name(&self) -> &str {
+        "router"
+    }
+
+    /// The first target's model. The model that served a request is in
+    /// [`GenerateResult::routing`], or a [`StreamEvent::Routing`] event.
+    fn model(&self) -> &str {
+        self.targets
+            .first()
+            .map(|t| t.provider.model())
+            .unwrap_or("unknown")
+    }
+
+    /// Everything at least one target supports.
+    fn capabilities(&self) -> ProviderCapabilities {
+        self.targets
+            .iter()
+            .map(|t| t.provider.capabilities())
+            .fold(ProviderCapabilities::default(), union)
+    }
+
+    async fn generate(
+        &self,
+        messages: &[Message],
+        tools: &[Tool],
+        options: &GenerateOptions,
+    ) -> error::Result<GenerateResult> {
+        let decision = self.route(messages, tools, options)?;
+        let index = decision.index;
+        let started = self.clock.now_ms();
+        let result = self.targets[index]
+            .provider
+            .generate(messages, tools, options)
+            .await;
+        self.record(index, started, result.is_err());
+        let mut result = result?;
+        result.routing = Some(decision);
+        Ok(result)
+    }
+
+    /// Routes like `generate`; the decision is the first event. Latency is
+    /// measured to stream establishment.
+    async fn stream(
+        &self,
+        messages: &[Message],
+        tools: &[Tool],
+        options: &GenerateOptions,
+    ) -> error::Result<BoxStream> {
+        let decision = self.route(messages, tools, options)?;
+        let index = decision.index;
+        let started = self.clock.now_ms();
+        let stream = self.targets[index]
+            .provider
+            .stream(messages, tools, options)
+            .await;
+        self.record(index, started, stream.is_err());
+        let routing = futures::stream::iter([Ok(StreamEvent::Routing(decision))]);
+        Ok(Box::new(routing.chain(stream?)))
+    }
+}
@@ -2,17 +2,30 @@
 //!
 //! Extends the existing `RetryProvider` with additional resilience patterns:
 //! - `FallbackProvider`: try multiple providers in order
+//! - `HedgedProvider`: race a backup provider when the first is slow
 //! - `CircuitBreaker`: prevent cascade failures with open/half-open/closed states
 //! - `ResilientProvider`: compose retry + fallback + circuit breaker
 
 use async_trait::async_trait;
+use futures::future::{self, Either};
+use futures::stream::{FuturesUnordered, StreamExt};
+use std::collections::VecDeque;
+use std::future::Future;
+use std::sync::Mutex;
 use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
 use std::time::Duration;
-use tracing::warn;
+use tracing::{debug, warn};
 
 use crate::error::{self, GaussError};
 use crate::message::Message;
-use crate::provider::{BoxStream, GenerateOptions, GenerateResult, Provider};
+use crate::metrics::MetricsRegistry;
+use crate::plugin::{GaussEvent, PluginRegistry};
+use crate::provider::clock::Clock;
+use crate::provider::ensemble::common_capabilities;
+use crate::provider::retry::sleep;
+use crate::provider::router::{RouteRequest, RoutingDecision};
+use crate::provider::{BoxStream, GenerateOptions, GenerateResult, Provider, ProviderCapabilities};
+use crate::streaming::StreamEvent;
 use crate::tool::Tool;
 
 // ---------------------------------------------------------------------------
@@ -23,6 +36,7 @@ use crate::tool::Tool;
 pub struct FallbackProvider {
     providers: Vec<crate::Shared<dyn Provider>>,
     fallback_on: FallbackPolicy,
+    plugins: Option<crate::Shared<PluginRegistry>>,
 }
 
 /// Policy for when to fall back to the next provider.
@@ -49,6 +63,7 @@ impl FallbackProvider {
         Self {
             providers,
             fallback_on: FallbackPolicy::default(),
+            plugins: None,
         }
     }
 
@@ -57,6 +72,24 @@ impl FallbackProvider {
         self
     }
 
+    /// Publish a `ProviderFallback` event whenever the chain moves on.
+    pub fn with_plugins(mut self, registry: crate::Shared<PluginRegistry>) -> Self {
+        self.plugins = Some(registry);
+        self
+    }
+
+    async fn record_fallback(&self, from: &dyn Provider, to: &dyn Provider, error: &GaussError) {
+        if let Some(ref plugins) = self.plugins {
+            plugins
+                .dispatch(&GaussEvent::ProviderFallback {
+                    from_provider: from.name().to_string(),
+                    to_provider: to.name().to_string(),
+                    error: error.to_string(),
+                })
+                .await;
+        }
+    }
+
     fn should_fallback(&self, error: &GaussError) -> bool {
         match &self.fallback_on {
             FallbackPolicy::OnAnyError => true,
@@ -115,6 +148,8 @@ impl Provider for FallbackProvider {
                             error = %e,
                             "Falling back to next provider"
                         );
+                        self.record_fallback(provider.as_ref(), self.providers[i + 1].as_ref(), &e)
+                            .await;
                         last_error = Some(e);
                     } else {
                         return Err(e);
@@ -144,6 +179,8 @@ impl Provider for FallbackProvider {
                             error = %e,
                             "Falling back to next provider (stream)"
                         );
+                        self.record_fallback(provider.as_ref(), self.providers[i + 1].as_ref(), &e)
+                            .await;
                         last_error = Some(e);
                     } else {
                         return Err(e);
@@ -156,6 +193,213 @@ impl Provider for FallbackProvider {
     }
 }
 
+// ---------------------------------------------------------------------------
+// HedgedProvider
+// ---------------------------------------------------------------------------
+
+/// Latency samples kept for [`HedgeDelay::Percentile`].
+const HEDGE_WINDOW: usize = 100;
+/// Samples needed before the percentile replaces the initial delay.
+const HEDGE_MIN_SAMPLES: usize = 10;
+
+/// How long to wait for a response before hedging to the next provider.
+#[derive(Debug, Clone, Copy)]
+pub enum HedgeDelay {
+    Fixed(Duration),
+    /// The given percentile (0.0–1.0) of recent winning latencies, or
+    /// `initial` until enough samples have been seen.
+    Percentile {
+        percentile: f64,
+        initial: Duration,
+    },
+}
+
+impl Default for HedgeDelay {
+    fn default() -> Self {
+        Self::Percentile {
+            percentile: 0.95,
+            initial: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Sends the request to the first provider and, if it has not answered
+/// within the hedge delay, to the next one as well. The first success wins
+/// and the requests still in flight are dropped (cancelled). A failure
+/// starts the next provider immediately.
+///
+/// For streams, the race is to an established stream rather than to the
+/// full response. The winner is reported in `routing` (or a leading
+/// [`StreamEvent::Routing`] event).
+pub struct HedgedProvider {
+    providers: Vec<crate::Shared<dyn Provider>>,
+    delay: HedgeDelay,
+    clock: Clock,
+    latencies: Mutex<VecDeque<u64>>,
+    hedges: AtomicU64,
+}
+
+impl HedgedProvider {
+    pub fn new(providers: Vec<crate::Shared<dyn Provider>>) -> Self {
+        Self {
+            providers,
+            delay: HedgeDelay::default(),
+            clock: Clock::new(),
+            latencies: Mutex::new(VecDeque::with_capacity(HEDGE_WINDOW)),
+            hedges: AtomicU64::new(0),
+        }
+    }
+
+    pub fn with_delay(mut self, delay: HedgeDelay) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// The delay the next request will wait before hedging.
+    pub fn current_delay(&self) -> Duration {
+        match self.delay {
+            HedgeDelay::Fixed(delay) => delay,
+            HedgeDelay::Percentile {
+                percentile,
+                initial,
+            } => {
+                let latencies = self.latencies.lock().unwrap_or_else(|e| e.into_inner());
+                if latencies.len() < HEDGE_MIN_SAMPLES {
+                    return initial;
+                }
+                let mut sorted: Vec<u64> = latencies.iter().copied().collect();
+                sorted.sort_unstable();
+                let rank = (percentile.clamp(0.0, 1.0) * sorted.len() as f64).ceil() as usize;
+                Duration::from_millis(sorted[rank.clamp(1, sorted.len()) - 1])
+            }
+        }
+    }
+
+    /// Number of hedge requests sent so far.
+    pub fn hedge_count(&self) -> u64 {
+        self.hedges.load(Ordering::Relaxed)
+    }
+
+    fn record_latency(&self, ms: u64) {
+        let mut latencies = self.latencies.lock().unwrap_or_else(|e| e.into_inner());
+        if latencies.len() == HEDGE_WINDOW {
+            latencies.pop_front();
+        }
+        latencies.push_back(ms);
+    }
+
+    /// The index of the winning provider and its result.
+    async fn race<'a, T, F, Fut>(&'a self, call: F) -> error::Result<(usize, T)>
+    where
+        F: Fn(&'a dyn Provider) -> Fut,
+        Fut: Future<Output = error::Result<T>> + 'a,
+    {
+        let launch = |i: usize| {
+            let started = self.clock.now_ms();
+            let call = call(self.providers[i].as_ref());
+            async move { (i, started, call.await) }
+        };
+
+        let mut in_flight = FuturesUnordered::new();
+        let mut next = 0;
+        let mut last_error = None;
+        loop {
+            if in_flight.is_empty() {
+                if next == self.providers.len() {
+                    return Err(last_error.unwrap_or_else(|| {
+                        GaussError::internal("No providers configured for hedging")
+                    }));
+                }
+                in_flight.push(launch(next));
+                next += 1;
+            }
+
+            let finished = if next < self.providers.len() {
+                let timer = Box::pin(sleep(self.current_delay()));
+                match future::select(in_flight.next(), timer).await {
+                    Either::Left((finished, _)) => finished,
+                    Either::Right(_) => None,
+                }
+            } else {
+                in_flight.next().await
+            };
+
+            match finished {
+                Some((i, started, Ok(value))) => {
+                    self.record_latency(self.clock.now_ms().saturating_sub(started));
+                    if i > 0 {
+                        debug!(provider = self.providers[i].name(), "Hedged request won");
+                    }
+                    return Ok((i, value));
+                }
+                Some((i, _, Err(e))) => {
+                    warn!(provider = self.providers[i].name(), error = %e, "Hedged request failed");
+                    last_error = Some(e);
+                }
+                None => {}
+            }
+            // Timed out or failed: bring in the next provider now.
+            if next < self.providers.len() {
+                debug!(provider = self.providers[next].name(), "Hedging request");
+                self.hedges.fetch_add(1, Ordering::Relaxed);
+                in_flight.push(launch(next));
+                next += 1;
+            }
+        }
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Provider for HedgedProvider {
+    fn name(&self) -> &str {
+        "hedged"
+    }
+
+    /// The first provider's model. The model that won is in
+    /// [`GenerateResult::routing`], or a [`StreamEvent::Routing`] event.
+    fn model(&self) -> &str {
+        self.providers
+            .first()
+            .map(|p| p.model())
+            .unwrap_or("unknown")
+    }
+
+    /// What every provider supports, since any of them may answer.
+    fn capabilities(&self) -> ProviderCapabilities {
+        common_capabilities(&self.providers)
+    }
+
+    async fn generate(
+        &self,
+        messages: &[Message],
+        tools: &[Tool],
+        options: &GenerateOptions,
+    ) -> error::Result<GenerateResult> {
+        let (index, mut result) = self.race(|p| p.generate(messages, tools, options)).await?;
+        // A winning router already knows the exact model.
+        result.routing.get_or_insert_with(|| {
+            let request = RouteRequest::new(messages, tools, options);
+            RoutingDecision::picked(index, self.providers[index].as_ref(), "hedged", &request)
+        });
+        Ok(result)
+    }
+
+    async fn stream(
+        &self,
+        messages: &[Message],
+        tools: &[Tool],
+        options: &GenerateOptions,
+    ) -> error::Result<BoxStream> {
+        let (index, stream) = self.race(|p| p.stream(messages, tools, options)).await?;
+        let request = RouteRequest::new(messages, tools, options);
+        let decision =
+            RoutingDecision::picked(index, self.providers[index].as_ref(), "hedged", &request);
+        let routing = futures::stream::iter([Ok(StreamEvent::Routing(decision))]);
+        Ok(Box::new(routing.chain(stream)))
+    }
+}
+
 // ---------------------------------------------------------------------------
 // CircuitBreaker
 // ---------------------------------------------------------------------------
@@ -168,6 +412,7 @@ pub struct CircuitBreaker {
     failure_count: AtomicU32,
     last_failure_time: AtomicU64,
     state: std::sync::atomic::AtomicU8,
+    metrics: Option<crate::Shared<MetricsRegistry>>,
 }
 
 /// Configuration for circuit breaker behavior.
@@ -204,6 +449,7 @@ impl CircuitBreaker {
             failure_count: AtomicU32::new(0),
             last_failure_time: AtomicU64::new(0),
             state: std::sync::atomic::AtomicU8::new(STATE_CLOSED),
+            metrics: None,
         }
     }
 
@@ -211,6 +457,19 @@ impl CircuitBreaker {
         Self::new(inner, CircuitBreakerConfig::default())
     }
 
+    /// Publish the breaker state to a metrics registry on every call.
+    pub fn with_metrics(mut self, registry: crate::Shared<MetricsRegistry>) -> Self {
+        registry.record_circuit_state(self.inner.name(), self.inner.model(), "closed");
+        self.metrics = Some(registry);
+        self
+    }
+
+    fn publish_state(&self) {
+        if let Some(ref metrics) = self.metrics {
+            metrics.record_circuit_state(self.inner.name(), self.inner.model(), self.state_name());
+        }
+    }
+
     fn now_ms() -> u64 {
         std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
@@ -233,6 +492,7 @@ impl CircuitBreaker {
     fn record_success(&self) {
         self.failure_count.store(0, Ordering::Relaxed);
         self.state.store(STATE_CLOSED, Ordering::Relaxed);
+        self.publish_state();
     }
 
     fn record_failure(&self) {
@@ -242,6 +502,7 @@ impl CircuitBreaker {
         if failures >= self.config.failure_threshold {
             self.state.store(STATE_OPEN, Ordering::Relaxed);
         }
+        self.publish_state();
     }
 
     /// Get the current state as a human-readable string.
@@ -273,6 +534,7 @@ impl Provider for CircuitBreaker {
         options: &GenerateOptions,
     ) -> error::Result<GenerateResult> {
         let state = self.current_state();
+        self.publish_state();
         if state == STATE_OPEN {
             return Err(GaussError::internal(format!(
                 "Circuit breaker is open for provider '{}'",
@@ -299,6 +561,7 @@ impl Provider for CircuitBreaker {
         options: &GenerateOptions,
     ) -> error::Result<BoxStream> {
         let state = self.current_state();
+        self.publish_state();
         if state == STATE_OPEN {
             return Err(GaussError::internal(format!(
                 "Circuit breaker is open for provider '{}'",
@@ -330,6 +593,8 @@ pub struct ResilientProviderBuilder {
     retry_config: Option<crate::provider::retry::RetryConfig>,
     circuit_breaker_config: Option<CircuitBreakerConfig>,
     fallback_policy: FallbackPolicy,
+    metrics: Option<crate::Shared<MetricsRegistry>>,
+    plugins: Option<crate::Shared<PluginRegistry>>,
 }
 
 impl ResilientProviderBuilder {
@@ -340,6 +605,8 @@ impl ResilientProviderBuilder {
             retry_config: None,
             circuit_breaker_config: None,
             fallback_policy: FallbackPolicy::default(),
+            metrics: None,
+            plugins: None,
         }
     }
 
@@ -367,6 +634,18 @@ impl ResilientProviderBuilder {
         self
     }
 
+    /// Report retries and circuit-breaker state to a metrics registry.
+    pub fn metrics(mut self, registry: crate::Shared<MetricsRegistry>) -> Self {
+        self.metrics = Some(registry);
+        self
+    }
+
+    /// Publish retry and fallback events to a plugin registry.
+    pub fn plugins(mut self, registry: crate::Shared<PluginRegistry>) -> Self {
+        self.plugins = Some(registry);
+        self
+    }
+
     /// Build the composed resilient provider.
     /// Wrapping order: CircuitBreaker → Retry → Fallback (innermost to outermost).
     pub fn build(self) -> crate::Shared<dyn Provider> {
@@ -374,23 +653,34 @@ impl ResilientProviderBuilder {
 
         // Wrap with circuit breaker (innermost)
         if let Some(cb_config) = self.circuit_breaker_config {
-            provider = crate::Shared::new(CircuitBreaker::new(provider, cb_config));
+            let mut breaker = CircuitBreaker::new(provider, cb_config);
+            if let Some(ref metrics) = self.metrics {
+                breaker = breaker.with_metrics(metrics.clone());
+            }
+            provider = crate::Shared::new(breaker);
         }
 
         // Wrap with retry
         if let Some(retry_config) = self.retry_config {
-            provider = crate::Shared::new(crate::provider::retry::RetryProvider::new(
-                provider,
-                retry_config,
-            ));
+            let mut retry = crate::provider::retry::RetryProvider::new(provider, retry_config);
+            if let Some(ref metrics) = self.metrics {
+                retry = retry.with_metrics(metrics.clone());
+            }
+            if let Some(ref plugins) = self.plugins {
+                retry = retry.with_plugins(plugins.clone());
+            }
+            provider = crate::Shared::new(retry);
         }
 
         // Wrap with fallback (outermost)
         if !self.fallbacks.is_empty() {
             let mut all = vec![provider];
             all.extend(self.fallbacks);
-            provider =
-                crate::Shared::new(FallbackProvider::new(all).with_policy(self.fallback_policy));
+            let mut fallback = FallbackProvider::new(all).with_policy(self.fallback_policy);
+            if let Some(plugins) = self.plugins {
+                fallback = fallback.with_plugins(plugins);
+            }
+            provider = crate::Shared::new(fallback);
         }
 
         provider
@@ -1,7 +1,8 @@
 use serde::{Deserialize, Serialize};
 
-use crate::message::Usage;
+use crate::message::{Citation, Usage};
 use crate::provider::FinishReason;
+use crate::provider::router::RoutingDecision;
 
 /// Events emitted during streaming generation.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,6 +14,18 @@ pub enum StreamEvent {
     /// A reasoning/thinking delta.
     ReasoningDelta(String),
 
+    /// What is needed to replay the reasoning streamed so far on a later
+    /// turn: the provider's item id and/or its encrypted content or signature.
+    ReasoningItem {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        id: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        encrypted_content: Option<String>,
+    },
+
+    /// A source cited by the generated text.
+    Citation(Citation),
+
     /// A partial tool call update.
     ToolCallDelta {
         index: usize,
@@ -30,6 +43,12 @@ pub enum StreamEvent {
     /// Token usage statistics.
     Usage(Usage),
 
+    /// Provider-specific metadata, as in `GenerateResult::provider_metadata`.
+    ProviderMetadata(serde_json::Value),
+
+    /// The target a router chose, as in `GenerateResult::routing`.
+    Routing(RoutingDecision),
+
     /// A partial structured output object (incremental JSON parse).
     ObjectDelta(serde_json::Value),
 
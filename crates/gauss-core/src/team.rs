@@ -6,8 +6,11 @@
 //! - Supervisor: a lead agent delegates to specialized agents
 
 use crate::agent::{Agent, AgentOutput};
+use crate::cost::RunCost;
 use crate::error::{self, GaussError};
 use crate::message::Message;
+use crate::plugin::{OrchestratorRun, PluginRegistry};
+use crate::telemetry::{SpanBuilder, SpanRecord, SpanType, TelemetryCollector, TraceContext};
 
 /// Team coordination strategy.
 #[derive(Debug, Clone)]
@@ -23,6 +26,22 @@ pub enum Strategy {
 pub struct TeamOutput {
     pub results: Vec<AgentOutput>,
     pub final_text: String,
+    /// Estimated cost of all member runs combined.
+    pub cost: RunCost,
+}
+
+impl TeamOutput {
+    fn new(results: Vec<AgentOutput>, final_text: String) -> Self {
+        let mut cost = RunCost::default();
+        for result in &results {
+            cost.merge(&result.cost);
+        }
+        Self {
+            results,
+            final_text,
+            cost,
+        }
+    }
 }
 
 /// A team of agents.
@@ -30,6 +49,8 @@ pub struct Team {
     name: String,
     agents: Vec<Agent>,
     strategy: Strategy,
+    telemetry: Option<crate::Shared<TelemetryCollector>>,
+    plugins: Option<crate::Shared<PluginRegistry>>,
 }
 
 impl Team {
@@ -38,18 +59,54 @@ impl Team {
             name: name.into(),
             agents: Vec::new(),
             strategy: Strategy::Sequential,
+            telemetry: None,
+            plugins: None,
         }
     }
 
     /// Run the team with initial messages.
     pub async fn run(&self, messages: Vec<Message>) -> error::Result<TeamOutput> {
+        let lifecycle = OrchestratorRun::start(self.plugins.as_ref(), "team", &self.name).await;
+        let result = self.run_instrumented(messages).await;
+        lifecycle.finish(result.is_err()).await;
+        result
+    }
+
+    async fn run_instrumented(&self, messages: Vec<Message>) -> error::Result<TeamOutput> {
+        let Some(ref telemetry) = self.telemetry else {
+            return self.run_strategy(messages, None, &mut Vec::new()).await;
+        };
+        let context = crate::telemetry::child_of_current();
+        let mut spans = Vec::new();
+        let result = self
+            .run_strategy(messages, Some(&context), &mut spans)
+            .await;
+        let span = SpanBuilder::new(format!("team {}", self.name), SpanType::Custom)
+            .context(context)
+            .attribute("team", self.name.clone())
+            .attribute("agents", self.agents.len());
+        telemetry.record_run(crate::telemetry::finish_span(span, spans, &result));
+        result
+    }
+
+    async fn run_strategy(
+        &self,
+        messages: Vec<Message>,
+        trace: Option<&TraceContext>,
+        spans: &mut Vec<SpanRecord>,
+    ) -> error::Result<TeamOutput> {
         match self.strategy {
-            Strategy::Sequential => self.run_sequential(messages).await,
-            Strategy::Parallel => self.run_parallel(messages).await,
+            Strategy::Sequential => self.run_sequential(messages, trace, spans).await,
+            Strategy::Parallel => self.run_parallel(messages, trace, spans).await,
         }
     }
 
-    async fn run_sequential(&self, messages: Vec<Message>) -> error::Result<TeamOutput> {
+    async fn run_sequential(
+        &self,
+        messages: Vec<Message>,
+        trace: Option<&TraceContext>,
+        spans: &mut Vec<SpanRecord>,
+    ) -> error::Result<TeamOutput> {
         if self.agents.is_empty() {
             return Err(GaussError::Agent {
                 message: format!("Team '{}' has no agents", self.name),
@@ -61,21 +118,23 @@ impl Team {
         let mut current_messages = messages;
 
         for agent in &self.agents {
-            let output = agent.run(current_messages).await?;
+            let output = agent.run_child(current_messages, trace, spans).await?;
             current_messages = vec![Message::user(&output.text)];
             results.push(output);
         }
 
         let final_text = results.last().map(|r| r.text.clone()).unwrap_or_default();
 
-        Ok(TeamOutput {
-            results,
-            final_text,
-        })
+        Ok(TeamOutput::new(results, final_text))
     }
 
     #[cfg(feature = "native")]
-    async fn run_parallel(&self, messages: Vec<Message>) -> error::Result<TeamOutput> {
+    async fn run_parallel(
+        &self,
+        messages: Vec<Message>,
+        trace: Option<&TraceContext>,
+        spans: &mut Vec<SpanRecord>,
+    ) -> error::Result<TeamOutput> {
         if self.agents.is_empty() {
             return Err(GaussError::Agent {
                 message: format!("Team '{}' has no agents", self.name),
@@ -88,21 +147,27 @@ impl Team {
         for agent in &self.agents {
             let msgs = messages.clone();
             let agent_clone = agent.clone();
-            handles.push(tokio::spawn(async move { agent_clone.run(msgs).await }));
+            let parent = trace.cloned();
+            handles.push(tokio::spawn(async move {
+                let mut spans = Vec::new();
+                let result = agent_clone
+                    .run_child(msgs, parent.as_ref(), &mut spans)
+                    .await;
+                (result, spans)
+            }));
         }
 
         let mut results = Vec::new();
         for handle in handles {
-            let output = handle
-                .await
-                .map_err(|e| GaussError::Agent {
-                    message: format!("Team task failed: {e}"),
-                    source: None,
-                })?
-                .map_err(|e| GaussError::Agent {
-                    message: format!("Agent failed: {e}"),
-                    source: None,
-                })?;
+            let (output, agent_spans) = handle.await.map_err(|e| GaussError::Agent {
+                message: format!("Team task failed: {e}"),
+                source: None,
+            })?;
+            spans.extend(agent_spans);
+            let output = output.map_err(|e| GaussError::Agent {
+                message: format!("Agent failed: {e}"),
+                source: None,
+            })?;
             results.push(output);
         }
 
@@ -112,16 +177,18 @@ impl Team {
             .collect::<Vec<_>>()
             .join("\n\n---\n\n");
 
-        Ok(TeamOutput {
-            results,
-            final_text,
-        })
+        Ok(TeamOutput::new(results, final_text))
     }
 
     #[cfg(not(feature = "native"))]
-    async fn run_parallel(&self, messages: Vec<Message>) -> error::Result<TeamOutput> {
+    async fn run_parallel(
+        &self,
+        messages: Vec<Message>,
+        trace: Option<&TraceContext>,
+        spans: &mut Vec<SpanRecord>,
+    ) -> error::Result<TeamOutput> {
         // Without tokio, fall back to sequential execution
-        self.run_sequential(messages).await
+        self.run_sequential(messages, trace, spans).await
     }
 }
 
@@ -130,6 +197,8 @@ pub struct TeamBuilder {
     name: String,
     agents: Vec<Agent>,
     strategy: Strategy,
+    telemetry: Option<crate::Shared<TelemetryCollector>>,
+    plugins: Option<crate::Shared<PluginRegistry>>,
 }
 
 impl TeamBuilder {
@@ -145,12 +214,32 @@ impl TeamBuilder {
         self
     }
 
+    /// Record a team span with each member's run nested under it.
+    pub fn telemetry(mut self, collector: crate::Shared<TelemetryCollector>) -> Self {
+        self.telemetry = Some(collector);
+        self
+    }
+
+    /// Publish team start/finish events, and member agents' events unless
+    /// they have their own registry.
+    pub fn plugins(mut self, registry: crate::Shared<PluginRegistry>) -> Self {
+        self.plugins = Some(registry);
+        self
+    }
+
     /// Build the team.
-    pub fn build(self) -> Team {
+    pub fn build(mut self) -> Team {
+        if let Some(ref plugins) = self.plugins {
+            for agent in &mut self.agents {
+                agent.inherit_plugins(plugins);
+            }
+        }
         Team {
             name: self.name,
             agents: self.agents,
             strategy: self.strategy,
+            telemetry: self.telemetry,
+            plugins: self.plugins,
         }
     }
 }
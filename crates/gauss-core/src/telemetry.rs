@@ -1,11 +1,155 @@
 //! Telemetry & observability — span-based tracing, metrics collection.
 //!
 //! Uses the `tracing` crate ecosystem for structured logging and spans.
-//! Can bridge to OpenTelemetry via `tracing-opentelemetry`.
+//! Spans carry W3C trace context and can be exported over OTLP with the
+//! `otel` feature (see [`crate::otel`]).
 
+use crate::metrics::MetricsRegistry;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::time::Instant;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+// ---------------------------------------------------------------------------
+// Trace context
+// ---------------------------------------------------------------------------
+
+/// W3C trace context (`traceparent`) identifying a span within a trace.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceContext {
+    /// 32 lowercase hex characters.
+    pub trace_id: String,
+    /// 16 lowercase hex characters.
+    pub span_id: String,
+    pub sampled: bool,
+}
+
+#[cfg(all(feature = "native", not(target_arch = "wasm32")))]
+tokio::task_local! {
+    static CURRENT_TRACE: TraceContext;
+}
+
+impl TraceContext {
+    /// Start a new trace.
+    pub fn new_root() -> Self {
+        Self {
+            trace_id: uuid::Uuid::new_v4().simple().to_string(),
+            span_id: new_span_id(),
+            sampled: true,
+        }
+    }
+
+    /// A new span in the same trace.
+    pub fn child(&self) -> Self {
+        Self {
+            trace_id: self.trace_id.clone(),
+            span_id: new_span_id(),
+            sampled: self.sampled,
+        }
+    }
+
+    /// Format as a `traceparent` header value.
+    pub fn traceparent(&self) -> String {
+        let flags = if self.sampled { "01" } else { "00" };
+        format!("00-{}-{}-{flags}", self.trace_id, self.span_id)
+    }
+
+    /// Parse a `traceparent` header value (version `00` only).
+    pub fn from_traceparent(header: &str) -> Option<Self> {
+        let mut parts = header.trim().split('-');
+        let (version, trace_id, span_id, flags) =
+            (parts.next()?, parts.next()?, parts.next()?, parts.next()?);
+        let is_hex = |s: &str, len: usize| {
+            s.len() == len && s.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+        };
+        if parts.next().is_some()
+            || version != "00"
+            || !is_hex(trace_id, 32)
+            || !is_hex(span_id, 16)
+            || !is_hex(flags, 2)
+            || trace_id.bytes().all(|b| b == b'0')
+            || span_id.bytes().all(|b| b == b'0')
+        {
+            return None;
+        }
+        Some(Self {
+            trace_id: trace_id.to_string(),
+            span_id: span_id.to_string(),
+            sampled: u8::from_str_radix(flags, 16).ok()? & 1 == 1,
+        })
+    }
+
+    /// The context of the span the current task is running in, if any.
+    #[cfg(all(feature = "native", not(target_arch = "wasm32")))]
+    pub fn current() -> Option<Self> {
+        CURRENT_TRACE.try_with(Clone::clone).ok()
+    }
+
+    /// Run a future with this context as the current span, so outgoing
+    /// provider, A2A and MCP requests carry it.
+    #[cfg(all(feature = "native", not(target_arch = "wasm32")))]
+    pub async fn scope<F: std::future::Future>(self, f: F) -> F::Output {
+        CURRENT_TRACE.scope(self, f).await
+    }
+}
+
+/// A child of the current task's trace context, or a new root.
+pub(crate) fn child_of_current() -> TraceContext {
+    #[cfg(all(feature = "native", not(target_arch = "wasm32")))]
+    if let Some(parent) = TraceContext::current() {
+        return parent.child();
+    }
+    TraceContext::new_root()
+}
+
+/// Run a future with `context` as the current trace context, if given.
+pub(crate) async fn in_trace_scope<F: std::future::Future>(
+    context: Option<TraceContext>,
+    f: F,
+) -> F::Output {
+    #[cfg(all(feature = "native", not(target_arch = "wasm32")))]
+    if let Some(context) = context {
+        return context.scope(f).await;
+    }
+    let _ = context;
+    f.await
+}
+
+/// Close an orchestrator-level span over its children, marking it failed
+/// if `result` is an error.
+pub(crate) fn finish_span<T>(
+    span: SpanBuilder,
+    children: Vec<SpanRecord>,
+    result: &crate::error::Result<T>,
+) -> SpanRecord {
+    let span = children.into_iter().fold(span, SpanBuilder::child);
+    match result {
+        Ok(_) => span.finish(),
+        Err(e) => span.finish_with_error(e.to_string()),
+    }
+}
+
+fn new_span_id() -> String {
+    let mut id = uuid::Uuid::new_v4().simple().to_string();
+    id.truncate(16);
+    id
+}
+
+/// Attach the current trace context as a `traceparent` header (with the
+/// `otel` feature; a no-op otherwise).
+pub(crate) fn inject_trace_context(req: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+    #[cfg(all(feature = "otel", not(target_arch = "wasm32")))]
+    if let Some(ctx) = TraceContext::current() {
+        return req.header("traceparent", ctx.traceparent());
+    }
+    req
+}
+
+pub(crate) fn unix_nanos() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0)
+}
 
 // ---------------------------------------------------------------------------
 // Span & Event types
@@ -23,12 +167,23 @@ pub struct SpanRecord {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
     pub children: Vec<SpanRecord>,
+    /// Wall-clock start time (nanoseconds since the Unix epoch).
+    #[serde(default)]
+    pub start_unix_nano: u64,
+    /// Trace context of this span, if one was assigned.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<TraceContext>,
+    /// Span id of a remote parent (e.g. from an incoming `traceparent`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_span_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum SpanType {
     AgentRun,
+    /// One iteration of the agent loop (model call plus tool calls).
+    AgentStep,
     ModelCall,
     ToolCall,
     WorkflowStep,
@@ -117,8 +272,11 @@ pub struct SpanBuilder {
     name: String,
     span_type: SpanType,
     start: Instant,
+    start_unix_nano: u64,
     attributes: HashMap<String, serde_json::Value>,
     children: Vec<SpanRecord>,
+    context: Option<TraceContext>,
+    parent_span_id: Option<String>,
 }
 
 impl SpanBuilder {
@@ -127,11 +285,26 @@ impl SpanBuilder {
             name: name.into(),
             span_type,
             start: Instant::now(),
+            start_unix_nano: unix_nanos(),
             attributes: HashMap::new(),
             children: Vec::new(),
+            context: None,
+            parent_span_id: None,
         }
     }
 
+    /// Assign the span's trace context.
+    pub fn context(mut self, context: TraceContext) -> Self {
+        self.context = Some(context);
+        self
+    }
+
+    /// Link this span to a remote parent span.
+    pub fn parent_span_id(mut self, span_id: impl Into<String>) -> Self {
+        self.parent_span_id = Some(span_id.into());
+        self
+    }
+
     pub fn attribute(
         mut self,
         key: impl Into<String>,
@@ -148,29 +321,27 @@ impl SpanBuilder {
 
     /// Finish the span successfully.
     pub fn finish(self) -> SpanRecord {
-        SpanRecord {
-            name: self.name,
-            span_type: self.span_type,
-            start_ms: 0, // relative to run start
-            duration_ms: self.start.elapsed().as_millis() as u64,
-            attributes: self.attributes,
-            status: SpanStatus::Ok,
-            error: None,
-            children: self.children,
-        }
+        self.finish_with(SpanStatus::Ok, None)
     }
 
     /// Finish the span with an error.
     pub fn finish_with_error(self, error: impl Into<String>) -> SpanRecord {
+        self.finish_with(SpanStatus::Error, Some(error.into()))
+    }
+
+    fn finish_with(self, status: SpanStatus, error: Option<String>) -> SpanRecord {
         SpanRecord {
             name: self.name,
             span_type: self.span_type,
-            start_ms: 0,
+            start_ms: 0, // relative to run start
             duration_ms: self.start.elapsed().as_millis() as u64,
             attributes: self.attributes,
-            status: SpanStatus::Error,
-            error: Some(error.into()),
+            status,
+            error,
             children: self.children,
+            start_unix_nano: self.start_unix_nano,
+            context: self.context,
+            parent_span_id: self.parent_span_id,
         }
     }
 }
@@ -184,6 +355,7 @@ impl SpanBuilder {
 pub struct TelemetryCollector {
     spans: std::sync::Mutex<Vec<SpanRecord>>,
     metrics: std::sync::Mutex<AgentMetrics>,
+    registry: Option<crate::Shared<MetricsRegistry>>,
 }
 
 impl TelemetryCollector {
@@ -191,6 +363,12 @@ impl TelemetryCollector {
         Self::default()
     }
 
+    /// Also feed every recorded run into a Prometheus metrics registry.
+    pub fn with_registry(mut self, registry: crate::Shared<MetricsRegistry>) -> Self {
+        self.registry = Some(registry);
+        self
+    }
+
     /// Record a completed span.
     pub fn record_span(&self, span: SpanRecord) {
         if let Ok(mut spans) = self.spans.lock() {
@@ -198,6 +376,19 @@ impl TelemetryCollector {
         }
     }
 
+    /// Record a finished run and update metrics from its span tree: model
+    /// calls, tool calls, steps, errors and the run's duration.
+    pub fn record_run(&self, span: SpanRecord) {
+        self.with_metrics(|metrics| {
+            metrics.total_duration_ms += span.duration_ms;
+            accumulate_metrics(metrics, &span);
+        });
+        if let Some(ref registry) = self.registry {
+            registry.record_run(&span);
+        }
+        self.record_span(span);
+    }
+
     /// Get a mutable reference to metrics for recording.
     pub fn with_metrics<F, R>(&self, f: F) -> R
     where
@@ -212,6 +403,14 @@ impl TelemetryCollector {
         self.spans.lock().map(|s| s.clone()).unwrap_or_default()
     }
 
+    /// Remove and return all collected spans.
+    pub fn drain_spans(&self) -> Vec<SpanRecord> {
+        self.spans
+            .lock()
+            .map(|mut s| std::mem::take(&mut *s))
+            .unwrap_or_default()
+    }
+
     /// Export current metrics snapshot.
     pub fn export_metrics(&self) -> AgentMetrics {
         self.metrics.lock().map(|m| m.clone()).unwrap_or_default()
@@ -227,3 +426,35 @@ impl TelemetryCollector {
         }
     }
 }
+
+fn accumulate_metrics(metrics: &mut AgentMetrics, span: &SpanRecord) {
+    let tokens = |key: &str| {
+        span.attributes
+            .get(key)
+            .and_then(serde_json::Value::as_u64)
+            .unwrap_or(0)
+    };
+    match span.span_type {
+        SpanType::ModelCall => {
+            metrics.record_model_call(
+                span.duration_ms,
+                tokens("input_tokens"),
+                tokens("output_tokens"),
+            );
+            metrics.total_reasoning_tokens += tokens("reasoning_tokens");
+            metrics.total_cache_tokens += tokens("cache_read_tokens");
+        }
+        SpanType::ToolCall => metrics.record_tool_call(span.duration_ms),
+        SpanType::AgentStep => metrics.total_steps += 1,
+        _ => {}
+    }
+    // Errors are reported by the innermost failing span only.
+    if let Some(ref error) = span.error
+        && span.children.iter().all(|c| c.error.is_none())
+    {
+        metrics.record_error(error.clone());
+    }
+    for child in &span.children {
+        accumulate_metrics(metrics, child);
+    }
+}
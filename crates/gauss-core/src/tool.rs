@@ -67,6 +67,7 @@ pub struct Tool {
     pub tags: Vec<String>,
     /// Optional usage examples.
     pub examples: Vec<ToolExample>,
+    returns_artifacts: bool,
     execute: Option<ToolExecuteFn>,
 }
 
@@ -78,6 +79,7 @@ impl std::fmt::Debug for Tool {
             .field("parameters", &self.parameters)
             .field("tags", &self.tags)
             .field("examples", &self.examples)
+            .field("returns_artifacts", &self.returns_artifacts)
             .field("has_execute", &self.execute.is_some())
             .finish()
     }
@@ -91,6 +93,7 @@ impl Tool {
             parameters: ToolParameters::default(),
             tags: Vec::new(),
             examples: Vec::new(),
+            returns_artifacts: false,
             execute: None,
         }
     }
@@ -110,6 +113,11 @@ impl Tool {
         self.execute.is_some()
     }
 
+    /// Whether results of this tool may carry an `"artifacts"` array (see [`split_artifacts`]).
+    pub fn returns_artifacts(&self) -> bool {
+        self.returns_artifacts
+    }
+
     /// Check if this tool matches a search query (name, description, or tags).
     pub fn matches(&self, query: &str) -> bool {
         let q = query.to_lowercase();
@@ -133,6 +141,7 @@ pub struct ToolBuilder {
     parameters: ToolParameters,
     tags: Vec<String>,
     examples: Vec<ToolExample>,
+    returns_artifacts: bool,
     execute: Option<ToolExecuteFn>,
 }
 
@@ -164,6 +173,12 @@ impl ToolBuilder {
         self
     }
 
+    /// Mark this tool as producing file artifacts, so the agent forwards them to the model.
+    pub fn with_artifacts(mut self) -> Self {
+        self.returns_artifacts = true;
+        self
+    }
+
     #[cfg(not(target_arch = "wasm32"))]
     pub fn execute<F, Fut>(mut self, f: F) -> Self
     where
@@ -191,6 +206,7 @@ impl ToolBuilder {
             parameters: self.parameters,
             tags: self.tags,
             examples: self.examples,
+            returns_artifacts: self.returns_artifacts,
             execute: self.execute,
         }
     }
@@ -364,6 +380,99 @@ pub async fn batch_execute(
     results
 }
 
+// ── Tool Result Artifacts ───────────────────────────────────────
+
+/// Lift file artifacts out of a tool result into content parts the model can read.
+///
+/// Tools that produce files (e.g. code execution with a workspace) report them as
+/// `{"artifacts": [{"path", "media_type", "size", "data"}]}` with base64 `data`.
+/// The bytes are removed from `result` — so they are not echoed back to the model
+/// as JSON text — and returned as `Content::Image` for `image/*` types and as
+/// decoded `Content::Text` for text-like types. Other binaries only keep their
+/// metadata in `result`. Tool messages cannot carry these parts on most providers,
+/// so the agent sends them in a follow-up user message (see [`artifacts_message`]).
+pub fn split_artifacts(result: &mut serde_json::Value) -> Vec<crate::message::Content> {
+    use crate::message::Content;
+    use base64::Engine;
+
+    let Some(artifacts) = result.get_mut("artifacts").and_then(|a| a.as_array_mut()) else {
+        return Vec::new();
+    };
+
+    let mut parts = Vec::new();
+    for artifact in artifacts {
+        let Some(media_type) = artifact
+            .get("media_type")
+            .and_then(|m| m.as_str())
+            .map(String::from)
+        else {
+            continue;
+        };
+        let path = artifact
+            .get("path")
+            .and_then(|p| p.as_str())
+            .unwrap_or_default()
+            .to_string();
+        let Some(data) = artifact
+            .as_object_mut()
+            .and_then(|a| a.remove("data"))
+            .and_then(|d| d.as_str().map(String::from))
+        else {
+            continue;
+        };
+        if media_type.starts_with("image/") {
+            parts.push(Content::Text {
+                text: format!("{path} ({media_type}):"),
+            });
+            parts.push(Content::Image {
+                url: None,
+                base64: Some(data),
+                media_type: Some(media_type),
+            });
+        } else if is_text_media_type(&media_type) {
+            let Ok(bytes) = base64::engine::general_purpose::STANDARD.decode(data.trim()) else {
+                continue;
+            };
+            parts.push(Content::Text {
+                text: format!(
+                    "{path} ({media_type}):\n{}",
+                    String::from_utf8_lossy(&bytes)
+                ),
+            });
+        }
+    }
+    parts
+}
+
+fn is_text_media_type(media_type: &str) -> bool {
+    media_type.starts_with("text/")
+        || matches!(
+            media_type,
+            "application/json" | "application/xml" | "application/javascript"
+        )
+        || media_type.ends_with("+json")
+        || media_type.ends_with("+xml")
+}
+
+/// Wrap artifact parts from [`split_artifacts`] into a user message that follows the
+/// tool results of a step. Returns `None` when there is nothing to send.
+pub fn artifacts_message(parts: Vec<crate::message::Content>) -> Option<crate::message::Message> {
+    use crate::message::{Content, Message, Role};
+
+    if parts.is_empty() {
+        return None;
+    }
+    let mut content = vec![Content::Text {
+        text: "Files produced by the tool calls above:".to_string(),
+    }];
+    content.extend(parts);
+    Some(Message {
+        role: Role::User,
+        content,
+        name: None,
+    })
+}
+
 // ── Tests ───────────────────────────────────────────────────────
 
 #[cfg(test)]
@@ -402,6 +511,39 @@ mod tests {
         assert_eq!(tool.examples[0].description, "Add 2 + 3");
     }
 
+    #[test]
+    fn test_split_artifacts() {
+        let mut result = serde_json::json!({
+            "stdout": "",
+            "artifacts": [
+                {"path": "plot.png", "media_type": "image/png", "size": 3, "data": "AAAA"},
+                {"path": "out.csv", "media_type": "text/csv", "size": 4, "data": "YSxiCg=="},
+                {"path": "big.bin", "media_type": "application/octet-stream", "size": 99}
+            ]
+        });
+        let parts = split_artifacts(&mut result);
+        assert_eq!(parts.len(), 3);
+        assert!(matches!(
+            &parts[1],
+            crate::message::Content::Image { base64: Some(data), media_type: Some(mt), .. }
+                if data == "AAAA" && mt == "image/png"
+        ));
+        assert!(matches!(
+            &parts[2],
+            crate::message::Content::Text { text } if text == "out.csv (text/csv):\na,b\n"
+        ));
+        assert!(result["artifacts"][0].get("data").is_none());
+        assert_eq!(result["artifacts"][0]["path"], "plot.png");
+
+        let mut plain = serde_json::json!({"answer": 42});
+        assert!(split_artifacts(&mut plain).is_empty());
+
+        let message = artifacts_message(parts).unwrap();
+        assert_eq!(message.role, crate::message::Role::User);
+        assert_eq!(message.content.len(), 4);
+        assert!(artifacts_message(Vec::new()).is_none());
+    }
+
     #[test]
     fn test_tool_matches() {
         let tool = make_tool("calculator", "A math calculator", &["math", "utility"]);
@@ -0,0 +1,815 @@
+//! Sandboxed WebAssembly plugins loaded at runtime.
+//!
+//! A plugin is a WebAssembly component targeting the `gauss-plugin` world in
+//! `wit/plugin.wit`. It exports `manifest` and a JSON `handle(hook, payload)`
+//! dispatcher, and can import the host's `log` and `http-fetch` functions.
+//! [`WasmPluginHost`] (feature `wasm-runtime`) loads components from disk and
+//! exposes each one as:
+//!
+//! - a [`Plugin`](crate::plugin::Plugin), so [`PluginRegistry`](crate::plugin::PluginRegistry)
+//!   drives `init`/`shutdown` in dependency order and forwards the bus events
+//!   listed in the manifest,
+//! - a [`Guardrail`](crate::guardrail::Guardrail) when the manifest sets `"guardrail": true`,
+//! - a [`Middleware`](crate::middleware::Middleware) when it sets `"middleware": true`,
+//! - one [`Tool`](crate::tool::Tool) per entry in `"tools"`.
+//!
+//! Plugins get no capabilities unless [`PluginPermissions`] grants them: no
+//! filesystem, no environment variables, no sockets, and `http-fetch` only to
+//! allowlisted hosts (redirects included). Every hook call runs under a fuel
+//! budget and the instance's linear memory is capped.
+//!
+//! [`WasmPluginConfig`] is plain data and is always available on native targets.
+//!
+//! ```no_run
+//! # #[cfg(feature = "wasm-runtime")]
+//! # async fn example() -> gauss_core::error::Result<()> {
+//! use gauss_core::plugin::PluginRegistry;
+//! use gauss_core::wasm_plugin::{PluginPermissions, WasmPluginConfig, WasmPluginHost};
+//!
+//! let host = WasmPluginHost::new()?;
+//! let plugin = host
+//!     .load(
+//!         WasmPluginConfig::new("plugins/pii_filter.wasm")
+//!             .permissions(PluginPermissions::new().allow_http("api.example.com"))
+//!             .fuel(50_000_000),
+//!     )
+//!     .await?;
+//!
+//! let mut registry = PluginRegistry::new();
+//! registry.register(std::sync::Arc::new(plugin.clone()));
+//! registry.init_all().await?;
+//!
+//! let guardrail = plugin.guardrail();
+//! let tools = plugin.tools();
+//! # let _ = (guardrail, tools);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::middleware::MiddlewarePriority;
+
+/// Default fuel budget for a single hook call.
+pub const DEFAULT_PLUGIN_FUEL: u64 = 1_000_000_000;
+
+/// Default cap on plugin linear memory (64 MiB).
+pub const DEFAULT_PLUGIN_MAX_MEMORY_BYTES: usize = 64 * 1024 * 1024;
+
+/// Capabilities granted to a plugin. The default grants nothing.
+#[derive(Debug, Clone, Default)]
+pub struct PluginPermissions {
+    /// Hosts `http-fetch` may reach: `example.com`, `example.com:8080`, or
+    /// `*.example.com` for any subdomain.
+    pub http_allow: Vec<String>,
+    /// Read-only host directories mapped into the guest, as `(host, guest)` paths.
+    pub preopens: Vec<(PathBuf, String)>,
+    /// Environment variables visible to the guest.
+    pub env: Vec<(String, String)>,
+}
+
+impl PluginPermissions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allow `http-fetch` to reach `host` (see [`PluginPermissions::http_allow`]).
+    pub fn allow_http(mut self, host: impl Into<String>) -> Self {
+        self.http_allow.push(host.into());
+        self
+    }
+
+    /// Map a host directory into the guest, read-only.
+    pub fn preopen(mut self, host: impl Into<PathBuf>, guest: impl Into<String>) -> Self {
+        self.preopens.push((host.into(), guest.into()));
+        self
+    }
+
+    pub fn env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.env.push((key.into(), value.into()));
+        self
+    }
+
+    /// Whether `http-fetch` may request `url`. Only `http` and `https` URLs
+    /// whose host (and port, when the pattern has one) match an entry pass.
+    pub fn allows_http(&self, url: &str) -> bool {
+        let Ok(url) = url::Url::parse(url) else {
+            return false;
+        };
+        if !matches!(url.scheme(), "http" | "https") {
+            return false;
+        }
+        let Some(host) = url.host_str().map(str::to_ascii_lowercase) else {
+            return false;
+        };
+        let port = url.port_or_known_default();
+
+        self.http_allow.iter().any(|pattern| {
+            let pattern = pattern.to_ascii_lowercase();
+            let (pattern_host, pattern_port) = match pattern.rsplit_once(':') {
+                Some((h, p)) => match p.parse::<u16>() {
+                    Ok(p) => (h.to_string(), Some(p)),
+                    Err(_) => return false,
+                },
+                None => (pattern, None),
+            };
+            if pattern_port.is_some() && pattern_port != port {
+                return false;
+            }
+            match pattern_host.strip_prefix("*.") {
+                Some(domain) => host
+                    .strip_suffix(domain)
+                    .is_some_and(|sub| sub.ends_with('.') && sub.len() > 1),
+                None => host == pattern_host,
+            }
+        })
+    }
+}
+
+/// Describes a plugin component to load.
+#[derive(Debug, Clone)]
+pub struct WasmPluginConfig {
+    /// Path to the component (`.wasm`, or `.wat` text).
+    pub path: PathBuf,
+    pub permissions: PluginPermissions,
+    /// Fuel budget per hook call (0 = unlimited).
+    pub fuel: u64,
+    /// Maximum guest linear memory in bytes (0 = unlimited).
+    pub max_memory_bytes: usize,
+    /// Passed to the plugin's `init` hook.
+    pub settings: serde_json::Value,
+}
+
+impl WasmPluginConfig {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            permissions: PluginPermissions::default(),
+            fuel: DEFAULT_PLUGIN_FUEL,
+            max_memory_bytes: DEFAULT_PLUGIN_MAX_MEMORY_BYTES,
+            settings: serde_json::Value::Object(Default::default()),
+        }
+    }
+
+    pub fn permissions(mut self, permissions: PluginPermissions) -> Self {
+        self.permissions = permissions;
+        self
+    }
+
+    pub fn fuel(mut self, fuel: u64) -> Self {
+        self.fuel = fuel;
+        self
+    }
+
+    pub fn max_memory_bytes(mut self, bytes: usize) -> Self {
+        self.max_memory_bytes = bytes;
+        self
+    }
+
+    pub fn settings(mut self, settings: serde_json::Value) -> Self {
+        self.settings = settings;
+        self
+    }
+}
+
+/// What a plugin provides, as returned by its `manifest` export.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginManifest {
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub dependencies: Vec<String>,
+    /// Bus event types forwarded to the `event` hook (`"*"` for all).
+    #[serde(default)]
+    pub events: Vec<String>,
+    /// Whether the plugin implements `validate-input`/`validate-output`.
+    #[serde(default)]
+    pub guardrail: bool,
+    /// Whether the plugin implements the agent and tool middleware hooks.
+    #[serde(default)]
+    pub middleware: bool,
+    #[serde(default)]
+    pub priority: MiddlewarePriority,
+    #[serde(default)]
+    pub tools: Vec<PluginToolSpec>,
+}
+
+/// A tool exported by a plugin, called through the `tool:<name>` hook.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginToolSpec {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// JSON Schema for the arguments.
+    #[serde(default)]
+    pub parameters: Option<serde_json::Value>,
+}
+
+#[cfg(feature = "wasm-runtime")]
+pub use runtime::{WasmGuardrail, WasmMiddleware, WasmPlugin, WasmPluginHost};
+
+#[cfg(feature = "wasm-runtime")]
+mod runtime {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use async_trait::async_trait;
+    use serde::Deserialize;
+    use serde::de::DeserializeOwned;
+    use serde_json::{Value, json};
+    use wasmtime::component::{Component, Linker, ResourceTable};
+    use wasmtime::{Config, Engine, Store, StoreLimits, StoreLimitsBuilder, Trap};
+    use wasmtime_wasi::{DirPerms, FilePerms, IoView, WasiCtx, WasiCtxBuilder, WasiView};
+
+    use super::{PluginManifest, PluginPermissions, WasmPluginConfig};
+    use crate::error::{GaussError, Result};
+    use crate::guardrail::{Guardrail, GuardrailAction, GuardrailResult};
+    use crate::message::Message;
+    use crate::middleware::{
+        AfterAgentParams, AfterAgentResult, AfterToolParams, AfterToolResult, BeforeAgentParams,
+        BeforeAgentResult, BeforeToolParams, BeforeToolResult, Middleware, MiddlewareContext,
+        MiddlewarePriority,
+    };
+    use crate::plugin::{EventBus, Plugin, PluginContext};
+    use crate::tool::Tool;
+
+    mod bindings {
+        wasmtime::component::bindgen!({
+            path: "wit/plugin.wit",
+            world: "gauss-plugin",
+            async: true,
+        });
+    }
+
+    use bindings::GaussPlugin;
+    use bindings::gauss::plugin::host::{self, HttpRequest, HttpResponse, LogLevel};
+
+    /// Fuel consumed between cooperative yields to the async executor.
+    const FUEL_YIELD_INTERVAL: u64 = 1_000_000;
+    const HTTP_TIMEOUT: Duration = Duration::from_secs(30);
+    const MAX_HTTP_RESPONSE_BYTES: usize = 8 * 1024 * 1024;
+    const MAX_HTTP_REDIRECTS: usize = 10;
+
+    /// Compiles and instantiates plugin components.
+    ///
+    /// One host shares its engine and linker across every plugin it loads.
+    #[derive(Clone)]
+    pub struct WasmPluginHost {
+        engine: Engine,
+        linker: Arc<Linker<HostState>>,
+    }
+
+    impl WasmPluginHost {
+        pub fn new() -> Result<Self> {
+            let mut config = Config::new();
+            config
+                .wasm_component_model(true)
+                .async_support(true)
+                .consume_fuel(true);
+            let engine = Engine::new(&config)
+                .map_err(|e| GaussError::internal(format!("wasm engine: {e:#}")))?;
+
+            let mut linker = Linker::new(&engine);
+            wasmtime_wasi::add_to_linker_async(&mut linker)
+                .map_err(|e| GaussError::internal(format!("wasi linker: {e:#}")))?;
+            GaussPlugin::add_to_linker(&mut linker, |state: &mut HostState| state)
+                .map_err(|e| GaussError::internal(format!("plugin linker: {e:#}")))?;
+
+            Ok(Self {
+                engine,
+                linker: Arc::new(linker),
+            })
+        }
+
+        /// Compile and instantiate the component described by `config`, then
+        /// read its manifest. The plugin is not initialized until
+        /// [`Plugin::init`] runs (usually via `PluginRegistry::init_all`).
+        pub async fn load(&self, config: WasmPluginConfig) -> Result<WasmPlugin> {
+            let path = config.path.display().to_string();
+            let err = |what: &str, e: wasmtime::Error| {
+                GaussError::plugin(&path, format!("{what}: {e:#}"))
+            };
+
+            let component =
+                Component::from_file(&self.engine, &config.path).map_err(|e| err("load", e))?;
+            let state = HostState::new(&path, &config)?;
+            let mut store = Store::new(&self.engine, state);
+            store.limiter(|state| &mut state.limits);
+            store
+                .fuel_async_yield_interval(Some(FUEL_YIELD_INTERVAL))
+                .map_err(|e| err("fuel", e))?;
+
+            let mut guest = Guest {
+                bindings: GaussPlugin::instantiate_async(&mut store, &component, &self.linker)
+                    .await
+                    .map_err(|e| err("instantiate", e))?,
+                store,
+                fuel: config.fuel,
+            };
+            guest.refuel().map_err(|e| err("fuel", e))?;
+            let manifest = guest
+                .bindings
+                .call_manifest(&mut guest.store)
+                .await
+                .map_err(|e| err("manifest", e))?;
+            let manifest: PluginManifest = serde_json::from_str(&manifest)
+                .map_err(|e| GaussError::plugin(&path, format!("invalid manifest: {e}")))?;
+            guest.store.data_mut().plugin = manifest.name.clone();
+
+            Ok(WasmPlugin {
+                manifest: Arc::new(manifest),
+                settings: Arc::new(config.settings),
+                guest: Arc::new(tokio::sync::Mutex::new(guest)),
+            })
+        }
+    }
+
+    impl std::fmt::Debug for WasmPluginHost {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("WasmPluginHost").finish_non_exhaustive()
+        }
+    }
+
+    /// A loaded plugin component. Clones share the same instance; hook calls
+    /// are serialized, and guest state persists between them.
+    ///
+    /// A call that traps (e.g. by running out of fuel) leaves the instance
+    /// unusable, and every later call fails until the plugin is reloaded.
+    #[derive(Clone)]
+    pub struct WasmPlugin {
+        manifest: Arc<PluginManifest>,
+        settings: Arc<Value>,
+        guest: Arc<tokio::sync::Mutex<Guest>>,
+    }
+
+    impl std::fmt::Debug for WasmPlugin {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("WasmPlugin")
+                .field("manifest", &self.manifest)
+                .finish_non_exhaustive()
+        }
+    }
+
+    impl WasmPlugin {
+        pub fn manifest(&self) -> &PluginManifest {
+            &self.manifest
+        }
+
+        /// Call a hook and parse its JSON reply. Guest errors become
+        /// [`GaussError::PluginError`].
+        pub async fn call(&self, hook: &str, payload: &Value) -> Result<Value> {
+            self.invoke(hook, payload)
+                .await?
+                .map(|reply| parse_reply(&reply))
+                .map_err(|message| GaussError::plugin(&self.manifest.name, message))
+        }
+
+        /// Run `handle` with a fresh fuel budget. The inner error is the guest's.
+        async fn invoke(
+            &self,
+            hook: &str,
+            payload: &Value,
+        ) -> Result<std::result::Result<String, String>> {
+            let name = &self.manifest.name;
+            let payload = serde_json::to_string(payload)
+                .map_err(|e| GaussError::plugin(name, format!("invalid payload: {e}")))?;
+            let mut guest = self.guest.lock().await;
+            let guest = &mut *guest;
+            guest
+                .refuel()
+                .map_err(|e| GaussError::plugin(name, format!("fuel: {e:#}")))?;
+            guest
+                .bindings
+                .call_handle(&mut guest.store, hook, &payload)
+                .await
+                .map_err(|e| match e.downcast_ref::<Trap>() {
+                    Some(Trap::OutOfFuel) => GaussError::plugin(
+                        name,
+                        format!("'{hook}' ran out of fuel ({} units)", guest.fuel),
+                    ),
+                    _ => GaussError::plugin(name, format!("'{hook}' failed: {e:#}")),
+                })
+        }
+
+        /// Call a hook whose `null` reply means "no change".
+        async fn call_optional<T: DeserializeOwned>(
+            &self,
+            hook: &str,
+            payload: &Value,
+        ) -> Result<Option<T>> {
+            let reply = self.call(hook, payload).await?;
+            serde_json::from_value(reply).map_err(|e| {
+                GaussError::plugin(&self.manifest.name, format!("invalid '{hook}' reply: {e}"))
+            })
+        }
+
+        /// The plugin as a guardrail, if its manifest declares one.
+        pub fn guardrail(&self) -> Option<WasmGuardrail> {
+            self.manifest.guardrail.then(|| WasmGuardrail {
+                plugin: self.clone(),
+            })
+        }
+
+        /// The plugin as middleware, if its manifest declares it.
+        pub fn middleware(&self) -> Option<WasmMiddleware> {
+            self.manifest.middleware.then(|| WasmMiddleware {
+                plugin: self.clone(),
+            })
+        }
+
+        /// The tools listed in the manifest.
+        pub fn tools(&self) -> Vec<Tool> {
+            self.manifest
+                .tools
+                .iter()
+                .map(|spec| {
+                    let plugin = self.clone();
+                    let tool_name = spec.name.clone();
+                    let mut builder = Tool::builder(&spec.name, &spec.description);
+                    if let Some(parameters) = &spec.parameters {
+                        builder = builder.parameters_json(parameters.clone());
+                    }
+                    builder
+                        .execute(move |args| {
+                            let plugin = plugin.clone();
+                            let tool_name = tool_name.clone();
+                            async move {
+                                let hook = format!("tool:{tool_name}");
+                                plugin
+                                    .invoke(&hook, &args)
+                                    .await?
+                                    .map(|reply| parse_reply(&reply))
+                                    .map_err(|message| GaussError::tool(&tool_name, message))
+                            }
+                        })
+                        .build()
+                })
+                .collect()
+        }
+    }
+
+    /// Replies are JSON; an empty reply is `null` and anything else that is
+    /// not JSON is taken as a plain string.
+    fn parse_reply(reply: &str) -> Value {
+        if reply.trim().is_empty() {
+            return Value::Null;
+        }
+        serde_json::from_str(reply).unwrap_or_else(|_| Value::String(reply.to_string()))
+    }
+
+    #[async_trait]
+    impl Plugin for WasmPlugin {
+        fn name(&self) -> &str {
+            &self.manifest.name
+        }
+
+        fn version(&self) -> &str {
+            &self.manifest.version
+        }
+
+        fn dependencies(&self) -> Vec<&str> {
+            self.manifest
+                .dependencies
+                .iter()
+                .map(String::as_str)
+                .collect()
+        }
+
+        async fn init(&self, _ctx: &mut PluginContext, bus: &mut EventBus) -> Result<()> {
+            self.call("init", &self.settings).await?;
+            for event_type in &self.manifest.events {
+                let plugin = self.clone();
+                bus.subscribe_async(
+                    event_type.clone(),
+                    Box::new(move |event| {
+                        let plugin = plugin.clone();
+                        Box::pin(async move {
+                            let payload = match serde_json::to_value(&event) {
+                                Ok(payload) => payload,
+                                Err(_) => return,
+                            };
+                            if let Err(e) = plugin.call("event", &payload).await {
+                                tracing::warn!(plugin = %plugin.manifest.name, error = %e, "Plugin event hook failed");
+                            }
+                        })
+                    }),
+                );
+            }
+            Ok(())
+        }
+
+        async fn shutdown(&self, _ctx: &mut PluginContext) -> Result<()> {
+            self.call("shutdown", &Value::Null).await.map(|_| ())
+        }
+    }
+
+    /// [`Guardrail`] backed by a plugin's `validate-input`/`validate-output` hooks,
+    /// which reply with a [`GuardrailAction`] or `null` to allow.
+    #[derive(Debug, Clone)]
+    pub struct WasmGuardrail {
+        plugin: WasmPlugin,
+    }
+
+    impl WasmGuardrail {
+        async fn validate(&self, hook: &str, payload: Value) -> Result<GuardrailResult> {
+            let action: Option<GuardrailAction> = self.plugin.call_optional(hook, &payload).await?;
+            Ok(
+                action.map_or_else(GuardrailResult::allow, |action| GuardrailResult {
+                    action,
+                    metadata: Default::default(),
+                }),
+            )
+        }
+    }
+
+    #[async_trait]
+    impl Guardrail for WasmGuardrail {
+        fn name(&self) -> &str {
+            &self.plugin.manifest.name
+        }
+
+        async fn validate_input(&self, messages: &[Message]) -> Result<GuardrailResult> {
+            self.validate("validate-input", json!({ "messages": messages }))
+                .await
+        }
+
+        async fn validate_output(&self, text: &str) -> Result<GuardrailResult> {
+            self.validate("validate-output", json!({ "text": text }))
+                .await
+        }
+    }
+
+    /// [`Middleware`] backed by a plugin's agent and tool hooks. Each hook
+    /// replies with the fields it changes, or `null` to leave things as they are.
+    #[derive(Debug, Clone)]
+    pub struct WasmMiddleware {
+        plugin: WasmPlugin,
+    }
+
+    #[derive(Deserialize, Default)]
+    #[serde(default)]
+    struct BeforeAgentReply {
+        messages: Option<Vec<Message>>,
+        instructions: Option<String>,
+        abort: bool,
+        early_result: Option<String>,
+    }
+
+    #[derive(Deserialize, Default)]
+    #[serde(default)]
+    struct AfterAgentReply {
+        text: Option<String>,
+    }
+
+    #[derive(Deserialize, Default)]
+    #[serde(default)]
+    struct BeforeToolReply {
+        args: Option<Value>,
+        skip: bool,
+        mock_result: Option<Value>,
+    }
+
+    #[derive(Deserialize, Default)]
+    #[serde(default)]
+    struct AfterToolReply {
+        result: Option<Value>,
+    }
+
+    #[async_trait]
+    impl Middleware for WasmMiddleware {
+        fn name(&self) -> &str {
+            &self.plugin.manifest.name
+        }
+
+        fn priority(&self) -> MiddlewarePriority {
+            self.plugin.manifest.priority
+        }
+
+        async fn before_agent(
+            &self,
+            ctx: &mut MiddlewareContext,
+            params: &BeforeAgentParams,
+        ) -> Result<Option<BeforeAgentResult>> {
+            let payload = json!({
+                "session_id": ctx.session_id,
+                "agent_name": ctx.agent_name,
+                "messages": params.messages,
+                "instructions": params.instructions,
+                "tools": params.tools.iter().map(|t| &t.name).collect::<Vec<_>>(),
+            });
+            let reply: Option<BeforeAgentReply> =
+                self.plugin.call_optional("before-agent", &payload).await?;
+            Ok(reply.map(|r| BeforeAgentResult {
+                messages: r.messages,
+                instructions: r.instructions,
+                tools: None,
+                abort: r.abort,
+                early_result: r.early_result,
+            }))
+        }
+
+        async fn after_agent(
+            &self,
+            _ctx: &mut MiddlewareContext,
+            params: &AfterAgentParams,
+        ) -> Result<Option<AfterAgentResult>> {
+            let payload = json!({
+                "session_id": params.session_id,
+                "messages": params.messages,
+                "result_text": params.result_text,
+            });
+            let reply: Option<AfterAgentReply> =
+                self.plugin.call_optional("after-agent", &payload).await?;
+            Ok(reply.map(|r| AfterAgentResult { text: r.text }))
+        }
+
+        async fn before_tool(
+            &self,
+            _ctx: &mut MiddlewareContext,
+            params: &BeforeToolParams,
+        ) -> Result<Option<BeforeToolResult>> {
+            let payload = json!({
+                "tool_name": params.tool_name,
+                "args": params.args,
+                "step_index": params.step_index,
+            });
+            let reply: Option<BeforeToolReply> =
+                self.plugin.call_optional("before-tool", &payload).await?;
+            Ok(reply.map(|r| BeforeToolResult {
+                args: r.args,
+                skip: r.skip,
+                mock_result: r.mock_result,
+            }))
+        }
+
+        async fn after_tool(
+            &self,
+            _ctx: &mut MiddlewareContext,
+            params: &AfterToolParams,
+        ) -> Result<Option<AfterToolResult>> {
+            let payload = json!({
+                "tool_name": params.tool_name,
+                "args": params.args,
+                "result": params.result,
+                "step_index": params.step_index,
+                "duration_ms": params.duration_ms,
+            });
+            let reply: Option<AfterToolReply> =
+                self.plugin.call_optional("after-tool", &payload).await?;
+            Ok(reply.map(|r| AfterToolResult { result: r.result }))
+        }
+    }
+
+    struct Guest {
+        store: Store<HostState>,
+        bindings: GaussPlugin,
+        fuel: u64,
+    }
+
+    impl Guest {
+        fn refuel(&mut self) -> wasmtime::Result<()> {
+            let fuel = if self.fuel == 0 { u64::MAX } else { self.fuel };
+            self.store.set_fuel(fuel)
+        }
+    }
+
+    /// Per-plugin store data: the WASI context and the host capabilities.
+    struct HostState {
+        plugin: String,
+        permissions: Arc<PluginPermissions>,
+        http: reqwest::Client,
+        wasi: WasiCtx,
+        table: ResourceTable,
+        limits: StoreLimits,
+    }
+
+    impl HostState {
+        fn new(path: &str, config: &WasmPluginConfig) -> Result<Self> {
+            let permissions = Arc::new(config.permissions.clone());
+
+            // No sockets, no inherited stdio or env; only what was granted.
+            let mut wasi = WasiCtxBuilder::new();
+            wasi.allow_ip_name_lookup(false)
+                .allow_tcp(false)
+                .allow_udp(false);
+            for (key, value) in &permissions.env {
+                wasi.env(key, value);
+            }
+            for (host, guest) in &permissions.preopens {
+                wasi.preopened_dir(host, guest, DirPerms::READ, FilePerms::READ)
+                    .map_err(|e| {
+                        GaussError::plugin(path, format!("preopen {}: {e:#}", host.display()))
+                    })?;
+            }
+
+            let redirect_permissions = permissions.clone();
+            let http = reqwest::Client::builder()
+                .timeout(HTTP_TIMEOUT)
+                .redirect(reqwest::redirect::Policy::custom(move |attempt| {
+                    if attempt.previous().len() >= MAX_HTTP_REDIRECTS {
+                        attempt.error("too many redirects")
+                    } else if redirect_permissions.allows_http(attempt.url().as_str()) {
+                        attempt.follow()
+                    } else {
+                        attempt.stop()
+                    }
+                }))
+                .build()
+                .map_err(|e| GaussError::plugin(path, format!("http client: {e}")))?;
+
+            let mut limits = StoreLimitsBuilder::new();
+            if config.max_memory_bytes > 0 {
+                limits = limits.memory_size(config.max_memory_bytes);
+            }
+
+            Ok(Self {
+                plugin: path.to_string(),
+                permissions,
+                http,
+                wasi: wasi.build(),
+                table: ResourceTable::new(),
+                limits: limits.build(),
+            })
+        }
+    }
+
+    /// Owns its inputs so the future stays `Send` (the WASI context is not `Sync`).
+    async fn fetch(
+        http: reqwest::Client,
+        permissions: Arc<PluginPermissions>,
+        request: HttpRequest,
+    ) -> std::result::Result<HttpResponse, String> {
+        if !permissions.allows_http(&request.url) {
+            return Err(format!("HTTP access to {} is not permitted", request.url));
+        }
+        let method = reqwest::Method::from_bytes(request.method.to_uppercase().as_bytes())
+            .map_err(|_| format!("invalid HTTP method: {}", request.method))?;
+        let mut builder = http.request(method, &request.url);
+        for (key, value) in &request.headers {
+            builder = builder.header(key, value);
+        }
+        if let Some(body) = request.body {
+            builder = builder.body(body);
+        }
+
+        let mut response = builder.send().await.map_err(|e| e.to_string())?;
+        let status = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .filter_map(|(k, v)| Some((k.to_string(), v.to_str().ok()?.to_string())))
+            .collect();
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+            if body.len() + chunk.len() > MAX_HTTP_RESPONSE_BYTES {
+                return Err(format!(
+                    "response body exceeds {MAX_HTTP_RESPONSE_BYTES} bytes"
+                ));
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok(HttpResponse {
+            status,
+            headers,
+            body: String::from_utf8_lossy(&body).into_owned(),
+        })
+    }
+
+    impl IoView for HostState {
+        fn table(&mut self) -> &mut ResourceTable {
+            &mut self.table
+        }
+    }
+
+    impl WasiView for HostState {
+        fn ctx(&mut self) -> &mut WasiCtx {
+            &mut self.wasi
+        }
+    }
+
+    impl host::Host for HostState {
+        async fn log(&mut self, level: LogLevel, message: String) {
+            let plugin = self.plugin.as_str();
+            match level {
+                LogLevel::Trace => tracing::trace!(plugin, "{message}"),
+                LogLevel::Debug => tracing::debug!(plugin, "{message}"),
+                LogLevel::Info => tracing::info!(plugin, "{message}"),
+                LogLevel::Warn => tracing::warn!(plugin, "{message}"),
+                LogLevel::Error => tracing::error!(plugin, "{message}"),
+            }
+        }
+
+        async fn http_fetch(
+            &mut self,
+            request: HttpRequest,
+        ) -> std::result::Result<HttpResponse, String> {
+            let result = fetch(self.http.clone(), self.permissions.clone(), request).await;
+            if let Err(e) = &result {
+                tracing::debug!(plugin = %self.plugin, error = %e, "Plugin http-fetch failed");
+            }
+            result
+        }
+    }
+}
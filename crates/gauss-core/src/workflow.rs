@@ -6,6 +6,8 @@
 use crate::agent::Agent;
 use crate::error::{self, GaussError};
 use crate::message::Message;
+use crate::plugin::{OrchestratorRun, PluginRegistry};
+use crate::telemetry::{SpanBuilder, SpanRecord, SpanType, TelemetryCollector, TraceContext};
 use std::collections::HashMap;
 use std::pin::Pin;
 
@@ -64,6 +66,8 @@ pub struct Workflow {
     steps: HashMap<String, Step>,
     dependencies: HashMap<String, Vec<String>>,
     entry_points: Vec<String>,
+    telemetry: Option<crate::Shared<TelemetryCollector>>,
+    plugins: Option<crate::Shared<PluginRegistry>>,
 }
 
 impl Workflow {
@@ -75,6 +79,39 @@ impl Workflow {
     pub async fn run(
         &self,
         initial_messages: Vec<Message>,
+    ) -> error::Result<HashMap<String, StepOutput>> {
+        let lifecycle = OrchestratorRun::start(self.plugins.as_ref(), "workflow", "workflow").await;
+        let result = self.run_instrumented(initial_messages).await;
+        lifecycle.finish(result.is_err()).await;
+        result
+    }
+
+    async fn run_instrumented(
+        &self,
+        initial_messages: Vec<Message>,
+    ) -> error::Result<HashMap<String, StepOutput>> {
+        let Some(ref telemetry) = self.telemetry else {
+            return self
+                .run_steps(initial_messages, None, &mut Vec::new())
+                .await;
+        };
+        let context = crate::telemetry::child_of_current();
+        let mut spans = Vec::new();
+        let result = self
+            .run_steps(initial_messages, Some(&context), &mut spans)
+            .await;
+        let span = SpanBuilder::new("workflow", SpanType::Custom)
+            .context(context)
+            .attribute("steps", self.steps.len());
+        telemetry.record_run(crate::telemetry::finish_span(span, spans, &result));
+        result
+    }
+
+    async fn run_steps(
+        &self,
+        initial_messages: Vec<Message>,
+        trace: Option<&TraceContext>,
+        spans: &mut Vec<SpanRecord>,
     ) -> error::Result<HashMap<String, StepOutput>> {
         let mut completed: HashMap<String, StepOutput> = HashMap::new();
         let mut pending: Vec<String> = self.entry_points.clone();
@@ -97,31 +134,42 @@ impl Workflow {
                     source: None,
                 })?;
 
-                let output = match step {
+                let step_trace = trace.map(TraceContext::child);
+                let mut children = Vec::new();
+                let result = match step {
                     Step::Agent { agent, input_fn } => {
                         let messages = if completed.is_empty() {
                             initial_messages.clone()
                         } else {
                             input_fn(&completed)
                         };
-                        let result = agent.run(messages).await?;
-                        StepOutput {
-                            step_id: step_id.clone(),
-                            text: result.text,
-                            data: result.structured_output,
-                        }
+                        agent
+                            .run_child(messages, step_trace.as_ref(), &mut children)
+                            .await
+                            .map(|result| StepOutput {
+                                step_id: step_id.clone(),
+                                text: result.text,
+                                data: result.structured_output,
+                            })
                     }
-                    Step::Function { execute } => execute(completed.clone()).await?,
+                    Step::Function { execute } => execute(completed.clone()).await,
                     Step::Router { route_fn } => {
                         let next_step = route_fn(&completed);
                         next_pending.push(next_step.clone());
-                        StepOutput {
+                        Ok(StepOutput {
                             step_id: step_id.clone(),
                             text: next_step,
                             data: None,
-                        }
+                        })
                     }
                 };
+                if let Some(context) = step_trace {
+                    let span = SpanBuilder::new(format!("step {step_id}"), SpanType::WorkflowStep)
+                        .context(context)
+                        .attribute("step_id", step_id.clone());
+                    spans.push(crate::telemetry::finish_span(span, children, &result));
+                }
+                let output = result?;
 
                 completed.insert(step_id.clone(), output);
 
@@ -154,6 +202,8 @@ impl Workflow {
 pub struct WorkflowBuilder {
     steps: HashMap<String, Step>,
     dependencies: HashMap<String, Vec<String>>,
+    telemetry: Option<crate::Shared<TelemetryCollector>>,
+    plugins: Option<crate::Shared<PluginRegistry>>,
 }
 
 impl WorkflowBuilder {
@@ -161,6 +211,8 @@ impl WorkflowBuilder {
         Self {
             steps: HashMap::new(),
             dependencies: HashMap::new(),
+            telemetry: None,
+            plugins: None,
         }
     }
 
@@ -249,8 +301,29 @@ impl WorkflowBuilder {
         self
     }
 
+    /// Record a workflow span with a span per step and the agent runs nested under it.
+    pub fn telemetry(mut self, collector: crate::Shared<TelemetryCollector>) -> Self {
+        self.telemetry = Some(collector);
+        self
+    }
+
+    /// Publish workflow start/finish events, and step agents' events unless
+    /// they have their own registry.
+    pub fn plugins(mut self, registry: crate::Shared<PluginRegistry>) -> Self {
+        self.plugins = Some(registry);
+        self
+    }
+
     /// Build the workflow.
-    pub fn build(self) -> Workflow {
+    pub fn build(mut self) -> Workflow {
+        if let Some(ref plugins) = self.plugins {
+            for step in self.steps.values_mut() {
+                if let Step::Agent { agent, .. } = step {
+                    agent.inherit_plugins(plugins);
+                }
+            }
+        }
+
         // Entry points = steps with no dependencies
         let entry_points: Vec<String> = self
             .steps
@@ -263,6 +336,8 @@ impl WorkflowBuilder {
             steps: self.steps,
             dependencies: self.dependencies,
             entry_points,
+            telemetry: self.telemetry,
+            plugins: self.plugins,
         }
     }
 }
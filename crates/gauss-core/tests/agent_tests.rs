@@ -1,7 +1,8 @@
-use gauss_core::agent::Agent;
+use gauss_core::agent::{Agent, StopCondition, StopReason};
 use gauss_core::message::Message;
 use gauss_core::provider::openai::OpenAiProvider;
 use gauss_core::provider::{ProviderConfig, ReasoningEffort};
+use gauss_core::telemetry::{SpanType, TelemetryCollector};
 use gauss_core::tool::Tool;
 use serde_json::json;
 use std::sync::Arc;
@@ -38,6 +39,7 @@ async fn test_agent_simple_text() {
     assert_eq!(output.steps, 1);
     assert_eq!(output.usage.input_tokens, 15);
     assert_eq!(output.usage.output_tokens, 6);
+    assert_eq!(output.stop_reason, StopReason::Completed);
 }
 
 #[tokio::test]
@@ -199,6 +201,219 @@ async fn test_agent_max_steps_limit() {
 
     let output = agent.run(vec![Message::user("loop")]).await.unwrap();
     assert_eq!(output.steps, 3);
+    assert_eq!(output.stop_reason, StopReason::MaxSteps);
+}
+
+async fn mount_tool_loop(mock_server: &MockServer, prompt_tokens: u64, completion_tokens: u64) {
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "choices": [{
+                "message": {
+                    "role": "assistant",
+                    "content": null,
+                    "tool_calls": [{
+                        "id": "call_loop",
+                        "type": "function",
+                        "function": {"name": "infinite", "arguments": "{}"}
+                    }]
+                },
+                "finish_reason": "tool_calls"
+            }],
+            "usage": {"prompt_tokens": prompt_tokens, "completion_tokens": completion_tokens}
+        })))
+        .mount(mock_server)
+        .await;
+}
+
+fn loop_tool() -> Tool {
+    Tool::builder("infinite", "Never-ending tool")
+        .execute(|_| async move { Ok(json!({"status": "again"})) })
+        .build()
+}
+
+#[tokio::test]
+async fn test_agent_reports_cost_per_step_and_model() {
+    let mock_server = MockServer::start().await;
+    mount_tool_loop(&mock_server, 1_000_000, 0).await;
+
+    let config = ProviderConfig::new("test-key").base_url(mock_server.uri());
+    let provider = Arc::new(OpenAiProvider::new("gpt-4o", config));
+
+    let agent = Agent::builder("cost-agent", provider)
+        .tool(loop_tool())
+        .max_steps(2)
+        .build();
+
+    let output = agent.run(vec![Message::user("loop")]).await.unwrap();
+    assert_eq!(output.cost.steps.len(), 2);
+    assert!((output.step_results[0].cost.total_cost_usd - 5.0).abs() < 1e-9);
+    let gpt = &output.cost.by_model["gpt-4o"];
+    assert_eq!(gpt.input_tokens, 2_000_000);
+    assert!((output.cost.total_cost_usd - 10.0).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn test_agent_stops_at_cost_budget() {
+    let mock_server = MockServer::start().await;
+    mount_tool_loop(&mock_server, 1_000_000, 0).await;
+
+    let config = ProviderConfig::new("test-key").base_url(mock_server.uri());
+    let provider = Arc::new(OpenAiProvider::new("gpt-4o", config));
+
+    let agent = Agent::builder("budget-agent", provider)
+        .tool(loop_tool())
+        .max_steps(10)
+        .stop_when(StopCondition::MaxCostUsd(12.0))
+        .build();
+
+    // $5 per call: the check before the fourth call sees $15 spent.
+    let output = agent.run(vec![Message::user("loop")]).await.unwrap();
+    assert_eq!(output.steps, 3);
+    assert_eq!(output.stop_reason, StopReason::BudgetExhausted);
+    assert_eq!(mock_server.received_requests().await.unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn test_agent_stops_at_token_budgets() {
+    let mock_server = MockServer::start().await;
+    mount_tool_loop(&mock_server, 10, 5).await;
+
+    let config = ProviderConfig::new("test-key").base_url(mock_server.uri());
+    let provider = Arc::new(OpenAiProvider::new("gpt-5.2", config));
+
+    let agent = Agent::builder("token-agent", provider.clone())
+        .tool(loop_tool())
+        .max_steps(10)
+        .stop_when(StopCondition::MaxTotalTokens(40))
+        .build();
+    let output = agent.run(vec![Message::user("loop")]).await.unwrap();
+    assert_eq!(output.steps, 3);
+    assert_eq!(output.usage.total_tokens(), 45);
+
+    // No reasoning tokens are reported, so a reasoning budget never trips.
+    let agent = Agent::builder("reasoning-agent", provider)
+        .tool(loop_tool())
+        .max_steps(4)
+        .stop_when(StopCondition::MaxReasoningTokens(1))
+        .build();
+    let output = agent.run(vec![Message::user("loop")]).await.unwrap();
+    assert_eq!(output.steps, 4);
+}
+
+#[tokio::test]
+async fn test_agent_records_span_tree_and_metrics() {
+    let mock_server = MockServer::start().await;
+    mount_tool_loop(&mock_server, 10, 5).await;
+
+    let config = ProviderConfig::new("test-key").base_url(mock_server.uri());
+    let provider = Arc::new(OpenAiProvider::new("gpt-4o", config));
+    let telemetry = Arc::new(TelemetryCollector::new());
+
+    let agent = Agent::builder("traced-agent", provider)
+        .tool(loop_tool())
+        .max_steps(2)
+        .telemetry(telemetry.clone())
+        .build();
+
+    let output = agent.run(vec![Message::user("loop")]).await.unwrap();
+    assert!(!output.run_id.is_empty());
+    let trace = output.trace.expect("traced run");
+
+    let spans = telemetry.export_spans();
+    assert_eq!(spans.len(), 1);
+    let run = &spans[0];
+    assert_eq!(run.span_type, SpanType::AgentRun);
+    assert_eq!(run.context.as_ref(), Some(&trace));
+    assert_eq!(run.attributes["gauss.run_id"], json!(output.run_id));
+    assert_eq!(run.children.len(), 2);
+
+    let step = &run.children[0];
+    assert_eq!(step.span_type, SpanType::AgentStep);
+    let kinds: Vec<_> = step.children.iter().map(|s| s.span_type).collect();
+    assert_eq!(kinds, vec![SpanType::ModelCall, SpanType::ToolCall]);
+    let model = &step.children[0];
+    assert_eq!(model.attributes["input_tokens"], json!(10));
+    assert_eq!(model.attributes["finish_reason"], json!("tool_calls"));
+    assert_eq!(
+        model.context.as_ref().unwrap().trace_id,
+        trace.trace_id,
+        "model span shares the run's trace"
+    );
+
+    let metrics = telemetry.export_metrics();
+    assert_eq!(metrics.total_steps, 2);
+    assert_eq!(metrics.model_call_count, 2);
+    assert_eq!(metrics.total_tool_calls, 2);
+    assert_eq!(metrics.total_input_tokens, 20);
+    assert_eq!(metrics.total_output_tokens, 10);
+    assert!(metrics.errors.is_empty());
+}
+
+#[tokio::test]
+async fn test_agent_stream_records_span_tree_and_metrics() {
+    use futures::StreamExt;
+    use gauss_core::provider::mock::MockProvider;
+
+    let provider = Arc::new(
+        MockProvider::new()
+            .tool_call("infinite", json!({}))
+            .text("done"),
+    );
+    let telemetry = Arc::new(TelemetryCollector::new());
+    let agent = Agent::builder("streamed-agent", provider)
+        .tool(loop_tool())
+        .telemetry(telemetry.clone())
+        .build();
+
+    let events: Vec<_> = agent
+        .run_stream(vec![Message::user("loop")])
+        .await
+        .unwrap()
+        .collect()
+        .await;
+    assert!(events.iter().all(|e| e.is_ok()));
+
+    let spans = telemetry.export_spans();
+    assert_eq!(spans.len(), 1);
+    let run = &spans[0];
+    assert_eq!(run.span_type, SpanType::AgentRun);
+    assert_eq!(run.attributes["steps"], json!(2));
+    assert_eq!(run.children.len(), 2);
+    let kinds: Vec<_> = run.children[0]
+        .children
+        .iter()
+        .map(|s| s.span_type)
+        .collect();
+    assert_eq!(kinds, vec![SpanType::ModelCall, SpanType::ToolCall]);
+    assert_eq!(
+        run.children[1].children[0].attributes["response"],
+        json!("done")
+    );
+
+    let metrics = telemetry.export_metrics();
+    assert_eq!(metrics.total_steps, 2);
+    assert_eq!(metrics.model_call_count, 2);
+    assert_eq!(metrics.total_tool_calls, 1);
+    assert_eq!(metrics.total_input_tokens, 20);
+    assert!(metrics.errors.is_empty());
+}
+
+#[tokio::test]
+async fn test_agent_without_telemetry_has_no_trace() {
+    let mock_server = MockServer::start().await;
+    mount_tool_loop(&mock_server, 10, 5).await;
+
+    let config = ProviderConfig::new("test-key").base_url(mock_server.uri());
+    let provider = Arc::new(OpenAiProvider::new("gpt-4o", config));
+    let agent = Agent::builder("plain-agent", provider)
+        .tool(loop_tool())
+        .max_steps(1)
+        .build();
+
+    let output = agent.run(vec![Message::user("loop")]).await.unwrap();
+    assert!(!output.run_id.is_empty());
+    assert!(output.trace.is_none());
 }
 
 #[tokio::test]
@@ -246,6 +461,7 @@ async fn test_agent_stop_on_tool_call() {
     let output = agent.run(vec![Message::user("what is 42?")]).await.unwrap();
     // Should stop after 1 step even though max_steps is 10
     assert_eq!(output.steps, 1);
+    assert_eq!(output.stop_reason, StopReason::StopCondition);
 }
 
 #[tokio::test]
@@ -328,3 +544,93 @@ async fn test_agent_on_step_finish_callback() {
     let _output = agent.run(vec![Message::user("test")]).await.unwrap();
     assert!(callback_called.load(std::sync::atomic::Ordering::Relaxed));
 }
+
+#[tokio::test]
+async fn test_agent_surfaces_tool_artifacts() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "choices": [{
+                "message": {
+                    "role": "assistant",
+                    "content": null,
+                    "tool_calls": [{
+                        "id": "call_1",
+                        "type": "function",
+                        "function": {"name": "plot", "arguments": "{}"}
+                    }, {
+                        "id": "call_2",
+                        "type": "function",
+                        "function": {"name": "list_files", "arguments": "{}"}
+                    }]
+                },
+                "finish_reason": "tool_calls"
+            }],
+            "usage": {"prompt_tokens": 10, "completion_tokens": 5}
+        })))
+        .up_to_n_times(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "choices": [{
+                "message": {"role": "assistant", "content": "Here is your chart."},
+                "finish_reason": "stop"
+            }],
+            "usage": {"prompt_tokens": 12, "completion_tokens": 4}
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let config = ProviderConfig::new("test-key").base_url(mock_server.uri());
+    let provider = Arc::new(OpenAiProvider::new("gpt-5.2", config));
+
+    let tool = Tool::builder("plot", "Draws a chart")
+        .with_artifacts()
+        .execute(|_| async move {
+            Ok(json!({
+                "exit_code": 0,
+                "artifacts": [{"path": "chart.png", "media_type": "image/png", "size": 3, "data": "AAAA"}]
+            }))
+        })
+        .build();
+    // Tools that are not marked as producing artifacts keep their results untouched.
+    let other = Tool::builder("list_files", "Lists files")
+        .execute(|_| async move {
+            Ok(json!({"artifacts": [{"path": "a.txt", "media_type": "text/plain", "data": "YQ=="}]}))
+        })
+        .build();
+
+    let agent = Agent::builder("plot-agent", provider)
+        .tool(tool)
+        .tool(other)
+        .build();
+    let output = agent.run(vec![Message::user("Plot it")]).await.unwrap();
+
+    let requests = mock_server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 2);
+    let body: serde_json::Value = serde_json::from_slice(&requests[1].body).unwrap();
+    let messages = body["messages"].as_array().unwrap();
+    let roles: Vec<&str> = messages
+        .iter()
+        .map(|m| m["role"].as_str().unwrap())
+        .collect();
+    assert_eq!(roles, ["user", "assistant", "tool", "tool", "user"]);
+    assert!(!messages[2]["content"].as_str().unwrap().contains("AAAA"));
+    assert!(messages[3]["content"].as_str().unwrap().contains("YQ=="));
+    let parts = messages[4]["content"].as_array().unwrap();
+    assert!(
+        parts
+            .iter()
+            .any(|p| p["type"] == "image_url"
+                && p["image_url"]["url"] == "data:image/png;base64,AAAA")
+    );
+
+    let recorded = &output.step_results[0].tool_results[0].result;
+    assert_eq!(recorded["artifacts"][0]["path"], "chart.png");
+    assert!(recorded["artifacts"][0].get("data").is_none());
+}
@@ -0,0 +1,216 @@
+use futures::StreamExt;
+use gauss_core::error::GaussError;
+use gauss_core::message::Message;
+use gauss_core::provider::auth::{AccessTokenSource, EntraTokenSource, StaticToken};
+use gauss_core::provider::openai::OpenAiProvider;
+use gauss_core::provider::{FinishReason, GenerateOptions, Provider, ProviderConfig};
+use gauss_core::streaming::StreamEvent;
+use serde_json::json;
+use std::sync::Arc;
+use wiremock::matchers::{body_string_contains, header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const DEPLOYMENT_PATH: &str = "/openai/deployments/gpt-4o-prod/chat/completions";
+
+fn azure(server: &MockServer) -> OpenAiProvider {
+    OpenAiProvider::new("gpt-4o", ProviderConfig::new("azure-key"))
+        .azure(format!("{}/", server.uri()), "gpt-4o-prod")
+}
+
+#[tokio::test]
+async fn api_key_requests_target_the_deployment() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path(DEPLOYMENT_PATH))
+        .and(query_param("api-version", "2024-10-21"))
+        .and(header("api-key", "azure-key"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "id": "chatcmpl-1",
+            "prompt_filter_results": [{
+                "prompt_index": 0,
+                "content_filter_results": {"hate": {"filtered": false, "severity": "safe"}}
+            }],
+            "choices": [{
+                "index": 0,
+                "message": {"role": "assistant", "content": "Hello from Azure"},
+                "finish_reason": "stop",
+                "content_filter_results": {"violence": {"filtered": false, "severity": "safe"}}
+            }],
+            "usage": {"prompt_tokens": 9, "completion_tokens": 3}
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = azure(&server);
+    assert_eq!(provider.name(), "azure_openai");
+    let result = provider
+        .generate(&[Message::user("hi")], &[], &GenerateOptions::default())
+        .await
+        .unwrap();
+
+    assert_eq!(result.text(), Some("Hello from Azure"));
+    assert_eq!(
+        result.provider_metadata["prompt_filter_results"][0]["content_filter_results"]["hate"]["severity"],
+        "safe"
+    );
+    assert_eq!(
+        result.provider_metadata["content_filter_results"]["violence"]["filtered"],
+        false
+    );
+
+    let request = &server.received_requests().await.unwrap()[0];
+    assert!(request.headers.get("authorization").is_none());
+    let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+    assert!(body.get("model").is_none(), "{body}");
+}
+
+#[tokio::test]
+async fn entra_tokens_and_streaming() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path(DEPLOYMENT_PATH))
+        .and(query_param("api-version", "2025-01-01-preview"))
+        .and(header("authorization", "Bearer entra-token"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(concat!(
+            // Azure leads with a chunk carrying only the prompt filter verdicts.
+            "data: {\"choices\":[],\"prompt_filter_results\":[{\"prompt_index\":0}]}\n\n",
+            "data: {\"choices\":[{\"index\":0,\"delta\":{\"content\":\"Hi\"}}]}\n\n",
+            "data: {\"choices\":[{\"index\":0,\"delta\":{},\"finish_reason\":\"content_filter\"}]}\n\n",
+            "data: [DONE]\n\n"
+        )))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = azure(&server)
+        .api_version("2025-01-01-preview")
+        .entra(Arc::new(StaticToken::new("entra-token")));
+    let events: Vec<StreamEvent> = provider
+        .stream(&[Message::user("hi")], &[], &GenerateOptions::default())
+        .await
+        .unwrap()
+        .map(|e| e.unwrap())
+        .collect()
+        .await;
+
+    assert_eq!(
+        serde_json::to_value(&events).unwrap(),
+        serde_json::to_value(vec![
+            StreamEvent::TextDelta("Hi".into()),
+            StreamEvent::FinishReason(FinishReason::ContentFilter),
+            StreamEvent::Done,
+        ])
+        .unwrap()
+    );
+    let request = &server.received_requests().await.unwrap()[0];
+    assert!(request.headers.get("api-key").is_none());
+}
+
+#[tokio::test]
+async fn entra_client_credentials_configured_before_azure() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/my-tenant/oauth2/v2.0/token"))
+        .and(body_string_contains("grant_type=client_credentials"))
+        .and(body_string_contains("client_id=app-id"))
+        .and(body_string_contains("client_secret=app-secret"))
+        .and(body_string_contains(
+            "scope=https%3A%2F%2Fcognitiveservices.azure.com%2F.default",
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "token_type": "Bearer",
+            "expires_in": 3599,
+            "access_token": "entra-token"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path(DEPLOYMENT_PATH))
+        .and(query_param("api-version", "2025-01-01-preview"))
+        .and(header("authorization", "Bearer entra-token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "choices": [{
+                "index": 0,
+                "message": {"role": "assistant", "content": "Hi"},
+                "finish_reason": "stop"
+            }]
+        })))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let source = EntraTokenSource::new("my-tenant", "app-id", "app-secret")
+        .with_authority(format!("{}/", server.uri()));
+    let provider = OpenAiProvider::new("gpt-4o", ProviderConfig::new("unused"))
+        .api_version("2025-01-01-preview")
+        .entra(Arc::new(source))
+        .azure(server.uri(), "gpt-4o-prod");
+    // The token is fetched once and reused.
+    for _ in 0..2 {
+        provider
+            .generate(&[Message::user("hi")], &[], &GenerateOptions::default())
+            .await
+            .unwrap();
+    }
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(401).set_body_json(json!({
+            "error": "invalid_client",
+            "error_description": "AADSTS7000215: Invalid client secret provided."
+        })))
+        .mount(&server)
+        .await;
+    let err = EntraTokenSource::new("my-tenant", "app-id", "wrong")
+        .with_authority(server.uri())
+        .access_token()
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Invalid client secret"), "{err}");
+}
+
+#[tokio::test]
+async fn filtered_prompts_are_guardrail_errors() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path(DEPLOYMENT_PATH))
+        .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+            "error": {
+                "code": "content_filter",
+                "message": "The response was filtered due to the prompt triggering Azure OpenAI's content management policy.",
+                "innererror": {"code": "ResponsibleAIPolicyViolation"}
+            }
+        })))
+        .mount(&server)
+        .await;
+
+    let err = azure(&server)
+        .generate(&[Message::user("bad")], &[], &GenerateOptions::default())
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(
+            &err,
+            GaussError::Guardrail { guardrail: Some(g), .. } if g == "azure_content_filter"
+        ),
+        "{err}"
+    );
+
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(401).set_body_json(json!({
+            "error": {"code": "401", "message": "Access denied due to invalid subscription key."}
+        })))
+        .mount(&server)
+        .await;
+    let err = OpenAiProvider::new("gpt-4o", ProviderConfig::new("wrong"))
+        .azure(server.uri(), "other")
+        .generate(&[Message::user("hi")], &[], &GenerateOptions::default())
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(&err, GaussError::Authentication { provider } if provider == "azure_openai"),
+        "{err}"
+    );
+}
@@ -0,0 +1,447 @@
+use futures::StreamExt;
+use gauss_core::error::GaussError;
+use gauss_core::message::{Content, Message};
+use gauss_core::provider::aws::{
+    self, AwsCredentials, EventStreamDecoder, EventStreamMessage, SigningParams,
+};
+use gauss_core::provider::bedrock::BedrockProvider;
+use gauss_core::provider::{FinishReason, GenerateOptions, Provider};
+use gauss_core::streaming::StreamEvent;
+use gauss_core::tool::{Tool, ToolChoice};
+use serde_json::{Value, json};
+use std::time::{Duration, UNIX_EPOCH};
+use wiremock::matchers::{body_partial_json, header_exists, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const MODEL: &str = "anthropic.claude-3-5-sonnet-20240620-v1:0";
+const MODEL_PATH: &str = "/model/anthropic.claude-3-5-sonnet-20240620-v1%3A0";
+
+fn provider(server: &MockServer) -> BedrockProvider {
+    BedrockProvider::new(
+        MODEL,
+        "us-west-2",
+        AwsCredentials::new("AKIDEXAMPLE", "secret").with_session_token("session"),
+    )
+    .endpoint(server.uri())
+}
+
+#[test]
+fn sigv4_matches_the_aws_test_suite() {
+    let credentials =
+        AwsCredentials::new("AKIDEXAMPLE", "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY");
+    let params = SigningParams {
+        credentials: &credentials,
+        region: "us-east-1",
+        service: "service",
+        time: UNIX_EPOCH + Duration::from_secs(1_440_938_160),
+    };
+
+    // get-vanilla
+    let url = url::Url::parse("https://example.amazonaws.com/").unwrap();
+    let headers = aws::sign("GET", &url, &[], b"", &params);
+    assert_eq!(
+        headers,
+        vec![
+            ("x-amz-date".to_string(), "20150830T123600Z".to_string()),
+            (
+                "authorization".to_string(),
+                "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, \
+                 SignedHeaders=host;x-amz-date, \
+                 Signature=5fa00fa31553b73ebf1942676e86291e8372ff2a2260956d9b8aae1d763fbf31"
+                    .to_string()
+            ),
+        ]
+    );
+
+    // get-vanilla-query-order-key-case: query parameters are sorted.
+    let url =
+        url::Url::parse("https://example.amazonaws.com/?Param2=value2&Param1=value1").unwrap();
+    let headers = aws::sign("GET", &url, &[], b"", &params);
+    assert!(
+        headers[1].1.ends_with(
+            "Signature=b97d918cfa904a5beff61c982a1b6f458b799221646efd99d3219ec94cdf2500"
+        )
+    );
+
+    // post-sts-header-before: temporary credentials sign the session token.
+    let token = "AQoDYXdzEPT//////////wEXAMPLEtc764bNrC9SAPBSM22wDOk4x4HIZ8j4FZTwdQWLWsKWHGBuFqwAeMicRXmxfpSPfIeoIYRqTflfKD8YUuwthAx7mSEI/qkPpKPi/kMcGdQrmGdeehM4IC1NtBmUpp2wUE8phUZampKsburEDy0KPkyQDYwT7WZ0wq5VSXDvp75YU9HFvlRd8Tx6q6fE8YQcHNVXAkiY9q6d+xo0rKwT38xVqr7ZD0u0iPPkUL64lIZbqBAz+scqKmlzm8FDrypNC9Yjc8fPOLn9FX9KSYvKTr4rvx3iSIlTJabIQwj2ICCR/oLxBA==";
+    let temporary = credentials.clone().with_session_token(token);
+    let url = url::Url::parse("https://example.amazonaws.com/").unwrap();
+    let headers = aws::sign(
+        "POST",
+        &url,
+        &[],
+        b"",
+        &SigningParams {
+            credentials: &temporary,
+            ..params
+        },
+    );
+    assert_eq!(headers[1], ("x-amz-security-token".into(), token.into()));
+    assert!(headers[2].1.ends_with(
+        "SignedHeaders=host;x-amz-date;x-amz-security-token, \
+         Signature=85d96828115b5dc0cfc3bd16ad9e210dd772bbebba041836c64533a82be05ead"
+    ));
+}
+
+#[test]
+fn credentials_load_from_env_and_profiles() {
+    let dir = std::env::temp_dir().join(format!("gauss-aws-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let credentials_file = dir.join("credentials");
+    let config_file = dir.join("config");
+    std::fs::write(
+        &credentials_file,
+        "[default]\naws_access_key_id = AKIADEFAULT\naws_secret_access_key = s1\n\n\
+         # comment\n[work]\naws_access_key_id=AKIAWORK\naws_secret_access_key=s2\naws_session_token=t2\n",
+    )
+    .unwrap();
+    std::fs::write(&config_file, "[profile work]\nregion = eu-west-1\n").unwrap();
+
+    // SAFETY: this is the only test in this binary that touches AWS_* variables.
+    unsafe {
+        std::env::set_var("AWS_SHARED_CREDENTIALS_FILE", &credentials_file);
+        std::env::set_var("AWS_CONFIG_FILE", &config_file);
+        std::env::set_var("AWS_PROFILE", "work");
+        std::env::remove_var("AWS_ACCESS_KEY_ID");
+        std::env::remove_var("AWS_SECRET_ACCESS_KEY");
+        std::env::remove_var("AWS_REGION");
+        std::env::remove_var("AWS_DEFAULT_REGION");
+    }
+
+    let work = AwsCredentials::load().unwrap();
+    assert_eq!(work.access_key_id, "AKIAWORK");
+    assert_eq!(work.session_token.as_deref(), Some("t2"));
+    assert_eq!(aws::region_from_env().as_deref(), Some("eu-west-1"));
+    assert_eq!(
+        AwsCredentials::from_profile("default")
+            .unwrap()
+            .access_key_id,
+        "AKIADEFAULT"
+    );
+    assert!(AwsCredentials::from_profile("missing").is_err());
+    assert!(!format!("{work:?}").contains("s2"));
+
+    unsafe {
+        std::env::set_var("AWS_ACCESS_KEY_ID", "AKIAENV");
+        std::env::set_var("AWS_SECRET_ACCESS_KEY", "s3");
+        std::env::set_var("AWS_REGION", "ap-south-1");
+    }
+    let provider = BedrockProvider::from_env(MODEL).unwrap();
+    assert_eq!(provider.name(), "bedrock");
+    assert_eq!(AwsCredentials::load().unwrap().access_key_id, "AKIAENV");
+    assert_eq!(aws::region_from_env().as_deref(), Some("ap-south-1"));
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn converse_maps_content_and_signs_requests() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path(format!("{MODEL_PATH}/converse")))
+        .and(header_exists("x-amz-date"))
+        .and(body_partial_json(json!({
+            "system": [{"text": "Be brief."}],
+            "messages": [
+                {"role": "user", "content": [
+                    {"text": "What's in the image?"},
+                    {"image": {"format": "jpeg", "source": {"bytes": "aGVsbG8="}}},
+                    {"document": {"format": "pdf", "name": "Q3 report pdf", "source": {"bytes": "JVBERi0="}}}
+                ]},
+                {"role": "assistant", "content": [
+                    {"reasoningContent": {"reasoningText": {"text": "Check.", "signature": "sig"}}},
+                    {"toolUse": {"toolUseId": "t1", "name": "lookup", "input": {"q": "cat"}}}
+                ]},
+                {"role": "user", "content": [
+                    {"toolResult": {"toolUseId": "t1", "content": [{"json": {"animal": "cat"}}]}},
+                    {"text": "Thanks"}
+                ]}
+            ],
+            "inferenceConfig": {"maxTokens": 512, "temperature": 0.2},
+            "additionalModelRequestFields": {"top_k": 40},
+            "toolConfig": {
+                "tools": [{"toolSpec": {"name": "lookup", "inputSchema": {"json": {"type": "object"}}}}],
+                "toolChoice": {"any": {}}
+            }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "output": {"message": {"role": "assistant", "content": [
+                {"reasoningContent": {"reasoningText": {"text": "It's a cat.", "signature": "sig2"}}},
+                {"text": "A cat."}
+            ]}},
+            "stopReason": "end_turn",
+            "usage": {"inputTokens": 30, "outputTokens": 4, "totalTokens": 34, "cacheReadInputTokens": 10},
+            "metrics": {"latencyMs": 250}
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut user = Message::user("What's in the image?");
+    user.content.push(Content::Image {
+        url: None,
+        base64: Some("aGVsbG8=".into()),
+        media_type: Some("image/jpeg".into()),
+    });
+    user.content.push(Content::Document {
+        source_type: "base64".into(),
+        data: Some("JVBERi0=".into()),
+        media_type: Some("application/pdf".into()),
+        title: Some("Q3 report.pdf".into()),
+        citations_enabled: false,
+    });
+    let assistant = Message {
+        role: gauss_core::message::Role::Assistant,
+        content: vec![
+            Content::Reasoning {
+                text: "Check.".into(),
+                id: None,
+                encrypted_content: Some("sig".into()),
+            },
+            Content::ToolCall {
+                id: "t1".into(),
+                name: "lookup".into(),
+                arguments: json!({"q": "cat"}),
+            },
+        ],
+        name: None,
+    };
+    let messages = vec![
+        Message::system("Be brief."),
+        user,
+        assistant,
+        Message::tool_result("t1", json!({"animal": "cat"})),
+        Message::user("Thanks"),
+    ];
+    let tools = vec![Tool::builder("lookup", "Look something up").build()];
+    let options = GenerateOptions {
+        max_tokens: Some(512),
+        temperature: Some(0.2),
+        top_k: Some(40),
+        tool_choice: Some(ToolChoice::Required),
+        ..Default::default()
+    };
+
+    let result = provider(&server)
+        .generate(&messages, &tools, &options)
+        .await
+        .unwrap();
+    assert_eq!(result.text(), Some("A cat."));
+    assert_eq!(result.thinking.as_deref(), Some("It's a cat."));
+    assert!(matches!(
+        &result.message.content[0],
+        Content::Reasoning { encrypted_content: Some(s), .. } if s == "sig2"
+    ));
+    assert!(matches!(result.finish_reason, FinishReason::Stop));
+    assert_eq!(
+        (result.usage.input_tokens, result.usage.output_tokens),
+        (30, 4)
+    );
+    assert_eq!(result.usage.cache_read_tokens, Some(10));
+    assert_eq!(result.provider_metadata["metrics"]["latencyMs"], 250);
+
+    let request = &server.received_requests().await.unwrap()[0];
+    let authorization = request.headers["authorization"].to_str().unwrap();
+    assert!(
+        authorization.starts_with("AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/"),
+        "{authorization}"
+    );
+    assert!(authorization.contains("/us-west-2/bedrock/aws4_request"));
+    assert!(
+        authorization.contains("SignedHeaders=content-type;host;x-amz-date;x-amz-security-token")
+    );
+    assert_eq!(request.headers["x-amz-security-token"], "session");
+}
+
+#[tokio::test]
+async fn signature_failures_are_authentication_errors() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(403).set_body_json(json!({
+            "message": "The request signature we calculated does not match"
+        })))
+        .mount(&server)
+        .await;
+
+    let err = provider(&server)
+        .generate(&[Message::user("hi")], &[], &GenerateOptions::default())
+        .await
+        .unwrap_err();
+    assert!(matches!(err, GaussError::Authentication { .. }), "{err}");
+}
+
+fn event(event_type: &str, payload: Value) -> Vec<u8> {
+    EventStreamMessage::event(event_type, &payload).encode()
+}
+
+#[tokio::test]
+async fn converse_stream_decodes_event_frames() {
+    let mut body = Vec::new();
+    body.extend(event("messageStart", json!({"role": "assistant"})));
+    body.extend(event(
+        "contentBlockDelta",
+        json!({"contentBlockIndex": 0, "delta": {"reasoningContent": {"text": "Hmm."}}}),
+    ));
+    body.extend(event(
+        "contentBlockDelta",
+        json!({"contentBlockIndex": 0, "delta": {"reasoningContent": {"signature": "sig"}}}),
+    ));
+    body.extend(event(
+        "contentBlockDelta",
+        json!({"contentBlockIndex": 1, "delta": {"text": "Looking "}}),
+    ));
+    body.extend(event(
+        "contentBlockStart",
+        json!({"contentBlockIndex": 2, "start": {"toolUse": {"toolUseId": "t1", "name": "lookup"}}}),
+    ));
+    body.extend(event(
+        "contentBlockDelta",
+        json!({"contentBlockIndex": 2, "delta": {"toolUse": {"input": "{\"q\":"}}}),
+    ));
+    body.extend(event(
+        "contentBlockDelta",
+        json!({"contentBlockIndex": 2, "delta": {"toolUse": {"input": "\"cat\"}"}}}),
+    ));
+    body.extend(event("contentBlockStop", json!({"contentBlockIndex": 2})));
+    body.extend(event("messageStop", json!({"stopReason": "tool_use"})));
+    body.extend(event(
+        "metadata",
+        json!({"usage": {"inputTokens": 12, "outputTokens": 7}, "metrics": {"latencyMs": 90}}),
+    ));
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path(format!("{MODEL_PATH}/converse-stream")))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "application/vnd.amazon.eventstream")
+                .set_body_bytes(body),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let events: Vec<StreamEvent> = provider(&server)
+        .stream(&[Message::user("hi")], &[], &GenerateOptions::default())
+        .await
+        .unwrap()
+        .map(|e| e.unwrap())
+        .collect()
+        .await;
+
+    assert_eq!(
+        serde_json::to_value(&events).unwrap(),
+        serde_json::to_value(vec![
+            StreamEvent::ReasoningDelta("Hmm.".into()),
+            StreamEvent::ReasoningItem {
+                id: None,
+                encrypted_content: Some("sig".into()),
+            },
+            StreamEvent::TextDelta("Looking ".into()),
+            StreamEvent::ToolCallDelta {
+                index: 0,
+                id: Some("t1".into()),
+                name: Some("lookup".into()),
+                arguments_delta: None,
+            },
+            StreamEvent::ToolCallDelta {
+                index: 0,
+                id: None,
+                name: None,
+                arguments_delta: Some("{\"q\":".into()),
+            },
+            StreamEvent::ToolCallDelta {
+                index: 0,
+                id: None,
+                name: None,
+                arguments_delta: Some("\"cat\"}".into()),
+            },
+            StreamEvent::FinishReason(FinishReason::ToolCalls),
+            StreamEvent::Usage(gauss_core::message::Usage {
+                input_tokens: 12,
+                output_tokens: 7,
+                ..Default::default()
+            }),
+            StreamEvent::Done,
+        ])
+        .unwrap()
+    );
+}
+
+#[tokio::test]
+async fn stream_exceptions_surface_as_errors() {
+    let exception = EventStreamMessage {
+        headers: vec![
+            (":message-type".into(), "exception".into()),
+            (":exception-type".into(), "throttlingException".into()),
+        ],
+        payload: br#"{"message":"Too many tokens"}"#.to_vec(),
+    };
+    let mut body = event(
+        "contentBlockDelta",
+        json!({"contentBlockIndex": 0, "delta": {"text": "Par"}}),
+    );
+    body.extend(exception.encode());
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(body))
+        .mount(&server)
+        .await;
+
+    let events: Vec<_> = provider(&server)
+        .stream(&[Message::user("hi")], &[], &GenerateOptions::default())
+        .await
+        .unwrap()
+        .collect()
+        .await;
+    assert_eq!(events.len(), 2);
+    assert!(matches!(events[1], Err(GaussError::RateLimited { .. })));
+}
+
+#[tokio::test]
+async fn streams_without_metadata_are_truncated() {
+    let mut body = event(
+        "contentBlockDelta",
+        json!({"contentBlockIndex": 0, "delta": {"text": "Par"}}),
+    );
+    body.extend(event("messageStop", json!({"stopReason": "end_turn"})));
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(body))
+        .mount(&server)
+        .await;
+
+    let events: Vec<_> = provider(&server)
+        .stream(&[Message::user("hi")], &[], &GenerateOptions::default())
+        .await
+        .unwrap()
+        .collect()
+        .await;
+    assert_eq!(events.len(), 3);
+    assert!(matches!(events[2], Err(GaussError::Provider { .. })));
+}
+
+#[test]
+fn decoder_handles_split_frames_and_rejects_corruption() {
+    let frame = event("messageStop", json!({"stopReason": "end_turn"}));
+    let mut decoder = EventStreamDecoder::new();
+    for byte in &frame[..frame.len() - 1] {
+        decoder.push(std::slice::from_ref(byte));
+        assert!(decoder.next_message().unwrap().is_none());
+    }
+    decoder.push(&frame[frame.len() - 1..]);
+    let message = decoder.next_message().unwrap().unwrap();
+    assert_eq!(message.header(":event-type"), Some("messageStop"));
+    assert_eq!(message.payload, br#"{"stopReason":"end_turn"}"#);
+    assert_eq!(decoder.buffered(), 0);
+
+    let mut corrupt = frame.clone();
+    let last = corrupt.len() - 5;
+    corrupt[last] ^= 0xff;
+    let mut decoder = EventStreamDecoder::new();
+    decoder.push(&corrupt);
+    assert!(decoder.next_message().is_err());
+}
@@ -0,0 +1,237 @@
+use async_trait::async_trait;
+use futures::StreamExt;
+use gauss_core::error::Result;
+use gauss_core::message::Message;
+use gauss_core::provider::cache::{
+    CacheEntry, CacheStore, CachedProvider, CachedResponse, DiskCacheStore, MemoryCacheStore,
+};
+use gauss_core::provider::mock::{MockProvider, MockResponse};
+use gauss_core::provider::{GenerateOptions, Provider};
+use gauss_core::rag::Embedding;
+use gauss_core::streaming::StreamEvent;
+use gauss_core::tool::Tool;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn counting_mock() -> Arc<MockProvider> {
+    Arc::new(MockProvider::new().repeat(MockResponse::Text("cached answer".into())))
+}
+
+fn ask(text: &str) -> Vec<Message> {
+    vec![Message::user(text)]
+}
+
+#[tokio::test]
+async fn exact_key_covers_messages_tools_and_options() {
+    let mock = counting_mock();
+    let cached = CachedProvider::new(mock.clone(), Arc::new(MemoryCacheStore::default()));
+    let options = GenerateOptions::default();
+
+    let first = cached.generate(&ask("hi"), &[], &options).await.unwrap();
+    assert!(first.provider_metadata.get("cache").is_none());
+    let second = cached.generate(&ask("hi"), &[], &options).await.unwrap();
+    assert_eq!(second.text(), Some("cached answer"));
+    assert_eq!(second.provider_metadata["cache"]["hit"], "exact");
+    assert_eq!(mock.call_count(), 1);
+
+    let warmer = GenerateOptions {
+        temperature: Some(0.9),
+        ..Default::default()
+    };
+    cached.generate(&ask("hi"), &[], &warmer).await.unwrap();
+    let tools = vec![Tool::builder("lookup", "Look something up").build()];
+    cached.generate(&ask("hi"), &tools, &options).await.unwrap();
+    cached
+        .generate(&ask("hi there"), &[], &options)
+        .await
+        .unwrap();
+    assert_eq!(mock.call_count(), 4);
+
+    let stats = cached.stats();
+    assert_eq!((stats.hits, stats.misses), (1, 4));
+}
+
+async fn collect(stream: gauss_core::provider::BoxStream) -> Vec<StreamEvent> {
+    stream.map(|e| e.unwrap()).collect().await
+}
+
+#[tokio::test]
+async fn streams_are_replayed_once_complete() {
+    let mock = Arc::new(
+        MockProvider::new()
+            .chunk_size(3)
+            .repeat(MockResponse::Text("streamed text".into())),
+    );
+    let cached = CachedProvider::new(mock.clone(), Arc::new(MemoryCacheStore::default()));
+    let options = GenerateOptions::default();
+
+    // An abandoned stream is not cached.
+    let mut partial = cached.stream(&ask("go"), &[], &options).await.unwrap();
+    partial.next().await.unwrap().unwrap();
+    drop(partial);
+
+    let live = collect(cached.stream(&ask("go"), &[], &options).await.unwrap()).await;
+    let replayed = collect(cached.stream(&ask("go"), &[], &options).await.unwrap()).await;
+    assert_eq!(mock.call_count(), 2);
+    // The replay is the recorded stream plus a hit marker before `Done`.
+    let (marker, rest): (Vec<_>, Vec<_>) = replayed
+        .iter()
+        .partition(|e| matches!(e, StreamEvent::ProviderMetadata(_)));
+    assert!(matches!(
+        marker.as_slice(),
+        [StreamEvent::ProviderMetadata(meta)] if meta["cache"]["hit"] == "exact"
+    ));
+    assert_eq!(
+        serde_json::to_value(&live).unwrap(),
+        serde_json::to_value(&rest).unwrap()
+    );
+    assert!(replayed.len() > 3 && replayed.last().unwrap().is_done());
+
+    // Streamed and generated responses are cached separately.
+    cached.generate(&ask("go"), &[], &options).await.unwrap();
+    assert_eq!(mock.call_count(), 3);
+
+    // A stream that ends without `Done` was cut short and is not cached.
+    let truncated = vec![StreamEvent::TextDelta("cut".into())];
+    let mock = Arc::new(
+        MockProvider::new()
+            .stream_events(truncated.clone())
+            .stream_events(truncated),
+    );
+    let cached = CachedProvider::new(mock.clone(), Arc::new(MemoryCacheStore::default()));
+    for _ in 0..2 {
+        collect(cached.stream(&ask("go"), &[], &options).await.unwrap()).await;
+    }
+    assert_eq!(mock.call_count(), 2);
+
+    // Consumers such as `Agent` stop reading at `Done`; that is enough.
+    let mock = Arc::new(MockProvider::new().repeat(MockResponse::Text("agent".into())));
+    let cached = CachedProvider::new(mock.clone(), Arc::new(MemoryCacheStore::default()));
+    for _ in 0..2 {
+        let mut stream = cached.stream(&ask("go"), &[], &options).await.unwrap();
+        while !stream.next().await.unwrap().unwrap().is_done() {}
+    }
+    assert_eq!(mock.call_count(), 1);
+}
+
+fn entry(text: &str) -> CacheEntry {
+    CacheEntry {
+        response: CachedResponse::Stream {
+            events: vec![StreamEvent::TextDelta(text.into())],
+        },
+        created_at_ms: 0,
+        semantic: None,
+    }
+}
+
+#[tokio::test]
+async fn memory_store_evicts_least_recently_used() {
+    let store = MemoryCacheStore::new(2);
+    store.put("a", entry("a")).await.unwrap();
+    store.put("b", entry("b")).await.unwrap();
+    assert!(store.get("a").await.unwrap().is_some());
+    store.put("c", entry("c")).await.unwrap();
+
+    assert_eq!(store.len(), 2);
+    assert!(store.get("b").await.unwrap().is_none());
+    assert!(store.get("a").await.unwrap().is_some());
+    assert!(store.get("c").await.unwrap().is_some());
+
+    // Overwriting doesn't evict.
+    store.put("c", entry("c2")).await.unwrap();
+    assert_eq!(store.len(), 2);
+}
+
+#[tokio::test]
+async fn disk_store_persists_and_ttl_expires() {
+    let dir = std::env::temp_dir().join(format!("gauss-cache-{}", uuid::Uuid::new_v4()));
+    let options = GenerateOptions::default();
+
+    let mock = counting_mock();
+    let cached = CachedProvider::new(mock.clone(), Arc::new(DiskCacheStore::new(&dir)));
+    cached.generate(&ask("hi"), &[], &options).await.unwrap();
+
+    // A new provider over the same directory sees the entry.
+    let reopened = CachedProvider::new(mock.clone(), Arc::new(DiskCacheStore::new(&dir)));
+    let hit = reopened.generate(&ask("hi"), &[], &options).await.unwrap();
+    assert_eq!(hit.provider_metadata["cache"]["hit"], "exact");
+    assert_eq!(mock.call_count(), 1);
+
+    let expiring = CachedProvider::new(mock.clone(), Arc::new(DiskCacheStore::new(&dir)))
+        .with_ttl(Duration::ZERO);
+    expiring.generate(&ask("hi"), &[], &options).await.unwrap();
+    assert_eq!(mock.call_count(), 2);
+
+    // Clearing removes only what the store wrote.
+    std::fs::write(dir.join("notes.txt"), "keep").unwrap();
+    std::fs::write(dir.join(".abc.123.tmp"), "partial").unwrap();
+    reopened.clear().await.unwrap();
+    let left: Vec<_> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|e| e.unwrap().file_name().into_string().unwrap())
+        .collect();
+    assert_eq!(left, vec!["notes.txt"]);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+/// Embeds by topic: prompts mentioning France point one way, the rest another.
+struct TopicEmbedding;
+
+#[async_trait]
+impl Embedding for TopicEmbedding {
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        let text = text.to_lowercase();
+        Ok(if text.contains("france") {
+            vec![1.0, 0.1 * text.len() as f32 / 100.0]
+        } else {
+            vec![0.0, 1.0]
+        })
+    }
+
+    fn dimensions(&self) -> usize {
+        2
+    }
+}
+
+#[tokio::test]
+async fn semantic_mode_reuses_near_duplicate_prompts() {
+    let mock = counting_mock();
+    let cached = CachedProvider::new(mock.clone(), Arc::new(MemoryCacheStore::default()))
+        .semantic(Arc::new(TopicEmbedding), 0.99);
+    let options = GenerateOptions::default();
+
+    cached
+        .generate(&ask("What is the capital of France?"), &[], &options)
+        .await
+        .unwrap();
+    let hit = cached
+        .generate(&ask("capital of france?"), &[], &options)
+        .await
+        .unwrap();
+    assert_eq!(hit.provider_metadata["cache"]["hit"], "semantic");
+    assert!(
+        hit.provider_metadata["cache"]["similarity"]
+            .as_f64()
+            .unwrap()
+            >= 0.99
+    );
+    assert_eq!(mock.call_count(), 1);
+
+    // Unrelated prompts miss.
+    cached
+        .generate(&ask("How tall is Everest?"), &[], &options)
+        .await
+        .unwrap();
+    assert_eq!(mock.call_count(), 2);
+
+    // So do similar prompts in a different conversation.
+    let history = vec![
+        Message::system("Answer in French."),
+        Message::user("capital of france?"),
+    ];
+    cached.generate(&history, &[], &options).await.unwrap();
+    assert_eq!(mock.call_count(), 3);
+
+    let stats = cached.stats();
+    assert_eq!((stats.hits, stats.semantic_hits, stats.misses), (0, 1, 3));
+}
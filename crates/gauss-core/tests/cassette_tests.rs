@@ -0,0 +1,405 @@
+use async_trait::async_trait;
+use futures::StreamExt;
+use gauss_core::agent::Agent;
+use gauss_core::error::{GaussError, Result};
+use gauss_core::message::{Message, Usage};
+use gauss_core::provider::cassette::*;
+use gauss_core::provider::openai::OpenAiProvider;
+use gauss_core::provider::{
+    BoxStream, FinishReason, GenerateOptions, GenerateResult, Provider, ProviderConfig,
+};
+use gauss_core::streaming::StreamEvent;
+use gauss_core::tool::Tool;
+use serde_json::json;
+use std::path::PathBuf;
+use std::sync::Arc;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn cassette_path(name: &str) -> PathBuf {
+    std::env::temp_dir()
+        .join(format!("gauss-cassettes-{}", uuid::Uuid::new_v4()))
+        .join(format!("{name}.json"))
+}
+
+/// Streams a fixed event sequence, or fails every call with a 503.
+struct ScriptedProvider {
+    events: Vec<StreamEvent>,
+    fail: bool,
+}
+
+#[async_trait]
+impl Provider for ScriptedProvider {
+    fn name(&self) -> &str {
+        "scripted"
+    }
+
+    fn model(&self) -> &str {
+        "script-1"
+    }
+
+    async fn generate(
+        &self,
+        messages: &[Message],
+        _tools: &[Tool],
+        _options: &GenerateOptions,
+    ) -> Result<GenerateResult> {
+        if self.fail {
+            return Err(GaussError::Provider {
+                message: "overloaded".into(),
+                status: Some(503),
+                provider: "scripted".into(),
+                source: None,
+            });
+        }
+        Ok(GenerateResult {
+            message: Message::assistant(format!("echo: {}", messages.len())),
+            usage: Usage::default(),
+            finish_reason: FinishReason::Stop,
+            provider_metadata: json!({}),
+            thinking: None,
+            citations: vec![],
+            grounding_metadata: None,
+            routing: None,
+        })
+    }
+
+    async fn stream(
+        &self,
+        _messages: &[Message],
+        _tools: &[Tool],
+        _options: &GenerateOptions,
+    ) -> Result<BoxStream> {
+        let events: Vec<Result<StreamEvent>> = self.events.iter().cloned().map(Ok).collect();
+        Ok(Box::new(futures::stream::iter(events)))
+    }
+}
+
+fn stream_events() -> Vec<StreamEvent> {
+    vec![
+        StreamEvent::TextDelta("Hel".into()),
+        StreamEvent::TextDelta("lo".into()),
+        StreamEvent::FinishReason(FinishReason::Stop),
+        StreamEvent::Done,
+    ]
+}
+
+async fn collect(stream: BoxStream) -> Vec<String> {
+    stream
+        .map(|event| serde_json::to_string(&event.unwrap()).unwrap())
+        .collect()
+        .await
+}
+
+#[tokio::test]
+async fn agent_replays_recorded_openai_run_offline() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion",
+            "choices": [{
+                "index": 0,
+                "message": { "role": "assistant", "content": "Recorded answer" },
+                "finish_reason": "stop"
+            }],
+            "usage": { "prompt_tokens": 7, "completion_tokens": 2, "total_tokens": 9 }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let path = cassette_path("agent");
+    let openai = Arc::new(OpenAiProvider::new(
+        "gpt-test",
+        ProviderConfig::new("test-key").base_url(server.uri()),
+    ));
+    let recorder = Arc::new(RecordingProvider::new(openai, &path));
+    let recorded = Agent::builder("assistant", recorder.clone())
+        .build()
+        .run(vec![Message::user("Hi")])
+        .await
+        .unwrap();
+    assert_eq!(recorded.text, "Recorded answer");
+    let cassette = recorder.cassette();
+    assert_eq!(cassette.interactions.len(), 1);
+    assert_eq!(cassette.interactions[0].request.kind, RequestKind::Generate);
+    assert_eq!(cassette.interactions[0].request.model, "gpt-test");
+    recorder.flush().unwrap();
+    drop(server);
+
+    let replay = Arc::new(ReplayProvider::from_file(&path).unwrap());
+    assert_eq!(replay.name(), "openai");
+    let replayed = Agent::builder("assistant", replay.clone())
+        .build()
+        .run(vec![Message::user("Hi")])
+        .await
+        .unwrap();
+    assert_eq!(replayed.text, "Recorded answer");
+    assert_eq!(replayed.usage.input_tokens, 7);
+    assert_eq!(replay.remaining(), 0);
+}
+
+#[tokio::test]
+async fn stream_is_recorded_and_replayed_event_for_event() {
+    let path = cassette_path("stream");
+    let recorder = RecordingProvider::new(
+        Arc::new(ScriptedProvider {
+            events: stream_events(),
+            fail: false,
+        }),
+        &path,
+    );
+    let messages = vec![Message::user("Say hello")];
+    let options = GenerateOptions::default();
+    let live = collect(recorder.stream(&messages, &[], &options).await.unwrap()).await;
+    assert_eq!(live.len(), 4);
+    recorder.flush().unwrap();
+
+    let replay = ReplayProvider::from_file(&path)
+        .unwrap()
+        .timing(ReplayTiming::Instant);
+    let replayed = collect(replay.stream(&messages, &[], &options).await.unwrap()).await;
+    assert_eq!(replayed, live);
+}
+
+#[tokio::test]
+async fn streams_are_recorded_when_the_consumer_stops_at_done() {
+    let path = cassette_path("stop-at-done");
+    let recorder = RecordingProvider::new(
+        Arc::new(ScriptedProvider {
+            events: stream_events(),
+            fail: false,
+        }),
+        &path,
+    );
+    let mut stream = recorder
+        .stream(&[Message::user("hi")], &[], &GenerateOptions::default())
+        .await
+        .unwrap();
+    // Read up to `Done`, then drop the stream without polling it again.
+    while !stream.next().await.unwrap().unwrap().is_done() {}
+    drop(stream);
+
+    let cassette = recorder.cassette();
+    assert_eq!(cassette.interactions.len(), 1);
+    assert!(matches!(
+        &cassette.interactions[0].response,
+        RecordedResponse::Stream { events, error: None } if events.len() == 4
+    ));
+
+    // Saved by rename: no temporary file is left next to the cassette.
+    recorder.flush().unwrap();
+    let dir = path.parent().unwrap();
+    let names: Vec<_> = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|e| e.unwrap().file_name())
+        .collect();
+    assert_eq!(names, vec![path.file_name().unwrap().to_owned()]);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn replay_reproduces_stream_timing() {
+    let request = RecordedRequest {
+        kind: RequestKind::Stream,
+        provider: "scripted".into(),
+        model: "script-1".into(),
+        messages: vec![Message::user("hi")],
+        tools: vec![],
+        options: GenerateOptions::default(),
+    };
+    let events = stream_events()
+        .into_iter()
+        .zip([0, 100, 250, 250])
+        .map(|(event, offset_ms)| RecordedEvent { offset_ms, event })
+        .collect();
+    let mut cassette = Cassette::new();
+    cassette.interactions.push(Interaction::new(
+        request,
+        RecordedResponse::Stream {
+            events,
+            error: None,
+        },
+        std::time::Duration::from_millis(250),
+    ));
+    let messages = vec![Message::user("hi")];
+
+    let replay = ReplayProvider::new(cassette.clone());
+    let started = tokio::time::Instant::now();
+    let events = collect(
+        replay
+            .stream(&messages, &[], &Default::default())
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(events.len(), 4);
+    assert_eq!(started.elapsed().as_millis(), 250);
+
+    let replay = ReplayProvider::new(cassette).timing(ReplayTiming::Scaled(0.5));
+    let started = tokio::time::Instant::now();
+    collect(
+        replay
+            .stream(&messages, &[], &Default::default())
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(started.elapsed().as_millis(), 125);
+}
+
+#[tokio::test]
+async fn match_strictness_controls_which_requests_replay() {
+    let path = cassette_path("strictness");
+    let recorder = RecordingProvider::new(
+        Arc::new(ScriptedProvider {
+            events: vec![],
+            fail: false,
+        }),
+        &path,
+    );
+    let recorded_options = GenerateOptions {
+        temperature: Some(0.2),
+        ..Default::default()
+    };
+    recorder
+        .generate(&[Message::user("one")], &[], &recorded_options)
+        .await
+        .unwrap();
+    recorder
+        .generate(
+            &[Message::user("one"), Message::user("two")],
+            &[],
+            &recorded_options,
+        )
+        .await
+        .unwrap();
+    recorder.flush().unwrap();
+
+    let other_options = GenerateOptions {
+        temperature: Some(0.9),
+        ..Default::default()
+    };
+    let exact = ReplayProvider::from_file(&path).unwrap();
+    let err = exact
+        .generate(&[Message::user("one")], &[], &other_options)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("No recorded"), "{err}");
+
+    let relaxed = ReplayProvider::from_file(&path)
+        .unwrap()
+        .strictness(MatchStrictness::IgnoreOptions);
+    let result = relaxed
+        .generate(&[Message::user("one")], &[], &other_options)
+        .await
+        .unwrap();
+    assert_eq!(result.text(), Some("echo: 1"));
+    // Matches are reused once exhausted.
+    let again = relaxed
+        .generate(&[Message::user("one")], &[], &other_options)
+        .await
+        .unwrap();
+    assert_eq!(again.text(), Some("echo: 1"));
+
+    let sequential = ReplayProvider::from_file(&path)
+        .unwrap()
+        .strictness(MatchStrictness::Sequential);
+    let texts = [
+        sequential
+            .generate(&[Message::user("anything")], &[], &other_options)
+            .await
+            .unwrap(),
+        sequential
+            .generate(&[Message::user("else")], &[], &other_options)
+            .await
+            .unwrap(),
+    ];
+    assert_eq!(texts[0].text(), Some("echo: 1"));
+    assert_eq!(texts[1].text(), Some("echo: 2"));
+    assert!(
+        sequential
+            .generate(&[Message::user("more")], &[], &other_options)
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn provider_errors_are_recorded_and_replayed() {
+    let path = cassette_path("errors");
+    let recorder = RecordingProvider::new(
+        Arc::new(ScriptedProvider {
+            events: vec![],
+            fail: true,
+        }),
+        &path,
+    );
+    let messages = vec![Message::user("hi")];
+    assert!(
+        recorder
+            .generate(&messages, &[], &Default::default())
+            .await
+            .is_err()
+    );
+    drop(recorder);
+
+    let replay = ReplayProvider::from_file(&path).unwrap();
+    match replay.generate(&messages, &[], &Default::default()).await {
+        Err(GaussError::Provider {
+            status, message, ..
+        }) => {
+            assert_eq!(status, Some(503));
+            assert_eq!(message, "overloaded");
+        }
+        other => panic!("expected provider error, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn recording_failures_do_not_fail_calls() {
+    // The cassette's parent "directory" is a file, so every write fails.
+    let blocker = cassette_path("blocker");
+    std::fs::create_dir_all(blocker.parent().unwrap()).unwrap();
+    std::fs::write(&blocker, "").unwrap();
+    let recorder = RecordingProvider::new(
+        Arc::new(ScriptedProvider {
+            events: vec![],
+            fail: false,
+        }),
+        blocker.join("cassette.json"),
+    );
+
+    let result = recorder
+        .generate(&[Message::user("hi")], &[], &Default::default())
+        .await
+        .unwrap();
+    assert_eq!(result.text(), Some("echo: 1"));
+    assert_eq!(recorder.cassette().interactions.len(), 1);
+}
+
+#[tokio::test]
+async fn replay_reports_recorded_capabilities() {
+    use gauss_core::provider::ProviderCapabilities;
+    use gauss_core::provider::mock::MockProvider;
+
+    let path = cassette_path("capabilities");
+    let mock = MockProvider::new().with_capabilities(ProviderCapabilities {
+        vision: true,
+        streaming: false,
+        ..Default::default()
+    });
+    let recorder = RecordingProvider::new(Arc::new(mock.text("hi")), &path);
+    recorder
+        .generate(&[Message::user("hi")], &[], &Default::default())
+        .await
+        .unwrap();
+    drop(recorder);
+
+    let replay = ReplayProvider::from_file(&path).unwrap();
+    let capabilities = replay.capabilities();
+    assert!(capabilities.vision);
+    assert!(!capabilities.streaming);
+}
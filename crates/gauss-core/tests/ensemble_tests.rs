@@ -0,0 +1,194 @@
+use futures::StreamExt;
+use gauss_core::message::Message;
+use gauss_core::provider::ensemble::{EnsembleProvider, JudgeAggregator, MajorityVote};
+use gauss_core::provider::mock::{MockError, MockProvider};
+use gauss_core::provider::{GenerateOptions, Provider};
+use gauss_core::streaming::StreamEvent;
+use serde_json::json;
+use std::sync::Arc;
+
+fn member(name: &str, answer: &str) -> Arc<MockProvider> {
+    Arc::new(MockProvider::new().with_name(name).text(answer))
+}
+
+fn ask() -> Vec<Message> {
+    vec![Message::user("Extract the order")]
+}
+
+#[tokio::test]
+async fn majority_vote_compares_structured_output() {
+    let ensemble = EnsembleProvider::new(vec![
+        member("a", r#"{"item": "book", "qty": 3}"#),
+        member("b", r#"{"item": "pen", "qty": 1}"#),
+        member("c", "{\n  \"qty\": 1,\n  \"item\": \"pen\"\n}"),
+    ]);
+    let result = ensemble
+        .generate(&ask(), &[], &GenerateOptions::default())
+        .await
+        .unwrap();
+
+    assert_eq!(result.text(), Some(r#"{"item": "pen", "qty": 1}"#));
+    let meta = &result.provider_metadata["ensemble"];
+    assert_eq!(meta["aggregator"], "majority_vote");
+    assert_eq!(meta["chosen"]["provider"], "b");
+    let routing = result.routing.unwrap();
+    assert_eq!((routing.index, routing.provider.as_str()), (1, "b"));
+    assert_eq!(routing.strategy, "ensemble");
+    assert_eq!(meta["details"], json!({"votes": 2, "distinct_answers": 2}));
+    // Every member's tokens are accounted for.
+    assert_eq!(
+        (result.usage.input_tokens, result.usage.output_tokens),
+        (30, 15)
+    );
+}
+
+#[tokio::test]
+async fn majority_vote_ties_and_thresholds() {
+    let members = || -> Vec<Arc<dyn Provider>> {
+        vec![
+            member("a", "Paris"),
+            member("b", "  paris"),
+            member("c", "Lyon"),
+        ]
+    };
+    // "Paris" and "paris" differ, so every answer has one vote and the first wins.
+    let result = EnsembleProvider::new(members())
+        .generate(&ask(), &[], &GenerateOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(result.provider_metadata["ensemble"]["chosen"]["index"], 0);
+
+    let err = EnsembleProvider::new(members())
+        .with_aggregator(Arc::new(MajorityVote::new().with_min_votes(2)))
+        .generate(&ask(), &[], &GenerateOptions::default())
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("No answer reached 2 votes"),
+        "{err}"
+    );
+}
+
+#[tokio::test]
+async fn judge_picks_the_best_answer() {
+    let judge = Arc::new(
+        MockProvider::new()
+            .with_name("judge")
+            .text(r#"{"best": 2, "reasoning": "Includes the quantity"}"#),
+    );
+    let ensemble = EnsembleProvider::new(vec![member("a", "A pen"), member("b", "One pen")])
+        .with_aggregator(Arc::new(
+            JudgeAggregator::new(judge.clone()).with_criteria("Prefer precise answers."),
+        ));
+
+    let result = ensemble
+        .generate(&ask(), &[], &GenerateOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(result.text(), Some("One pen"));
+    let details = &result.provider_metadata["ensemble"]["details"];
+    assert_eq!(details["reasoning"], "Includes the quantity");
+    assert_eq!(result.usage.input_tokens, 30);
+    // Two members and the judge, each under its own model.
+    let usage_by_model = result.provider_metadata["usage_by_model"]
+        .as_array()
+        .unwrap();
+    assert_eq!(usage_by_model.len(), 3);
+    assert_eq!(usage_by_model[2]["usage"]["input_tokens"], 10);
+
+    let request = &judge.requests()[0];
+    let prompt = request.last_user_text().unwrap();
+    assert!(prompt.contains("Extract the order"));
+    assert!(prompt.contains("ANSWER 1:\nA pen") && prompt.contains("ANSWER 2:\nOne pen"));
+    assert!(request.options.output_schema.is_some());
+
+    // Out-of-range verdicts are errors rather than silent picks.
+    let judge = Arc::new(MockProvider::new().text(r#"{"best": 7, "reasoning": "?"}"#));
+    let err = EnsembleProvider::new(vec![member("a", "x")])
+        .with_aggregator(Arc::new(JudgeAggregator::new(judge)))
+        .generate(&ask(), &[], &GenerateOptions::default())
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("no valid answer"), "{err}");
+}
+
+#[tokio::test]
+async fn failed_members_are_skipped_and_streams_replay_the_choice() {
+    let failing = Arc::new(MockProvider::new().error(MockError::status(500, "boom")));
+    let ensemble = EnsembleProvider::new(vec![failing, member("ok", "42")]);
+    let mut stream = ensemble
+        .stream(&ask(), &[], &GenerateOptions::default())
+        .await
+        .unwrap();
+    let mut text = String::new();
+    let mut done = false;
+    let mut routed = None;
+    while let Some(event) = stream.next().await {
+        match event.unwrap() {
+            StreamEvent::Routing(decision) => routed = Some(decision.provider),
+            StreamEvent::TextDelta(delta) => text.push_str(&delta),
+            StreamEvent::Done => done = true,
+            _ => {}
+        }
+    }
+    assert_eq!((text.as_str(), done), ("42", true));
+    assert_eq!(routed.as_deref(), Some("ok"));
+
+    let strict = EnsembleProvider::new(vec![
+        Arc::new(MockProvider::new().error(MockError::status(500, "boom"))),
+        member("ok", "42"),
+    ])
+    .with_min_responses(2);
+    let err = strict
+        .generate(&ask(), &[], &GenerateOptions::default())
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("needs 2 responses, got 1"),
+        "{err}"
+    );
+}
+
+#[tokio::test]
+async fn agents_price_each_member_at_its_own_rate() {
+    use gauss_core::agent::{Agent, AgentStreamEvent};
+    use gauss_core::cost::estimate_cost;
+    use gauss_core::message::Usage;
+
+    let usage = Usage {
+        input_tokens: 1_000_000,
+        output_tokens: 100_000,
+        ..Default::default()
+    };
+    let priced = |model: &str| {
+        Arc::new(
+            MockProvider::new()
+                .with_model(model)
+                .usage(usage.clone())
+                .repeat(gauss_core::provider::mock::MockResponse::Text("42".into())),
+        )
+    };
+    let ensemble = Arc::new(EnsembleProvider::new(vec![
+        priced("gpt-4o"),
+        priced("gpt-4o-mini"),
+    ]));
+    let expected = estimate_cost("gpt-4o", &usage).total_cost_usd
+        + estimate_cost("gpt-4o-mini", &usage).total_cost_usd;
+    let agent = Agent::builder("voter", ensemble).build();
+
+    let output = agent.run(ask()).await.unwrap();
+    assert!((output.cost.total_cost_usd - expected).abs() < 1e-9);
+    assert_eq!(
+        output.cost.by_model.keys().collect::<Vec<_>>(),
+        ["gpt-4o", "gpt-4o-mini"]
+    );
+
+    let mut stream = agent.run_stream(ask()).await.unwrap();
+    let mut streamed = None;
+    while let Some(event) = stream.next().await {
+        if let AgentStreamEvent::Done { cost, .. } = event.unwrap() {
+            streamed = Some(cost.total_cost_usd);
+        }
+    }
+    assert!((streamed.unwrap() - expected).abs() < 1e-9);
+}
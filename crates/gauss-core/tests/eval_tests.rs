@@ -1,5 +1,91 @@
+use async_trait::async_trait;
+use gauss_core::agent::{AgentOutput, StepResult, ToolCallInfo};
+use gauss_core::error::{GaussError, Result};
 use gauss_core::eval::*;
-use std::sync::Arc;
+use gauss_core::message::{Message, Usage};
+use gauss_core::provider::{BoxStream, FinishReason, GenerateOptions, GenerateResult, Provider};
+use gauss_core::rag::Embedding;
+use gauss_core::tool::Tool;
+use std::sync::{Arc, Mutex};
+
+/// Judge that replays canned replies in order and records the prompts it saw.
+struct ScriptedJudge {
+    replies: Mutex<Vec<String>>,
+    prompts: Mutex<Vec<String>>,
+}
+
+impl ScriptedJudge {
+    fn new(replies: &[&str]) -> Arc<Self> {
+        Arc::new(Self {
+            replies: Mutex::new(replies.iter().rev().map(|r| r.to_string()).collect()),
+            prompts: Mutex::new(Vec::new()),
+        })
+    }
+}
+
+#[async_trait]
+impl Provider for ScriptedJudge {
+    fn name(&self) -> &str {
+        "judge"
+    }
+
+    fn model(&self) -> &str {
+        "mock"
+    }
+
+    async fn generate(
+        &self,
+        messages: &[Message],
+        _tools: &[Tool],
+        options: &GenerateOptions,
+    ) -> Result<GenerateResult> {
+        assert!(options.output_schema.is_some());
+        let prompt = messages.last().and_then(|m| m.text()).unwrap_or_default();
+        self.prompts.lock().unwrap().push(prompt.to_string());
+        let reply = self
+            .replies
+            .lock()
+            .unwrap()
+            .pop()
+            .expect("unexpected judge call");
+        Ok(GenerateResult {
+            message: Message::assistant(reply),
+            usage: Usage::default(),
+            finish_reason: FinishReason::Stop,
+            provider_metadata: serde_json::json!({}),
+            thinking: None,
+            citations: vec![],
+            grounding_metadata: None,
+            routing: None,
+        })
+    }
+
+    async fn stream(
+        &self,
+        _messages: &[Message],
+        _tools: &[Tool],
+        _options: &GenerateOptions,
+    ) -> Result<BoxStream> {
+        Err(GaussError::provider("judge", "Stream not supported"))
+    }
+}
+
+/// Embeds text as letter counts for "a" and "b".
+struct LetterEmbedding;
+
+#[async_trait]
+impl Embedding for LetterEmbedding {
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        Ok(vec![
+            text.matches('a').count() as f32,
+            text.matches('b').count() as f32,
+        ])
+    }
+
+    fn dimensions(&self) -> usize {
+        2
+    }
+}
 
 #[tokio::test]
 async fn test_exact_match_scorer() {
@@ -80,6 +166,7 @@ async fn test_eval_runner() {
             expected_output: Some("4".into()),
             context: None,
             metadata: Default::default(),
+            expected_trajectory: None,
         },
         EvalCase {
             id: "t2".into(),
@@ -87,6 +174,7 @@ async fn test_eval_runner() {
             expected_output: Some("6".into()),
             context: None,
             metadata: Default::default(),
+            expected_trajectory: None,
         },
     ];
 
@@ -115,6 +203,7 @@ async fn test_eval_runner_partial_pass() {
             expected_output: Some("correct".into()),
             context: None,
             metadata: Default::default(),
+            expected_trajectory: None,
         },
         EvalCase {
             id: "t2".into(),
@@ -122,6 +211,7 @@ async fn test_eval_runner_partial_pass() {
             expected_output: Some("correct".into()),
             context: None,
             metadata: Default::default(),
+            expected_trajectory: None,
         },
     ];
 
@@ -146,3 +236,598 @@ async fn test_eval_runner_partial_pass() {
     assert_eq!(report.passed, 1);
     assert_eq!(report.failed, 1);
 }
+
+#[tokio::test]
+async fn test_judge_scorer_weighted_rubric() {
+    let judge = ScriptedJudge::new(&["```json\n{\"criteria\": [\
+            {\"name\": \"accuracy\", \"score\": 1.0, \"reason\": \"correct\"},\
+            {\"name\": \"concise\", \"score\": 0.5, \"reason\": \"wordy\"}],\
+          \"reasoning\": \"Right answer, too long.\"}\n```"]);
+    let scorer = JudgeScorer::new(judge.clone()).with_rubric(
+        Rubric::new()
+            .criterion("accuracy", "Matches the reference", 3.0)
+            .criterion("concise", "No filler", 1.0),
+    );
+
+    let detail = scorer
+        .score_detailed("2+2?", "It is 4, of course.", Some("4"), None)
+        .await
+        .unwrap();
+    assert!((detail.score - 0.875).abs() < 1e-9);
+    assert_eq!(detail.reason.as_deref(), Some("Right answer, too long."));
+    assert_eq!(detail.metadata["criteria"].as_array().unwrap().len(), 2);
+
+    let prompt = judge.prompts.lock().unwrap()[0].clone();
+    assert!(prompt.contains("REFERENCE ANSWER:\n4"));
+    assert!(prompt.contains("- concise: No filler"));
+}
+
+#[tokio::test]
+async fn test_judge_reasoning_recorded_in_eval_result() {
+    let judge = ScriptedJudge::new(&[
+        r#"{"criteria": [{"name": "correctness", "score": 0.2, "reason": "wrong"}], "reasoning": "Says 5."}"#,
+    ]);
+    let mut runner = EvalRunner::new();
+    runner.add_scorer(Arc::new(JudgeScorer::new(judge)));
+    runner.add_scorer(Arc::new(ExactMatchScorer));
+
+    let case = EvalCase {
+        id: "t1".into(),
+        input: "2+2?".into(),
+        expected_output: Some("4".into()),
+        context: None,
+        metadata: Default::default(),
+        expected_trajectory: None,
+    };
+    let result = runner.evaluate_case(&case, "5").await.unwrap();
+    assert!((result.scores["judge"] - 0.2).abs() < 1e-9);
+    assert_eq!(result.metadata["judge"]["reason"], "Says 5.");
+    assert!(!result.metadata.contains_key("exact_match"));
+    assert!(!result.passed);
+}
+
+#[tokio::test]
+async fn test_pairwise_scorer_swaps_positions() {
+    // Candidate wins when shown first, then loses when shown second: position bias → 0.5.
+    let judge = ScriptedJudge::new(&[
+        r#"{"winner": "a", "reason": "first is better"}"#,
+        r#"{"winner": "a", "reason": "first is better"}"#,
+    ]);
+    let scorer = PairwiseScorer::new(judge.clone());
+    let detail = scorer
+        .score_detailed("q", "candidate", Some("baseline"), None)
+        .await
+        .unwrap();
+    assert!((detail.score - 0.5).abs() < 1e-9);
+    assert_eq!(detail.metadata["verdicts"].as_array().unwrap().len(), 2);
+
+    let prompts = judge.prompts.lock().unwrap().clone();
+    assert!(prompts[0].find("candidate") < prompts[0].find("baseline"));
+    assert!(prompts[1].find("baseline") < prompts[1].find("candidate"));
+
+    let judge = ScriptedJudge::new(&[r#"{"winner": "B", "reason": "baseline"}"#]);
+    let verdict = PairwiseScorer::new(judge)
+        .compare("q", "x", "y")
+        .await
+        .unwrap();
+    assert_eq!(verdict.winner, PairwiseWinner::B);
+}
+
+#[tokio::test]
+async fn test_faithfulness_scorer() {
+    let judge = ScriptedJudge::new(&[r#"{"claims": [
+        {"claim": "Paris is the capital", "supported": true},
+        {"claim": "It has 10M residents", "supported": false}
+    ], "reasoning": "Population is not in the context."}"#]);
+    let scorer = FaithfulnessScorer::new(judge);
+
+    let detail = scorer
+        .score_detailed(
+            "Tell me about Paris",
+            "Paris is the capital with 10M residents.",
+            None,
+            Some("Paris is the capital of France."),
+        )
+        .await
+        .unwrap();
+    assert!((detail.score - 0.5).abs() < 1e-9);
+
+    let no_context = scorer.score("q", "a", None, None).await.unwrap();
+    assert_eq!(no_context, 0.0);
+}
+
+#[tokio::test]
+async fn test_semantic_similarity_scorer() {
+    let scorer = SemanticSimilarityScorer::new(Arc::new(LetterEmbedding));
+
+    let same = scorer
+        .score("q", "aab", Some("aaaabb"), None)
+        .await
+        .unwrap();
+    assert!((same - 1.0).abs() < 1e-6);
+
+    let orthogonal = scorer.score("q", "aaa", Some("bbb"), None).await.unwrap();
+    assert!(orthogonal.abs() < 1e-6);
+
+    assert_eq!(scorer.score("q", "aaa", None, None).await.unwrap(), 0.0);
+}
+
+fn cases(n: usize) -> Vec<EvalCase> {
+    (0..n)
+        .map(|i| EvalCase {
+            id: format!("c{i}"),
+            input: format!("q{i}"),
+            expected_output: Some("ok".into()),
+            context: None,
+            metadata: Default::default(),
+            expected_trajectory: None,
+        })
+        .collect()
+}
+
+fn result(case_id: &str, score: f64) -> EvalResult {
+    EvalResult {
+        case_id: case_id.into(),
+        scores: [("exact_match".to_string(), score)].into(),
+        passed: score >= 0.5,
+        actual_output: String::new(),
+        error: None,
+        duration_ms: 1,
+        metadata: Default::default(),
+        trial_stats: None,
+    }
+}
+
+fn report(results: Vec<EvalResult>) -> EvalReport {
+    let passed = results.iter().filter(|r| r.passed).count();
+    EvalReport {
+        total: results.len(),
+        passed,
+        failed: results.len() - passed,
+        errored: 0,
+        avg_scores: Default::default(),
+        results,
+        total_duration_ms: 10,
+    }
+}
+
+#[tokio::test]
+async fn test_eval_runner_concurrency_keeps_dataset_order() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+    let mut runner = EvalRunner::new().with_concurrency(4);
+    runner.add_scorer(Arc::new(ExactMatchScorer));
+
+    let dataset = cases(12);
+    let report = runner
+        .run(&dataset, |case| {
+            let (in_flight, peak) = (in_flight.clone(), peak.clone());
+            let delay = 20 - case.id[1..].parse::<u64>().unwrap();
+            async move {
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(std::time::Duration::from_millis(delay)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                Ok("ok".to_string())
+            }
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(report.passed, 12);
+    assert_eq!(peak.load(Ordering::SeqCst), 4);
+    let ids: Vec<_> = report.results.iter().map(|r| r.case_id.as_str()).collect();
+    assert_eq!(
+        ids,
+        dataset.iter().map(|c| c.id.as_str()).collect::<Vec<_>>()
+    );
+}
+
+#[tokio::test]
+async fn test_eval_runner_trials_record_variance() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let mut runner = EvalRunner::new().with_trials(4);
+    runner.add_scorer(Arc::new(ExactMatchScorer));
+
+    let report = runner
+        .run(&cases(1), |_| {
+            let n = calls.fetch_add(1, Ordering::SeqCst);
+            async move {
+                match n {
+                    0 => Err(GaussError::internal("flaky")),
+                    1 => Ok("wrong".to_string()),
+                    _ => Ok("ok".to_string()),
+                }
+            }
+        })
+        .await
+        .unwrap();
+
+    let result = &report.results[0];
+    let stats = result.trial_stats.as_ref().unwrap();
+    assert_eq!((stats.trials, stats.errors), (4, 1));
+    let exact = stats.scores["exact_match"];
+    assert!((exact.mean - 2.0 / 3.0).abs() < 1e-9);
+    assert!((exact.std_dev - (1.0f64 / 3.0).sqrt()).abs() < 1e-9);
+    assert_eq!((exact.min, exact.max), (0.0, 1.0));
+    assert!((result.scores["exact_match"] - exact.mean).abs() < 1e-9);
+    assert!(result.passed);
+}
+
+#[tokio::test]
+async fn test_eval_runner_resumes_from_checkpoint() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let path = std::env::temp_dir().join(format!(
+        "gauss-eval-checkpoint-{}.jsonl",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+    let dataset = cases(5);
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    let mut runner = EvalRunner::new().with_checkpoint(&path);
+    runner.add_scorer(Arc::new(ExactMatchScorer));
+
+    // An interrupted run: three cases finished, the fourth was mid-write.
+    runner
+        .run(&dataset[..3], |_| async { Ok("ok".to_string()) })
+        .await
+        .unwrap();
+    {
+        use std::io::Write;
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        write!(file, "{{\"case_id\": \"c3\", \"sco").unwrap();
+    }
+
+    let report = runner
+        .run(&dataset, |_| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async { Ok("ok".to_string()) }
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+    assert_eq!(report.total, 5);
+    assert_eq!(report.passed, 5);
+    assert_eq!(report.results.len(), 5);
+
+    // The fragment was cut off, so the resumed records parse on a later resume.
+    let text = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(text.lines().count(), 5);
+    runner
+        .run(&dataset, |_| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async { Ok("ok".to_string()) }
+        })
+        .await
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_eval_runner_retries_errored_checkpoint_results() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let path = std::env::temp_dir().join(format!(
+        "gauss-eval-checkpoint-errors-{}.jsonl",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+    let dataset = cases(3);
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    let mut runner = EvalRunner::new().with_checkpoint(&path);
+    runner.add_scorer(Arc::new(ExactMatchScorer));
+
+    // First run: c1 fails.
+    runner
+        .run(&dataset, |case| {
+            let failed = case.id == "c1";
+            async move {
+                if failed {
+                    Err(gauss_core::error::GaussError::internal("boom"))
+                } else {
+                    Ok("ok".to_string())
+                }
+            }
+        })
+        .await
+        .unwrap();
+
+    // Kept as finished when retries are off.
+    let kept = EvalRunner::new()
+        .with_checkpoint(&path)
+        .with_retry_errors(false)
+        .run(&dataset, |_| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async { Ok("ok".to_string()) }
+        })
+        .await
+        .unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+    assert_eq!(kept.errored, 1);
+
+    let report = runner
+        .run(&dataset, |_| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async { Ok("ok".to_string()) }
+        })
+        .await
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    assert_eq!(report.errored, 0);
+    assert_eq!(report.passed, 3);
+}
+
+#[test]
+fn test_report_compare_flags_regressions() {
+    let baseline = report((0..20).map(|i| result(&format!("c{i}"), 1.0)).collect());
+    let mut current: Vec<EvalResult> = (0..20)
+        .map(|i| result(&format!("c{i}"), if i % 2 == 0 { 0.0 } else { 1.0 }))
+        .collect();
+    current.push(result("new", 1.0));
+    current.retain(|r| r.case_id != "c19");
+    let current = report(current);
+
+    let cmp = current.compare(&baseline);
+    assert!(cmp.has_regressions());
+    assert_eq!(cmp.regressions.len(), 10);
+    assert_eq!(cmp.regressions[0].case_id, "c0");
+    assert_eq!(cmp.new_cases, vec!["new"]);
+    assert_eq!(cmp.missing_cases, vec!["c19"]);
+    let exact = &cmp.scorers[0];
+    assert!(exact.significant && exact.is_regression());
+    assert!((exact.delta + 10.0 / 19.0).abs() < 1e-9);
+
+    // A single noisy case is a per-case regression but not a significant scorer shift.
+    let mut noisy: Vec<EvalResult> = (0..20).map(|i| result(&format!("c{i}"), 1.0)).collect();
+    noisy[3] = result("c3", 0.9);
+    let cmp = report(noisy).compare(&baseline);
+    assert_eq!(cmp.regressions.len(), 1);
+    assert!(!cmp.scorers[0].significant);
+
+    let md = cmp.to_markdown();
+    assert!(md.contains("### Regressions (1)"));
+    assert!(md.contains("`c3`: pass → pass (exact_match -0.100)"));
+}
+
+#[test]
+fn test_report_junit_and_markdown() {
+    let mut errored = result("c<2>", 0.0);
+    errored.error = Some("provider \"down\"".into());
+    let mut failed = result("c1", 0.0);
+    failed.actual_output = "a & b".into();
+    let mut report = report(vec![result("c0", 1.0), failed, errored]);
+    report.failed = 1;
+    report.errored = 1;
+    report.avg_scores.insert("exact_match".into(), 0.5);
+
+    let xml = report.to_junit_xml("smoke");
+    assert!(xml.contains(r#"<testsuite name="smoke" tests="3" failures="1" errors="1""#));
+    assert!(xml.contains(r#"<testcase classname="smoke" name="c0" time="0.001"/>"#));
+    assert!(xml.contains(r#"<failure message="exact_match=0.000">a &amp; b</failure>"#));
+    assert!(xml.contains(r#"name="c&lt;2&gt;""#));
+    assert!(xml.contains(r#"<error message="provider &quot;down&quot;"/>"#));
+
+    let md = report.to_markdown();
+    assert!(md.contains("**1/3 passed** (33.3%), 1 failed, 1 errored"));
+    assert!(md.contains("| exact_match | 0.500 |"));
+    assert!(md.contains("- `c1`: exact_match=0.000"));
+}
+
+#[tokio::test]
+async fn test_eval_runner_rate_limit() {
+    let mut runner = EvalRunner::new().with_concurrency(3).with_rate_limit(600);
+    runner.add_scorer(Arc::new(ExactMatchScorer));
+
+    let start = std::time::Instant::now();
+    let report = runner
+        .run(&cases(3), |_| async { Ok("ok".to_string()) })
+        .await
+        .unwrap();
+    assert_eq!(report.passed, 3);
+    // 600/min = one call every 100ms; the third starts no earlier than 200ms.
+    assert!(start.elapsed() >= std::time::Duration::from_millis(200));
+}
+
+fn agent_run(text: &str, steps: &[&[(&str, serde_json::Value)]]) -> AgentOutput {
+    let step_results: Vec<StepResult> = steps
+        .iter()
+        .enumerate()
+        .map(|(i, calls)| StepResult {
+            step_index: i,
+            message: Message::assistant(""),
+            finish_reason: FinishReason::ToolCalls,
+            usage: Usage::default(),
+            cost: Default::default(),
+            tool_calls: calls
+                .iter()
+                .map(|(name, args)| ToolCallInfo {
+                    id: format!("call_{i}_{name}"),
+                    name: name.to_string(),
+                    arguments: args.clone(),
+                })
+                .collect(),
+            tool_results: vec![],
+        })
+        .collect();
+    AgentOutput {
+        text: text.into(),
+        messages: vec![],
+        usage: Usage::default(),
+        steps: step_results.len() + 1,
+        step_results,
+        structured_output: None,
+        thinking: None,
+        citations: vec![],
+        grounding_metadata: vec![],
+        cost: Default::default(),
+        run_id: String::new(),
+        trace: None,
+        stop_reason: Default::default(),
+    }
+}
+
+#[test]
+fn test_trajectory_match_modes() {
+    use serde_json::json;
+
+    let search = json!({"query": "rust async", "limit": 5});
+    let fetch = json!({"url": "https://docs.rs", "opts": {"cache": true}});
+    let actual = [("search", &search), ("log", &json!({})), ("fetch", &fetch)];
+
+    let expected = |mode| {
+        ExpectedTrajectory::new(mode)
+            .call(ExpectedToolCall::new("search").arg("query", ArgMatcher::contains("rust")))
+            .call(
+                ExpectedToolCall::new("fetch")
+                    .arg("url", ArgMatcher::regex(r"^https://"))
+                    .arg("/opts/cache", ArgMatcher::equals(true)),
+            )
+    };
+
+    assert_eq!(
+        expected(TrajectoryMatch::InOrder).score_calls(&actual).0,
+        1.0
+    );
+    assert_eq!(
+        expected(TrajectoryMatch::Subset).score_calls(&actual).0,
+        1.0
+    );
+    // The extra "log" call breaks exact and unordered matching.
+    assert_eq!(expected(TrajectoryMatch::Exact).score_calls(&actual).0, 0.0);
+    assert_eq!(
+        expected(TrajectoryMatch::Unordered).score_calls(&actual).0,
+        0.0
+    );
+
+    let swapped = [("fetch", &fetch), ("search", &search)];
+    assert_eq!(
+        expected(TrajectoryMatch::Unordered).score_calls(&swapped).0,
+        1.0
+    );
+    assert_eq!(
+        expected(TrajectoryMatch::Subset).score_calls(&swapped).0,
+        1.0
+    );
+    let (score, matched) = expected(TrajectoryMatch::InOrder).score_calls(&swapped);
+    assert_eq!(score, 0.5);
+    assert_eq!(matched, vec![true, false]);
+    assert_eq!(
+        expected(TrajectoryMatch::Exact).score_calls(&swapped).0,
+        0.0
+    );
+
+    // Greedy matching would give the limit-5 call to the generic expectation and
+    // leave the specific one unmatched; augmenting paths reassign it.
+    let any = ExpectedTrajectory::new(TrajectoryMatch::Unordered)
+        .call(ExpectedToolCall::new("search"))
+        .call(ExpectedToolCall::new("search").arg("limit", ArgMatcher::one_of([json!(5)])));
+    let calls = [("search", &search), ("search", &json!({"limit": 1}))];
+    assert_eq!(any.score_calls(&calls), (1.0, vec![true, true]));
+    let calls = [
+        ("search", &json!({"limit": 1})),
+        ("search", &json!({"limit": 2})),
+    ];
+    assert_eq!(any.score_calls(&calls), (0.0, vec![true, false]));
+}
+
+#[test]
+fn test_expected_trajectory_from_json() {
+    let case: EvalCase = serde_json::from_str(
+        r#"{"id": "t", "input": "find docs", "expected_trajectory": {
+            "mode": "subset",
+            "max_steps": 3,
+            "calls": [{"name": "search", "arguments": {
+                "query": {"contains": "docs"},
+                "lang": {"one_of": ["en", "de"]},
+                "page": 1,
+                "debug": {"any": true}
+            }}]
+        }}"#,
+    )
+    .unwrap();
+    let trajectory = case.expected_trajectory.unwrap();
+    assert_eq!(trajectory.mode, TrajectoryMatch::Subset);
+    assert_eq!(trajectory.max_steps, Some(3));
+    let args = &trajectory.calls[0].arguments;
+    assert_eq!(args["query"], ArgMatcher::contains("docs"));
+    assert_eq!(args["page"], ArgMatcher::equals(1));
+    assert_eq!(args["debug"], ArgMatcher::any());
+    assert!(trajectory.calls[0].matches(
+        "search",
+        &serde_json::json!({"query": "the docs", "lang": "de", "page": 1, "debug": false})
+    ));
+}
+
+#[tokio::test]
+async fn test_run_agent_with_trajectory_scorer() {
+    use serde_json::json;
+
+    let mut dataset = cases(2);
+    for case in &mut dataset {
+        case.expected_trajectory = Some(
+            ExpectedTrajectory::new(TrajectoryMatch::InOrder)
+                .call(ExpectedToolCall::new("search"))
+                .call(ExpectedToolCall::new("answer"))
+                .max_steps(3),
+        );
+    }
+
+    let mut runner = EvalRunner::new().with_threshold(0.75);
+    runner.add_scorer(Arc::new(TrajectoryScorer));
+    runner.add_scorer(Arc::new(ExactMatchScorer));
+
+    let report = runner
+        .run_agent(&dataset, |case| {
+            let output = if case.id == "c0" {
+                agent_run("ok", &[&[("search", json!({}))], &[("answer", json!({}))]])
+            } else {
+                // Right answer, but never searched and took too many steps.
+                agent_run("ok", &[&[], &[], &[("answer", json!({}))]])
+            };
+            async move { Ok(output) }
+        })
+        .await
+        .unwrap();
+
+    let good = &report.results[0];
+    assert_eq!(good.scores["trajectory"], 1.0);
+    assert!(good.passed);
+    assert_eq!(good.metadata["trajectory"]["metadata"]["steps"], 3);
+
+    let bad = &report.results[1];
+    assert_eq!(bad.scores["trajectory"], 0.0);
+    assert_eq!(bad.scores["exact_match"], 1.0);
+    assert!(!bad.passed);
+    let reason = bad.metadata["trajectory"]["reason"].as_str().unwrap();
+    assert!(reason.contains("unmatched: search()"), "{reason}");
+    assert!(reason.contains("took 4 steps (max 3)"), "{reason}");
+
+    // Exceeding max_steps fails the case even when the scores would pass it.
+    let mut lenient = EvalRunner::new().with_threshold(0.0);
+    lenient.add_scorer(Arc::new(ExactMatchScorer));
+    let slow = agent_run("ok", &[&[], &[], &[("answer", json!({}))]]);
+    let over = lenient
+        .evaluate_agent_output(&dataset[1], &slow)
+        .await
+        .unwrap();
+    assert_eq!(over.scores["exact_match"], 1.0);
+    assert!(!over.passed);
+
+    // Text-only runs leave the trajectory unscored.
+    let text_only = runner.evaluate_case(&dataset[0], "ok").await.unwrap();
+    assert_eq!(text_only.scores["trajectory"], 0.0);
+}
@@ -0,0 +1,216 @@
+use gauss_core::agent::Agent;
+use gauss_core::message::Message;
+use gauss_core::metrics::{MetricsRegistry, MetricsServer};
+use gauss_core::provider::openai::OpenAiProvider;
+use gauss_core::provider::retry::{RetryConfig, RetryProvider};
+use gauss_core::provider::{GenerateOptions, Provider, ProviderConfig};
+use gauss_core::telemetry::TelemetryCollector;
+use gauss_core::tool::Tool;
+use serde_json::json;
+use std::sync::Arc;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn tool_call_response() -> serde_json::Value {
+    json!({
+        "choices": [{
+            "message": {
+                "role": "assistant",
+                "content": null,
+                "tool_calls": [{
+                    "id": "call_1",
+                    "type": "function",
+                    "function": {"name": "lookup", "arguments": "{}"}
+                }]
+            },
+            "finish_reason": "tool_calls"
+        }],
+        "usage": {"prompt_tokens": 100, "completion_tokens": 20}
+    })
+}
+
+fn text_response(content: &str) -> serde_json::Value {
+    json!({
+        "choices": [{
+            "message": {"role": "assistant", "content": content},
+            "finish_reason": "stop"
+        }],
+        "usage": {"prompt_tokens": 50, "completion_tokens": 10}
+    })
+}
+
+fn provider(uri: &str) -> Arc<OpenAiProvider> {
+    let config = ProviderConfig::new("test-key").base_url(uri);
+    Arc::new(OpenAiProvider::new("gpt-4o", config))
+}
+
+#[tokio::test]
+async fn test_agent_runs_feed_registry() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(tool_call_response()))
+        .up_to_n_times(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(text_response("done")))
+        .mount(&mock_server)
+        .await;
+
+    let registry = Arc::new(MetricsRegistry::new());
+    let telemetry = Arc::new(TelemetryCollector::new().with_registry(registry.clone()));
+    let lookup = Tool::builder("lookup", "Look something up")
+        .execute(|_| async move { Err(gauss_core::error::GaussError::tool("lookup", "offline")) })
+        .build();
+    let agent = Agent::builder("researcher", provider(&mock_server.uri()))
+        .tool(lookup)
+        .telemetry(telemetry)
+        .build();
+
+    agent.run(vec![Message::user("go")]).await.unwrap();
+
+    let model = [
+        ("agent", "researcher"),
+        ("provider", "openai"),
+        ("model", "gpt-4o"),
+    ];
+    assert_eq!(
+        registry.value("gauss_model_requests_total", &model),
+        Some(2.0)
+    );
+    assert_eq!(
+        registry.value("gauss_model_request_duration_seconds", &model),
+        Some(2.0)
+    );
+    let input = [model.as_slice(), &[("type", "input")]].concat();
+    assert_eq!(registry.value("gauss_tokens_total", &input), Some(150.0));
+    assert!(registry.value("gauss_cost_usd_total", &model).unwrap() > 0.0);
+
+    let tool = [("agent", "researcher"), ("tool", "lookup")];
+    assert_eq!(registry.value("gauss_tool_calls_total", &tool), Some(1.0));
+    let tool_error = [tool.as_slice(), &[("error", "tool")]].concat();
+    assert_eq!(
+        registry.value("gauss_tool_errors_total", &tool_error),
+        Some(1.0)
+    );
+    assert_eq!(
+        registry.value(
+            "gauss_agent_runs_total",
+            &[("agent", "researcher"), ("status", "ok")]
+        ),
+        Some(1.0)
+    );
+
+    let text = registry.render();
+    assert!(text.contains("# TYPE gauss_model_requests_total counter\n"));
+    assert!(text.contains(
+        "gauss_model_requests_total{agent=\"researcher\",model=\"gpt-4o\",provider=\"openai\"} 2\n"
+    ));
+    assert!(text.contains("# TYPE gauss_tool_call_duration_seconds histogram\n"));
+}
+
+#[tokio::test]
+async fn test_model_errors_are_labeled_by_variant() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .respond_with(ResponseTemplate::new(401).set_body_json(json!({"error": "bad key"})))
+        .mount(&mock_server)
+        .await;
+
+    let registry = Arc::new(MetricsRegistry::new());
+    let telemetry = Arc::new(TelemetryCollector::new().with_registry(registry.clone()));
+    let agent = Agent::builder("broken", provider(&mock_server.uri()))
+        .telemetry(telemetry)
+        .build();
+
+    let err = agent.run(vec![Message::user("go")]).await.unwrap_err();
+    let labels = [
+        ("agent", "broken"),
+        ("provider", "openai"),
+        ("model", "gpt-4o"),
+        ("error", err.kind()),
+    ];
+    assert_eq!(
+        registry.value("gauss_model_errors_total", &labels),
+        Some(1.0)
+    );
+    assert_eq!(
+        registry.value(
+            "gauss_agent_runs_total",
+            &[("agent", "broken"), ("status", "error")]
+        ),
+        Some(1.0)
+    );
+}
+
+#[tokio::test]
+async fn test_retry_provider_counts_retries() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .respond_with(ResponseTemplate::new(503).set_body_json(json!({"error": "unavailable"})))
+        .up_to_n_times(2)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(text_response("ok")))
+        .mount(&mock_server)
+        .await;
+
+    let registry = Arc::new(MetricsRegistry::new());
+    let retry = RetryProvider::new(
+        provider(&mock_server.uri()),
+        RetryConfig {
+            initial_delay_ms: 1,
+            ..RetryConfig::default()
+        },
+    )
+    .with_metrics(registry.clone());
+
+    retry
+        .generate(&[Message::user("hi")], &[], &GenerateOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(
+        registry.value(
+            "gauss_provider_retries_total",
+            &[
+                ("provider", "openai"),
+                ("model", "gpt-4o"),
+                ("error", "provider")
+            ]
+        ),
+        Some(2.0)
+    );
+}
+
+#[tokio::test]
+async fn test_metrics_server_exposes_registry() {
+    let registry = Arc::new(MetricsRegistry::new());
+    registry.inc_counter("gauss_demo_total", "Demo.", &[("agent", "a")], 3.0);
+
+    let server = MetricsServer::bind("127.0.0.1:0", registry).await.unwrap();
+    let addr = server.local_addr().unwrap();
+    let handle = tokio::spawn(server.serve());
+
+    let response = reqwest::get(format!("http://{addr}/metrics"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert!(
+        response.headers()["content-type"]
+            .to_str()
+            .unwrap()
+            .starts_with("text/plain; version=0.0.4")
+    );
+    let body = response.text().await.unwrap();
+    assert!(body.contains("gauss_demo_total{agent=\"a\"} 3\n"));
+
+    let missing = reqwest::get(format!("http://{addr}/other")).await.unwrap();
+    assert_eq!(missing.status(), 404);
+    handle.abort();
+}
@@ -0,0 +1,269 @@
+use futures::StreamExt;
+use gauss_core::agent::{Agent, AgentStreamEvent, StopCondition, StopReason};
+use gauss_core::error::GaussError;
+use gauss_core::message::{Message, Role};
+use gauss_core::provider::mock::{MockError, MockProvider, MockResponse};
+use gauss_core::provider::retry::{RetryConfig, RetryProvider};
+use gauss_core::provider::{FinishReason, GenerateOptions, Provider, ProviderCapabilities};
+use gauss_core::streaming::StreamEvent;
+use gauss_core::tool::Tool;
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn weather_tool() -> Tool {
+    Tool::builder("get_weather", "Look up the weather")
+        .execute(|args| async move { Ok(json!({"city": args["city"], "forecast": "sunny"})) })
+        .build()
+}
+
+#[tokio::test]
+async fn mock_drives_agent_tool_loop_and_records_requests() {
+    let mock = Arc::new(
+        MockProvider::new()
+            .tool_call("get_weather", json!({"city": "Paris"}))
+            .text("It is sunny in Paris."),
+    );
+    let output = Agent::builder("weather", mock.clone())
+        .tool(weather_tool())
+        .build()
+        .run(vec![Message::user("Weather in Paris?")])
+        .await
+        .unwrap();
+
+    assert_eq!(output.text, "It is sunny in Paris.");
+    assert_eq!(output.steps, 2);
+    assert_eq!(mock.call_count(), 2);
+    assert_eq!(mock.remaining(), 0);
+
+    let requests = mock.requests();
+    assert!(!requests[0].stream);
+    assert_eq!(requests[0].last_user_text(), Some("Weather in Paris?"));
+    assert_eq!(requests[0].tool_names(), vec!["get_weather"]);
+    let tool_result = requests[1]
+        .messages
+        .iter()
+        .find(|m| m.role == Role::Tool)
+        .expect("second request carries the tool result");
+    assert!(format!("{:?}", tool_result.content).contains("sunny"));
+}
+
+#[tokio::test]
+async fn mock_errors_map_to_gauss_errors() {
+    let mock = MockProvider::new()
+        .error(MockError::rate_limited(Some(1500)))
+        .error(MockError::status(500, "internal"))
+        .error(MockError::Authentication);
+    let messages = [Message::user("hi")];
+    let options = GenerateOptions::default();
+
+    match mock.generate(&messages, &[], &options).await {
+        Err(GaussError::RateLimited {
+            provider,
+            retry_after_ms,
+            ..
+        }) => {
+            assert_eq!(provider, "mock");
+            assert_eq!(retry_after_ms, Some(1500));
+        }
+        other => panic!("expected rate limit, got {other:?}"),
+    }
+    match mock.generate(&messages, &[], &options).await {
+        Err(GaussError::Provider { status, .. }) => assert_eq!(status, Some(500)),
+        other => panic!("expected provider error, got {other:?}"),
+    }
+    assert!(matches!(
+        mock.generate(&messages, &[], &options).await,
+        Err(GaussError::Authentication { .. })
+    ));
+    let exhausted = mock.generate(&messages, &[], &options).await.unwrap_err();
+    assert!(exhausted.to_string().contains("script exhausted"));
+}
+
+#[tokio::test]
+async fn retry_provider_recovers_from_scripted_server_error() {
+    let mock = Arc::new(
+        MockProvider::new()
+            .error(MockError::status(503, "overloaded"))
+            .text("recovered"),
+    );
+    let retry = RetryProvider::new(
+        mock.clone(),
+        RetryConfig {
+            initial_delay_ms: 1,
+            ..Default::default()
+        },
+    );
+    let result = retry
+        .generate(&[Message::user("hi")], &[], &GenerateOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(result.text(), Some("recovered"));
+    assert_eq!(mock.call_count(), 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn mock_streams_chunks_with_latency() {
+    let mock = MockProvider::new()
+        .chunk_size(2)
+        .latency(Duration::from_millis(200))
+        .chunk_delay(Duration::from_millis(10))
+        .text("Hello");
+    let started = tokio::time::Instant::now();
+    let events: Vec<StreamEvent> = mock
+        .stream(&[Message::user("hi")], &[], &GenerateOptions::default())
+        .await
+        .unwrap()
+        .map(|e| e.unwrap())
+        .collect()
+        .await;
+
+    let deltas: Vec<String> = events
+        .iter()
+        .filter_map(|e| match e {
+            StreamEvent::TextDelta(d) => Some(d.clone()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(deltas, vec!["He", "ll", "o"]);
+    assert!(matches!(
+        events[3],
+        StreamEvent::FinishReason(FinishReason::Stop)
+    ));
+    assert!(matches!(events.last(), Some(StreamEvent::Done)));
+    // 200ms latency plus 10ms before each of the 5 events after the first.
+    assert_eq!(started.elapsed().as_millis(), 250);
+    assert!(mock.requests()[0].stream);
+}
+
+#[tokio::test]
+async fn agent_streams_scripted_tool_calls() {
+    let mock = Arc::new(
+        MockProvider::new()
+            .tool_call("get_weather", json!({"city": "Oslo"}))
+            .text("Cold."),
+    );
+    let agent = Agent::builder("weather", mock.clone())
+        .tool(weather_tool())
+        .build();
+    let events: Vec<AgentStreamEvent> = agent
+        .run_stream(vec![Message::user("Oslo?")])
+        .await
+        .unwrap()
+        .map(|e| e.unwrap())
+        .collect()
+        .await;
+
+    let text: String = events
+        .iter()
+        .filter_map(|e| match e {
+            AgentStreamEvent::TextDelta { delta, .. } => Some(delta.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(text, "Cold.");
+    assert_eq!(mock.call_count(), 2);
+    assert!(mock.requests().iter().all(|r| r.stream));
+}
+
+#[tokio::test]
+async fn agent_stream_reports_budget_exhaustion_in_done() {
+    let mock = Arc::new(MockProvider::new().repeat(MockResponse::ToolCalls(vec![(
+        "get_weather".into(),
+        json!({"city": "Oslo"}),
+    )])));
+    let agent = Agent::builder("weather", mock.clone())
+        .tool(weather_tool())
+        .stop_when(StopCondition::MaxTotalTokens(20))
+        .build();
+    let events: Vec<AgentStreamEvent> = agent
+        .run_stream(vec![Message::user("Oslo?")])
+        .await
+        .unwrap()
+        .map(|e| e.unwrap())
+        .collect()
+        .await;
+
+    // 15 tokens per call: the check before the third call sees 30.
+    assert_eq!(mock.call_count(), 2);
+    match events.last() {
+        Some(AgentStreamEvent::Done {
+            steps,
+            usage,
+            cost,
+            stop_reason,
+            ..
+        }) => {
+            assert_eq!(*steps, 2);
+            assert_eq!(usage.total_tokens(), 30);
+            assert_eq!(cost.steps.len(), 2);
+            assert_eq!(*stop_reason, StopReason::BudgetExhausted);
+        }
+        other => panic!("expected Done, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn structured_output_repeat_and_capabilities() {
+    let mock = MockProvider::new()
+        .with_name("fake")
+        .with_model("fake-1")
+        .with_capabilities(ProviderCapabilities {
+            vision: true,
+            ..Default::default()
+        })
+        .structured(json!({"answer": 42}))
+        .repeat(MockResponse::Text("again".into()));
+
+    assert_eq!(mock.name(), "fake");
+    assert_eq!(mock.model(), "fake-1");
+    assert!(mock.capabilities().vision);
+    assert!(!mock.capabilities().streaming);
+
+    let options = GenerateOptions::default();
+    let first = mock
+        .generate(&[Message::user("q")], &[], &options)
+        .await
+        .unwrap();
+    let value: serde_json::Value = serde_json::from_str(first.text().unwrap()).unwrap();
+    assert_eq!(value, json!({"answer": 42}));
+    for _ in 0..2 {
+        let next = mock
+            .generate(&[Message::user("q")], &[], &options)
+            .await
+            .unwrap();
+        assert_eq!(next.text(), Some("again"));
+    }
+}
+
+#[tokio::test]
+async fn generate_folds_scripted_stream_events() {
+    let mock = MockProvider::new().stream_events(vec![
+        StreamEvent::TextDelta("a".into()),
+        StreamEvent::ToolCallDelta {
+            index: 0,
+            id: Some("call_x".into()),
+            name: Some("lookup".into()),
+            arguments_delta: Some("{\"q\":".into()),
+        },
+        StreamEvent::ToolCallDelta {
+            index: 0,
+            id: None,
+            name: None,
+            arguments_delta: Some("1}".into()),
+        },
+        StreamEvent::FinishReason(FinishReason::ToolCalls),
+        StreamEvent::Done,
+    ]);
+    let result = mock
+        .generate(&[Message::user("q")], &[], &GenerateOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(result.text(), Some("a"));
+    assert_eq!(result.finish_reason, FinishReason::ToolCalls);
+    let calls = result.tool_calls();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].0, "call_x");
+    assert_eq!(calls[0].1, "lookup");
+    assert_eq!(calls[0].2, &json!({"q": 1}));
+}
@@ -0,0 +1,461 @@
+use futures::StreamExt;
+use gauss_core::error::GaussError;
+use gauss_core::message::{Content, Message, Role, Usage};
+use gauss_core::provider::ollama::{OllamaEmbedding, OllamaProvider, PullProgress};
+use gauss_core::provider::{FinishReason, GenerateOptions, Provider, ProviderConfig};
+use gauss_core::rag::Embedding;
+use gauss_core::streaming::StreamEvent;
+use gauss_core::tool::Tool;
+use serde_json::{Value, json};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn config(server: &MockServer) -> ProviderConfig {
+    ProviderConfig::new("ollama").base_url(server.uri())
+}
+
+async fn request_body(server: &MockServer, index: usize) -> Value {
+    let requests = server.received_requests().await.unwrap();
+    serde_json::from_slice(&requests[index].body).unwrap()
+}
+
+#[tokio::test]
+async fn chat_maps_messages_options_and_tool_calls() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/chat"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "model": "qwen3",
+            "created_at": "2025-06-01T12:00:00Z",
+            "message": {
+                "role": "assistant",
+                "content": "",
+                "thinking": "The user wants the weather.",
+                "tool_calls": [{"function": {"name": "get_weather", "arguments": {"city": "Paris"}}}]
+            },
+            "done": true,
+            "done_reason": "stop",
+            "total_duration": 1_500_000_000u64,
+            "prompt_eval_count": 42,
+            "eval_count": 7
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = OllamaProvider::new("qwen3", config(&server))
+        .num_ctx(16_384)
+        .option("repeat_penalty", json!(1.1))
+        .keep_alive("30m");
+    assert_eq!(provider.name(), "ollama");
+
+    let messages = vec![
+        Message::system("Be brief."),
+        Message {
+            role: Role::User,
+            content: vec![
+                Content::Text {
+                    text: "What's in this picture?".into(),
+                },
+                Content::Image {
+                    url: Some("data:image/png;base64,iVBORw0KGgo=".into()),
+                    base64: None,
+                    media_type: None,
+                },
+            ],
+            name: None,
+        },
+        Message {
+            role: Role::Assistant,
+            content: vec![Content::ToolCall {
+                id: "call_0".into(),
+                name: "lookup".into(),
+                arguments: json!({"q": "cat"}),
+            }],
+            name: None,
+        },
+        Message::tool_result("call_0", json!({"answer": "a cat"})),
+    ];
+    let tools = vec![Tool::builder("get_weather", "Current weather").build()];
+    let options = GenerateOptions {
+        temperature: Some(0.2),
+        max_tokens: Some(256),
+        stop_sequences: Some(vec!["END".into()]),
+        thinking_budget: Some(1024),
+        output_schema: Some(json!({"type": "object"})),
+        ..Default::default()
+    };
+
+    let result = provider
+        .generate(&messages, &tools, &options)
+        .await
+        .unwrap();
+
+    assert_eq!(result.finish_reason, FinishReason::ToolCalls);
+    assert_eq!(
+        result.thinking.as_deref(),
+        Some("The user wants the weather.")
+    );
+    let calls = result.tool_calls();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].0, "call_0");
+    assert_eq!(calls[0].1, "get_weather");
+    assert_eq!(calls[0].2, &json!({"city": "Paris"}));
+    assert_eq!(result.usage.input_tokens, 42);
+    assert_eq!(result.usage.output_tokens, 7);
+    assert_eq!(result.provider_metadata["total_duration"], 1_500_000_000u64);
+
+    let body = request_body(&server, 0).await;
+    assert_eq!(body["model"], "qwen3");
+    assert_eq!(body["stream"], false);
+    assert_eq!(body["keep_alive"], "30m");
+    assert_eq!(body["think"], true);
+    assert_eq!(body["format"], json!({"type": "object"}));
+    assert_eq!(
+        body["options"],
+        json!({
+            "num_ctx": 16384,
+            "repeat_penalty": 1.1,
+            "temperature": 0.2,
+            "num_predict": 256,
+            "stop": ["END"]
+        })
+    );
+    assert_eq!(body["tools"][0]["function"]["name"], "get_weather");
+    assert_eq!(
+        body["messages"],
+        json!([
+            {"role": "system", "content": "Be brief."},
+            {"role": "user", "content": "What's in this picture?", "images": ["iVBORw0KGgo="]},
+            {"role": "assistant", "content": "", "tool_calls": [
+                {"type": "function", "function": {"name": "lookup", "arguments": {"q": "cat"}}}
+            ]},
+            {"role": "tool", "content": "{\"answer\":\"a cat\"}", "tool_name": "lookup"}
+        ])
+    );
+}
+
+#[tokio::test]
+async fn image_urls_are_rejected() {
+    let provider = OllamaProvider::new("llava", ProviderConfig::new("ollama"));
+    let message = Message {
+        role: Role::User,
+        content: vec![Content::Image {
+            url: Some("https://example.com/cat.png".into()),
+            base64: None,
+            media_type: None,
+        }],
+        name: None,
+    };
+    let err = provider
+        .generate(&[message], &[], &GenerateOptions::default())
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("base64"), "{err}");
+}
+
+#[tokio::test]
+async fn streams_ndjson_chunks() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/chat"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(concat!(
+            "{\"message\":{\"role\":\"assistant\",\"content\":\"\",\"thinking\":\"Hmm\"},\"done\":false}\n",
+            "{\"message\":{\"role\":\"assistant\",\"content\":\"Hel\"},\"done\":false}\n",
+            "\n",
+            "{\"message\":{\"role\":\"assistant\",\"content\":\"lo\"},\"done\":false}\n",
+            "{\"message\":{\"role\":\"assistant\",\"content\":\"\",\"tool_calls\":[{\"function\":{\"name\":\"f\",\"arguments\":{\"x\":1}}}]},\"done\":false}\n",
+            "{\"message\":{\"role\":\"assistant\",\"content\":\"\"},\"done\":true,\"done_reason\":\"stop\",\"prompt_eval_count\":5,\"eval_count\":3}"
+        )))
+        .mount(&server)
+        .await;
+
+    // A trailing `/v1` from OpenAI-compatible configs is ignored.
+    let provider = OllamaProvider::new(
+        "llama3.3",
+        ProviderConfig::new("ollama").base_url(format!("{}/v1/", server.uri())),
+    );
+    let events: Vec<StreamEvent> = provider
+        .stream(&[Message::user("hi")], &[], &GenerateOptions::default())
+        .await
+        .unwrap()
+        .map(|e| e.unwrap())
+        .collect()
+        .await;
+
+    assert_eq!(
+        serde_json::to_value(&events).unwrap(),
+        serde_json::to_value(vec![
+            StreamEvent::ReasoningDelta("Hmm".into()),
+            StreamEvent::TextDelta("Hel".into()),
+            StreamEvent::TextDelta("lo".into()),
+            StreamEvent::ToolCallDelta {
+                index: 0,
+                id: Some("call_0".into()),
+                name: Some("f".into()),
+                arguments_delta: Some("{\"x\":1}".into()),
+            },
+            StreamEvent::FinishReason(FinishReason::ToolCalls),
+            StreamEvent::Usage(Usage {
+                input_tokens: 5,
+                output_tokens: 3,
+                ..Default::default()
+            }),
+            StreamEvent::Done,
+        ])
+        .unwrap()
+    );
+    assert_eq!(request_body(&server, 0).await["stream"], true);
+}
+
+#[tokio::test]
+async fn stream_errors_and_missing_models() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/chat"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(concat!(
+            "{\"message\":{\"role\":\"assistant\",\"content\":\"Hi\"},\"done\":false}\n",
+            "{\"error\":\"model runner has unexpectedly stopped\"}\n"
+        )))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/generate"))
+        .respond_with(
+            ResponseTemplate::new(404).set_body_json(json!({"error": "model 'nope' not found"})),
+        )
+        .mount(&server)
+        .await;
+
+    let provider = OllamaProvider::new("llama3.3", config(&server));
+    let events: Vec<_> = provider
+        .stream(&[Message::user("hi")], &[], &GenerateOptions::default())
+        .await
+        .unwrap()
+        .collect()
+        .await;
+    assert_eq!(events.len(), 2);
+    assert!(matches!(events[0], Ok(StreamEvent::TextDelta(ref t)) if t == "Hi"));
+    let err = events[1].as_ref().unwrap_err();
+    assert!(err.to_string().contains("unexpectedly stopped"), "{err}");
+
+    let err = OllamaProvider::new("nope", config(&server))
+        .complete("hi", None, &GenerateOptions::default())
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(&err, GaussError::Provider { status: Some(404), message, .. } if message == "model 'nope' not found"),
+        "{err}"
+    );
+}
+
+#[tokio::test]
+async fn completes_over_generate() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/generate"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "model": "codellama",
+            "response": "    return a + b",
+            "done": true,
+            "done_reason": "length",
+            "prompt_eval_count": 12,
+            "eval_count": 6
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let result = OllamaProvider::new("codellama", config(&server))
+        .complete(
+            "def add(a, b):\n",
+            Some("\n\nprint(add(1, 2))"),
+            &GenerateOptions {
+                seed: Some(7),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(result.text(), Some("    return a + b"));
+    assert_eq!(result.finish_reason, FinishReason::Length);
+    assert_eq!(result.usage.input_tokens, 12);
+
+    let body = request_body(&server, 0).await;
+    assert_eq!(body["prompt"], "def add(a, b):\n");
+    assert_eq!(body["suffix"], "\n\nprint(add(1, 2))");
+    assert_eq!(body["options"], json!({"seed": 7}));
+    assert!(body.get("keep_alive").is_none());
+}
+
+#[tokio::test]
+async fn lists_and_pulls_models() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/tags"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "models": [{
+                "name": "llama3.2:3b",
+                "model": "llama3.2:3b",
+                "modified_at": "2025-05-01T10:00:00Z",
+                "size": 2019393189u64,
+                "digest": "a80c4f17acd5",
+                "details": {
+                    "format": "gguf",
+                    "family": "llama",
+                    "families": null,
+                    "parameter_size": "3.2B",
+                    "quantization_level": "Q4_K_M"
+                }
+            }]
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/pull"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(concat!(
+            "{\"status\":\"pulling manifest\"}\n",
+            "{\"status\":\"pulling a80c4f17acd5\",\"digest\":\"sha256:a80c4f17acd5\",\"total\":200,\"completed\":50}\n",
+            "{\"status\":\"pulling a80c4f17acd5\",\"digest\":\"sha256:a80c4f17acd5\",\"total\":200,\"completed\":200}\n",
+            "{\"status\":\"verifying sha256 digest\"}\n",
+            "{\"status\":\"success\"}\n"
+        )))
+        .mount(&server)
+        .await;
+
+    let provider = OllamaProvider::new("llama3.2:3b", config(&server));
+    let models = provider.list_models().await.unwrap();
+    assert_eq!(models.len(), 1);
+    assert_eq!(models[0].name, "llama3.2:3b");
+    assert_eq!(models[0].size, 2019393189);
+    assert_eq!(models[0].details.quantization_level, "Q4_K_M");
+    assert!(models[0].details.families.is_none());
+
+    let mut progress: Vec<PullProgress> = Vec::new();
+    provider
+        .pull("llama3.2:3b", |p| progress.push(p.clone()))
+        .await
+        .unwrap();
+    let statuses: Vec<&str> = progress.iter().map(|p| p.status.as_str()).collect();
+    assert_eq!(
+        statuses,
+        [
+            "pulling manifest",
+            "pulling a80c4f17acd5",
+            "pulling a80c4f17acd5",
+            "verifying sha256 digest",
+            "success"
+        ]
+    );
+    assert_eq!(progress[1].fraction(), Some(0.25));
+    assert_eq!(progress[0].fraction(), None);
+    assert_eq!(
+        request_body(&server, 1).await,
+        json!({"model": "llama3.2:3b", "stream": true})
+    );
+}
+
+#[tokio::test]
+async fn failed_pulls_are_errors() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/pull"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(concat!(
+            "{\"status\":\"pulling manifest\"}\n",
+            "{\"error\":\"pull model manifest: file does not exist\"}\n"
+        )))
+        .mount(&server)
+        .await;
+
+    let provider = OllamaProvider::new("llama3.3", config(&server));
+    let mut updates = 0;
+    let err = provider
+        .pull("does-not-exist", |_| updates += 1)
+        .await
+        .unwrap_err();
+    assert_eq!(updates, 1);
+    assert!(err.to_string().contains("file does not exist"), "{err}");
+}
+
+#[tokio::test]
+async fn show_registers_context_window_and_capabilities() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/show"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "parameters": "stop \"<|eot_id|>\"",
+            "template": "{{ .Prompt }}",
+            "details": {"format": "gguf", "family": "llama", "parameter_size": "11B"},
+            "model_info": {
+                "general.architecture": "mllama",
+                "mllama.context_length": 131072,
+                "mllama.embedding_length": 4096
+            },
+            "capabilities": ["completion", "vision"]
+        })))
+        .mount(&server)
+        .await;
+
+    // A model name no built-in catalog entry matches.
+    let model = "ollama-tests-vision:11b";
+    let provider = OllamaProvider::new(model, config(&server));
+    assert!(provider.capabilities().tool_use);
+
+    let shown = provider.show(model).await.unwrap();
+    assert_eq!(shown.context_length, Some(131_072));
+    assert!(shown.has_capability("vision"));
+    assert!(!shown.has_capability("tools"));
+    assert_eq!(shown.template.as_deref(), Some("{{ .Prompt }}"));
+
+    let info = provider.num_ctx(8192).register_model().await.unwrap();
+    assert_eq!(info.context_window, 8192);
+    assert_eq!(info.provider.as_deref(), Some("ollama"));
+    assert_eq!(info.capabilities.unwrap().structured_output, None);
+
+    let catalog = gauss_core::catalog::global().read().unwrap();
+    assert_eq!(catalog.context_window(model), 8192);
+    drop(catalog);
+
+    let caps = OllamaProvider::new(model, ProviderConfig::new("ollama")).capabilities();
+    assert!(caps.vision);
+    assert!(caps.structured_output);
+    assert!(!caps.tool_use);
+    assert!(!caps.extended_thinking);
+    assert!(caps.streaming);
+}
+
+#[tokio::test]
+async fn embeds_over_api_embed() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/embed"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "model": "nomic-embed-text",
+            "embeddings": [[0.1, 0.2, 0.3], [0.4, 0.5, 0.6]]
+        })))
+        .mount(&server)
+        .await;
+
+    let embedder = OllamaEmbedding::new("nomic-embed-text", config(&server)).keep_alive("5m");
+    assert_eq!(embedder.dimensions(), 0);
+    assert!(embedder.embed_batch(&[]).await.unwrap().is_empty());
+
+    let vectors = embedder.embed_batch(&["a", "b"]).await.unwrap();
+    assert_eq!(vectors, vec![vec![0.1, 0.2, 0.3], vec![0.4, 0.5, 0.6]]);
+    assert_eq!(embedder.dimensions(), 3);
+    assert_eq!(
+        request_body(&server, 0).await,
+        json!({"model": "nomic-embed-text", "input": ["a", "b"], "keep_alive": "5m"})
+    );
+
+    // One vector back for a single input is a mismatch, not a silent truncation.
+    let err = embedder.embed("only one").await.unwrap_err();
+    assert!(err.to_string().contains("Expected 1 embeddings"), "{err}");
+    assert_eq!(
+        OllamaEmbedding::new("nomic-embed-text", ProviderConfig::new("ollama"))
+            .with_dimensions(768)
+            .dimensions(),
+        768
+    );
+}
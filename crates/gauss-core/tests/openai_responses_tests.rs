@@ -0,0 +1,552 @@
+use futures::StreamExt;
+use gauss_core::agent::{Agent, AgentStreamEvent};
+use gauss_core::error::GaussError;
+use gauss_core::message::{Content, Message};
+use gauss_core::provider::openai_responses::OpenAiResponsesProvider;
+use gauss_core::provider::{
+    FinishReason, GenerateOptions, Provider, ProviderConfig, ReasoningEffort,
+};
+use gauss_core::streaming::StreamEvent;
+use gauss_core::tool::Tool;
+use serde_json::{Value, json};
+use std::time::Duration;
+use wiremock::matchers::{body_partial_json, method, path};
+use wiremock::{Mock, MockServer, Request, ResponseTemplate};
+
+fn provider(server: &MockServer) -> OpenAiResponsesProvider {
+    OpenAiResponsesProvider::new(
+        "o4-mini",
+        ProviderConfig::new("test-key").base_url(server.uri()),
+    )
+}
+
+fn completed_response() -> Value {
+    json!({
+        "id": "resp_1",
+        "status": "completed",
+        "store": false,
+        "output": [
+            {
+                "type": "reasoning",
+                "id": "rs_1",
+                "summary": [{"type": "summary_text", "text": "Looked it up."}],
+                "encrypted_content": "gAAAA-secret"
+            },
+            {"type": "web_search_call", "id": "ws_1", "status": "completed"},
+            {
+                "type": "message",
+                "role": "assistant",
+                "content": [{
+                    "type": "output_text",
+                    "text": "Rust 2024 is out.",
+                    "annotations": [{
+                        "type": "url_citation",
+                        "start_index": 0,
+                        "end_index": 9,
+                        "url": "https://blog.rust-lang.org/",
+                        "title": "Rust Blog"
+                    }]
+                }]
+            }
+        ],
+        "usage": {
+            "input_tokens": 20,
+            "input_tokens_details": {"cached_tokens": 4},
+            "output_tokens": 12,
+            "output_tokens_details": {"reasoning_tokens": 8}
+        }
+    })
+}
+
+#[tokio::test]
+async fn generate_parses_reasoning_citations_and_usage() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/responses"))
+        .and(body_partial_json(json!({
+            "model": "o4-mini",
+            "store": false,
+            "include": ["reasoning.encrypted_content"],
+            "max_output_tokens": 256,
+            "reasoning": {"effort": "high", "summary": "auto"},
+            "tools": [{"type": "web_search"}],
+            "input": [
+                {"role": "system", "content": [{"type": "input_text", "text": "Be brief."}]},
+                {"role": "user", "content": [{"type": "input_text", "text": "News?"}]}
+            ]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(completed_response()))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = provider(&server).web_search(true).encrypted_reasoning(true);
+    let caps = provider.capabilities();
+    assert!(caps.web_search && caps.citations && caps.reasoning_effort);
+
+    let options = GenerateOptions {
+        max_tokens: Some(256),
+        reasoning_effort: Some(ReasoningEffort::High),
+        ..Default::default()
+    };
+    let result = provider
+        .generate(
+            &[Message::system("Be brief."), Message::user("News?")],
+            &[],
+            &options,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(result.text(), Some("Rust 2024 is out."));
+    assert_eq!(result.finish_reason, FinishReason::Stop);
+    assert_eq!(result.thinking.as_deref(), Some("Looked it up."));
+    assert!(matches!(
+        &result.message.content[0],
+        Content::Reasoning { text, id: Some(id), encrypted_content: Some(enc) }
+            if text == "Looked it up." && id == "rs_1" && enc == "gAAAA-secret"
+    ));
+    assert_eq!(result.citations.len(), 1);
+    assert_eq!(result.citations[0].cited_text, "Rust 2024");
+    assert_eq!(
+        result.citations[0].url.as_deref(),
+        Some("https://blog.rust-lang.org/")
+    );
+    assert_eq!(
+        result.citations[0].document_title.as_deref(),
+        Some("Rust Blog")
+    );
+    assert_eq!(result.usage.input_tokens, 20);
+    assert_eq!(result.usage.reasoning_tokens, Some(8));
+    assert_eq!(result.usage.cache_read_tokens, Some(4));
+    assert_eq!(result.provider_metadata["status"], "completed");
+    // Unstored responses cannot be continued.
+    assert!(result.provider_metadata.get("response_id").is_none());
+}
+
+#[tokio::test]
+async fn history_with_tool_calls_and_reasoning_maps_to_input_items() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/responses"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "id": "resp_2",
+            "status": "completed",
+            "output": [{
+                "type": "function_call",
+                "call_id": "call_2",
+                "name": "lookup",
+                "arguments": "{\"q\":\"more\"}"
+            }]
+        })))
+        .mount(&server)
+        .await;
+
+    let assistant = Message {
+        role: gauss_core::message::Role::Assistant,
+        content: vec![
+            Content::Reasoning {
+                text: "Need data.".into(),
+                id: Some("rs_0".into()),
+                encrypted_content: Some("enc".into()),
+            },
+            Content::ToolCall {
+                id: "call_1".into(),
+                name: "lookup".into(),
+                arguments: json!({"q": "rust"}),
+            },
+        ],
+        name: None,
+    };
+    let messages = vec![
+        Message::user("Find rust"),
+        assistant,
+        Message::tool_result("call_1", json!("found")),
+    ];
+    let tool = Tool::builder("lookup", "Search").build();
+    let options = GenerateOptions {
+        previous_response_id: Some("resp_0".into()),
+        ..Default::default()
+    };
+    let result = provider(&server)
+        .generate(&messages, &[tool], &options)
+        .await
+        .unwrap();
+
+    assert_eq!(result.finish_reason, FinishReason::ToolCalls);
+    let calls = result.tool_calls();
+    assert_eq!(calls[0].0, "call_2");
+    assert_eq!(calls[0].2, &json!({"q": "more"}));
+    assert_eq!(result.provider_metadata["response_id"], "resp_2");
+
+    let requests = server.received_requests().await.unwrap();
+    let body: Value = serde_json::from_slice(&requests[0].body).unwrap();
+    assert_eq!(body["previous_response_id"], "resp_0");
+    assert_eq!(body["tools"][0]["type"], "function");
+    assert_eq!(body["tools"][0]["name"], "lookup");
+    assert_eq!(
+        body["input"],
+        json!([
+            {"role": "user", "content": [{"type": "input_text", "text": "Find rust"}]},
+            {
+                "type": "reasoning",
+                "id": "rs_0",
+                "summary": [{"type": "summary_text", "text": "Need data."}],
+                "encrypted_content": "enc"
+            },
+            {
+                "type": "function_call",
+                "call_id": "call_1",
+                "name": "lookup",
+                "arguments": "{\"q\":\"rust\"}"
+            },
+            {"type": "function_call_output", "call_id": "call_1", "output": "found"}
+        ])
+    );
+}
+
+#[tokio::test]
+async fn stream_maps_typed_events() {
+    let events = [
+        json!({"type": "response.created", "response": {"id": "resp_3", "status": "in_progress"}}),
+        json!({"type": "response.reasoning_summary_text.delta", "output_index": 0, "delta": "Think"}),
+        json!({"type": "response.output_text.delta", "output_index": 1, "delta": "Hel"}),
+        json!({"type": "response.output_text.delta", "output_index": 1, "delta": "lo"}),
+        json!({
+            "type": "response.output_text.annotation.added",
+            "output_index": 1,
+            "content_index": 0,
+            "annotation": {
+                "type": "url_citation",
+                "start_index": 0,
+                "end_index": 5,
+                "url": "https://example.com/",
+                "title": "Example"
+            }
+        }),
+        json!({
+            "type": "response.output_item.done",
+            "output_index": 0,
+            "item": {"type": "reasoning", "id": "rs_3", "summary": [], "encrypted_content": "enc_3"}
+        }),
+        json!({
+            "type": "response.output_item.added",
+            "output_index": 2,
+            "item": {"type": "function_call", "call_id": "call_9", "name": "lookup", "arguments": ""}
+        }),
+        json!({"type": "response.function_call_arguments.delta", "output_index": 2, "delta": "{\"q\":1}"}),
+        json!({
+            "type": "response.completed",
+            "response": {
+                "id": "resp_3",
+                "status": "completed",
+                "usage": {"input_tokens": 3, "output_tokens": 5}
+            }
+        }),
+    ];
+    let sse: String = events
+        .iter()
+        .map(|e| format!("event: {}\ndata: {e}\n\n", e["type"].as_str().unwrap()))
+        .collect();
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/responses"))
+        .and(body_partial_json(json!({"stream": true})))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "text/event-stream")
+                .set_body_string(sse),
+        )
+        .mount(&server)
+        .await;
+
+    let events: Vec<StreamEvent> = provider(&server)
+        .stream(&[Message::user("hi")], &[], &GenerateOptions::default())
+        .await
+        .unwrap()
+        .map(|e| e.unwrap())
+        .collect()
+        .await;
+
+    assert!(matches!(&events[0], StreamEvent::ReasoningDelta(d) if d == "Think"));
+    assert!(matches!(&events[1], StreamEvent::TextDelta(d) if d == "Hel"));
+    assert!(matches!(&events[2], StreamEvent::TextDelta(d) if d == "lo"));
+    assert!(matches!(
+        &events[3],
+        StreamEvent::Citation(c)
+            if c.cited_text == "Hello" && c.url.as_deref() == Some("https://example.com/")
+    ));
+    assert!(matches!(
+        &events[4],
+        StreamEvent::ReasoningItem { id: Some(id), encrypted_content: Some(enc) }
+            if id == "rs_3" && enc == "enc_3"
+    ));
+    assert!(matches!(
+        &events[5],
+        StreamEvent::ToolCallDelta { index: 0, id: Some(id), name: Some(name), arguments_delta: None }
+            if id == "call_9" && name == "lookup"
+    ));
+    assert!(matches!(
+        &events[6],
+        StreamEvent::ToolCallDelta { index: 0, id: None, arguments_delta: Some(a), .. } if a == "{\"q\":1}"
+    ));
+    assert!(matches!(
+        events[7],
+        StreamEvent::FinishReason(FinishReason::ToolCalls)
+    ));
+    assert!(matches!(&events[8], StreamEvent::Usage(u) if u.output_tokens == 5));
+    assert!(matches!(
+        &events[9],
+        StreamEvent::ProviderMetadata(meta) if meta["response_id"] == "resp_3"
+    ));
+    assert!(matches!(events[10], StreamEvent::Done));
+    assert_eq!(events.len(), 11);
+}
+
+fn lookup_then_answer() -> impl Fn(&Request) -> ResponseTemplate {
+    let calls = std::sync::atomic::AtomicUsize::new(0);
+    move |_: &Request| {
+        if calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst) == 0 {
+            ResponseTemplate::new(200).set_body_json(json!({
+                "id": "resp_a",
+                "status": "completed",
+                "output": [{
+                    "type": "function_call",
+                    "call_id": "call_a",
+                    "name": "lookup",
+                    "arguments": "{}"
+                }]
+            }))
+        } else {
+            ResponseTemplate::new(200).set_body_json(json!({
+                "id": "resp_b",
+                "status": "completed",
+                "output": [{
+                    "type": "message",
+                    "role": "assistant",
+                    "content": [{"type": "output_text", "text": "Done", "annotations": []}]
+                }]
+            }))
+        }
+    }
+}
+
+#[tokio::test]
+async fn agent_chains_steps_by_response_id_when_enabled() {
+    for chain in [false, true] {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/responses"))
+            .respond_with(lookup_then_answer())
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let tool = Tool::builder("lookup", "Search")
+            .execute(|_| async move { Ok(json!("found")) })
+            .build();
+        let agent = Agent::builder("chained", std::sync::Arc::new(provider(&server)))
+            .instructions("Be brief.")
+            .tool(tool)
+            .chain_responses(chain)
+            .build();
+        let output = agent.run(vec![Message::user("Find it")]).await.unwrap();
+        assert_eq!(output.text, "Done");
+
+        let requests = server.received_requests().await.unwrap();
+        let first: Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert!(first.get("previous_response_id").is_none());
+        assert_eq!(first["input"].as_array().unwrap().len(), 2);
+        let second: Value = serde_json::from_slice(&requests[1].body).unwrap();
+        if chain {
+            assert_eq!(second["previous_response_id"], "resp_a");
+            assert_eq!(
+                second["input"],
+                json!([{"type": "function_call_output", "call_id": "call_a", "output": "found"}])
+            );
+        } else {
+            // Off by default: the whole conversation is resent.
+            assert!(second.get("previous_response_id").is_none());
+            assert_eq!(second["input"].as_array().unwrap().len(), 4);
+        }
+    }
+}
+
+#[tokio::test]
+async fn streamed_agent_replays_reasoning_and_chains_stored_responses() {
+    let call = |id: &str| {
+        json!({
+            "type": "response.output_item.added",
+            "output_index": 1,
+            "item": {"type": "function_call", "call_id": id, "name": "lookup", "arguments": "{}"}
+        })
+    };
+    let steps = [
+        vec![
+            json!({"type": "response.reasoning_summary_text.delta", "output_index": 0, "delta": "Plan"}),
+            json!({
+                "type": "response.output_item.done",
+                "output_index": 0,
+                "item": {"type": "reasoning", "id": "rs_a", "summary": [], "encrypted_content": "enc_a"}
+            }),
+            call("call_a"),
+            json!({"type": "response.completed", "response": {"id": "resp_a", "status": "completed", "store": false}}),
+        ],
+        vec![
+            call("call_b"),
+            json!({"type": "response.completed", "response": {"id": "resp_b", "status": "completed"}}),
+        ],
+        vec![
+            json!({"type": "response.output_text.delta", "output_index": 0, "delta": "Done"}),
+            json!({"type": "response.completed", "response": {"id": "resp_c", "status": "completed"}}),
+        ],
+    ];
+    let bodies: Vec<String> = steps
+        .iter()
+        .map(|events| {
+            events
+                .iter()
+                .map(|e| format!("event: {}\ndata: {e}\n\n", e["type"].as_str().unwrap()))
+                .collect()
+        })
+        .collect();
+
+    let server = MockServer::start().await;
+    let calls = std::sync::atomic::AtomicUsize::new(0);
+    Mock::given(method("POST"))
+        .and(path("/responses"))
+        .respond_with(move |_: &Request| {
+            let n = calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "text/event-stream")
+                .set_body_string(bodies[n.min(2)].clone())
+        })
+        .expect(3)
+        .mount(&server)
+        .await;
+
+    let tool = Tool::builder("lookup", "Search")
+        .execute(|_| async move { Ok(json!("found")) })
+        .build();
+    let agent = Agent::builder("chained", std::sync::Arc::new(provider(&server)))
+        .tool(tool)
+        .chain_responses(true)
+        .build();
+    let events: Vec<AgentStreamEvent> = agent
+        .run_stream(vec![Message::user("Find it")])
+        .await
+        .unwrap()
+        .map(|e| e.unwrap())
+        .collect()
+        .await;
+    assert!(matches!(events.last(), Some(AgentStreamEvent::Done { text, .. }) if text == "Done"));
+
+    let requests = server.received_requests().await.unwrap();
+    // The unstored first response is replayed in full, reasoning included.
+    let second: Value = serde_json::from_slice(&requests[1].body).unwrap();
+    assert!(second.get("previous_response_id").is_none());
+    assert_eq!(
+        second["input"][1],
+        json!({
+            "type": "reasoning",
+            "id": "rs_a",
+            "summary": [{"type": "summary_text", "text": "Plan"}],
+            "encrypted_content": "enc_a"
+        })
+    );
+    assert_eq!(second["input"].as_array().unwrap().len(), 4);
+    // The stored second response is continued by id.
+    let third: Value = serde_json::from_slice(&requests[2].body).unwrap();
+    assert_eq!(third["previous_response_id"], "resp_b");
+    assert_eq!(
+        third["input"],
+        json!([{"type": "function_call_output", "call_id": "call_b", "output": "found"}])
+    );
+}
+
+#[tokio::test]
+async fn background_mode_polls_until_terminal() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/responses"))
+        .and(body_partial_json(json!({"background": true})))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(json!({"id": "resp_bg", "status": "queued"})),
+        )
+        .mount(&server)
+        .await;
+
+    let polls = std::sync::atomic::AtomicUsize::new(0);
+    Mock::given(method("GET"))
+        .and(path("/responses/resp_bg"))
+        .respond_with(move |_: &Request| {
+            let n = polls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            if n == 0 {
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({"id": "resp_bg", "status": "in_progress"}))
+            } else {
+                ResponseTemplate::new(200).set_body_json(json!({
+                    "id": "resp_bg",
+                    "status": "incomplete",
+                    "incomplete_details": {"reason": "max_output_tokens"},
+                    "output": [{
+                        "type": "message",
+                        "role": "assistant",
+                        "content": [{"type": "output_text", "text": "Partial", "annotations": []}]
+                    }]
+                }))
+            }
+        })
+        .expect(3)
+        .mount(&server)
+        .await;
+
+    let provider = provider(&server).background(Duration::from_millis(1));
+    let result = provider
+        .generate(
+            &[Message::user("long task")],
+            &[],
+            &GenerateOptions::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.text(), Some("Partial"));
+    assert_eq!(result.finish_reason, FinishReason::Length);
+
+    let retrieved = provider.retrieve("resp_bg").await.unwrap();
+    assert_eq!(retrieved.provider_metadata["status"], "incomplete");
+}
+
+#[tokio::test]
+async fn errors_map_to_gauss_errors() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/responses"))
+        .respond_with(ResponseTemplate::new(429).set_body_json(json!({
+            "error": {"message": "slow down"}
+        })))
+        .mount(&server)
+        .await;
+    let err = provider(&server)
+        .generate(&[Message::user("hi")], &[], &GenerateOptions::default())
+        .await
+        .unwrap_err();
+    assert!(matches!(err, GaussError::RateLimited { .. }));
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/responses"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "id": "resp_f",
+            "status": "failed",
+            "error": {"code": "server_error", "message": "model crashed"}
+        })))
+        .mount(&server)
+        .await;
+    let err = provider(&server)
+        .generate(&[Message::user("hi")], &[], &GenerateOptions::default())
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("model crashed"), "{err}");
+}
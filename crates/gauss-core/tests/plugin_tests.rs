@@ -240,3 +240,323 @@ async fn custom_event() {
 
     assert!(received_data.lock().unwrap().contains("value"));
 }
+
+// ---------------------------------------------------------------------------
+// Runtime event emission
+// ---------------------------------------------------------------------------
+
+use gauss_core::agent::Agent;
+use gauss_core::guardrail::{ContentModerationGuardrail, GuardrailChain};
+use gauss_core::hitl::ApprovalManager;
+use gauss_core::message::Message;
+use gauss_core::provider::openai::OpenAiProvider;
+use gauss_core::provider::{GenerateOptions, Provider, ProviderConfig};
+use gauss_core::resilience::FallbackProvider;
+use gauss_core::team::Team;
+use gauss_core::tool::Tool;
+use serde_json::json;
+use std::sync::Mutex;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// A registry that records the type of every event it sees.
+fn recording_registry() -> (Arc<PluginRegistry>, Arc<Mutex<Vec<GaussEvent>>>) {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let sink = events.clone();
+    let mut registry = PluginRegistry::new();
+    registry.bus.subscribe(
+        "*",
+        Box::new(move |event| sink.lock().unwrap().push(event.clone())),
+    );
+    (Arc::new(registry), events)
+}
+
+fn event_types(events: &Mutex<Vec<GaussEvent>>) -> Vec<String> {
+    events
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|e| e.event_type().to_string())
+        .collect()
+}
+
+fn text_response(content: &str) -> serde_json::Value {
+    json!({
+        "choices": [{
+            "message": {"role": "assistant", "content": content},
+            "finish_reason": "stop"
+        }],
+        "usage": {"prompt_tokens": 5, "completion_tokens": 3}
+    })
+}
+
+fn openai(uri: &str) -> Arc<OpenAiProvider> {
+    Arc::new(OpenAiProvider::new(
+        "gpt-4o",
+        ProviderConfig::new("test-key").base_url(uri),
+    ))
+}
+
+#[tokio::test]
+async fn async_handlers_are_awaited() {
+    let mut bus = EventBus::new();
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let sink = seen.clone();
+    bus.subscribe_async(
+        "agent_start",
+        Box::new(move |event| {
+            let sink = sink.clone();
+            Box::pin(async move {
+                tokio::task::yield_now().await;
+                sink.lock().unwrap().push(event.event_type().to_string());
+            })
+        }),
+    );
+
+    bus.publish_async(&GaussEvent::AgentStart {
+        agent_name: "a".into(),
+        session_id: "s".into(),
+    })
+    .await;
+    assert_eq!(*seen.lock().unwrap(), vec!["agent_start"]);
+}
+
+#[tokio::test]
+async fn slow_async_handlers_do_not_stall_the_agent() {
+    use gauss_core::provider::mock::MockProvider;
+
+    let started = Arc::new(tokio::sync::Notify::new());
+    let signal = started.clone();
+    let mut registry = PluginRegistry::new();
+    registry.bus.subscribe_async(
+        "agent_start",
+        Box::new(move |_| {
+            let signal = signal.clone();
+            Box::pin(async move {
+                signal.notify_one();
+                tokio::time::sleep(std::time::Duration::from_secs(60)).await;
+            })
+        }),
+    );
+    let agent = Agent::builder("slow-plugin", Arc::new(MockProvider::new().text("done")))
+        .plugins(Arc::new(registry))
+        .build();
+
+    let output = tokio::time::timeout(
+        std::time::Duration::from_secs(5),
+        agent.run(vec![Message::user("go")]),
+    )
+    .await
+    .expect("agent waited on a slow plugin")
+    .unwrap();
+    assert_eq!(output.text, "done");
+    started.notified().await;
+}
+
+#[tokio::test]
+async fn agent_publishes_lifecycle_events() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "choices": [{
+                "message": {
+                    "role": "assistant",
+                    "content": null,
+                    "tool_calls": [{
+                        "id": "call_1",
+                        "type": "function",
+                        "function": {"name": "echo", "arguments": "{\"x\":1}"}
+                    }]
+                },
+                "finish_reason": "tool_calls"
+            }],
+            "usage": {"prompt_tokens": 10, "completion_tokens": 2}
+        })))
+        .up_to_n_times(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(text_response("done")))
+        .mount(&mock_server)
+        .await;
+
+    let (registry, events) = recording_registry();
+    let echo = Tool::builder("echo", "Echo")
+        .execute(|args| async move { Ok(args) })
+        .build();
+    let agent = Agent::builder("events-agent", openai(&mock_server.uri()))
+        .tool(echo)
+        .plugins(registry)
+        .build();
+
+    let output = agent.run(vec![Message::user("go")]).await.unwrap();
+
+    assert_eq!(
+        event_types(&events),
+        vec![
+            "agent_start",
+            "tool_call_start",
+            "tool_call_finish",
+            "step_finish",
+            "step_finish",
+            "agent_finish"
+        ]
+    );
+    let events = events.lock().unwrap();
+    match &events[3] {
+        GaussEvent::StepFinish {
+            step_index,
+            finish_reason,
+            tool_calls,
+            input_tokens,
+            ..
+        } => {
+            assert_eq!(*step_index, 0);
+            assert_eq!(finish_reason, "tool_calls");
+            assert_eq!(*tool_calls, 1);
+            assert_eq!(*input_tokens, 10);
+        }
+        other => panic!("unexpected event {other:?}"),
+    }
+    match &events[5] {
+        GaussEvent::AgentFinish {
+            session_id,
+            result_text,
+            steps,
+            ..
+        } => {
+            assert_eq!(session_id, &output.run_id);
+            assert_eq!(result_text, "done");
+            assert_eq!(*steps, 2);
+        }
+        other => panic!("unexpected event {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn agent_stream_publishes_finish_or_error_on_every_exit() {
+    use futures::StreamExt;
+    use gauss_core::provider::mock::{MockError, MockProvider, MockResponse};
+
+    let echo = Tool::builder("echo", "Echo")
+        .execute(|args| async move { Ok(args) })
+        .build();
+
+    // Out of steps while the model still calls tools.
+    let (registry, events) = recording_registry();
+    let looping =
+        MockProvider::new().repeat(MockResponse::ToolCalls(vec![("echo".into(), json!({}))]));
+    let agent = Agent::builder("stream-agent", Arc::new(looping))
+        .tool(echo)
+        .max_steps(2)
+        .plugins(registry)
+        .build();
+    let results: Vec<_> = agent
+        .run_stream(vec![Message::user("go")])
+        .await
+        .unwrap()
+        .collect()
+        .await;
+    assert!(results.iter().all(|r| r.is_ok()));
+    assert_eq!(
+        event_types(&events).last().map(String::as_str),
+        Some("agent_finish")
+    );
+
+    // The provider fails.
+    let (registry, events) = recording_registry();
+    let failing = MockProvider::new().error(MockError::status(500, "upstream down"));
+    let agent = Agent::builder("stream-agent", Arc::new(failing))
+        .plugins(registry)
+        .build();
+    let results: Vec<_> = agent
+        .run_stream(vec![Message::user("go")])
+        .await
+        .unwrap()
+        .collect()
+        .await;
+    assert!(results.last().unwrap().is_err());
+    assert_eq!(event_types(&events), vec!["agent_start", "error"]);
+}
+
+#[tokio::test]
+async fn team_passes_registry_to_agents() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(text_response("ok")))
+        .mount(&mock_server)
+        .await;
+
+    let (registry, events) = recording_registry();
+    let team = Team::builder("crew")
+        .agent(Agent::builder("a", openai(&mock_server.uri())).build())
+        .plugins(registry)
+        .build();
+    team.run(vec![Message::user("go")]).await.unwrap();
+
+    assert_eq!(
+        event_types(&events),
+        vec![
+            "orchestrator_start",
+            "agent_start",
+            "step_finish",
+            "agent_finish",
+            "orchestrator_finish"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn providers_guardrails_and_approvals_publish_events() {
+    let failing = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(500).set_body_json(json!({"error": "down"})))
+        .mount(&failing)
+        .await;
+    let healthy = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(text_response("ok")))
+        .mount(&healthy)
+        .await;
+
+    let (registry, events) = recording_registry();
+    let fallback = FallbackProvider::new(vec![openai(&failing.uri()), openai(&healthy.uri())])
+        .with_plugins(registry.clone());
+    fallback
+        .generate(&[Message::user("hi")], &[], &GenerateOptions::default())
+        .await
+        .unwrap();
+
+    let mut chain = GuardrailChain::new().with_plugins(registry.clone());
+    chain.add(Arc::new(
+        ContentModerationGuardrail::new().block_pattern("secret", "no secrets"),
+    ));
+    let result = chain.validate_output("the secret plan").await.unwrap();
+    assert!(result.blocked);
+
+    let approvals = ApprovalManager::new().with_plugins(registry);
+    let request = approvals
+        .request_approval("delete".into(), json!({"id": 1}), 0, "s1".into())
+        .unwrap();
+
+    assert_eq!(
+        event_types(&events),
+        vec![
+            "provider_fallback",
+            "guardrail_decision",
+            "approval_request"
+        ]
+    );
+    let events = events.lock().unwrap();
+    assert!(matches!(
+        &events[1],
+        GaussEvent::GuardrailDecision { stage, action, .. } if stage == "output" && action == "block"
+    ));
+    assert!(matches!(
+        &events[2],
+        GaussEvent::ApprovalRequest { request_id, .. } if *request_id == request.id
+    ));
+}
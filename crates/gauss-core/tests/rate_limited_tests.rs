@@ -0,0 +1,242 @@
+use futures::StreamExt;
+use gauss_core::message::{Message, Usage};
+use gauss_core::provider::mock::{MockError, MockProvider};
+use gauss_core::provider::rate_limited::{RateLimitedProvider, RateLimiter, RateLimits};
+use gauss_core::provider::{GenerateOptions, Provider};
+use gauss_core::streaming::StreamEvent;
+use reqwest::header::{HeaderMap, HeaderValue};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::Instant;
+
+fn ask(text: &str) -> Vec<Message> {
+    vec![Message::user(text)]
+}
+
+#[tokio::test(start_paused = true)]
+async fn requests_queue_fifo_across_shared_wrappers() {
+    let mock = Arc::new(
+        MockProvider::new().repeat(gauss_core::provider::mock::MockResponse::Text("ok".into())),
+    );
+    let limiter = Arc::new(RateLimiter::new(RateLimits::new().requests_per_minute(2)));
+    let a = RateLimitedProvider::new(mock.clone(), limiter.clone());
+    let b = RateLimitedProvider::new(mock.clone(), limiter.clone());
+    let options = GenerateOptions::default();
+
+    let started = Instant::now();
+    let (m1, m2, m3, m4) = (ask("1"), ask("2"), ask("3"), ask("4"));
+    let calls = vec![
+        a.generate(&m1, &[], &options),
+        b.generate(&m2, &[], &options),
+        a.generate(&m3, &[], &options),
+        b.generate(&m4, &[], &options),
+    ];
+    let mut finished = Vec::new();
+    let mut pending: futures::stream::FuturesOrdered<_> = calls.into_iter().collect();
+    while let Some(result) = pending.next().await {
+        assert_eq!(result.unwrap().text(), Some("ok"));
+        finished.push(started.elapsed().as_secs());
+    }
+
+    // Burst of two, then one request every 30 seconds.
+    assert_eq!(finished, vec![0, 0, 30, 60]);
+    let order: Vec<_> = mock
+        .requests()
+        .iter()
+        .map(|r| r.last_user_text().unwrap().to_string())
+        .collect();
+    assert_eq!(order, vec!["1", "2", "3", "4"]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn input_and_output_tokens_are_budgeted() {
+    let mock = Arc::new(
+        MockProvider::new()
+            .usage(Usage {
+                input_tokens: 10,
+                output_tokens: 60,
+                ..Default::default()
+            })
+            .repeat(gauss_core::provider::mock::MockResponse::Text("ok".into())),
+    );
+    let limiter = Arc::new(RateLimiter::new(RateLimits::default()).limit(
+        "mock",
+        "mock-model",
+        RateLimits::new().output_tokens_per_minute(100),
+    ));
+    let provider = RateLimitedProvider::new(mock.clone(), limiter.clone());
+    let options = GenerateOptions::default();
+
+    let started = Instant::now();
+    provider.generate(&ask("a"), &[], &options).await.unwrap();
+    provider.generate(&ask("b"), &[], &options).await.unwrap();
+    // 120 of 100 output tokens used: the next call waits until the debt is repaid.
+    assert_eq!(started.elapsed(), Duration::ZERO);
+    provider.generate(&ask("c"), &[], &options).await.unwrap();
+    assert_eq!(started.elapsed().as_secs(), 12);
+
+    // Input reservations come from the token estimate of the prompt.
+    let limiter = Arc::new(RateLimiter::new(
+        RateLimits::new().input_tokens_per_minute(60),
+    ));
+    let provider = RateLimitedProvider::new(
+        Arc::new(
+            MockProvider::new().repeat(gauss_core::provider::mock::MockResponse::Text("ok".into())),
+        ),
+        limiter,
+    );
+    let prompt = ask(&"word ".repeat(40));
+    let estimate = gauss_core::context::count_messages_tokens(&prompt) as u64;
+    assert!(estimate > 50 && estimate <= 60, "{estimate}");
+    let started = Instant::now();
+    provider.generate(&prompt, &[], &options).await.unwrap();
+    // The mock reports 10 input tokens, so settling leaves 50 of 60 available
+    // and the second call waits for the rest of its estimate at 1 token/s.
+    provider.generate(&prompt, &[], &options).await.unwrap();
+    assert_eq!(started.elapsed().as_secs(), estimate - 50);
+}
+
+#[tokio::test(start_paused = true)]
+async fn unused_reservations_are_returned() {
+    // A failed call gives back its input reservation.
+    let limiter = Arc::new(RateLimiter::new(
+        RateLimits::new().input_tokens_per_minute(60),
+    ));
+    let mock = MockProvider::new()
+        .error(MockError::status(500, "upstream down"))
+        .text("ok");
+    let provider = RateLimitedProvider::new(Arc::new(mock), limiter);
+    let prompt = ask(&"word ".repeat(40));
+    let options = GenerateOptions::default();
+
+    let started = Instant::now();
+    assert!(provider.generate(&prompt, &[], &options).await.is_err());
+    provider.generate(&prompt, &[], &options).await.unwrap();
+    assert_eq!(started.elapsed(), Duration::ZERO);
+
+    // A stream that reports no usage gives back its output reservation.
+    let limiter = Arc::new(RateLimiter::new(
+        RateLimits::new().output_tokens_per_minute(100),
+    ));
+    let events = vec![StreamEvent::TextDelta("hi".into()), StreamEvent::Done];
+    let mock = MockProvider::new()
+        .stream_events(events.clone())
+        .stream_events(events);
+    let provider = RateLimitedProvider::new(Arc::new(mock), limiter);
+    let options = GenerateOptions {
+        max_tokens: Some(100),
+        ..Default::default()
+    };
+
+    let started = Instant::now();
+    for _ in 0..2 {
+        let stream = provider.stream(&ask("a"), &[], &options).await.unwrap();
+        assert_eq!(stream.count().await, 2);
+    }
+    assert_eq!(started.elapsed(), Duration::ZERO);
+}
+
+#[tokio::test(start_paused = true)]
+async fn rate_limited_errors_pause_the_queue() {
+    let mock = Arc::new(
+        MockProvider::new()
+            .error(MockError::rate_limited(Some(5_000)))
+            .text("after"),
+    );
+    let limiter = Arc::new(RateLimiter::new(RateLimits::default()));
+    let provider = RateLimitedProvider::new(mock, limiter);
+    let options = GenerateOptions::default();
+
+    let started = Instant::now();
+    assert!(provider.generate(&ask("a"), &[], &options).await.is_err());
+    let result = provider.generate(&ask("b"), &[], &options).await.unwrap();
+    assert_eq!(result.text(), Some("after"));
+    assert_eq!(started.elapsed().as_millis(), 5_000);
+}
+
+#[tokio::test(start_paused = true)]
+async fn limits_are_learned_from_response_headers() {
+    let mut headers = HeaderMap::new();
+    for (name, value) in [
+        ("x-ratelimit-limit-requests", "60"),
+        ("x-ratelimit-remaining-requests", "0"),
+        ("x-ratelimit-reset-requests", "3s"),
+    ] {
+        headers.insert(name, HeaderValue::from_static(value));
+    }
+    let limiter = Arc::new(RateLimiter::new(RateLimits::default()));
+    let mock = MockProvider::new()
+        .with_name("headers")
+        .with_model("headers-1")
+        .rate_limit_headers(headers)
+        .repeat(gauss_core::provider::mock::MockResponse::Text("ok".into()));
+    let provider = RateLimitedProvider::new(Arc::new(mock), limiter);
+    let options = GenerateOptions::default();
+
+    let started = Instant::now();
+    provider.generate(&ask("a"), &[], &options).await.unwrap();
+    provider.generate(&ask("b"), &[], &options).await.unwrap();
+    // Provider reported no remaining requests, resetting in 3s.
+    assert_eq!(started.elapsed().as_secs(), 3);
+}
+
+#[tokio::test(start_paused = true)]
+async fn cancelled_waiters_leave_the_queue() {
+    let mock = Arc::new(
+        MockProvider::new().repeat(gauss_core::provider::mock::MockResponse::Text("ok".into())),
+    );
+    let limiter = Arc::new(RateLimiter::new(RateLimits::new().requests_per_minute(1)));
+    let provider = RateLimitedProvider::new(mock.clone(), limiter.clone());
+    let options = GenerateOptions::default();
+    let (m1, m2, m3) = (ask("1"), ask("2"), ask("3"));
+
+    provider.generate(&m1, &[], &options).await.unwrap();
+    let holder = provider.generate(&m2, &[], &options);
+    let queued = provider.generate(&m3, &[], &options);
+    let (holder, queued) = (Box::pin(holder), Box::pin(queued));
+    let mut both = futures::future::select(holder, queued);
+    // Neither can finish yet; poll both once so they take their places in line.
+    assert!(futures::poll!(&mut both).is_pending());
+    assert_eq!(limiter.waiting("mock", "mock-model"), 1);
+    drop(both);
+    assert_eq!(limiter.waiting("mock", "mock-model"), 0);
+
+    // The gate is free again: the next caller waits only for capacity.
+    let started = Instant::now();
+    provider.generate(&ask("4"), &[], &options).await.unwrap();
+    assert_eq!(started.elapsed().as_secs(), 60);
+    assert_eq!(mock.call_count(), 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn waiters_cancelled_after_being_handed_the_turn_pass_it_on() {
+    let mock = Arc::new(
+        MockProvider::new().repeat(gauss_core::provider::mock::MockResponse::Text("ok".into())),
+    );
+    let limiter = Arc::new(RateLimiter::new(RateLimits::new().requests_per_minute(1)));
+    let provider = RateLimitedProvider::new(mock.clone(), limiter.clone());
+    let options = GenerateOptions::default();
+    let (m1, m2, m3) = (ask("1"), ask("2"), ask("3"));
+
+    provider.generate(&m1, &[], &options).await.unwrap();
+    let mut holder = Box::pin(provider.generate(&m2, &[], &options));
+    let mut queued = Box::pin(provider.generate(&m3, &[], &options));
+    assert!(futures::poll!(&mut holder).is_pending());
+    assert!(futures::poll!(&mut queued).is_pending());
+
+    // The holder finishes and hands the turn to `queued`, which is cancelled
+    // before it ever sees it.
+    holder.await.unwrap();
+    drop(queued);
+    assert_eq!(limiter.waiting("mock", "mock-model"), 0);
+
+    let m4 = ask("4");
+    tokio::time::timeout(
+        Duration::from_secs(120),
+        provider.generate(&m4, &[], &options),
+    )
+    .await
+    .expect("the handed-over turn was lost")
+    .unwrap();
+    assert_eq!(mock.call_count(), 3);
+}
@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use futures::StreamExt;
 use gauss_core::error::{GaussError, Result};
 use gauss_core::message::{Message, Usage};
 use gauss_core::provider::{BoxStream, FinishReason, GenerateOptions, GenerateResult, Provider};
@@ -47,7 +48,10 @@ impl Provider for FailThenSucceed {
                 usage: Usage::default(),
                 finish_reason: FinishReason::Stop,
                 provider_metadata: serde_json::json!({}),
-                thinking: None, citations: vec![], grounding_metadata: None,
+                thinking: None,
+                citations: vec![],
+                grounding_metadata: None,
+                routing: None,
             })
         }
     }
@@ -138,7 +142,10 @@ impl Provider for AlwaysSucceeds {
             usage: Usage::default(),
             finish_reason: FinishReason::Stop,
             provider_metadata: serde_json::json!({}),
-            thinking: None, citations: vec![], grounding_metadata: None,
+            thinking: None,
+            citations: vec![],
+            grounding_metadata: None,
+            routing: None,
         })
     }
 
@@ -292,3 +299,157 @@ async fn resilient_with_circuit_breaker_and_fallback() {
 
     assert_eq!(result.message.text().unwrap(), "Success from backup");
 }
+
+#[tokio::test]
+async fn circuit_breaker_publishes_state_metrics() {
+    let registry = Arc::new(gauss_core::metrics::MetricsRegistry::new());
+    let inner: Arc<dyn Provider> = Arc::new(AlwaysFails::new("failing"));
+    let cb = CircuitBreaker::new(
+        inner,
+        CircuitBreakerConfig {
+            failure_threshold: 1,
+            recovery_timeout_ms: 60_000,
+            success_threshold: 1,
+        },
+    )
+    .with_metrics(registry.clone());
+
+    let state = |name| {
+        registry.value(
+            "gauss_circuit_breaker_state",
+            &[("provider", "failing"), ("model", "mock"), ("state", name)],
+        )
+    };
+    assert_eq!(state("closed"), Some(1.0));
+
+    let _ = cb
+        .generate(&[Message::user("hi")], &[], &GenerateOptions::default())
+        .await;
+    assert_eq!(state("open"), Some(1.0));
+    assert_eq!(state("closed"), Some(0.0));
+}
+
+fn timed_mock(name: &str, latency_ms: u64) -> Arc<gauss_core::provider::mock::MockProvider> {
+    Arc::new(
+        gauss_core::provider::mock::MockProvider::new()
+            .with_name(name)
+            .latency(std::time::Duration::from_millis(latency_ms))
+            .repeat(gauss_core::provider::mock::MockResponse::Text(format!(
+                "from {name}"
+            ))),
+    )
+}
+
+#[tokio::test(start_paused = true)]
+async fn hedged_request_wins_when_primary_is_slow() {
+    let primary = timed_mock("primary", 5_000);
+    let backup = timed_mock("backup", 100);
+    let hedged = HedgedProvider::new(vec![primary.clone(), backup.clone()])
+        .with_delay(HedgeDelay::Fixed(std::time::Duration::from_millis(200)));
+
+    let started = tokio::time::Instant::now();
+    let result = hedged
+        .generate(&[Message::user("hi")], &[], &GenerateOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(result.text(), Some("from backup"));
+    assert_eq!(started.elapsed().as_millis(), 300);
+    assert_eq!(hedged.hedge_count(), 1);
+    assert_eq!((primary.call_count(), backup.call_count()), (1, 1));
+    let routing = result.routing.unwrap();
+    assert_eq!((routing.index, routing.provider.as_str()), (1, "backup"));
+    assert_eq!(routing.strategy, "hedged");
+
+    // A primary that answers within the delay is never hedged.
+    let fast = timed_mock("fast", 50);
+    let unused = timed_mock("unused", 50);
+    let hedged = HedgedProvider::new(vec![fast, unused.clone()])
+        .with_delay(HedgeDelay::Fixed(std::time::Duration::from_millis(200)));
+    let started = tokio::time::Instant::now();
+    hedged
+        .generate(&[Message::user("hi")], &[], &GenerateOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(started.elapsed().as_millis(), 50);
+    assert_eq!(unused.call_count(), 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn hedged_failure_starts_next_provider_immediately() {
+    let failing = Arc::new(FailThenSucceed::new("failing", 1));
+    let backup = timed_mock("backup", 100);
+    let hedged = HedgedProvider::new(vec![failing, backup])
+        .with_delay(HedgeDelay::Fixed(std::time::Duration::from_secs(10)));
+
+    let started = tokio::time::Instant::now();
+    let result = hedged
+        .generate(&[Message::user("hi")], &[], &GenerateOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(result.text(), Some("from backup"));
+    assert_eq!(started.elapsed().as_millis(), 100);
+
+    let all_fail = HedgedProvider::new(vec![
+        Arc::new(AlwaysFails::new("a")),
+        Arc::new(AlwaysFails::new("b")),
+    ]);
+    assert!(
+        all_fail
+            .generate(&[Message::user("hi")], &[], &GenerateOptions::default())
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn hedged_capabilities_and_streams_report_the_winner() {
+    let vision = gauss_core::provider::ProviderCapabilities {
+        streaming: true,
+        vision: true,
+        ..Default::default()
+    };
+    let primary = gauss_core::provider::mock::MockProvider::new()
+        .with_name("primary")
+        .with_model("gpt-4o")
+        .with_capabilities(vision)
+        .latency(std::time::Duration::from_secs(5))
+        .text("slow");
+    let backup = gauss_core::provider::mock::MockProvider::new()
+        .with_name("backup")
+        .with_model("gpt-4o-mini")
+        .text("fast");
+    let hedged = HedgedProvider::new(vec![Arc::new(primary), Arc::new(backup)])
+        .with_delay(HedgeDelay::Fixed(std::time::Duration::from_millis(100)));
+
+    // Either provider may answer, so only what both support is advertised.
+    let caps = hedged.capabilities();
+    assert!(caps.streaming && !caps.vision && !caps.tool_use);
+
+    let mut stream = hedged
+        .stream(&[Message::user("hi")], &[], &GenerateOptions::default())
+        .await
+        .unwrap();
+    let first = stream.next().await.unwrap().unwrap();
+    assert!(matches!(
+        first,
+        gauss_core::streaming::StreamEvent::Routing(d) if d.model == "gpt-4o-mini"
+    ));
+}
+
+#[tokio::test(start_paused = true)]
+async fn hedge_delay_tracks_latency_percentile() {
+    let hedged = HedgedProvider::new(vec![timed_mock("primary", 120), timed_mock("backup", 10)])
+        .with_delay(HedgeDelay::Percentile {
+            percentile: 0.9,
+            initial: std::time::Duration::from_secs(1),
+        });
+    assert_eq!(hedged.current_delay().as_millis(), 1_000);
+    for _ in 0..10 {
+        hedged
+            .generate(&[Message::user("hi")], &[], &GenerateOptions::default())
+            .await
+            .unwrap();
+    }
+    assert_eq!(hedged.current_delay().as_millis(), 120);
+    assert_eq!(hedged.hedge_count(), 0);
+}
@@ -1,10 +1,14 @@
 use gauss_core::error::GaussError;
 use gauss_core::message::Message;
+use gauss_core::provider::mock::{MockError, MockProvider};
 use gauss_core::provider::openai::OpenAiProvider;
-use gauss_core::provider::retry::{RetryConfig, RetryProvider};
+use gauss_core::provider::rate_limit::{self, RateLimitInfo};
+use gauss_core::provider::retry::{RetryBudget, RetryConfig, RetryProvider, is_transient};
 use gauss_core::provider::{GenerateOptions, Provider, ProviderConfig};
+use reqwest::header::{HeaderMap, HeaderValue};
 use serde_json::json;
 use std::sync::Arc;
+use std::time::{Duration, UNIX_EPOCH};
 use wiremock::matchers::{method, path};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
@@ -116,3 +120,231 @@ async fn test_no_retry_on_auth_error() {
 
     assert!(matches!(err, GaussError::Authentication { .. }));
 }
+
+fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+    let mut map = HeaderMap::new();
+    for (name, value) in pairs {
+        map.insert(*name, HeaderValue::from_str(value).unwrap());
+    }
+    map
+}
+
+fn fast_config() -> RetryConfig {
+    RetryConfig {
+        initial_delay_ms: 1,
+        max_delay_ms: 1_000,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_parse_openai_and_anthropic_rate_limit_headers() {
+    let openai = RateLimitInfo::from_headers(&headers(&[
+        ("x-ratelimit-limit-requests", "500"),
+        ("x-ratelimit-remaining-requests", "0"),
+        ("x-ratelimit-reset-requests", "1m30.5s"),
+        ("x-ratelimit-limit-tokens", "30000"),
+        ("x-ratelimit-remaining-tokens", "29000"),
+        ("x-ratelimit-reset-tokens", "20ms"),
+    ]));
+    assert_eq!(openai.requests.limit, Some(500));
+    assert_eq!(openai.requests.reset_ms, Some(90_500));
+    assert_eq!(openai.tokens.remaining, Some(29_000));
+    assert_eq!(openai.tokens.reset_ms, Some(20));
+    // No retry-after: wait for the exhausted request quota to reset.
+    assert_eq!(openai.retry_delay_ms(), Some(90_500));
+
+    let now = UNIX_EPOCH + Duration::from_secs(1_714_564_800); // 2024-05-01T12:00:00Z
+    let anthropic = RateLimitInfo::from_headers_at(
+        &headers(&[
+            ("retry-after", "7"),
+            ("anthropic-ratelimit-requests-remaining", "12"),
+            ("anthropic-ratelimit-input-tokens-limit", "40000"),
+            ("anthropic-ratelimit-input-tokens-remaining", "0"),
+            (
+                "anthropic-ratelimit-input-tokens-reset",
+                "2024-05-01T12:00:30Z",
+            ),
+            (
+                "anthropic-ratelimit-output-tokens-reset",
+                "2024-05-01T14:01:00+02:00",
+            ),
+        ]),
+        now,
+    );
+    assert_eq!(anthropic.retry_after_ms, Some(7_000));
+    assert_eq!(anthropic.requests.remaining, Some(12));
+    assert_eq!(anthropic.input_tokens.reset_ms, Some(30_000));
+    assert_eq!(anthropic.output_tokens.reset_ms, Some(60_000));
+    assert_eq!(anthropic.retry_delay_ms(), Some(7_000));
+
+    let http_date = RateLimitInfo::from_headers_at(
+        &headers(&[("retry-after", "Wed, 01 May 2024 12:00:05 GMT")]),
+        now,
+    );
+    assert_eq!(http_date.retry_after_ms, Some(5_000));
+    let ms =
+        RateLimitInfo::from_headers(&headers(&[("retry-after-ms", "250"), ("retry-after", "9")]));
+    assert_eq!(ms.retry_after_ms, Some(250));
+    assert!(RateLimitInfo::from_headers(&HeaderMap::new()).is_empty());
+    assert_eq!(rate_limit::parse_duration("6m0s"), Some(360_000));
+    assert_eq!(rate_limit::parse_duration("soon"), None);
+}
+
+#[tokio::test]
+async fn test_retry_honors_retry_after_header() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .respond_with(
+            ResponseTemplate::new(429)
+                .insert_header("retry-after-ms", "150")
+                .insert_header("x-ratelimit-remaining-requests", "0")
+                .set_body_json(json!({
+                    "error": {"message": "Slow down", "type": "rate_limit_error"}
+                })),
+        )
+        .up_to_n_times(2)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "choices": [{
+                "message": {"role": "assistant", "content": "ok"},
+                "finish_reason": "stop"
+            }]
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let config = ProviderConfig::new("test-key").base_url(mock_server.uri());
+    let inner = Arc::new(OpenAiProvider::new("gpt-retry-after", config));
+    let err = inner
+        .generate(&[Message::user("test")], &[], &GenerateOptions::default())
+        .await
+        .unwrap_err();
+    match err {
+        GaussError::RateLimited {
+            message,
+            retry_after_ms,
+            ..
+        } => {
+            assert_eq!(message, "Slow down");
+            assert_eq!(retry_after_ms, Some(150));
+        }
+        other => panic!("expected rate limit, got {other:?}"),
+    }
+    let observed = rate_limit::latest("openai", "gpt-retry-after").unwrap();
+    assert_eq!(observed.requests.remaining, Some(0));
+
+    let provider = RetryProvider::new(inner, fast_config());
+    let started = std::time::Instant::now();
+    let result = provider
+        .generate(&[Message::user("test")], &[], &GenerateOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(result.text(), Some("ok"));
+    assert!(started.elapsed() >= Duration::from_millis(150));
+}
+
+#[tokio::test]
+async fn test_retry_after_beyond_max_delay_is_returned() {
+    let mock = Arc::new(
+        MockProvider::new()
+            .error(MockError::rate_limited(Some(60_000)))
+            .text("never"),
+    );
+    let provider = RetryProvider::new(mock.clone(), fast_config());
+    let err = provider
+        .generate(&[Message::user("test")], &[], &GenerateOptions::default())
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        GaussError::RateLimited {
+            retry_after_ms: Some(60_000),
+            ..
+        }
+    ));
+    assert_eq!(mock.call_count(), 1);
+}
+
+#[tokio::test]
+async fn test_overloaded_and_connection_errors_are_retried() {
+    let mock = Arc::new(
+        MockProvider::new()
+            .error(MockError::status(529, "Overloaded"))
+            .text("recovered"),
+    );
+    let provider = RetryProvider::new(mock.clone(), fast_config());
+    let result = provider
+        .generate(&[Message::user("test")], &[], &GenerateOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(result.text(), Some("recovered"));
+    assert_eq!(mock.call_count(), 2);
+
+    let reset = GaussError::Provider {
+        message: "request failed".into(),
+        status: None,
+        provider: "test".into(),
+        source: Some(Box::new(std::io::Error::from(
+            std::io::ErrorKind::ConnectionReset,
+        ))),
+    };
+    assert!(is_transient(&reset));
+    assert!(is_transient(&GaussError::provider(
+        "anthropic",
+        "Overloaded"
+    )));
+    assert!(!is_transient(&GaussError::provider("test", "bad request")));
+    assert!(!is_transient(&GaussError::Provider {
+        message: "invalid".into(),
+        status: Some(400),
+        provider: "test".into(),
+        source: None,
+    }));
+}
+
+#[tokio::test]
+async fn test_shared_retry_budget_limits_retries() {
+    let budget = Arc::new(RetryBudget::new(0.0, 2));
+    let mock = Arc::new(
+        MockProvider::new()
+            .error(MockError::status(503, "unavailable"))
+            .error(MockError::status(503, "unavailable"))
+            .error(MockError::status(503, "unavailable"))
+            .error(MockError::status(503, "unavailable"))
+            .text("unreachable"),
+    );
+    let first = RetryProvider::new(mock.clone(), fast_config()).with_budget(budget.clone());
+    let second = RetryProvider::new(mock.clone(), fast_config()).with_budget(budget.clone());
+
+    // The first call spends both retries from the shared budget and fails.
+    assert!(
+        first
+            .generate(&[Message::user("a")], &[], &GenerateOptions::default())
+            .await
+            .is_err()
+    );
+    assert_eq!(mock.call_count(), 3);
+    assert_eq!(budget.balance(), 0.0);
+
+    // The second provider draws from the same, now empty, budget and fails
+    // without retrying even though `max_retries` allows three attempts more.
+    assert!(
+        second
+            .generate(&[Message::user("b")], &[], &GenerateOptions::default())
+            .await
+            .is_err()
+    );
+    assert_eq!(mock.call_count(), 4);
+
+    let refilling = RetryBudget::new(0.5, 1);
+    assert!(refilling.try_withdraw());
+    assert!(!refilling.try_withdraw());
+    refilling.deposit();
+    refilling.deposit();
+    assert!(refilling.try_withdraw());
+}
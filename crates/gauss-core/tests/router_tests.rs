@@ -0,0 +1,254 @@
+use futures::StreamExt;
+use gauss_core::agent::{Agent, AgentStreamEvent};
+use gauss_core::message::{Content, Message};
+use gauss_core::provider::mock::{MockError, MockProvider, MockResponse};
+use gauss_core::provider::router::{RouterProvider, RoutingPolicy, RoutingStrategy};
+use gauss_core::provider::{GenerateOptions, Provider, ProviderCapabilities};
+use gauss_core::streaming::StreamEvent;
+use gauss_core::tool::Tool;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn mock(name: &str, model: &str) -> MockProvider {
+    MockProvider::new()
+        .with_name(name)
+        .with_model(model)
+        .repeat(MockResponse::Text(format!("from {name}")))
+}
+
+fn ask(text: &str) -> Vec<Message> {
+    vec![Message::user(text)]
+}
+
+#[tokio::test]
+async fn vision_requests_skip_text_only_targets() {
+    let text_only = Arc::new(mock("text", "gpt-4"));
+    let vision = Arc::new(
+        mock("vision", "gpt-4o").with_capabilities(ProviderCapabilities {
+            vision: true,
+            ..Default::default()
+        }),
+    );
+    let router = RouterProvider::new()
+        .target(text_only.clone())
+        .target(vision.clone());
+    let options = GenerateOptions::default();
+
+    let result = router.generate(&ask("hi"), &[], &options).await.unwrap();
+    assert_eq!(result.text(), Some("from text"));
+    assert_eq!(result.routing.unwrap().strategy, "priority");
+
+    let mut image = Message::user("What is this?");
+    image.content.push(Content::Image {
+        url: Some("https://example.com/cat.png".into()),
+        base64: None,
+        media_type: None,
+    });
+    let image = vec![image];
+    let result = router.generate(&image, &[], &options).await.unwrap();
+    assert_eq!(result.text(), Some("from vision"));
+    let decision = result.routing.unwrap();
+    assert_eq!((decision.index, decision.model.as_str()), (1, "gpt-4o"));
+    assert_eq!(decision.rejected.len(), 1);
+    assert_eq!(decision.rejected[0].reason, "missing capability: vision");
+    assert!(router.capabilities().vision);
+}
+
+#[tokio::test]
+async fn cheapest_target_within_ceiling_wins() {
+    let router = RouterProvider::new()
+        .target(Arc::new(mock("big", "gpt-4")))
+        .target(Arc::new(mock("mid", "gpt-4o")))
+        .target(Arc::new(mock("small", "gpt-4o-mini")))
+        .with_policy(RoutingPolicy {
+            strategy: RoutingStrategy::Cheapest,
+            max_cost_usd: Some(0.02),
+            ..Default::default()
+        });
+    let options = GenerateOptions {
+        max_tokens: Some(1000),
+        ..Default::default()
+    };
+
+    let decision = router.route(&ask("hi"), &[], &options).unwrap();
+    assert_eq!(decision.model, "gpt-4o-mini");
+    assert_eq!(decision.strategy, "cheapest");
+    assert!(decision.estimated_cost_usd > 0.0 && decision.estimated_cost_usd < 0.001);
+    // gpt-4 costs $0.06 per 1000 output tokens; gpt-4o stays under the ceiling.
+    assert_eq!(decision.rejected.len(), 1);
+    assert_eq!(decision.rejected[0].model, "gpt-4");
+
+    let tight = RouterProvider::new()
+        .target(Arc::new(mock("big", "gpt-4")))
+        .with_policy(RoutingPolicy {
+            max_cost_usd: Some(0.001),
+            ..Default::default()
+        });
+    let err = tight.route(&ask("hi"), &[], &options).unwrap_err();
+    assert!(err.to_string().contains("No eligible target"), "{err}");
+    assert!(
+        err.to_string().contains("big/gpt-4: estimated cost"),
+        "{err}"
+    );
+}
+
+#[tokio::test]
+async fn prompts_larger_than_the_context_window_move_on() {
+    let router = RouterProvider::new()
+        .target(Arc::new(mock("small-window", "gpt-4")))
+        .target(Arc::new(mock("large-window", "gpt-4o")));
+    let prompt = ask(&"lorem ipsum dolor ".repeat(3000));
+    let result = router
+        .generate(&prompt, &[], &GenerateOptions::default())
+        .await
+        .unwrap();
+    let decision = result.routing.unwrap();
+    assert_eq!(decision.provider, "large-window");
+    assert!(decision.estimated_input_tokens > 8192);
+    assert!(
+        decision.rejected[0]
+            .reason
+            .contains("context window is 8192")
+    );
+
+    // Structured output and tools are capabilities too.
+    let options = GenerateOptions {
+        output_schema: Some(serde_json::json!({"type": "object"})),
+        ..Default::default()
+    };
+    let tools = vec![Tool::builder("lookup", "Look something up").build()];
+    let router = RouterProvider::new().target(Arc::new(
+        mock("tools-only", "gpt-4o").with_capabilities(ProviderCapabilities {
+            tool_use: true,
+            ..Default::default()
+        }),
+    ));
+    assert!(
+        router
+            .route(&ask("hi"), &tools, &GenerateOptions::default())
+            .is_ok()
+    );
+    let err = router.route(&ask("hi"), &tools, &options).unwrap_err();
+    assert!(err.to_string().contains("structured output"), "{err}");
+}
+
+#[tokio::test(start_paused = true)]
+async fn failing_and_slow_targets_are_avoided() {
+    let flaky = Arc::new(
+        MockProvider::new()
+            .with_name("flaky")
+            .error(MockError::status(503, "unavailable"))
+            .repeat(MockResponse::Text("from flaky".into())),
+    );
+    let slow = Arc::new(mock("slow", "gpt-4o").latency(Duration::from_millis(900)));
+    let fast = Arc::new(mock("fast", "gpt-4o").latency(Duration::from_millis(100)));
+    let router = RouterProvider::new()
+        .target(flaky.clone())
+        .target(slow.clone())
+        .target(fast.clone())
+        .with_policy(RoutingPolicy {
+            strategy: RoutingStrategy::LowestLatency,
+            max_error_rate: Some(0.1),
+            ewma_alpha: 0.5,
+            ..Default::default()
+        });
+    let options = GenerateOptions::default();
+
+    // No samples yet: registration order breaks the tie.
+    assert!(router.generate(&ask("1"), &[], &options).await.is_err());
+    let stats = router.stats();
+    assert_eq!((stats[0].requests, stats[0].errors), (1, 1));
+    assert_eq!(stats[0].error_rate, 0.5);
+
+    // The flaky target sits out its cooldown; unsampled targets are tried first.
+    for _ in 0..2 {
+        router.generate(&ask("2"), &[], &options).await.unwrap();
+    }
+    let result = router.generate(&ask("3"), &[], &options).await.unwrap();
+    assert_eq!(result.text(), Some("from fast"));
+    assert_eq!(result.routing.unwrap().rejected[0].provider, "flaky");
+    assert_eq!(router.stats()[1].latency_ewma_ms, Some(900.0));
+    assert_eq!(router.stats()[2].latency_ewma_ms, Some(100.0));
+    assert_eq!((slow.call_count(), fast.call_count()), (1, 2));
+
+    // After the cooldown it becomes eligible again.
+    tokio::time::advance(Duration::from_secs(31)).await;
+    let decision = router.route(&ask("4"), &[], &options).unwrap();
+    assert!(decision.rejected.is_empty());
+}
+
+#[tokio::test]
+async fn weights_and_custom_route_functions() {
+    let heavy = Arc::new(mock("heavy", "gpt-4o"));
+    let never = Arc::new(mock("never", "gpt-4o"));
+    let router = RouterProvider::new()
+        .weighted_target(heavy.clone(), 3.0)
+        .weighted_target(never.clone(), 0.0)
+        .with_policy(RoutingPolicy {
+            strategy: RoutingStrategy::Weighted,
+            ..Default::default()
+        });
+    let options = GenerateOptions::default();
+    for _ in 0..20 {
+        assert_eq!(router.route(&ask("hi"), &[], &options).unwrap().index, 0);
+    }
+
+    let router = RouterProvider::new()
+        .target(heavy)
+        .target(never)
+        .with_route_fn(|request, candidates| {
+            if request.estimated_input_tokens > 20 {
+                candidates.iter().position(|c| c.provider == "never")
+            } else {
+                None
+            }
+        });
+    let decision = router.route(&ask("hi"), &[], &options).unwrap();
+    assert_eq!(
+        (decision.index, decision.strategy.as_str()),
+        (0, "priority")
+    );
+    let long = ask(&"a considerably longer prompt ".repeat(5));
+    let result = router.generate(&long, &[], &options).await.unwrap();
+    assert_eq!(result.text(), Some("from never"));
+    assert_eq!(result.routing.unwrap().strategy, "custom");
+}
+
+#[tokio::test]
+async fn agents_cost_the_routed_model_and_streams_report_the_route() {
+    let router = Arc::new(
+        RouterProvider::new()
+            .target(Arc::new(mock("big", "gpt-4")))
+            .target(Arc::new(mock("small", "gpt-4o-mini")))
+            .with_policy(RoutingPolicy {
+                strategy: RoutingStrategy::Cheapest,
+                ..Default::default()
+            }),
+    );
+    assert_eq!(router.model(), "gpt-4");
+
+    let agent = Agent::builder("routed", router.clone()).build();
+    let output = agent.run(ask("hi")).await.unwrap();
+    assert_eq!(output.text, "from small");
+    assert_eq!(output.cost.steps[0].model, "gpt-4o-mini");
+    assert!(output.cost.by_model.contains_key("gpt-4o-mini"));
+
+    let mut stream = router
+        .stream(&ask("hi"), &[], &GenerateOptions::default())
+        .await
+        .unwrap();
+    let first = stream.next().await.unwrap().unwrap();
+    assert!(matches!(first, StreamEvent::Routing(d) if d.model == "gpt-4o-mini"));
+
+    let events: Vec<AgentStreamEvent> = agent
+        .run_stream(ask("hi"))
+        .await
+        .unwrap()
+        .map(|e| e.unwrap())
+        .collect()
+        .await;
+    assert!(matches!(
+        events.last(),
+        Some(AgentStreamEvent::Done { cost, .. }) if cost.steps[0].model == "gpt-4o-mini"
+    ));
+}
@@ -3,6 +3,7 @@ use gauss_core::message::Message;
 use gauss_core::provider::openai::OpenAiProvider;
 use gauss_core::provider::{Provider, ProviderConfig};
 use gauss_core::team::{Strategy, Team};
+use gauss_core::telemetry::{SpanType, TelemetryCollector};
 use serde_json::json;
 use std::sync::Arc;
 use wiremock::matchers::{method, path};
@@ -66,6 +67,9 @@ async fn test_team_sequential_strategy() {
 
     assert_eq!(output.results.len(), 2);
     assert_eq!(output.final_text, "Agent 2 output");
+    assert_eq!(output.cost.steps.len(), 2);
+    assert_eq!(output.cost.by_model["gpt-test"].input_tokens, 10);
+    assert!(output.cost.total_cost_usd > 0.0);
 }
 
 #[tokio::test]
@@ -116,3 +120,40 @@ async fn test_team_empty_returns_error() {
         "Expected 'no agents' error, got: {err}"
     );
 }
+
+#[tokio::test]
+async fn test_team_nests_agent_spans() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_openai_response("done")))
+        .mount(&mock_server)
+        .await;
+
+    let provider = make_provider(&mock_server.uri());
+    let telemetry = Arc::new(TelemetryCollector::new());
+    let team = Team::builder("traced-team")
+        .strategy(Strategy::Parallel)
+        .agent(Agent::builder("a", provider.clone() as Arc<dyn Provider>).build())
+        .agent(Agent::builder("b", provider as Arc<dyn Provider>).build())
+        .telemetry(telemetry.clone())
+        .build();
+
+    team.run(vec![Message::user("go")]).await.unwrap();
+
+    let spans = telemetry.export_spans();
+    assert_eq!(spans.len(), 1);
+    let root = &spans[0];
+    assert_eq!(root.name, "team traced-team");
+    assert_eq!(root.children.len(), 2);
+    let trace_id = &root.context.as_ref().unwrap().trace_id;
+    for run in &root.children {
+        assert_eq!(run.span_type, SpanType::AgentRun);
+        assert_eq!(&run.context.as_ref().unwrap().trace_id, trace_id);
+    }
+
+    let metrics = telemetry.export_metrics();
+    assert_eq!(metrics.total_steps, 2);
+    assert_eq!(metrics.model_call_count, 2);
+    assert_eq!(metrics.total_input_tokens, 10);
+}
@@ -59,3 +59,189 @@ fn test_collector_clear() {
     collector.clear();
     assert_eq!(collector.export_spans().len(), 0);
 }
+
+#[test]
+fn test_trace_context_traceparent_roundtrip() {
+    let root = TraceContext::new_root();
+    let child = root.child();
+    assert_eq!(child.trace_id, root.trace_id);
+    assert_ne!(child.span_id, root.span_id);
+
+    let header = child.traceparent();
+    assert_eq!(TraceContext::from_traceparent(&header), Some(child));
+    assert!(TraceContext::from_traceparent("00-abc-def-01").is_none());
+    assert!(
+        TraceContext::from_traceparent("00-00000000000000000000000000000000-00f067aa0ba902b7-01")
+            .is_none()
+    );
+}
+
+#[cfg(feature = "otel")]
+mod otel {
+    use gauss_core::message::Message;
+    use gauss_core::otel::{OtelConfig, OtlpExporter};
+    use gauss_core::provider::openai::OpenAiProvider;
+    use gauss_core::provider::{GenerateOptions, Provider, ProviderConfig};
+    use gauss_core::telemetry::*;
+    use serde_json::{Value, json};
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn attr<'a>(span: &'a Value, key: &str) -> Option<&'a Value> {
+        span["attributes"]
+            .as_array()?
+            .iter()
+            .find(|a| a["key"] == key)
+            .map(|a| &a["value"])
+    }
+
+    fn run_span() -> SpanRecord {
+        let model_call = SpanBuilder::new("chat gpt-4o", SpanType::ModelCall)
+            .attribute("model", "gpt-4o")
+            .attribute("input_tokens", 12)
+            .attribute("output_tokens", 7)
+            .attribute("finish_reason", "stop")
+            .attribute("prompt", "Email me at jane@example.com")
+            .attribute("response", "Sure")
+            .finish();
+        let tool_call = SpanBuilder::new("execute_tool search", SpanType::ToolCall)
+            .attribute("tool_name", "search")
+            .finish_with_error("timeout");
+        SpanBuilder::new("invoke_agent helper", SpanType::AgentRun)
+            .attribute("agent_name", "helper")
+            .child(model_call)
+            .child(tool_call)
+            .finish()
+    }
+
+    #[tokio::test]
+    async fn test_otlp_export_to_mock_collector() {
+        let collector_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/traces"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({})))
+            .expect(1)
+            .mount(&collector_server)
+            .await;
+
+        let collector = TelemetryCollector::new();
+        collector.record_span(run_span());
+
+        let exporter = OtlpExporter::new(
+            OtelConfig::new(collector_server.uri())
+                .service_name("support-bot")
+                .header("x-api-key", "secret"),
+        );
+        assert_eq!(exporter.flush(&collector).await.unwrap(), 1);
+        assert!(collector.export_spans().is_empty());
+
+        let requests = collector_server.received_requests().await.unwrap();
+        assert_eq!(requests[0].headers["x-api-key"], "secret");
+        let body: Value = serde_json::from_slice(&requests[0].body).unwrap();
+        let resource = &body["resourceSpans"][0];
+        assert_eq!(
+            resource["resource"]["attributes"][0]["value"]["stringValue"],
+            "support-bot"
+        );
+
+        let spans = resource["scopeSpans"][0]["spans"].as_array().unwrap();
+        assert_eq!(spans.len(), 3);
+        let (agent, model, tool) = (&spans[0], &spans[1], &spans[2]);
+        assert_eq!(agent["parentSpanId"], "");
+        assert_eq!(model["traceId"], agent["traceId"]);
+        assert_eq!(model["parentSpanId"], agent["spanId"]);
+        assert_eq!(model["kind"], 3);
+
+        assert_eq!(
+            attr(agent, "gen_ai.operation.name").unwrap()["stringValue"],
+            "invoke_agent"
+        );
+        assert_eq!(
+            attr(agent, "gen_ai.agent.name").unwrap()["stringValue"],
+            "helper"
+        );
+        assert_eq!(
+            attr(model, "gen_ai.request.model").unwrap()["stringValue"],
+            "gpt-4o"
+        );
+        assert_eq!(
+            attr(model, "gen_ai.usage.input_tokens").unwrap()["intValue"],
+            "12"
+        );
+        assert_eq!(
+            attr(model, "gen_ai.response.finish_reasons").unwrap()["arrayValue"]["values"][0]["stringValue"],
+            "stop"
+        );
+        // Content is not captured by default.
+        assert!(attr(model, "gen_ai.input.messages").is_none());
+        assert_eq!(tool["status"]["code"], 2);
+        assert_eq!(tool["status"]["message"], "timeout");
+    }
+
+    #[tokio::test]
+    async fn test_otlp_export_failure_keeps_spans() {
+        let collector_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&collector_server)
+            .await;
+
+        let collector = TelemetryCollector::new();
+        collector.record_span(run_span());
+        let exporter = OtlpExporter::new(OtelConfig::new(collector_server.uri()));
+        assert!(exporter.flush(&collector).await.is_err());
+        assert_eq!(collector.export_spans().len(), 1);
+    }
+
+    #[test]
+    fn test_otlp_content_capture_with_redaction() {
+        let exporter = OtlpExporter::new(OtelConfig::default().capture_content(true).redact_pii());
+        let body = exporter.encode(&[run_span()]);
+        let model = &body["resourceSpans"][0]["scopeSpans"][0]["spans"][1];
+        assert_eq!(
+            attr(model, "gen_ai.input.messages").unwrap()["stringValue"],
+            "Email me at [EMAIL_REDACTED]"
+        );
+        assert_eq!(
+            attr(model, "gen_ai.output.messages").unwrap()["stringValue"],
+            "Sure"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_trace_context_propagates_to_provider_requests() {
+        let provider_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/chat/completions"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "choices": [{
+                    "message": {"role": "assistant", "content": "hi"},
+                    "finish_reason": "stop"
+                }],
+                "usage": {"prompt_tokens": 1, "completion_tokens": 1}
+            })))
+            .mount(&provider_server)
+            .await;
+
+        let provider = OpenAiProvider::new(
+            "gpt-4o",
+            ProviderConfig::new("test-key").base_url(provider_server.uri()),
+        );
+        let ctx = TraceContext::new_root();
+        ctx.clone()
+            .scope(provider.generate(&[Message::user("hi")], &[], &GenerateOptions::default()))
+            .await
+            .unwrap();
+        provider
+            .generate(&[Message::user("hi")], &[], &GenerateOptions::default())
+            .await
+            .unwrap();
+
+        let requests = provider_server.received_requests().await.unwrap();
+        assert_eq!(
+            requests[0].headers["traceparent"],
+            ctx.traceparent().as_str()
+        );
+        assert!(requests[1].headers.get("traceparent").is_none());
+    }
+}
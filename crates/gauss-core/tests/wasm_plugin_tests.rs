@@ -0,0 +1,232 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+use gauss_core::wasm_plugin::PluginPermissions;
+
+#[test]
+fn permissions_grant_nothing_by_default() {
+    let permissions = PluginPermissions::new();
+    assert!(!permissions.allows_http("https://example.com/"));
+    assert!(permissions.preopens.is_empty());
+    assert!(permissions.env.is_empty());
+}
+
+#[test]
+fn http_allowlist_matches_hosts_ports_and_subdomains() {
+    let permissions = PluginPermissions::new()
+        .allow_http("api.example.com")
+        .allow_http("*.internal.test")
+        .allow_http("localhost:8080");
+
+    assert!(permissions.allows_http("https://api.example.com/v1"));
+    assert!(permissions.allows_http("http://API.example.com"));
+    assert!(!permissions.allows_http("https://example.com"));
+    assert!(!permissions.allows_http("https://api.example.com.evil.io"));
+
+    assert!(permissions.allows_http("https://svc.internal.test/x"));
+    assert!(permissions.allows_http("https://a.b.internal.test"));
+    assert!(!permissions.allows_http("https://internal.test"));
+
+    assert!(permissions.allows_http("http://localhost:8080/health"));
+    assert!(!permissions.allows_http("http://localhost:9090/health"));
+
+    assert!(!permissions.allows_http("ftp://api.example.com"));
+    assert!(!permissions.allows_http("not a url"));
+}
+
+#[cfg(feature = "wasm-runtime")]
+mod runtime {
+    use gauss_core::guardrail::Guardrail;
+    use gauss_core::middleware::{BeforeToolParams, Middleware, MiddlewareContext};
+    use gauss_core::plugin::{GaussEvent, PluginRegistry};
+    use gauss_core::wasm_plugin::{WasmPluginConfig, WasmPluginHost};
+    use serde_json::json;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    const VERDICT: &str = r#"{"action":"block","reason":"blocked by wasm"}"#;
+
+    /// A component exporting `manifest` and `handle`. `body` is the core
+    /// `handle` function body; `$ok` writes an `ok(string)` return area.
+    fn component(manifest: &serde_json::Value, body: &str) -> String {
+        let manifest = manifest.to_string();
+        let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+        format!(
+            r#"(component
+  (core module $m
+    (memory (export "memory") 1)
+    (global $heap (mut i32) (i32.const 4096))
+    (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+      (local $ptr i32)
+      (local.set $ptr
+        (i32.and
+          (i32.add (global.get $heap) (i32.sub (local.get 2) (i32.const 1)))
+          (i32.sub (i32.const 0) (local.get 2))))
+      (global.set $heap (i32.add (local.get $ptr) (local.get 3)))
+      (local.get $ptr))
+    (data (i32.const 1024) "{manifest_data}")
+    (data (i32.const 3072) "{verdict_data}")
+    (data (i32.const 3584) "null")
+    (func (export "manifest") (result i32)
+      (i32.store (i32.const 0) (i32.const 1024))
+      (i32.store (i32.const 4) (i32.const {manifest_len}))
+      (i32.const 0))
+    (func $ok (param i32 i32) (result i32)
+      (i32.store8 (i32.const 8) (i32.const 0))
+      (i32.store (i32.const 12) (local.get 0))
+      (i32.store (i32.const 16) (local.get 1))
+      (i32.const 8))
+    (func (export "handle") (param i32 i32 i32 i32) (result i32)
+      {body}))
+  (core instance $i (instantiate $m))
+  (func (export "manifest") (result string)
+    (canon lift (core func $i "manifest") (memory (core memory $i "memory")) (realloc (core func $i "realloc"))))
+  (func (export "handle") (param "hook" string) (param "payload" string)
+    (result (result string (error string)))
+    (canon lift (core func $i "handle") (memory (core memory $i "memory")) (realloc (core func $i "realloc")))))
+"#,
+            manifest_data = escape(&manifest),
+            manifest_len = manifest.len(),
+            verdict_data = escape(VERDICT),
+        )
+    }
+
+    /// `tool:*` echoes the arguments, `validate-*` blocks, everything else is `null`.
+    fn dispatching_body() -> String {
+        format!(
+            r#"(if (i32.eq (i32.load8_u (local.get 0)) (i32.const 116))
+        (then (return (call $ok (local.get 2) (local.get 3)))))
+      (if (i32.eq (i32.load8_u (local.get 0)) (i32.const 118))
+        (then (return (call $ok (i32.const 3072) (i32.const {})))))
+      (call $ok (i32.const 3584) (i32.const 4))"#,
+            VERDICT.len()
+        )
+    }
+
+    fn write_component(name: &str, wat: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("gauss-wasm-plugin-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(format!("{name}.wat"));
+        std::fs::write(&path, wat).unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn wasm_plugin_exposes_guardrail_middleware_and_tools() {
+        let manifest = json!({
+            "name": "policy",
+            "version": "0.1.0",
+            "guardrail": true,
+            "middleware": true,
+            "priority": "Early",
+            "tools": [{
+                "name": "echo",
+                "description": "Echo the arguments",
+                "parameters": {"type": "object", "properties": {"text": {"type": "string"}}}
+            }]
+        });
+        let path = write_component("policy", &component(&manifest, &dispatching_body()));
+
+        let host = WasmPluginHost::new().unwrap();
+        let plugin = host.load(WasmPluginConfig::new(&path)).await.unwrap();
+        assert_eq!(plugin.manifest().name, "policy");
+        assert_eq!(plugin.manifest().version, "0.1.0");
+
+        let guardrail = plugin.guardrail().expect("manifest declares a guardrail");
+        assert_eq!(guardrail.name(), "policy");
+        let result = guardrail.validate_output("some text").await.unwrap();
+        assert!(result.action.is_blocked());
+        assert!(format!("{:?}", result.action).contains("blocked by wasm"));
+
+        let middleware = plugin.middleware().expect("manifest declares middleware");
+        assert_eq!(
+            middleware.priority(),
+            gauss_core::middleware::MiddlewarePriority::Early
+        );
+        let mut ctx = MiddlewareContext {
+            session_id: "s1".into(),
+            agent_name: None,
+            timestamp: 0,
+            metadata: Default::default(),
+        };
+        let params = BeforeToolParams {
+            tool_name: "echo".into(),
+            args: json!({}),
+            step_index: 0,
+        };
+        assert!(
+            middleware
+                .before_tool(&mut ctx, &params)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        let tools = plugin.tools();
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].name, "echo");
+        let output = tools[0].execute(json!({"text": "hi"})).await.unwrap();
+        assert_eq!(output, json!({"text": "hi"}));
+    }
+
+    #[tokio::test]
+    async fn registry_initializes_wasm_plugins_and_forwards_events() {
+        let base = json!({"name": "base", "version": "1.0.0", "events": ["agent_start"]});
+        let dependent = json!({"name": "dependent", "version": "1.0.0", "dependencies": ["base"]});
+        let host = WasmPluginHost::new().unwrap();
+        let base = host
+            .load(WasmPluginConfig::new(write_component(
+                "base",
+                &component(&base, &dispatching_body()),
+            )))
+            .await
+            .unwrap();
+        let dependent = host
+            .load(WasmPluginConfig::new(write_component(
+                "dependent",
+                &component(&dependent, &dispatching_body()),
+            )))
+            .await
+            .unwrap();
+        assert!(base.guardrail().is_none());
+        assert!(base.tools().is_empty());
+
+        let mut registry = PluginRegistry::new();
+        registry.register(Arc::new(dependent));
+        registry.register(Arc::new(base));
+        registry.init_all().await.unwrap();
+        assert_eq!(registry.bus.subscription_count(), 1);
+
+        registry
+            .emit_async(&GaussEvent::AgentStart {
+                agent_name: "a".into(),
+                session_id: "s".into(),
+            })
+            .await;
+        registry.shutdown_all().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn wasm_plugin_out_of_fuel_is_an_error() {
+        let manifest = json!({"name": "spinner", "version": "0.1.0"});
+        let body = "(loop $spin (br $spin)) (unreachable)";
+        let path = write_component("spinner", &component(&manifest, body));
+
+        let host = WasmPluginHost::new().unwrap();
+        let plugin = host
+            .load(WasmPluginConfig::new(&path).fuel(100_000))
+            .await
+            .unwrap();
+        let err = plugin.call("init", &json!({})).await.unwrap_err();
+        assert!(err.to_string().contains("ran out of fuel"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn wasm_plugin_load_fails_for_missing_file() {
+        let host = WasmPluginHost::new().unwrap();
+        let err = host
+            .load(WasmPluginConfig::new("/nonexistent/plugin.wasm"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Plugin error"), "{err}");
+    }
+}
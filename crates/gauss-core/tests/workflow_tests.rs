@@ -2,6 +2,7 @@ use gauss_core::agent::Agent;
 use gauss_core::message::Message;
 use gauss_core::provider::openai::OpenAiProvider;
 use gauss_core::provider::{GenerateOptions, Provider, ProviderConfig};
+use gauss_core::telemetry::{SpanType, TelemetryCollector};
 use gauss_core::workflow::{StepOutput, Workflow};
 use serde_json::json;
 use std::sync::Arc;
@@ -162,3 +163,47 @@ async fn test_workflow_deadlock_detection() {
         }
     }
 }
+
+#[tokio::test]
+async fn test_workflow_records_step_spans() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_openai_response("drafted")))
+        .mount(&mock_server)
+        .await;
+
+    let provider = make_provider(&mock_server.uri());
+    let telemetry = Arc::new(TelemetryCollector::new());
+    let workflow = Workflow::builder()
+        .agent_step(
+            "draft",
+            Agent::builder("writer", provider as Arc<dyn Provider>).build(),
+            |_| vec![Message::user("write")],
+        )
+        .function_step("publish", |_| {
+            Box::pin(async {
+                Ok(StepOutput {
+                    step_id: "publish".into(),
+                    text: "published".into(),
+                    data: None,
+                })
+            })
+        })
+        .dependency("publish", "draft")
+        .telemetry(telemetry.clone())
+        .build();
+
+    workflow.run(vec![Message::user("go")]).await.unwrap();
+
+    let spans = telemetry.export_spans();
+    assert_eq!(spans.len(), 1);
+    let root = &spans[0];
+    assert_eq!(root.name, "workflow");
+    let names: Vec<_> = root.children.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, vec!["step draft", "step publish"]);
+    assert_eq!(root.children[0].span_type, SpanType::WorkflowStep);
+    assert_eq!(root.children[0].children[0].span_type, SpanType::AgentRun);
+    assert!(root.children[1].children.is_empty());
+    assert_eq!(telemetry.export_metrics().model_call_count, 1);
+}
@@ -409,7 +409,13 @@ pub async fn agent_stream_with_tool_executor(
                     napi::threadsafe_function::ThreadsafeFunctionCallMode::NonBlocking,
                 );
             }
-            Ok(AgentStreamEvent::Done { text, steps, usage }) => {
+            Ok(AgentStreamEvent::Done {
+                text,
+                steps,
+                usage,
+                cost,
+                stop_reason,
+            }) => {
                 final_text = text;
                 final_steps = steps as u32;
                 final_input_tokens = usage.input_tokens as u32;
@@ -421,6 +427,8 @@ pub async fn agent_stream_with_tool_executor(
                     "steps": final_steps,
                     "inputTokens": final_input_tokens,
                     "outputTokens": final_output_tokens,
+                    "costUsd": cost.total_cost_usd,
+                    "stopReason": stop_reason,
                 }))
                 .unwrap_or_default();
                 let _ = stream_callback.call(
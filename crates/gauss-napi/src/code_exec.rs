@@ -28,9 +28,8 @@ pub async fn execute_code(
         bash: language == "bash",
         timeout: std::time::Duration::from_secs(timeout_secs.unwrap_or(30) as u64),
         working_dir,
-        env: Vec::new(),
         sandbox: sandbox_config,
-        interpreters: std::collections::HashMap::new(),
+        ..CodeExecutionConfig::default()
     };
 
     let orch = CodeExecutionOrchestrator::new(config);
@@ -45,6 +44,9 @@ pub async fn execute_code(
         "exitCode": result.exit_code,
         "timedOut": result.timed_out,
         "runtime": result.runtime,
+        "stdoutTruncated": result.stdout_truncated,
+        "stderrTruncated": result.stderr_truncated,
+        "artifacts": result.artifacts,
         "success": result.success(),
     }))
 }
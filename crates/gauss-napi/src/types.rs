@@ -143,9 +143,8 @@ pub fn napi_code_exec_to_config(opts: &CodeExecutionOptions) -> CodeExecutionCon
         bash: opts.bash.unwrap_or(true),
         timeout: std::time::Duration::from_secs(opts.timeout_secs.unwrap_or(30) as u64),
         working_dir: opts.working_dir.clone(),
-        env: Vec::new(),
         sandbox,
-        interpreters: std::collections::HashMap::new(),
+        ..CodeExecutionConfig::default()
     }
 }
 
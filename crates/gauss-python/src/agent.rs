@@ -72,9 +72,8 @@ fn apply_options(
                     ce["timeout_secs"].as_u64().unwrap_or(30),
                 ),
                 working_dir: ce["working_dir"].as_str().map(|s| s.to_string()),
-                env: Vec::new(),
                 sandbox,
-                interpreters: std::collections::HashMap::new(),
+                ..gauss_core::code_execution::CodeExecutionConfig::default()
             }
         } else {
             gauss_core::code_execution::CodeExecutionConfig::all()
@@ -376,7 +375,13 @@ pub fn agent_stream(
                     "finish_reason": format!("{:?}", finish_reason),
                     "has_tool_calls": has_tool_calls,
                 })),
-                Ok(AgentStreamEvent::Done { text, steps, usage }) => {
+                Ok(AgentStreamEvent::Done {
+                    text,
+                    steps,
+                    usage,
+                    cost,
+                    stop_reason,
+                }) => {
                     final_text = text.clone();
                     final_steps = steps as u32;
                     final_input_tokens = usage.input_tokens as u32;
@@ -388,6 +393,8 @@ pub fn agent_stream(
                         "steps": final_steps,
                         "input_tokens": final_input_tokens,
                         "output_tokens": final_output_tokens,
+                        "cost_usd": cost.total_cost_usd,
+                        "stop_reason": stop_reason,
                     }))
                 }
                 Ok(AgentStreamEvent::RawEvent { step, event }) => {
@@ -142,9 +142,8 @@ pub fn execute_code(
             bash: language == "bash",
             timeout: std::time::Duration::from_secs(timeout_secs.unwrap_or(30)),
             working_dir,
-            env: Vec::new(),
             sandbox: sandbox_config,
-            interpreters: std::collections::HashMap::new(),
+            ..gauss_core::code_execution::CodeExecutionConfig::default()
         };
 
         let orch = gauss_core::code_execution::CodeExecutionOrchestrator::new(config);
@@ -159,6 +158,9 @@ pub fn execute_code(
             "exit_code": result.exit_code,
             "timed_out": result.timed_out,
             "runtime": result.runtime,
+            "stdout_truncated": result.stdout_truncated,
+            "stderr_truncated": result.stderr_truncated,
+            "artifacts": result.artifacts,
             "success": result.success(),
         });
 
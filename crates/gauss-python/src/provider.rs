@@ -47,7 +47,7 @@ pub fn create_provider(
         "anthropic" => Arc::new(AnthropicProvider::new(model, config)),
         "google" => Arc::new(GoogleProvider::new(model, config)),
         "groq" => Arc::new(GroqProvider::create(model, config)),
-        "ollama" => Arc::new(OllamaProvider::create(model, config)),
+        "ollama" => Arc::new(OllamaProvider::new(model, config)),
         "deepseek" => Arc::new(DeepSeekProvider::create(model, config)),
         "openrouter" => Arc::new(OpenRouterProvider::create(model, config)),
         "together" => Arc::new(TogetherProvider::create(model, config)),
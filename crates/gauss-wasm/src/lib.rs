@@ -64,7 +64,7 @@ pub fn create_provider(
         "anthropic" => Shared::new(AnthropicProvider::new(model, config)),
         "google" => Shared::new(GoogleProvider::new(model, config)),
         "groq" => Shared::new(GroqProvider::create(model, config)),
-        "ollama" => Shared::new(OllamaProvider::create(model, config)),
+        "ollama" => Shared::new(OllamaProvider::new(model, config)),
         "deepseek" => Shared::new(DeepSeekProvider::create(model, config)),
         "openrouter" => Shared::new(OpenRouterProvider::create(model, config)),
         "together" => Shared::new(TogetherProvider::create(model, config)),